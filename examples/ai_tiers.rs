@@ -0,0 +1,38 @@
+// Plays a batch of seeded games with each of the computer player's difficulties (or
+// just the one named), then prints how each did. Usage:
+//
+//     cargo run --example ai_tiers [games] [easy|medium|hard]
+
+use snake_game::ai::{Ai, Difficulty, DIFFICULTIES};
+use snake_game::game::{Game, HEIGHT, WIDTH};
+use snake_game::headless;
+
+const MAX_MOVES: u64 = 20_000;
+
+fn main() {
+    let games: u64 = std::env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(100);
+    let only = std::env::args()
+        .nth(2)
+        .and_then(|arg| Difficulty::parse(&arg));
+
+    for difficulty in DIFFICULTIES {
+        if only.is_some_and(|only| only != difficulty) {
+            continue;
+        }
+        let runs: Vec<_> = (0..games)
+            .map(|seed| {
+                headless::play(
+                    Game::headless(seed, (WIDTH, HEIGHT)),
+                    &mut Ai::new(difficulty, seed),
+                    MAX_MOVES,
+                )
+            })
+            .collect();
+        println!("{}", difficulty.name());
+        println!("{}", headless::summary(&runs));
+        println!();
+    }
+}
@@ -0,0 +1,83 @@
+// Plays a batch of seeded games with a bot that heads straight for the nearest food
+// it can safely eat, then prints how it did. Usage:
+//
+//     cargo run --example greedy_bot [games]
+
+use snake_game::game::{Direction, FoodType, Game, DIRECTIONS, HEIGHT, WIDTH};
+use snake_game::headless::{self, Controller};
+
+const MAX_MOVES: u64 = 20_000;
+
+struct GreedyBot;
+
+impl Controller for GreedyBot {
+    fn next_move(&mut self, game: &Game) -> Option<Direction> {
+        let targets: Vec<(i32, i32)> = game
+            .foods
+            .iter()
+            .filter(|food| food.food_type != FoodType::ShinyMetal || game.can_digest_metal())
+            .map(|food| food.position)
+            .collect();
+        // Of the moves that don't kill the snake right away, take the one that ends up
+        // closest to a target. With nowhere safe to go, keep going and hope.
+        safe_moves(game)
+            .into_iter()
+            .min_by_key(|&(_, cell)| {
+                targets
+                    .iter()
+                    .map(|&target| distance(game, cell, target))
+                    .min()
+                    .unwrap_or(0)
+            })
+            .map(|(direction, _)| direction)
+    }
+}
+
+// Directions the snake can take next move without dying, with the cell each leads to
+fn safe_moves(game: &Game) -> Vec<(Direction, (i32, i32))> {
+    let head = game.snake.body[0].position;
+    DIRECTIONS
+        .iter()
+        .filter(|&&direction| direction != game.snake.direction.opposite())
+        .filter_map(|&direction| Some((direction, game.neighbor(head, direction)?)))
+        .filter(|&(_, cell)| {
+            !game.snake.body.iter().any(|seg| seg.position == cell)
+                && !game.boss_covers(cell)
+                && !game.foods.iter().any(|food| {
+                    food.position == cell
+                        && food.food_type == FoodType::ShinyMetal
+                        && !game.can_digest_metal()
+                })
+        })
+        .collect()
+}
+
+// Moves between two cells, going across the edges when the board wraps
+fn distance(game: &Game, a: (i32, i32), b: (i32, i32)) -> i32 {
+    let dx = (a.0 - b.0).abs();
+    let dy = (a.1 - b.1).abs();
+    if game.wrap_around {
+        dx.min(game.width - dx) + dy.min(game.height - dy)
+    } else {
+        dx + dy
+    }
+}
+
+fn main() {
+    let games: u64 = std::env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(100);
+
+    let runs: Vec<_> = (0..games)
+        .map(|seed| {
+            headless::play(
+                Game::headless(seed, (WIDTH, HEIGHT)),
+                &mut GreedyBot,
+                MAX_MOVES,
+            )
+        })
+        .collect();
+    println!("Greedy bot");
+    println!("{}", headless::summary(&runs));
+}
@@ -0,0 +1,82 @@
+// Plays a batch of seeded games with wrap-around turned off and a bot that goes from
+// wall to wall, stepping over a row at each end like a lawnmower, then prints how
+// it did. Usage:
+//
+//     cargo run --example wall_follower [games]
+
+use snake_game::game::{Direction, FoodType, Game, HEIGHT, WIDTH};
+use snake_game::headless::{self, Controller};
+
+const MAX_MOVES: u64 = 20_000;
+
+struct WallFollower {
+    sweep: Direction,        // Which way the rows are being worked through, up or down
+    turn: Option<Direction>, // Where to head once the step over to the next row is made
+}
+
+impl Controller for WallFollower {
+    fn next_move(&mut self, game: &Game) -> Option<Direction> {
+        let heading = game.snake.direction;
+        if let Some(turn) = self.turn.take() {
+            if is_safe(game, turn) {
+                return Some(turn);
+            }
+        }
+        if is_safe(game, heading) {
+            return None;
+        }
+        if matches!(heading, Direction::Left | Direction::Right) {
+            // Reached a wall: step over a row and come back the other way. At the top
+            // or bottom of the board the sweep turns around.
+            if !is_safe(game, self.sweep) {
+                self.sweep = self.sweep.opposite();
+            }
+            self.turn = Some(heading.opposite());
+            Some(self.sweep)
+        } else {
+            [Direction::Left, Direction::Right]
+                .into_iter()
+                .find(|&direction| is_safe(game, direction))
+        }
+    }
+}
+
+// Moving this way next won't kill the snake
+fn is_safe(game: &Game, direction: Direction) -> bool {
+    match game.neighbor(game.snake.body[0].position, direction) {
+        Some(cell) => {
+            !game.snake.body.iter().any(|seg| seg.position == cell)
+                && !game.boss_covers(cell)
+                && !game.foods.iter().any(|food| {
+                    food.position == cell
+                        && food.food_type == FoodType::ShinyMetal
+                        && !game.can_digest_metal()
+                })
+        }
+        None => false,
+    }
+}
+
+fn main() {
+    let games: u64 = std::env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(100);
+
+    let runs: Vec<_> = (0..games)
+        .map(|seed| {
+            let mut game = Game::headless(seed, (WIDTH, HEIGHT));
+            game.wrap_around = false;
+            headless::play(
+                game,
+                &mut WallFollower {
+                    sweep: Direction::Down,
+                    turn: None,
+                },
+                MAX_MOVES,
+            )
+        })
+        .collect();
+    println!("Wall follower");
+    println!("{}", headless::summary(&runs));
+}
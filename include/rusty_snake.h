@@ -0,0 +1,62 @@
+/* C interface to the Rusty Snake rules. Build the library with
+ *
+ *     cargo rustc --release --lib --features ffi --crate-type cdylib
+ *
+ * and link against target/release/libsnake_game.so (snake_game.dll on Windows,
+ * libsnake_game.dylib on macOS). Every function treats a null game as an empty one.
+ */
+
+#ifndef RUSTY_SNAKE_H
+#define RUSTY_SNAKE_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct SnakeGame SnakeGame;
+
+/* Directions for snake_step */
+#define SNAKE_UP 0
+#define SNAKE_RIGHT 1
+#define SNAKE_DOWN 2
+#define SNAKE_LEFT 3
+#define SNAKE_STRAIGHT -1
+
+/* A new game of width x height cells (the standard board if either is 0 or less),
+ * already started and heading right. Free with snake_destroy. */
+SnakeGame *snake_create(uint64_t seed, int32_t width, int32_t height);
+void snake_destroy(SnakeGame *game);
+
+/* Steer and make one move. Returns the points scored, or -1 if the snake died. */
+int32_t snake_step(SnakeGame *game, int32_t direction);
+
+bool snake_game_over(const SnakeGame *game);
+uint32_t snake_score(const SnakeGame *game);
+int32_t snake_width(const SnakeGame *game);
+int32_t snake_height(const SnakeGame *game);
+uint32_t snake_length(const SnakeGame *game);
+
+/* Segment `index` (0 is the head). kind: 0 head, 1 empty stomach, 2 full stomach,
+ * 3 tail. Any out pointer may be null. False if there is no such segment. */
+bool snake_segment(const SnakeGame *game, uint32_t index, int32_t *x, int32_t *y,
+                   int32_t *kind);
+
+uint32_t snake_food_count(const SnakeGame *game);
+/* Food `index`. kind: 0 scrap, 1 shiny metal, 2 water, 3 pumpkin. */
+bool snake_food(const SnakeGame *game, uint32_t index, int32_t *x, int32_t *y,
+                int32_t *kind);
+
+/* The board as channels x height x width bytes of 0 or 1, channels in the order head,
+ * body, scrap, metal, water, boss, rat. Written to `out` if `capacity` is big enough;
+ * returns the size needed either way. */
+size_t snake_observe(const SnakeGame *game, uint8_t *out, size_t capacity);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
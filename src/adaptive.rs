@@ -0,0 +1,115 @@
+// Adaptive difficulty: a mode that keeps the challenge in a band by watching how the
+// player has been doing. Deaths and close calls (turning away with a wall or the body
+// right ahead) are counted over the last WINDOW frames of play in the mode, runs before
+// this one included. Every REVIEW_EVERY frames, too many of them ease the pace and
+// bring metal back more slowly, too few make the snake faster and put more metal out.
+// The state goes to the input log at the start of a run, so a replay adjusts the same.
+
+pub const MAX_LEVEL: i32 = 3; // Steps either way from the classic game
+const MINUTE: u64 = 7200; // Frames, at 120 a second
+const WINDOW: u64 = 2 * MINUTE; // How far back the measure looks
+const REVIEW_EVERY: u64 = MINUTE / 4;
+const CLOSE_CALLS_PER_DEATH: f64 = 5.0; // Close calls that weigh as much as a death
+const BAND: (f64, f64) = (0.4, 1.2); // Deaths a minute, or their worth in close calls
+const PACE_STEP: u64 = 15; // Percent faster per level up, slower per level down
+const METAL_REST: u32 = 20; // Moves eaten metal stays away for, per level down
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Adaptive {
+    pub level: i32,        // Below 0 is easier than classic, above is harder
+    clock: u64,            // Frames played in the mode
+    deaths: Vec<u64>,      // On the clock, within the window
+    close_calls: Vec<u64>, // The same
+}
+
+impl Adaptive {
+    // A frame of play
+    pub fn tick(&mut self) {
+        self.clock += 1;
+        if !self.clock.is_multiple_of(REVIEW_EVERY) {
+            return;
+        }
+        let clock = self.clock;
+        self.deaths.retain(|&time| time + WINDOW > clock);
+        self.close_calls.retain(|&time| time + WINDOW > clock);
+        let pressure = self.pressure();
+        self.level = if pressure > BAND.1 {
+            self.level - 1
+        } else if pressure < BAND.0 {
+            self.level + 1
+        } else {
+            self.level
+        }
+        .clamp(-MAX_LEVEL, MAX_LEVEL);
+    }
+
+    pub fn died(&mut self) {
+        self.deaths.push(self.clock);
+    }
+
+    pub fn close_call(&mut self) {
+        self.close_calls.push(self.clock);
+    }
+
+    // Deaths a minute over the window so far, with close calls counted in
+    pub fn pressure(&self) -> f64 {
+        let minutes = self.clock.clamp(1, WINDOW) as f64 / MINUTE as f64;
+        let deaths =
+            self.deaths.len() as f64 + self.close_calls.len() as f64 / CLOSE_CALLS_PER_DEATH;
+        deaths / minutes
+    }
+
+    // `interval` frames between moves at this level
+    pub fn interval(&self, interval: u64) -> u64 {
+        let pace = 100 + PACE_STEP as i64 * self.level as i64;
+        (interval * 100).div_ceil(pace as u64).max(1)
+    }
+
+    // Pieces of metal kept on the board, one more for every two levels up
+    pub fn metal_pieces(&self) -> usize {
+        1 + self.level.max(0) as usize / 2
+    }
+
+    // Moves before eaten metal turns up again
+    pub fn metal_rest(&self) -> u32 {
+        METAL_REST * (-self.level).max(0) as u32
+    }
+
+    // e.g. "level:-1 clock:36000 deaths:30100,34500 close_calls:35020", for the
+    // profile and input log
+    pub fn words(&self) -> Vec<String> {
+        let times = |times: &[u64]| {
+            let times: Vec<String> = times.iter().map(|time| time.to_string()).collect();
+            times.join(",")
+        };
+        vec![
+            format!("level:{}", self.level),
+            format!("clock:{}", self.clock),
+            format!("deaths:{}", times(&self.deaths)),
+            format!("close_calls:{}", times(&self.close_calls)),
+        ]
+    }
+
+    pub fn parse<'a>(words: impl IntoIterator<Item = &'a str>) -> Option<Adaptive> {
+        let times = |value: &str| -> Option<Vec<u64>> {
+            value
+                .split(',')
+                .filter(|time| !time.is_empty())
+                .map(|time| time.parse().ok())
+                .collect()
+        };
+        let mut adaptive = Adaptive::default();
+        for word in words {
+            match word.split_once(':')? {
+                ("level", level) => {
+                    adaptive.level = level.parse::<i32>().ok()?.clamp(-MAX_LEVEL, MAX_LEVEL)
+                }
+                ("clock", clock) => adaptive.clock = clock.parse().ok()?,
+                ("deaths", deaths) => adaptive.deaths = times(deaths)?,
+                ("close_calls", close_calls) => adaptive.close_calls = times(close_calls)?,
+                _ => return None,
+            }
+        }
+        Some(adaptive)
+    }
+}
@@ -0,0 +1,291 @@
+// Computer players in three strengths, as `Controller`s so they can drive any snake a
+// player could. Easy wanders about without dying on the next move, Medium heads for the
+// nearest food, and Hard plans a path to the food and only takes it if the snake can
+// still reach its own tail afterwards. Every tier now and then makes a random (but
+// safe) move instead of the planned one, so Hard stays beatable. They steer in the four
+// screen directions, so they play the square board.
+
+use crate::game::{Direction, FoodType, Game, DIRECTIONS};
+use crate::headless::Controller;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+pub const DIFFICULTIES: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+
+impl Difficulty {
+    pub fn parse(value: &str) -> Option<Difficulty> {
+        match value {
+            "easy" => Some(Difficulty::Easy),
+            "medium" => Some(Difficulty::Medium),
+            "hard" => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+        }
+    }
+
+    // One move in this many is a random safe one instead of the planned one
+    fn slip_chance(&self) -> u32 {
+        match self {
+            Difficulty::Easy => 1,
+            Difficulty::Medium => 12,
+            Difficulty::Hard => 40,
+        }
+    }
+}
+
+// How the last move was picked, for drawing over the board: the cells looked at and
+// the path taken, first step first
+#[derive(Default)]
+pub struct Plan {
+    pub considered: Vec<(i32, i32)>,
+    pub path: Vec<(i32, i32)>,
+}
+
+pub struct Ai {
+    pub difficulty: Difficulty,
+    pub plan: Plan,
+    rng: StdRng, // Seeded separately from the game, so the same game can face any tier
+}
+
+impl Ai {
+    pub fn new(difficulty: Difficulty, seed: u64) -> Ai {
+        Ai {
+            difficulty,
+            plan: Plan::default(),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Controller for Ai {
+    fn next_move(&mut self, game: &Game) -> Option<Direction> {
+        let safe = safe_moves(game);
+        self.plan = Plan {
+            considered: safe.iter().map(|(_, cell)| *cell).collect(),
+            path: Vec::new(),
+        };
+        if safe.is_empty() {
+            // Nowhere safe to go: keep going and hope
+            return None;
+        }
+        match self.difficulty {
+            Difficulty::Easy => {}
+            Difficulty::Medium => self.plan.path = greedy(game, &safe).into_iter().collect(),
+            Difficulty::Hard => plan(game, &safe, &mut self.plan),
+        }
+        if self.plan.path.is_empty() || self.rng.gen_ratio(1, self.difficulty.slip_chance()) {
+            let (_, cell) = safe[self.rng.gen_range(0..safe.len())];
+            self.plan.path = vec![cell];
+        }
+        let first = self.plan.path[0];
+        safe.iter()
+            .find(|(_, cell)| *cell == first)
+            .map(|(direction, _)| *direction)
+    }
+}
+
+// Directions the snake can take next move without dying, with the cell each leads to.
+// The head is checked against the body before the tail moves, so the tail's cell is
+// as deadly as the rest.
+fn safe_moves(game: &Game) -> Vec<(Direction, (i32, i32))> {
+    let head = game.snake.body[0].position;
+    let body = &game.snake.body;
+    DIRECTIONS
+        .iter()
+        .filter(|&&direction| direction != game.snake.direction.opposite())
+        .filter_map(|&direction| Some((direction, game.neighbor(head, direction)?)))
+        .filter(|&(_, cell)| !body.iter().any(|seg| seg.position == cell) && !blocked(game, cell))
+        .collect()
+}
+
+// Cells to stay off besides the body: where the crane is or could step next, and metal
+// that would kill the snake or give it indigestion (which makes its turns come late)
+fn blocked(game: &Game, cell: (i32, i32)) -> bool {
+    let near_boss = game.boss.is_some_and(|boss| {
+        boss.cells((game.width, game.height))
+            .iter()
+            .any(|&covered| distance(game, cell, covered) <= 1)
+    });
+    near_boss || is_bad_metal(game, cell)
+}
+
+fn is_bad_metal(game: &Game, cell: (i32, i32)) -> bool {
+    !game.can_digest_metal()
+        && game
+            .foods
+            .iter()
+            .any(|food| food.position == cell && food.food_type == FoodType::ShinyMetal)
+}
+
+fn targets(game: &Game) -> Vec<(i32, i32)> {
+    game.foods
+        .iter()
+        .filter(|food| !is_bad_metal(game, food.position))
+        .map(|food| food.position)
+        .collect()
+}
+
+// Moves between two cells, going across the edges when the board wraps
+fn distance(game: &Game, a: (i32, i32), b: (i32, i32)) -> i32 {
+    let dx = (a.0 - b.0).abs();
+    let dy = (a.1 - b.1).abs();
+    if game.wrap_around {
+        dx.min(game.width - dx) + dy.min(game.height - dy)
+    } else {
+        dx + dy
+    }
+}
+
+// Medium: the safe move that ends up closest to any food
+fn greedy(game: &Game, safe: &[(Direction, (i32, i32))]) -> Option<(i32, i32)> {
+    let targets = targets(game);
+    safe.iter()
+        .min_by_key(|(_, cell)| {
+            targets
+                .iter()
+                .map(|&target| distance(game, *cell, target))
+                .min()
+                .unwrap_or(0)
+        })
+        .map(|(_, cell)| *cell)
+}
+
+// Hard: the first step of the shortest path to a food that leaves a way back to the
+// tail. Without one, the move that keeps the most room around the head.
+fn plan(game: &Game, safe: &[(Direction, (i32, i32))], plan: &mut Plan) {
+    let body: Vec<(i32, i32)> = game.snake.body.iter().map(|seg| seg.position).collect();
+    plan.considered.clear();
+    let path = shortest_path(game, &body, &targets(game), &mut plan.considered);
+    if let Some(path) = path {
+        if reaches_tail(game, &after(&body, &path)) {
+            plan.path = path;
+            return;
+        }
+    }
+    plan.path = safe
+        .iter()
+        .max_by_key(|(_, cell)| {
+            let next = after(&body, &[*cell]);
+            (reaches_tail(game, &next), room(game, &next))
+        })
+        .map(|(_, cell)| *cell)
+        .into_iter()
+        .collect();
+}
+
+// The body once the head has followed `path`, having eaten at its end and grown by one
+fn after(body: &[(i32, i32)], path: &[(i32, i32)]) -> Vec<(i32, i32)> {
+    path.iter()
+        .rev()
+        .chain(body.iter())
+        .take(body.len() + 1)
+        .copied()
+        .collect()
+}
+
+// A* from the head to the closest of `targets`, not counting the head. Every cell the
+// search reached goes into `searched`. A body cell is free once the tail has left it a move
+// before (segments grown at the tail stack up on one cell, which frees with the last).
+fn shortest_path(
+    game: &Game,
+    body: &[(i32, i32)],
+    targets: &[(i32, i32)],
+    searched: &mut Vec<(i32, i32)>,
+) -> Option<Vec<(i32, i32)>> {
+    let head = body[0];
+    let estimate = |cell: (i32, i32)| {
+        targets
+            .iter()
+            .map(|&target| distance(game, cell, target))
+            .min()
+            .unwrap_or(0)
+    };
+    let mut frees_after: HashMap<(i32, i32), usize> = HashMap::new();
+    for (i, cell) in body.iter().enumerate() {
+        frees_after.entry(*cell).or_insert(body.len() - i);
+    }
+    let mut open = BinaryHeap::from([Reverse((estimate(head), 0usize, head))]);
+    let mut came_from: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+    let mut best = HashMap::from([(head, 0usize)]);
+    while let Some(Reverse((_, moves, cell))) = open.pop() {
+        if targets.contains(&cell) {
+            let mut path = vec![cell];
+            while let Some(&previous) = came_from.get(&path[path.len() - 1]) {
+                if previous == head {
+                    break;
+                }
+                path.push(previous);
+            }
+            path.reverse();
+            searched.extend(best.into_keys());
+            return Some(path);
+        }
+        if best.get(&cell).is_some_and(|known| *known < moves) {
+            continue;
+        }
+        for direction in DIRECTIONS {
+            let Some(next) = game.neighbor(cell, direction) else {
+                continue;
+            };
+            let moves = moves + 1;
+            if frees_after.get(&next).is_some_and(|free| moves <= *free) || blocked(game, next) {
+                continue;
+            }
+            if best.get(&next).is_some_and(|known| *known <= moves) {
+                continue;
+            }
+            best.insert(next, moves);
+            came_from.insert(next, cell);
+            open.push(Reverse((moves as i32 + estimate(next), moves, next)));
+        }
+    }
+    searched.extend(best.into_keys());
+    None
+}
+
+// Whether the head of `body` can find its way to the tail, which keeps moving out of
+// the way, so the snake can always follow itself round
+fn reaches_tail(game: &Game, body: &[(i32, i32)]) -> bool {
+    let tail = body[body.len() - 1];
+    reachable(game, body[0], &body[1..body.len() - 1]).contains(&tail)
+}
+
+// Free cells reachable from the head of `body`
+fn room(game: &Game, body: &[(i32, i32)]) -> usize {
+    reachable(game, body[0], &body[1..]).len() - 1
+}
+
+// Cells reachable from `start` around `occupied`, `start` included
+fn reachable(game: &Game, start: (i32, i32), occupied: &[(i32, i32)]) -> HashSet<(i32, i32)> {
+    let occupied: HashSet<(i32, i32)> = occupied.iter().copied().collect();
+    let mut seen = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(cell) = queue.pop_front() {
+        for direction in DIRECTIONS {
+            let Some(next) = game.neighbor(cell, direction) else {
+                continue;
+            };
+            if occupied.contains(&next) || blocked(game, next) || !seen.insert(next) {
+                continue;
+            }
+            queue.push_back(next);
+        }
+    }
+    seen
+}
@@ -0,0 +1,112 @@
+// Segment animations, so sudden length changes don't look like teleportation. New
+// segments scale up from nothing, and drinking water sends a ripple from the head
+// down the body that grows the five new stomach segments as it reaches them. The head
+// swings round to a new heading rather than snapping to it.
+// Everything is timed in game frames, so it holds still while the game is paused. With
+// reduced motion the view skips all of it and draws the snake as it stands.
+
+use crate::events::{GameEvent, Subscriber};
+use crate::{FoodType, Game};
+
+const GROW_FRAMES: u64 = 15; // How long a new segment takes to reach full size
+const RIPPLE_FRAMES_PER_SEGMENT: u64 = 3;
+const RIPPLE_WIDTH: f64 = 2.0; // Segments either side of the crest that swell
+const RIPPLE_SWELL: f64 = 0.3; // Extra size at the crest
+const HEAD_TURN_RATE: f64 = 0.2; // Share of the remaining turn the head makes per frame
+
+pub struct Animations {
+    growing: Vec<(usize, u64)>, // Body index and the frame it starts growing
+    ripple: Option<u64>,        // Frame the crest left the head
+    drank: Option<u64>,         // Frame water was last drunk, whatever it grows rides a ripple
+    head_angle: f64,            // Radians clockwise from east
+    head_frame: u64,            // Frame the head angle was last eased on
+}
+
+impl Animations {
+    pub fn new() -> Animations {
+        Animations {
+            growing: Vec::new(),
+            ripple: None,
+            drank: None,
+            head_angle: 0.0,
+            head_frame: 0,
+        }
+    }
+
+    // Call once per update, after the update's events
+    pub fn update(&mut self, game: &Game) {
+        let now = game.frame_count;
+        if now != self.head_frame {
+            self.head_frame = now;
+            let target = heading_angle(game);
+            // The short way round
+            let turn = (target - self.head_angle + std::f64::consts::PI)
+                .rem_euclid(std::f64::consts::TAU)
+                - std::f64::consts::PI;
+            self.head_angle += turn * HEAD_TURN_RATE;
+        }
+        self.growing.retain(|&(_, start)| start + GROW_FRAMES > now);
+        if self.ripple.is_some_and(|start| {
+            ripple_front(start, now) > game.snake.body.len() as f64 + RIPPLE_WIDTH
+        }) {
+            self.ripple = None;
+        }
+    }
+
+    pub fn head_angle(&self) -> f64 {
+        self.head_angle
+    }
+
+    // Size multiplier for the segment at `index`: below 1 while growing in, above 1
+    // while the ripple passes
+    pub fn scale(&self, index: usize, now: u64) -> f64 {
+        let mut scale = 1.0;
+        if let Some(&(_, start)) = self.growing.iter().find(|(i, _)| *i == index) {
+            scale = now.saturating_sub(start) as f64 / GROW_FRAMES as f64;
+        }
+        if let Some(start) = self.ripple {
+            let distance = (ripple_front(start, now) - index as f64).abs();
+            scale *= 1.0 + RIPPLE_SWELL * (1.0 - distance / RIPPLE_WIDTH).max(0.0);
+        }
+        scale.min(1.0 + RIPPLE_SWELL)
+    }
+}
+
+impl Subscriber for Animations {
+    fn notify(&mut self, game: &Game, event: &GameEvent) {
+        let now = game.frame_count;
+        match event {
+            GameEvent::FoodEaten {
+                food: FoodType::Water,
+                ..
+            } => self.drank = Some(now),
+            GameEvent::SegmentAdded(index) => {
+                // Inserting a segment moves everything behind it one index down the body
+                for (growing, _) in &mut self.growing {
+                    if *growing >= *index {
+                        *growing += 1;
+                    }
+                }
+                let start = if self.drank == Some(now) {
+                    self.ripple = Some(now);
+                    now + *index as u64 * RIPPLE_FRAMES_PER_SEGMENT
+                } else {
+                    now
+                };
+                self.growing.push((*index, start));
+            }
+            _ => {}
+        }
+    }
+}
+
+// Where the head points when it isn't turning, in radians clockwise from east
+pub fn heading_angle(game: &Game) -> f64 {
+    let headings = game.grid.grid().headings() as f64;
+    game.current_heading() as f64 * std::f64::consts::TAU / headings
+}
+
+// Body index the ripple's crest has reached
+fn ripple_front(start: u64, now: u64) -> f64 {
+    now.saturating_sub(start) as f64 / RIPPLE_FRAMES_PER_SEGMENT as f64
+}
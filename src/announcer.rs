@@ -0,0 +1,124 @@
+// Accessibility option: speak state changes through the platform's text-to-speech
+// engine so blind players can hear what is happening. Needs the `tts` feature; without
+// it announcements are dropped.
+
+use crate::Game;
+
+#[cfg(feature = "tts")]
+mod backend {
+    pub struct Speaker(Option<tts::Tts>);
+
+    impl Speaker {
+        pub fn new() -> Speaker {
+            match tts::Tts::default() {
+                Ok(tts) => Speaker(Some(tts)),
+                Err(e) => {
+                    eprintln!("Text-to-speech unavailable: {}", e);
+                    Speaker(None)
+                }
+            }
+        }
+
+        pub fn speak(&mut self, text: &str) {
+            if let Some(tts) = self.0.as_mut() {
+                // Interrupt whatever is being said, the newest state is what matters
+                if let Err(e) = tts.speak(text, true) {
+                    eprintln!("Error speaking: {}", e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "tts"))]
+mod backend {
+    pub struct Speaker;
+
+    impl Speaker {
+        pub fn new() -> Speaker {
+            Speaker
+        }
+
+        pub fn speak(&mut self, _text: &str) {}
+    }
+}
+
+// The parts of the game state that are worth announcing when they change
+#[derive(Clone, Copy, PartialEq)]
+struct Snapshot {
+    started: bool,
+    over: bool,
+    paused: bool,
+    entering_name: bool,
+    score: u32,
+}
+
+impl Snapshot {
+    fn of(game: &Game) -> Snapshot {
+        Snapshot {
+            started: game.game_started,
+            over: game.game_over,
+            paused: game.paused,
+            entering_name: game.entering_name,
+            score: game.score,
+        }
+    }
+}
+
+pub struct Announcer {
+    speaker: Option<backend::Speaker>, // None when the option is off
+    last: Option<Snapshot>,
+}
+
+impl Announcer {
+    pub fn new(enabled: bool) -> Announcer {
+        Announcer {
+            speaker: if enabled {
+                Some(backend::Speaker::new())
+            } else {
+                None
+            },
+            last: None,
+        }
+    }
+
+    // Compare the game against what was seen last frame and speak any changes
+    pub fn observe(&mut self, game: &Game) {
+        let speaker = match self.speaker.as_mut() {
+            Some(s) => s,
+            None => return,
+        };
+        let now = Snapshot::of(game);
+        if self.last == Some(now) {
+            return;
+        }
+        let last = self.last.replace(now);
+
+        let message = match last {
+            _ if !now.started && !now.over => {
+                "Rusty Snake. Press a direction key to start.".to_string()
+            }
+            Some(last) if now.over && !last.over => {
+                let cause = game.death_cause.map_or("", |c| c.description());
+                let mut message = if game.won {
+                    format!("Victory! Goal reached. Final score {}.", now.score)
+                } else {
+                    format!("Game over: {}. Final score {}.", cause, now.score)
+                };
+                if now.entering_name {
+                    message.push_str(" New high score! Type your name and press Enter.");
+                }
+                message
+            }
+            Some(last) if last.entering_name && !now.entering_name => {
+                "Name saved. Press Enter to restart.".to_string()
+            }
+            Some(last) if now.paused != last.paused => {
+                if now.paused { "Paused" } else { "Resumed" }.to_string()
+            }
+            Some(last) if now.score != last.score => format!("Score {}", now.score),
+            _ => return,
+        };
+        speaker.speak(&message);
+    }
+}
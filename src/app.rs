@@ -0,0 +1,1622 @@
+// Everything the main loop owns besides the window: the current game, the screens
+// around it and the feedback systems. Input from every device arrives here as an
+// `InputEvent` so it can be recorded and played back.
+
+use crate::ai::Ai;
+use crate::animation::Animations;
+use crate::announcer::Announcer;
+use crate::audio::{self, AudioEngine};
+use crate::camera::Camera;
+use crate::cheats::{CheatBuffer, CheatInput};
+use crate::cli::Options;
+use crate::colors::{self, ColorPicker};
+use crate::console::{self, Console};
+use crate::controls::{self, ControlScheme, KeyBindings, PRESETS};
+use crate::database::Database;
+use crate::decorations::Decorations;
+use crate::editor::LevelEditor;
+use crate::events::{self, GameEvent};
+use crate::fireworks::Fireworks;
+use crate::food_cues::FoodCues;
+use crate::food_styles::{BiteEffects, FoodStyles};
+use crate::frontend::UPDATES_PER_SECOND;
+use crate::game::{HighScoreEntry, FOOD_TYPES, SCORE_BACKUPS};
+use crate::gamepad::{Gamepad, PadInput, Rumble};
+use crate::grid::GridKind;
+use crate::hall_of_fame::HallOfFame;
+use crate::headless::Controller;
+use crate::input_log::{Claim, InputEvent, InputRecorder, InputReplay, Verification};
+use crate::keys::{key_to_char, key_to_digit, Key};
+use crate::levels::{self, Layout, LevelFile, Medal, COMMUNITY_DIR, LEVELS_DIR};
+use crate::music::Music;
+use crate::mutators::{Mutators, MUTATORS};
+use crate::names;
+use crate::pins::{PinEntry, PinPurpose, Pins, PIN_LENGTH};
+use crate::profile::Profile;
+use crate::roguelite::{self, GameMode, UPGRADES};
+use crate::rules::InputTiming;
+use crate::score_server::ScoreServer;
+use crate::scorecard;
+use crate::seasons::{self, Palette, Season, DEFAULT_PALETTE};
+use crate::settings::{Adjustable, Settings, ADJUSTABLE};
+use crate::shop::{self, Loadout};
+use crate::skins::Skin;
+use crate::sound_effects::SoundEffects;
+use crate::stats::{Run, Stats, StatsPage};
+use crate::tips::Tips;
+use crate::ui::{step_focus, Nav};
+use crate::update_check::UpdateCheck;
+use crate::versus::{Clock, Versus};
+use crate::zen::Zen;
+use crate::{Direction, FoodType, Game, BLOCK_SIZE};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Reasons the game pauses itself. Any key resumes.
+#[derive(Clone, Copy, PartialEq)]
+pub enum AutoPause {
+    PadLost,   // The controller disconnected mid-run
+    Idle,      // No input for `idle_pause` seconds
+    FocusLost, // The window went to the background
+}
+
+pub struct App {
+    pub game: Game,
+    pub settings: Settings,
+    pub profile: Profile,
+    pub unlocks: Vec<Skin>, // Skins the last run unlocked, shown on the game over screen
+    pub coins_earned: u32,  // By the last run
+    pub medal_earned: Option<Medal>, // By the last run, if better than the level's before
+    pub stars_earned: Option<u32>, // By the last puzzle, if more than it had before
+    pub points_earned: u32, // Roguelite upgrade points, by the last run
+    pub shop: Option<usize>, // Selected item while the shop is open
+    pub upgrades_menu: Option<usize>, // Selected upgrade while the upgrade screen is open
+    pub menu_message: String, // Outcome of the last purchase in the shop or upgrade screen
+    pub bindings: KeyBindings,
+    pub controls_menu: Option<usize>, // Selected preset while the controls screen is open
+    pub color_picker: Option<ColorPicker>, // Open while the snake color is being picked
+    pub settings_menu: Option<usize>, // Selected setting while the settings screen is open
+    pub restore_menu: Option<usize>,  // Selected backup while the restore screen is open
+    pub backups: Vec<Option<Vec<HighScoreEntry>>>, // As they were when it was opened
+    pub hall_of_fame: HallOfFame,
+    pub hall_of_fame_menu: Option<usize>, // Selected season while the hall of fame is open
+    pub gamepad: Gamepad,
+    pub console: Console,
+    pub dev: bool,     // Developer tools are available
+    board: (i32, i32), // Board size for every new game, depends on the window mode
+    pub camera: Camera,
+    pub animations: Animations,
+    pub food_styles: FoodStyles,
+    pub bites: BiteEffects, // Crumbs and popups of recent bites
+    sound_effects: SoundEffects,
+    pub decorations: Decorations,
+    pub stats: Stats,
+    database: Database, // Keeps up with the stats, scores and profile as they're saved
+    pub stats_screen: bool, // Showing where past runs ended
+    pub stats_page: StatsPage,
+    run_foods: [u32; FOOD_TYPES.len()], // Eaten this run, for the stats
+    pub trail: Vec<(i32, i32)>,         // Every cell the head has been on this run, in order
+    pub path_view: bool,                // Showing the trail after the run
+    score_server: Option<ScoreServer>,
+    pub update_check: UpdateCheck,
+    pub pins: Pins,
+    pub pin_entry: Option<PinEntry>, // A PIN being typed on the game-over screen
+    pub clearing: Option<String>,    // Name being typed to clear its high scores
+    pub auto_pause: Option<AutoPause>, // Why the game paused itself, if it did
+    last_input: u64,                 // Update the last input event arrived on
+    pub in_background: bool,         // The window lost focus and hasn't got it back
+    pub bot: Option<Ai>,             // Computer player steering instead of the player
+    skip_text: bool,                 // The key that opened a name prompt also arrives as text
+    pub fireworks: Fireworks,        // Set off when a run reaches its goal
+    pub mutators: Mutators,          // For every run this session, until changed
+    pub mutators_menu: Option<usize>, // Selected mutator while the mutator screen is open
+    pub tips: Tips,                  // For the game over screen
+    pub versus: Option<Versus>,      // Player two's side of a versus match
+    pub zen: Option<Zen>,            // The screensaver, while it runs
+    pub editor: Option<LevelEditor>, // Open while a level is being edited
+    pub photo_mode: bool,            // Paused with the HUD hidden and the camera free
+    pub level_field: Option<LevelField>, // Being typed in the editor
+    pub level: Option<LevelFile>,    // Hand-made level every run is played on, until changed
+    pub levels_menu: Option<usize>,  // Selected entry while the level select is open
+    pub community: Vec<LevelFile>,   // Shared levels, in the order the level select shows
+    pub level_sort: LevelSort,
+    seed: u64,      // The current game's
+    stepping: bool, // Dev: simulation only advances one tick per key press
+    season: Option<&'static Season>,
+    audio: Box<dyn AudioEngine>,
+    food_cues: FoodCues,
+    music: Music,
+    announcer: Announcer,
+    cheat_buffer: CheatBuffer,
+    seeds: StdRng, // Every game's seed comes from here, so one seed reproduces a session
+    updates: u64,  // Update events processed so far, the clock input logs go by
+    recorder: Option<InputRecorder>,
+    replay: Option<InputReplay>,
+    pub verification: Option<Verification>, // How the last replay matched its recording
+}
+
+impl App {
+    pub fn new(settings: Settings, options: &Options) -> App {
+        let replay = options.replay_input.as_ref().map(|path| {
+            InputReplay::load(path).unwrap_or_else(|e| {
+                eprintln!("Error reading input log {}: {}", path.display(), e);
+                std::process::exit(1);
+            })
+        });
+        let seed = match &replay {
+            Some(replay) => replay.seed,
+            None => rand::thread_rng().gen(),
+        };
+        // A replay gets the season it was recorded in, whatever the date is now
+        let season = match &replay {
+            Some(replay) => replay.season.as_deref().and_then(seasons::by_name),
+            None if settings.seasonal => seasons::current(),
+            None => None,
+        };
+        // and the rules and board, whatever the settings are now, if the log says
+        let mut settings = settings;
+        if let Some(rules) = replay.as_ref().and_then(|replay| replay.rules) {
+            settings.set_rules(rules);
+        }
+        let board = replay
+            .as_ref()
+            .and_then(|replay| replay.board)
+            .unwrap_or(settings.window_mode.board_size());
+        // A new month ends the season on every table, before anything reads them.
+        // Playing back someone's session leaves the local tables alone.
+        let mut hall_of_fame = HallOfFame::load();
+        if replay.is_none() {
+            hall_of_fame.close_past_seasons();
+        }
+        let mut database = Database::open();
+        let recorder = options.record_input.as_ref().and_then(|path| {
+            let season = season.map(|season| season.name);
+            let recorder = InputRecorder::create(path, seed, season, settings.rules(), board)
+                .map_err(|e| eprintln!("Error creating input log {}: {}", path.display(), e))
+                .ok()?;
+            database.record_replay(path, seed, season);
+            Some(recorder)
+        });
+
+        let mut seeds = StdRng::seed_from_u64(seed);
+        let game_seed = seeds.gen();
+        let mut game = Game::new(game_seed, board);
+        game.special_food = season.and_then(|season| season.special_food.clone());
+        game.rules = settings.rules();
+        game.set_topology(game.rules.topology);
+        // Playing back someone's session shouldn't touch the local high scores
+        game.save_scores = replay.is_none();
+        if let Some(path) = &options.import_scores {
+            match game.import_high_scores(path) {
+                Ok(report) => println!("Imported high scores: {}", report.summary()),
+                Err(e) => eprintln!("Error importing high scores from {}", e),
+            }
+            database.high_scores_changed();
+        }
+        if let Some(path) = &options.import_level {
+            match levels::import(path) {
+                Ok(destination) => println!("Imported level to {}", destination.display()),
+                Err(e) => eprintln!("Error importing level {}", e),
+            }
+        }
+        let mut community = levels::scan(Path::new(COMMUNITY_DIR));
+        sort_levels(&mut community, LevelSort::Name);
+
+        App {
+            game,
+            bindings: settings.key_bindings(settings.control_preset),
+            profile: Profile::load(),
+            unlocks: Vec::new(),
+            coins_earned: 0,
+            medal_earned: None,
+            stars_earned: None,
+            points_earned: 0,
+            upgrades_menu: None,
+            shop: None,
+            menu_message: String::new(),
+            controls_menu: None,
+            color_picker: None,
+            settings_menu: None,
+            restore_menu: None,
+            backups: Vec::new(),
+            hall_of_fame,
+            hall_of_fame_menu: None,
+            gamepad: Gamepad::new(&settings),
+            console: Console::new(),
+            dev: cfg!(debug_assertions) || options.dev,
+            board,
+            camera: Camera::new(board),
+            animations: Animations::new(),
+            food_styles: FoodStyles::load(settings.theme),
+            bites: BiteEffects::new(game_seed),
+            sound_effects: SoundEffects::new(),
+            decorations: Decorations::scatter(settings.theme, game_seed, board),
+            fireworks: Fireworks::new(game_seed),
+            stats: Stats::load(),
+            database,
+            stats_screen: false,
+            stats_page: StatsPage::Deaths,
+            run_foods: [0; FOOD_TYPES.len()],
+            trail: Vec::new(),
+            path_view: false,
+            score_server: options.score_server.and_then(ScoreServer::start),
+            update_check: UpdateCheck::start(settings.check_updates),
+            pins: Pins::load(),
+            pin_entry: None,
+            clearing: None,
+            auto_pause: None,
+            last_input: 0,
+            in_background: false,
+            bot: options.bot.map(|difficulty| Ai::new(difficulty, game_seed)),
+            skip_text: false,
+            mutators: Mutators::default(),
+            mutators_menu: None,
+            tips: Tips::load(),
+            versus: None,
+            zen: None,
+            editor: None,
+            photo_mode: false,
+            level_field: None,
+            level: None,
+            levels_menu: None,
+            community,
+            level_sort: LevelSort::Name,
+            seed: game_seed,
+            stepping: false,
+            season,
+            audio: audio::create_engine(&settings),
+            food_cues: FoodCues::new(),
+            music: Music::new(),
+            announcer: Announcer::new(settings.screen_reader),
+            cheat_buffer: CheatBuffer::new(),
+            settings,
+            seeds,
+            updates: 0,
+            recorder,
+            replay,
+            verification: None,
+        }
+    }
+
+    // What it takes to play the current run again, shown on the game over screen and
+    // saved with score cards and input logs
+    pub fn run_stamp(&self) -> String {
+        let mutators = if self.game.mutators.any() {
+            self.game.mutators.ids()
+        } else {
+            "none".to_string()
+        };
+        format!(
+            "seed={} mode={} mutators={} board={}x{}",
+            self.seed,
+            self.profile.mode.id(),
+            mutators,
+            self.board.0,
+            self.board.1
+        )
+    }
+
+    // A replayed run about to start is set up the way its log says it was, whatever the
+    // local profile and levels have made of the waiting game
+    fn replay_start(&mut self) {
+        let Some(stamp) = self.replay.as_mut().and_then(|replay| replay.next_start()) else {
+            return;
+        };
+        self.profile.mode = stamp.mode;
+        let mutators = std::mem::replace(&mut self.mutators, stamp.mutators);
+        if stamp.seed != self.seed || stamp.board != self.board {
+            self.board = stamp.board;
+            self.restart_with_seed(stamp.seed);
+        } else if stamp.mutators != mutators {
+            self.apply_mutators();
+        }
+    }
+
+    // Live input is ignored while a recording is being played back
+    pub fn replaying(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    pub fn input(&mut self, event: InputEvent) {
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.record(self.updates, &event);
+        }
+        self.last_input = self.updates;
+        if self.zen.is_some()
+            && matches!(
+                event,
+                InputEvent::Key(_) | InputEvent::Steer(_) | InputEvent::Tap
+            )
+        {
+            // Any input ends the screensaver, and does nothing else
+            self.zen = None;
+            return;
+        }
+        match event {
+            InputEvent::Key(key) => self.handle_key(key),
+            InputEvent::Steer(direction) => self.steer(direction),
+            InputEvent::Tap => self.tap(),
+            InputEvent::Command(command) => self.run_command(&command),
+            InputEvent::Loadout(loadout) => self.apply_loadout(loadout),
+            InputEvent::Upgrades(upgrades) => upgrades.apply(&mut self.game),
+            InputEvent::Grid(grid) => self.game.set_grid(grid),
+            InputEvent::Kids => self.game.set_kids(),
+            InputEvent::Versus => self.start_versus(),
+            InputEvent::Duel => {
+                self.start_versus();
+                if let Some(versus) = self.versus.as_mut() {
+                    versus.clock = Some(Clock::new());
+                }
+            }
+            InputEvent::Adaptive(adaptive) => {
+                self.game.ranked = false;
+                self.game.adaptive = Some(adaptive);
+            }
+            InputEvent::Focus(item) => self.focus_menu(item),
+            InputEvent::Pad(connected) => self.pad_connection(connected),
+            InputEvent::Idle => self.pause_itself(AutoPause::Idle),
+            InputEvent::Window(focused) => {
+                // Only recorded going to the background when that should pause
+                self.in_background = !focused;
+                if !focused {
+                    self.pause_itself(AutoPause::FocusLost);
+                }
+            }
+        }
+    }
+
+    fn pause_itself(&mut self, reason: AutoPause) {
+        if self.game.game_started && !self.game.game_over && !self.game.paused {
+            self.game.toggle_pause();
+            self.auto_pause = Some(reason);
+        }
+    }
+
+    // Nobody at the keys (answering the door, say) pauses the run before the snake
+    // wanders into a wall. Not while the bot plays, it never touches them.
+    fn check_idle(&mut self) {
+        let Some(seconds) = self.settings.idle_pause else {
+            return;
+        };
+        let running = self.game.game_started && !self.game.game_over && !self.game.paused;
+        if running
+            && self.bot.is_none()
+            && self.updates - self.last_input >= seconds * UPDATES_PER_SECOND
+        {
+            self.input(InputEvent::Idle);
+        }
+    }
+
+    // A controller dropping out mid-run (flat battery, pulled cable) pauses the game
+    // rather than letting the snake run into a wall. Plugging it back in resumes.
+    fn pad_connection(&mut self, connected: bool) {
+        if !connected {
+            self.pause_itself(AutoPause::PadLost);
+        } else if self.auto_pause == Some(AutoPause::PadLost) {
+            self.resume_auto_pause();
+        }
+    }
+
+    fn resume_auto_pause(&mut self) {
+        self.auto_pause = None;
+        if self.game.paused {
+            self.game.toggle_pause();
+        }
+    }
+
+    // Whether one of the list screens is open, which the d-pad moves around instead of
+    // steering
+    fn menu_open(&self) -> bool {
+        self.settings_menu.is_some()
+            || self.restore_menu.is_some()
+            || self.hall_of_fame_menu.is_some()
+            || self.color_picker.is_some()
+            || self.mutators_menu.is_some()
+            || self.shop.is_some()
+            || self.upgrades_menu.is_some()
+            || self.controls_menu.is_some()
+            || self.editor.is_some()
+            || self.levels_menu.is_some()
+            || self.photo_mode
+    }
+
+    // Move the open menu's focus to an item, checked in the order the screens are drawn
+    fn focus_menu(&mut self, item: usize) {
+        if self.settings_menu.is_some() {
+            self.settings_menu = Some(item);
+        } else if self.restore_menu.is_some() {
+            self.restore_menu = Some(item);
+        } else if self.hall_of_fame_menu.is_some() {
+            self.hall_of_fame_menu = Some(item);
+        } else if let Some(picker) = self.color_picker.as_mut() {
+            picker.row = item;
+        } else if self.mutators_menu.is_some() {
+            self.mutators_menu = Some(item);
+        } else if self.shop.is_some() {
+            self.shop = Some(item);
+        } else if self.upgrades_menu.is_some() {
+            self.upgrades_menu = Some(item);
+        } else if self.controls_menu.is_some() {
+            self.controls_menu = Some(item);
+        } else if self.levels_menu.is_some() {
+            self.levels_menu = Some(item);
+        }
+    }
+
+    // Typed text, for the console and the name prompts. Commands are recorded once
+    // entered, not character by character.
+    pub fn type_text(&mut self, text: &str) {
+        if std::mem::take(&mut self.skip_text) {
+            return;
+        }
+        if self.console.open {
+            self.console
+                .input
+                .extend(text.chars().filter(|c| *c != '`'));
+        } else if let Some(name) = self.clearing.as_mut() {
+            names::type_into(name, text);
+        } else if let (Some(field), Some(editor)) = (self.level_field, self.editor.as_mut()) {
+            match field {
+                LevelField::Name => names::type_into(&mut editor.name, text),
+                LevelField::Author => names::type_into(&mut editor.author, text),
+            }
+        } else if self.game.game_over && self.game.entering_name && self.pin_entry.is_none() {
+            names::type_into(&mut self.game.player_name, text);
+        }
+    }
+
+    fn run_command(&mut self, command: &str) {
+        self.console.print(format!("> {}", command));
+        let output = match console::run(self, command) {
+            Ok(output) => output,
+            Err(e) => format!("error: {}", e),
+        };
+        if !output.is_empty() {
+            self.console.print(output);
+        }
+    }
+
+    fn handle_key(&mut self, key: Key) {
+        self.skip_text = false;
+        // Any key takes over from the lost controller or wakes the game up, and only
+        // resumes
+        if self.auto_pause.is_some() {
+            self.resume_auto_pause();
+            return;
+        }
+        if self.dev && key == Key::Backquote {
+            self.console.open = !self.console.open;
+            return;
+        }
+        if self.console.open {
+            match key {
+                Key::Return => {
+                    let command = std::mem::take(&mut self.console.input);
+                    if !command.trim().is_empty() {
+                        self.input(InputEvent::Command(command));
+                    }
+                }
+                Key::Backspace => {
+                    self.console.input.pop();
+                }
+                _ => {}
+            }
+            return;
+        }
+        if self.dev && self.dev_key(key) {
+            return;
+        }
+
+        if self.path_view {
+            if key == Key::V || key == Key::Backspace {
+                self.path_view = false;
+            }
+        } else if self.stats_screen {
+            match (key, Nav::from_key(key, &self.bindings)) {
+                (Key::H, _) | (_, Some(Nav::Back)) => self.stats_screen = false,
+                (_, Some(Nav::Left)) => self.stats_page = self.stats_page.step(-1),
+                (_, Some(Nav::Right)) => self.stats_page = self.stats_page.step(1),
+                _ => {}
+            }
+        } else if self.photo_mode {
+            self.photo_key(key);
+        } else if self.editor.is_some() {
+            self.editor_key(key);
+        } else if let Some(selected) = self.levels_menu {
+            self.levels_key(key, selected);
+        } else if let Some(selected) = self.mutators_menu {
+            self.mutators_key(key, selected);
+        } else if let Some(selected) = self.shop {
+            self.shop_key(key, selected);
+        } else if let Some(selected) = self.upgrades_menu {
+            self.upgrades_key(key, selected);
+        } else if self.color_picker.is_some() {
+            self.color_key(key);
+        } else if let Some(selected) = self.settings_menu {
+            self.settings_key(key, selected);
+        } else if let Some(selected) = self.restore_menu {
+            self.restore_key(key, selected);
+        } else if let Some(selected) = self.hall_of_fame_menu {
+            self.hall_of_fame_key(key, selected);
+        } else if let Some(selected) = self.controls_menu {
+            // Controls screen: pick a preset with up/down, confirm with Enter, C or
+            // Backspace to leave as it was
+            match (key, Nav::from_key(key, &self.bindings)) {
+                (_, Some(nav @ (Nav::Up | Nav::Down))) => {
+                    self.controls_menu = Some(step_focus(selected, PRESETS.len(), nav));
+                }
+                (_, Some(Nav::Confirm)) => {
+                    self.bindings = self.settings.key_bindings(PRESETS[selected]);
+                    self.controls_menu = None;
+                }
+                (Key::C, _) | (_, Some(Nav::Back)) => self.controls_menu = None,
+                _ => {}
+            }
+        } else if self.game.game_over && !self.versus_running() {
+            if self.pin_entry.is_some() {
+                self.pin_key(key);
+            } else if self.clearing.is_some() {
+                self.clear_key(key);
+            } else if self.game.entering_name {
+                self.name_key(key);
+            } else if key == Key::Return {
+                self.restart();
+            } else if key == Key::Backspace && self.game.puzzle.is_some() {
+                self.game.undo();
+            } else if key == Key::V {
+                self.path_view = true;
+            } else if key == Key::E {
+                self.menu_message = match scorecard::export(self) {
+                    Ok(path) => format!("Score card saved to {}", path),
+                    Err(e) => format!("Couldn't save the score card: {}", e),
+                };
+            } else if key == Key::D {
+                self.clearing = Some(String::new());
+                self.skip_text = true;
+                self.menu_message.clear();
+            } else if key == Key::R {
+                self.backups = self.game.score_backups();
+                self.restore_menu = Some(0);
+                self.menu_message.clear();
+            }
+        } else if !self.game.game_started && self.cheat_key(key) {
+            // Part of a cheat code
+        } else if !self.game.game_started && key == Key::C {
+            self.controls_menu = PRESETS.iter().position(|p| *p == self.bindings.preset);
+        } else if !self.game.game_started && key == Key::G {
+            self.settings_menu = Some(0);
+        } else if !self.game.game_started && key == Key::O {
+            self.color_picker = Some(ColorPicker::new(self.profile.snake_color));
+        } else if !self.game.game_started && key == Key::X {
+            self.mutators_menu = Some(0);
+        } else if !self.game.game_started && key == Key::H {
+            self.stats_screen = true;
+        } else if !self.game.game_started && key == Key::K {
+            self.hall_of_fame_menu = Some(0);
+        } else if !self.game.game_started && key == Key::Z {
+            self.zen = Some(Zen::new(self.seed, self.board));
+        } else if !self.game.game_started && key == Key::L {
+            self.levels_menu = Some(0);
+            self.menu_message.clear();
+        } else if !self.game.game_started && key == Key::B {
+            self.shop = Some(0);
+            self.menu_message.clear();
+        } else if !self.game.game_started && key == Key::M {
+            self.profile.mode = match self.profile.mode {
+                GameMode::Classic => GameMode::Roguelite,
+                GameMode::Roguelite => GameMode::Diagonal,
+                GameMode::Diagonal => GameMode::Hex,
+                GameMode::Hex => GameMode::Kids,
+                GameMode::Kids => GameMode::Adaptive,
+                GameMode::Adaptive => GameMode::Versus,
+                GameMode::Versus => GameMode::Duel,
+                GameMode::Duel => GameMode::Classic,
+            };
+            self.save_profile();
+        } else if !self.game.game_started
+            && key == Key::U
+            && self.profile.mode == GameMode::Roguelite
+        {
+            self.upgrades_menu = Some(0);
+            self.menu_message.clear();
+        } else if !self.game.game_started && key == Key::Tab {
+            self.profile.cycle_skin();
+            self.save_profile();
+        } else if self.game.game_started && self.game.puzzle.is_some() && key == Key::Backspace {
+            self.game.undo();
+        } else if self.game.paused && !self.game.game_over && key == Key::C {
+            self.photo_mode = true;
+            self.camera.set_detached(true);
+            self.menu_message.clear();
+        } else if self.bindings.is_pause(key) {
+            self.game.toggle_pause();
+        } else if self.bindings.is_spit(key) {
+            self.game.spit();
+        } else if let Some((player, direction)) =
+            self.versus.as_ref().and_then(|versus| versus.steering(key))
+        {
+            match (player, self.versus.as_mut()) {
+                (1, Some(versus)) => {
+                    versus.rival.steer(direction);
+                }
+                _ => self.steer(direction),
+            }
+            if let Some(versus) = self.versus.as_mut() {
+                versus.hurry(&mut self.game, player);
+            }
+        } else if let Some(direction) = self.bindings.direction(key) {
+            self.steer(direction);
+        } else if let Some([vertical, horizontal]) = corner_key(key) {
+            // On the diagonal board the numpad corners steer both halves of a chord
+            if self.game.grid == GridKind::Diagonal {
+                self.steer(vertical);
+                self.steer(horizontal);
+            }
+        }
+    }
+
+    // High score name entry. Return saves, asking for the PIN first if the name is
+    // protected; Tab protects a new name with a PIN before saving.
+    fn name_key(&mut self, key: Key) {
+        let name = self.game.player_name.clone();
+        match key {
+            Key::Return | Key::Tab if name.is_empty() => {}
+            Key::Return if self.pins.is_protected(&name) => {
+                self.pin_entry = Some(PinEntry::new(PinPurpose::Save, &name));
+            }
+            Key::Return => self.save_high_score(),
+            Key::Tab if !self.pins.is_protected(&name) => {
+                self.pin_entry = Some(PinEntry::new(PinPurpose::Claim, &name));
+            }
+            Key::Backspace => names::backspace(&mut self.game.player_name),
+            // Letters arrive through `type_text`
+            _ => {}
+        }
+    }
+
+    fn save_high_score(&mut self) {
+        self.game.add_high_score();
+        self.game.entering_name = false;
+        self.database.high_scores_changed();
+    }
+
+    // Naming whose scores to clear, Backspace on an empty name backs out
+    fn clear_key(&mut self, key: Key) {
+        let Some(name) = self.clearing.as_mut() else {
+            return;
+        };
+        match key {
+            Key::Backspace if name.is_empty() => self.clearing = None,
+            Key::Backspace => names::backspace(name),
+            Key::Return if !name.is_empty() => {
+                let name = std::mem::take(name);
+                self.clearing = None;
+                if self.pins.is_protected(&name) {
+                    self.pin_entry = Some(PinEntry::new(PinPurpose::Clear, &name));
+                } else {
+                    self.clear_scores(&name);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn clear_scores(&mut self, name: &str) {
+        let removed = self.game.remove_high_scores(name);
+        self.database.high_scores_changed();
+        self.menu_message = format!("Cleared {} high scores of {}", removed, name);
+    }
+
+    // Typing a PIN: digits, Backspace to correct or back out, Return once it's complete
+    fn pin_key(&mut self, key: Key) {
+        let Some(entry) = self.pin_entry.as_mut() else {
+            return;
+        };
+        match key {
+            Key::Backspace if entry.digits.is_empty() => self.pin_entry = None,
+            Key::Backspace => {
+                entry.digits.pop();
+            }
+            Key::Return if entry.digits.len() == PIN_LENGTH => {
+                if let Some(entry) = self.pin_entry.take() {
+                    self.use_pin(entry);
+                }
+            }
+            _ => {
+                if let Some(digit) = key_to_digit(key) {
+                    if entry.digits.len() < PIN_LENGTH {
+                        entry.digits.push(digit);
+                    }
+                }
+            }
+        }
+    }
+
+    fn use_pin(&mut self, entry: PinEntry) {
+        let PinEntry {
+            purpose,
+            name,
+            digits,
+        } = entry;
+        match purpose {
+            PinPurpose::Claim => {
+                // A replay plays the keys back, but mustn't change anyone's PIN
+                if self.game.save_scores {
+                    self.pins.set(&name, &digits);
+                }
+                self.save_high_score();
+            }
+            _ if !self.pins.check(&name, &digits) => {
+                self.menu_message = format!("Wrong PIN for {}", name);
+            }
+            PinPurpose::Save => self.save_high_score(),
+            PinPurpose::Clear => self.clear_scores(&name),
+        }
+    }
+
+    // Shop screen: pick an item with up/down, buy it with Enter, leave with B or Backspace
+    fn shop_key(&mut self, key: Key, selected: usize) {
+        let items = shop::items(&self.profile);
+        match (key, Nav::from_key(key, &self.bindings)) {
+            (_, Some(nav @ (Nav::Up | Nav::Down))) => {
+                self.shop = Some(step_focus(selected, items.len(), nav));
+            }
+            (_, Some(Nav::Confirm)) => {
+                let item = items[selected];
+                self.menu_message = match shop::buy(&mut self.profile, item) {
+                    Ok(()) => {
+                        self.save_profile();
+                        format!("Bought {}", item.name())
+                    }
+                    Err(e) => e.to_string(),
+                };
+                // A bought skin leaves the shelves
+                let remaining = shop::items(&self.profile).len();
+                self.shop = Some(selected.min(remaining - 1));
+            }
+            (Key::B, _) | (_, Some(Nav::Back)) => self.shop = None,
+            _ => {}
+        }
+    }
+
+    // Color picker: up/down picks a row, left/right changes it, Enter keeps the color
+    // and O or Backspace leaves without it
+    fn color_key(&mut self, key: Key) {
+        let Some(picker) = self.color_picker.as_mut() else {
+            return;
+        };
+        match (key, Nav::from_key(key, &self.bindings)) {
+            (_, Some(nav @ (Nav::Up | Nav::Down))) => {
+                picker.row = step_focus(picker.row, colors::ROWS.len(), nav);
+            }
+            (_, Some(Nav::Left)) => picker.adjust(-1),
+            (_, Some(Nav::Right)) => picker.adjust(1),
+            (_, Some(Nav::Confirm)) => {
+                self.profile.snake_color = picker.color;
+                self.color_picker = None;
+                self.save_profile();
+            }
+            (Key::O, _) | (_, Some(Nav::Back)) => self.color_picker = None,
+            _ => {}
+        }
+    }
+
+    // Settings screen: up/down to pick, left/right to change, G or Backspace to leave.
+    // Every change takes effect and is saved right away.
+    fn settings_key(&mut self, key: Key, selected: usize) {
+        let step = match (key, Nav::from_key(key, &self.bindings)) {
+            (_, Some(nav @ (Nav::Up | Nav::Down))) => {
+                self.settings_menu = Some(step_focus(selected, ADJUSTABLE.len(), nav));
+                return;
+            }
+            (Key::G, _) | (_, Some(Nav::Back)) => {
+                self.settings_menu = None;
+                return;
+            }
+            (_, Some(Nav::Left)) => -1,
+            (_, Some(Nav::Right | Nav::Confirm)) => 1,
+            _ => return,
+        };
+        let setting = ADJUSTABLE[selected];
+        self.settings.adjust(setting, step);
+        match setting {
+            Adjustable::Volume | Adjustable::Mute => {
+                self.audio.set_volume(self.settings.output_volume());
+            }
+            Adjustable::Theme => {
+                let board = (self.game.width, self.game.height);
+                self.decorations = Decorations::scatter(self.settings.theme, self.seed, board);
+                self.food_styles = FoodStyles::load(self.settings.theme);
+            }
+            // The waiting run is played with it too
+            Adjustable::SlowStart => self.game.rules = self.settings.rules(),
+            // The frontend picks up the new text size, the window mode waits for a relaunch
+            Adjustable::Music
+            | Adjustable::UiScale
+            | Adjustable::ReducedMotion
+            | Adjustable::WindowMode => {}
+        }
+        if !self.replaying() {
+            self.settings.save();
+        }
+    }
+
+    // Restore screen: up/down to pick a backup, Enter puts it back, R or Backspace to
+    // leave
+    fn restore_key(&mut self, key: Key, selected: usize) {
+        match (key, Nav::from_key(key, &self.bindings)) {
+            (_, Some(nav @ (Nav::Up | Nav::Down))) => {
+                self.restore_menu = Some(step_focus(selected, SCORE_BACKUPS, nav));
+            }
+            (_, Some(Nav::Confirm)) if self.backups[selected].is_some() => {
+                let generation = selected + 1;
+                self.menu_message = match self.game.restore_high_scores(generation) {
+                    Ok(count) => format!("Restored backup {} ({} scores)", generation, count),
+                    Err(e) => format!("Couldn't restore: {}", e),
+                };
+                self.database.high_scores_changed();
+                self.restore_menu = None;
+            }
+            (Key::R, _) | (_, Some(Nav::Back)) => self.restore_menu = None,
+            _ => {}
+        }
+    }
+
+    // Hall of fame: up/down to browse past seasons, K or Backspace to leave
+    fn hall_of_fame_key(&mut self, key: Key, selected: usize) {
+        match (key, Nav::from_key(key, &self.bindings)) {
+            (_, Some(nav @ (Nav::Up | Nav::Down))) => {
+                let count = self.hall_of_fame.seasons.len();
+                self.hall_of_fame_menu = Some(step_focus(selected, count, nav));
+            }
+            (Key::K, _) | (_, Some(Nav::Back)) => self.hall_of_fame_menu = None,
+            _ => {}
+        }
+    }
+
+    // Mutator screen: up/down to pick, Enter toggles, X or Backspace to leave
+    fn mutators_key(&mut self, key: Key, selected: usize) {
+        match (key, Nav::from_key(key, &self.bindings)) {
+            (_, Some(nav @ (Nav::Up | Nav::Down))) => {
+                self.mutators_menu = Some(step_focus(selected, MUTATORS.len(), nav));
+            }
+            (_, Some(Nav::Confirm)) => {
+                self.mutators.toggle(MUTATORS[selected]);
+                self.apply_mutators();
+            }
+            (Key::X, _) | (_, Some(Nav::Back)) => self.mutators_menu = None,
+            _ => {}
+        }
+    }
+
+    // Set the waiting game up for the current mutators; the board size may change
+    fn apply_mutators(&mut self) {
+        self.game.set_mutators(self.mutators, self.board);
+        if let Some(level) = &self.level {
+            self.game.set_layout(level.layout.clone());
+            // Hand-made levels don't compete with the plain board's high scores
+            self.game.ranked = false;
+            if let Some(puzzle) = &level.puzzle {
+                self.game.set_puzzle(puzzle.clone());
+            }
+        }
+        let topology = self.level.as_ref().and_then(|level| level.topology);
+        self.game
+            .set_topology(topology.unwrap_or(self.game.rules.topology));
+        let board = (self.game.width, self.game.height);
+        self.decorations = Decorations::scatter(self.settings.theme, self.seed, board);
+        self.frame_room();
+    }
+
+    // Keep the camera on the room the snake is in, or on the whole board without rooms
+    fn frame_room(&mut self) {
+        let height = self.game.height as f64 * BLOCK_SIZE;
+        let focus = self.game.level.as_ref().map(|level| {
+            let (left, right) = level.view_columns();
+            let width = (right - left) as f64 * BLOCK_SIZE;
+            [left as f64 * BLOCK_SIZE, 0.0, width, height]
+        });
+        self.camera.set_focus(focus);
+    }
+
+    // Photo mode: arrows pan the camera, +/- zoom, Enter saves a picture, C, Backspace
+    // or the pause key go back to the pause screen
+    fn photo_key(&mut self, key: Key) {
+        let nav = Nav::from_key(key, &self.bindings);
+        if key == Key::C || nav == Some(Nav::Back) || self.bindings.is_pause(key) {
+            self.photo_mode = false;
+            self.camera.set_detached(false);
+            return;
+        }
+        match (key, nav) {
+            (_, Some(Nav::Up)) => self.camera.pan(0.0, -1.0),
+            (_, Some(Nav::Down)) => self.camera.pan(0.0, 1.0),
+            (_, Some(Nav::Left)) => self.camera.pan(-1.0, 0.0),
+            (_, Some(Nav::Right)) => self.camera.pan(1.0, 0.0),
+            (Key::Equals, _) | (Key::NumPadPlus, _) => self.camera.zoom_by(1.25),
+            (Key::Minus, _) | (Key::NumPadMinus, _) => self.camera.zoom_by(0.8),
+            // A replay goes through photo mode again, but mustn't write any files
+            (_, Some(Nav::Confirm)) if self.game.save_scores => {
+                self.menu_message = match scorecard::export_photo(self) {
+                    Ok(path) => format!("Photo saved to {}", path),
+                    Err(e) => format!("Couldn't save the photo: {}", e),
+                };
+            }
+            _ => {}
+        }
+    }
+
+    // Level select: up/down to pick, Enter to play (or to edit your own level), Tab to
+    // sort the shared levels by name or author, L or Backspace to leave
+    fn levels_key(&mut self, key: Key, selected: usize) {
+        let count = LEVEL_CHOICES + self.community.len();
+        match (key, Nav::from_key(key, &self.bindings)) {
+            (_, Some(nav @ (Nav::Up | Nav::Down))) => {
+                self.levels_menu = Some(step_focus(selected, count, nav));
+            }
+            (_, Some(Nav::Confirm)) => {
+                self.levels_menu = None;
+                match selected {
+                    0 => self.play_level(None),
+                    1 => self.open_editor(),
+                    _ => {
+                        let level = self.community[selected - LEVEL_CHOICES].clone();
+                        // Picking a level switches its recommended mutators on
+                        self.mutators = level.mutators;
+                        self.play_level(Some(level));
+                    }
+                }
+            }
+            (Key::Tab, _) => {
+                self.level_sort = self.level_sort.next();
+                sort_levels(&mut self.community, self.level_sort);
+            }
+            (Key::L, _) | (_, Some(Nav::Back)) => self.levels_menu = None,
+            _ => {}
+        }
+    }
+
+    // Every run from now on is played on `level`, or on the plain board
+    fn play_level(&mut self, level: Option<LevelFile>) {
+        self.level = level;
+        self.restart_with_seed(self.seed);
+    }
+
+    // The level last saved from the editor, or an empty board the size of the window's
+    fn open_editor(&mut self) {
+        let path = edited_level();
+        let level = if path.exists() {
+            LevelFile::load(&path).unwrap_or_else(|e| {
+                eprintln!("Error loading level {}", e);
+                LevelFile::new(Layout::new(self.board))
+            })
+        } else {
+            LevelFile::new(Layout::new(self.board))
+        };
+        self.editor = Some(LevelEditor::open(level));
+        self.menu_message.clear();
+    }
+
+    // Level editor: arrows move the cursor, Space puts the pen down or lifts it, Tab
+    // picks the brush, Z and Y undo and redo, M marks a corner, C copies, V pastes.
+    // N and B name and sign the level, X exports it to share. Enter saves and plays the
+    // level, Backspace saves and goes back to the level select.
+    fn editor_key(&mut self, key: Key) {
+        if let Some(field) = self.level_field {
+            self.level_field_key(key, field);
+            return;
+        }
+        let direction = self.bindings.direction(key);
+        let Some(editor) = self.editor.as_mut() else {
+            return;
+        };
+        match (key, direction) {
+            (Key::N, _) => {
+                self.level_field = Some(LevelField::Name);
+                self.skip_text = true;
+            }
+            (Key::B, _) => {
+                self.level_field = Some(LevelField::Author);
+                self.skip_text = true;
+            }
+            (Key::X, _) => self.export_level(),
+            (Key::Space, _) => editor.toggle_pen(),
+            (Key::Tab, _) => editor.cycle_brush(),
+            (Key::Z, _) => {
+                editor.undo();
+            }
+            (Key::Y, _) => {
+                editor.redo();
+            }
+            (Key::M, _) => editor.toggle_mark(),
+            (Key::C, _) => editor.copy(),
+            (Key::V, _) => {
+                editor.paste();
+            }
+            (Key::Return, _) => self.close_editor(true),
+            (Key::Backspace, _) => {
+                self.close_editor(false);
+                self.levels_menu = Some(1);
+            }
+            (Key::Up, _) | (_, Some(Direction::Up)) => editor.move_cursor((0, -1)),
+            (Key::Down, _) | (_, Some(Direction::Down)) => editor.move_cursor((0, 1)),
+            (Key::Left, _) | (_, Some(Direction::Left)) => editor.move_cursor((-1, 0)),
+            (Key::Right, _) | (_, Some(Direction::Right)) => editor.move_cursor((1, 0)),
+            _ => {}
+        }
+    }
+
+    // Typing the level's name or author: Enter or Tab is done, Backspace corrects
+    fn level_field_key(&mut self, key: Key, field: LevelField) {
+        let Some(editor) = self.editor.as_mut() else {
+            return;
+        };
+        let text = match field {
+            LevelField::Name => &mut editor.name,
+            LevelField::Author => &mut editor.author,
+        };
+        match key {
+            Key::Return | Key::Tab => self.level_field = None,
+            Key::Backspace => names::backspace(text),
+            // Letters arrive through `type_text`
+            _ => {}
+        }
+    }
+
+    // Save a copy of the level to hand out, named after it, next to the score cards.
+    // It's recommended with the mutators on now.
+    fn export_level(&mut self) {
+        let Some(editor) = &self.editor else {
+            return;
+        };
+        if editor.name.is_empty() {
+            self.menu_message = "Name the level first (N)".to_string();
+            return;
+        }
+        let level = editor.level(self.mutators);
+        let path = level.file_name();
+        // A replay goes through the editor again, but mustn't write any files
+        if !self.game.save_scores {
+            return;
+        }
+        self.menu_message = match level.save(Path::new(&path)) {
+            Ok(()) => format!("Exported to {}", path),
+            Err(e) => format!("Couldn't export the level: {}", e),
+        };
+    }
+
+    fn close_editor(&mut self, play: bool) {
+        let Some(editor) = self.editor.take() else {
+            return;
+        };
+        let level = editor.level(self.mutators);
+        if self.game.save_scores {
+            if let Err(e) = level.save(&edited_level()) {
+                eprintln!("Error saving level {}", e);
+            }
+        }
+        if play {
+            self.play_level(Some(level));
+        }
+    }
+
+    // Upgrade screen, works like the shop: up/down, Enter to buy, U or Backspace to leave
+    fn upgrades_key(&mut self, key: Key, selected: usize) {
+        match (key, Nav::from_key(key, &self.bindings)) {
+            (_, Some(nav @ (Nav::Up | Nav::Down))) => {
+                self.upgrades_menu = Some(step_focus(selected, UPGRADES.len(), nav));
+            }
+            (_, Some(Nav::Confirm)) => {
+                let upgrade = UPGRADES[selected];
+                self.menu_message = match roguelite::buy(&mut self.profile, upgrade) {
+                    Ok(()) => {
+                        self.save_profile();
+                        format!(
+                            "{} is now level {}",
+                            upgrade.name(),
+                            self.profile.upgrades.level(upgrade)
+                        )
+                    }
+                    Err(e) => e.to_string(),
+                };
+            }
+            (Key::U, _) | (_, Some(Nav::Back)) => self.upgrades_menu = None,
+            _ => {}
+        }
+    }
+
+    // Replays must not change the player's profile
+    fn save_profile(&mut self) {
+        if !self.replaying() {
+            self.profile.save();
+            self.database.profile_saved(&self.profile);
+        }
+    }
+
+    // Hand the consumables bought in the shop to the run that's starting. Goes through
+    // the input log, so a replay gets the same loadout whatever the profile holds now.
+    fn arm_run(&mut self) {
+        if self.replaying() {
+            return;
+        }
+        let loadout = std::mem::take(&mut self.profile.loadout);
+        if loadout.any() {
+            self.save_profile();
+            self.input(InputEvent::Loadout(loadout));
+        }
+        match self.profile.mode {
+            GameMode::Classic => {}
+            GameMode::Roguelite => self.input(InputEvent::Upgrades(self.profile.upgrades)),
+            GameMode::Diagonal => self.input(InputEvent::Grid(GridKind::Diagonal)),
+            GameMode::Hex => self.input(InputEvent::Grid(GridKind::Hex)),
+            GameMode::Kids => self.input(InputEvent::Kids),
+            GameMode::Adaptive => self.input(InputEvent::Adaptive(self.profile.adaptive.clone())),
+            GameMode::Versus => self.input(InputEvent::Versus),
+            GameMode::Duel => self.input(InputEvent::Duel),
+        }
+    }
+
+    // Player two joins the run that just started. Matches don't make the high scores.
+    fn start_versus(&mut self) {
+        self.game.ranked = false;
+        self.versus = Some(Versus::new(&self.game, self.seed, self.board));
+    }
+
+    // A versus match with either snake still going
+    pub fn versus_running(&self) -> bool {
+        self.versus
+            .as_ref()
+            .is_some_and(|versus| versus.running(&self.game))
+    }
+
+    fn apply_loadout(&mut self, loadout: Loadout) {
+        if loadout.shield {
+            self.game.shield_moves = shop::START_SHIELD_MOVES;
+        }
+        if loadout.extra_life {
+            self.game.extra_lives += 1;
+        }
+    }
+
+    // Developer toggles:
+    //   F1      god mode (counts as a cheat, so the run can't make the high scores)
+    //   F2      step mode, F3 advances one tick
+    //   F4      detach the camera, then I/J/K/L pan and +/- zoom
+    fn dev_key(&mut self, key: Key) -> bool {
+        match key {
+            Key::F1 => {
+                self.game.cheats.invincible = !self.game.cheats.invincible;
+            }
+            Key::F2 => self.stepping = !self.stepping,
+            Key::F3 if self.stepping => self.step(),
+            Key::F4 => self.camera.toggle_detached(),
+            Key::I if self.camera.detached => self.camera.pan(0.0, -2.0),
+            Key::K if self.camera.detached => self.camera.pan(0.0, 2.0),
+            Key::J if self.camera.detached => self.camera.pan(-2.0, 0.0),
+            Key::L if self.camera.detached => self.camera.pan(2.0, 0.0),
+            Key::Equals | Key::NumPadPlus if self.camera.detached => self.camera.zoom_by(1.25),
+            Key::Minus | Key::NumPadMinus if self.camera.detached => self.camera.zoom_by(0.8),
+            _ => return false,
+        }
+        true
+    }
+
+    // The season's palette, with the snake in the player's own color if one is picked
+    pub fn palette(&self) -> Palette {
+        let palette = self
+            .season
+            .map_or(&DEFAULT_PALETTE, |season| &season.palette);
+        self.profile
+            .snake_color
+            .map_or(*palette, |color| colors::derive(color, palette))
+    }
+
+    // Active developer toggles, for the HUD
+    pub fn dev_flags(&self) -> String {
+        let mut flags = Vec::new();
+        if self.game.cheats.invincible {
+            flags.push("GOD");
+        }
+        if self.stepping {
+            flags.push("STEP (F3)");
+        }
+        if self.camera.detached {
+            flags.push("FREE CAM");
+        }
+        let mut flags = flags.join("  ");
+        // Strict input timing shows every press waiting for the next move
+        if self.game.rules.input_timing == InputTiming::Strict {
+            let queued: Vec<String> = self
+                .game
+                .queued
+                .iter()
+                .map(|d| format!("{:?}", d))
+                .collect();
+            flags += &format!("  QUEUE [{}]", queued.join(" "));
+        }
+        flags.trim_start().to_string()
+    }
+
+    // Let the bot steer ahead of a move. Its turns go into the input log like anyone
+    // else's, so a replay doesn't need the bot.
+    fn bot_move(&mut self) {
+        let Some(bot) = self.bot.as_mut() else {
+            return;
+        };
+        if self.replay.is_none() && self.game.tick_due() {
+            if let Some(direction) = bot.next_move(&self.game) {
+                self.input(InputEvent::Steer(direction));
+            }
+        }
+    }
+
+    // Run frames until the snake has moved once
+    fn step(&mut self) {
+        loop {
+            let ticked = self.game.tick_due();
+            self.bot_move();
+            self.advance();
+            if ticked || !self.game.game_started || self.game.game_over || self.game.paused {
+                break;
+            }
+        }
+    }
+
+    // Feed a title screen key to the cheat code buffer, returns true if it was used
+    fn cheat_key(&mut self, key: Key) -> bool {
+        let c = match key_to_char(key) {
+            Some(c) => c,
+            None => return false,
+        };
+        match self.cheat_buffer.push(c) {
+            CheatInput::Activated(cheat) => {
+                self.game.activate_cheat(cheat);
+                true
+            }
+            CheatInput::Partial => true,
+            CheatInput::NoMatch => false,
+        }
+    }
+
+    // Steer the snake, with a subtle rumble tick if it actually turned
+    fn steer(&mut self, direction: Direction) {
+        // The editor has the board
+        if self.editor.is_some() {
+            return;
+        }
+        let starting = !self.game.game_started;
+        if starting && !self.game.game_over && !self.game.paused {
+            self.replay_start();
+        }
+        if self.game.steer(direction) {
+            self.gamepad.rumble(Rumble::Turn);
+        }
+        if starting && self.game.game_started {
+            let stamp = self.run_stamp();
+            if let Some(recorder) = self.recorder.as_mut() {
+                recorder.record_start(self.updates, &stamp);
+            }
+            self.arm_run();
+        }
+    }
+
+    fn tap(&mut self) {
+        // Resuming would leave photo mode's camera behind
+        if self.photo_mode {
+            return;
+        }
+        if self.game.game_over {
+            if !self.game.entering_name && !self.versus_running() {
+                self.restart();
+            }
+        } else {
+            self.game.toggle_pause();
+        }
+    }
+
+    fn restart(&mut self) {
+        let seed = self.seeds.gen();
+        self.restart_with_seed(seed);
+    }
+
+    pub fn restart_with_seed(&mut self, seed: u64) {
+        let save_scores = self.game.save_scores;
+        if std::mem::take(&mut self.photo_mode) {
+            self.camera.set_detached(false);
+        }
+        self.cheat_buffer = CheatBuffer::new();
+        self.game = Game::new(seed, self.board);
+        self.game.save_scores = save_scores;
+        self.versus = None;
+        self.game.special_food = self.season.and_then(|season| season.special_food.clone());
+        self.game.rules = self.settings.rules();
+        self.animations = Animations::new();
+        self.bites = BiteEffects::new(seed);
+        self.run_foods = [0; FOOD_TYPES.len()];
+        self.fireworks = Fireworks::new(seed);
+        self.trail.clear();
+        self.path_view = false;
+        self.menu_message.clear();
+        self.unlocks.clear();
+        self.coins_earned = 0;
+        self.medal_earned = None;
+        self.stars_earned = None;
+        self.points_earned = 0;
+        self.seed = seed;
+        self.apply_mutators();
+    }
+
+    // Called for every update event. `cursor` is the mouse position in window pixels.
+    pub fn update(&mut self, cursor: [f64; 2]) {
+        if let Some(replay) = self.replay.as_mut() {
+            replay.check_missed(self.updates);
+            let due = replay.due(self.updates);
+            if replay.finished() && due.is_empty() {
+                // Nothing left to play back, hand control back to the player
+                println!("Input replay finished");
+                self.verification = Some(replay.verification);
+                self.replay = None;
+            }
+            for event in due {
+                self.input(event);
+            }
+        } else {
+            // Turn live device state into input events
+            // In menus the d-pad moves the focus and the face buttons pick and go back
+            for pad_input in self.gamepad.poll() {
+                let event = match pad_input {
+                    PadInput::Direction(direction) if self.menu_open() => {
+                        InputEvent::Key(direction_key(direction))
+                    }
+                    PadInput::Direction(direction) => InputEvent::Steer(direction),
+                    PadInput::Confirm => InputEvent::Key(Key::Return),
+                    PadInput::Back => InputEvent::Key(Key::Backspace),
+                    PadInput::Start => match self.bindings.pause.first() {
+                        Some(key) => InputEvent::Key(*key),
+                        None => continue,
+                    },
+                    PadInput::Connected => InputEvent::Pad(true),
+                    PadInput::Disconnected => InputEvent::Pad(false),
+                };
+                self.input(event);
+            }
+            if self.settings.control_scheme == ControlScheme::Mouse && self.game.tick_due() {
+                let head = self.game.snake.body[0].position;
+                let board = (self.game.width, self.game.height);
+                let cursor = self.camera.to_board(cursor, board);
+                if let Some(direction) =
+                    controls::mouse_direction(head, cursor, &self.game.snake.direction)
+                {
+                    if direction != self.game.snake.direction {
+                        self.input(InputEvent::Steer(direction));
+                    }
+                }
+            }
+            if !self.stepping {
+                self.bot_move();
+            }
+            self.check_idle();
+        }
+        self.updates += 1;
+
+        if let Some(zen) = self.zen.as_mut() {
+            zen.update();
+        } else if !self.stepping {
+            self.advance();
+        }
+        if self.settings.music {
+            self.music.update(&self.game, self.audio.as_mut());
+        }
+    }
+
+    // One frame of simulation plus the feedback that goes with it
+    fn advance(&mut self) {
+        let turns = self
+            .versus
+            .as_ref()
+            .map_or([true, true], |versus| versus.turns(&self.game));
+        let ticked = turns[0] && self.game.tick_due();
+        let was_over = self.game.game_over;
+        let board = (self.game.width, self.game.height);
+        // Anything that happened between updates, like growth from the console
+        let mut events = std::mem::take(&mut self.game.events);
+        if turns[0] {
+            self.game.update();
+        }
+        events.append(&mut self.game.events);
+        if let Some(versus) = self.versus.as_mut() {
+            versus.update(&mut self.game, turns, ticked);
+        }
+        if ticked {
+            let hash = self.game.state_hash();
+            if let Some(recorder) = self.recorder.as_mut() {
+                recorder.record_hash(self.updates, hash);
+            }
+            if let Some(replay) = self.replay.as_mut() {
+                replay.check(self.updates, hash);
+            }
+        }
+        if (self.game.width, self.game.height) != board {
+            // An expanding board grew, spread the junk over the new space too
+            let board = (self.game.width, self.game.height);
+            self.decorations = Decorations::scatter(self.settings.theme, self.seed, board);
+        }
+        self.frame_room();
+        if self.game.game_started {
+            let head = self.game.snake.body[0].position;
+            if self.trail.last() != Some(&head) {
+                self.trail.push(head);
+            }
+        }
+        events::publish(
+            &self.game,
+            &events,
+            &mut [
+                &mut self.sound_effects,
+                &mut self.bites,
+                &mut self.animations,
+                &mut self.stats,
+                &mut self.database,
+            ],
+        );
+        let eaten = events.iter().find_map(|event| match event {
+            GameEvent::FoodEaten { food, .. } => Some(food.clone()),
+            _ => None,
+        });
+        if let Some(food) = &eaten {
+            if let Some(i) = FOOD_TYPES.iter().position(|f| f == food) {
+                self.run_foods[i] += 1;
+            }
+        }
+        if self.game.won {
+            self.fireworks.update();
+        }
+        if self.game.game_over && !was_over {
+            if !self.game.won {
+                self.gamepad.rumble(Rumble::Death);
+            }
+            // A puzzle's end can be taken back, so it isn't a run for the stats
+            if self.game.save_scores && self.game.puzzle.is_none() {
+                let run = Run {
+                    time: SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map_or(0, |d| d.as_secs()),
+                    score: self.game.score,
+                    foods: self.run_foods,
+                    near_misses: self.game.near_misses,
+                };
+                self.database
+                    .record_run(&run, self.profile.mode, self.board);
+                self.stats.record_run(run);
+            }
+            let stamp = self.run_stamp();
+            let claim = Claim {
+                score: self.game.score,
+                replay: self.game.state_hash(),
+                settings: self.game.rules.fingerprint(),
+            };
+            if let Some(recorder) = self.recorder.as_mut() {
+                recorder.record_run(self.updates, &stamp, claim);
+                self.database.replay_run(self.game.score);
+            }
+            if let Some(replay) = self.replay.as_mut() {
+                replay.check_claim(claim);
+            }
+            // Same rules as the high scores: no cheats, no replays
+            let recorded = self.game.save_scores && !self.game.cheats.any();
+            if recorded && self.game.puzzle.is_some() {
+                // Puzzles earn stars and nothing else
+                self.stars_earned = match (&self.level, self.game.stars()) {
+                    (Some(level), Some(stars)) if self.profile.award_stars(&level.id(), stars) => {
+                        Some(stars)
+                    }
+                    _ => None,
+                };
+                self.save_profile();
+            } else if recorded {
+                self.unlocks = self.profile.finish_run(self.game.score);
+                self.coins_earned = shop::coins_for(self.game.score);
+                if let Some(level) = &self.level {
+                    self.medal_earned = level
+                        .medal(self.game.score)
+                        .filter(|medal| self.profile.award_medal(&level.id(), *medal));
+                }
+                if self.profile.mode == GameMode::Roguelite {
+                    self.points_earned = roguelite::points_for(self.game.score);
+                    self.profile.upgrade_points += self.points_earned;
+                }
+                if let Some(adaptive) = &self.game.adaptive {
+                    self.profile.adaptive = adaptive.clone();
+                }
+                self.save_profile();
+            }
+        } else if eaten == Some(FoodType::ShinyMetal) {
+            self.gamepad.rumble(Rumble::Metal);
+        }
+        self.sound_effects.play(
+            &self.game,
+            &self.camera,
+            &self.food_styles,
+            self.audio.as_mut(),
+        );
+        self.bites.update(&self.game, &self.food_styles);
+        if eaten.is_some() && self.game.kids {
+            audio::cheer(self.audio.as_mut());
+        }
+        self.animations.update(&self.game);
+        if ticked && self.settings.food_cues {
+            self.food_cues.tick(&self.game, self.audio.as_mut());
+        }
+        self.announcer.observe(&self.game);
+        if let Some(server) = self.score_server.as_mut() {
+            server.publish(&self.game);
+        }
+    }
+}
+
+// Entries the level select has before the shared levels: the plain board and your own
+pub const LEVEL_CHOICES: usize = 2;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum LevelField {
+    Name,
+    Author,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum LevelSort {
+    Name,
+    Author,
+}
+
+impl LevelSort {
+    pub fn name(self) -> &'static str {
+        match self {
+            LevelSort::Name => "name",
+            LevelSort::Author => "author",
+        }
+    }
+
+    pub fn next(self) -> LevelSort {
+        match self {
+            LevelSort::Name => LevelSort::Author,
+            LevelSort::Author => LevelSort::Name,
+        }
+    }
+}
+
+fn sort_levels(levels: &mut [LevelFile], sort: LevelSort) {
+    match sort {
+        LevelSort::Name => levels.sort_by_key(|level| level.name.to_lowercase()),
+        LevelSort::Author => {
+            levels.sort_by_key(|level| (level.author.to_lowercase(), level.name.to_lowercase()))
+        }
+    }
+}
+
+// Where the editor keeps its level
+fn edited_level() -> PathBuf {
+    PathBuf::from(LEVELS_DIR).join("custom.txt")
+}
+
+fn corner_key(key: Key) -> Option<[Direction; 2]> {
+    match key {
+        Key::NumPad7 => Some([Direction::Up, Direction::Left]),
+        Key::NumPad9 => Some([Direction::Up, Direction::Right]),
+        Key::NumPad1 => Some([Direction::Down, Direction::Left]),
+        Key::NumPad3 => Some([Direction::Down, Direction::Right]),
+        _ => None,
+    }
+}
+
+// Arrow key with the same meaning, for the d-pad in menus
+fn direction_key(direction: Direction) -> Key {
+    match direction {
+        Direction::Up => Key::Up,
+        Direction::Down => Key::Down,
+        Direction::Left => Key::Left,
+        Direction::Right => Key::Right,
+    }
+}
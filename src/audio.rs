@@ -0,0 +1,248 @@
+// Sound output. All sounds are synthesized tones, so no audio assets are needed.
+// The real backends are only compiled in with the `audio` (rodio) and `sdl2-audio`
+// features; without either the game runs silently through `SilentEngine`.
+
+use crate::settings::Settings;
+use std::time::Duration;
+
+// Only read by the real backends
+#[cfg_attr(not(any(feature = "audio", feature = "sdl2-audio")), allow(dead_code))]
+pub struct Tone {
+    pub frequency: f32,
+    pub duration: Duration,
+    pub volume: f32, // 0.0 - 1.0, scaled again by the master volume
+    pub pan: f32,    // -1.0 is hard left, 1.0 is hard right
+}
+
+pub trait AudioEngine {
+    fn play_tone(&mut self, tone: Tone);
+    // Master volume for the tones played from now on, 0.0 - 1.0
+    fn set_volume(&mut self, volume: f32);
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum AudioBackend {
+    Rodio,
+    Sdl2,
+}
+
+impl AudioBackend {
+    pub fn parse(value: &str) -> Option<AudioBackend> {
+        match value {
+            "rodio" => Some(AudioBackend::Rodio),
+            "sdl2" => Some(AudioBackend::Sdl2),
+            _ => None,
+        }
+    }
+}
+
+// A bright major chord for every bite in kids mode
+pub fn cheer(audio: &mut dyn AudioEngine) {
+    for frequency in [523.0, 659.0, 784.0] {
+        audio.play_tone(Tone {
+            frequency,
+            duration: Duration::from_millis(180),
+            volume: 0.25,
+            pan: 0.0,
+        });
+    }
+}
+
+pub struct SilentEngine;
+
+impl AudioEngine for SilentEngine {
+    fn play_tone(&mut self, _tone: Tone) {}
+    fn set_volume(&mut self, _volume: f32) {}
+}
+
+// Start with the preferred backend, then try the others, falling back to silence if
+// no device can be opened
+pub fn create_engine(settings: &Settings) -> Box<dyn AudioEngine> {
+    let backends = match settings.audio_backend {
+        AudioBackend::Rodio => [AudioBackend::Rodio, AudioBackend::Sdl2],
+        AudioBackend::Sdl2 => [AudioBackend::Sdl2, AudioBackend::Rodio],
+    };
+    for backend in backends {
+        match open(backend, settings.output_volume()) {
+            Some(Ok(engine)) => return engine,
+            Some(Err(e)) => eprintln!("Audio unavailable: {}", e),
+            None => {} // Not compiled in
+        }
+    }
+    Box::new(SilentEngine)
+}
+
+#[cfg_attr(
+    not(any(feature = "audio", feature = "sdl2-audio")),
+    allow(unused_variables)
+)]
+fn open(backend: AudioBackend, volume: f32) -> Option<Result<Box<dyn AudioEngine>, String>> {
+    match backend {
+        #[cfg(feature = "audio")]
+        AudioBackend::Rodio => Some(
+            rodio_engine::RodioEngine::new(volume)
+                .map(|engine| Box::new(engine) as Box<dyn AudioEngine>)
+                .map_err(|e| e.to_string()),
+        ),
+        #[cfg(feature = "sdl2-audio")]
+        AudioBackend::Sdl2 => Some(
+            sdl2_engine::Sdl2Engine::new(volume)
+                .map(|engine| Box::new(engine) as Box<dyn AudioEngine>),
+        ),
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
+
+// Split a pan position into left/right channel gains (constant power)
+#[cfg(any(feature = "audio", feature = "sdl2-audio"))]
+fn pan_gains(pan: f32) -> (f32, f32) {
+    let angle = (pan.clamp(-1.0, 1.0) + 1.0) * std::f32::consts::FRAC_PI_4;
+    (angle.cos(), angle.sin())
+}
+
+#[cfg(feature = "audio")]
+mod rodio_engine {
+    use super::{pan_gains, AudioEngine, Tone};
+    use rodio::source::{ChannelVolume, SineWave, Source};
+    use rodio::{OutputStream, OutputStreamHandle, StreamError};
+    use std::time::Duration;
+
+    pub struct RodioEngine {
+        _stream: OutputStream, // Output stops when this is dropped
+        handle: OutputStreamHandle,
+        master_volume: f32,
+    }
+
+    impl RodioEngine {
+        pub fn new(master_volume: f32) -> Result<RodioEngine, StreamError> {
+            let (stream, handle) = OutputStream::try_default()?;
+            Ok(RodioEngine {
+                _stream: stream,
+                handle,
+                master_volume,
+            })
+        }
+    }
+
+    impl AudioEngine for RodioEngine {
+        fn play_tone(&mut self, tone: Tone) {
+            let (left, right) = pan_gains(tone.pan);
+            let volume = tone.volume * self.master_volume;
+            let source = SineWave::new(tone.frequency)
+                .take_duration(tone.duration)
+                .fade_in(Duration::from_millis(5))
+                .amplify(volume);
+            let source = ChannelVolume::new(source, vec![left, right]);
+            if let Err(e) = self.handle.play_raw(source) {
+                eprintln!("Error playing sound: {}", e);
+            }
+        }
+
+        fn set_volume(&mut self, volume: f32) {
+            self.master_volume = volume;
+        }
+    }
+}
+
+// SDL2 has no ready-made sources like rodio, so tones are mixed by hand in the audio
+// callback
+#[cfg(feature = "sdl2-audio")]
+mod sdl2_engine {
+    use super::{pan_gains, AudioEngine, Tone};
+    use sdl2::audio::{AudioCallback, AudioDevice, AudioSpecDesired};
+    use sdl2::Sdl;
+
+    const SAMPLE_RATE: i32 = 44_100;
+    const FADE_SECONDS: f32 = 0.005; // Ramp at both ends of a tone, avoids clicks
+
+    struct Voice {
+        phase: f32, // Radians
+        step: f32,  // Phase advance per sample
+        position: u32,
+        length: u32, // Samples
+        fade: u32,
+        gains: (f32, f32),
+    }
+
+    struct Mixer {
+        voices: Vec<Voice>,
+    }
+
+    impl AudioCallback for Mixer {
+        type Channel = f32;
+
+        // Interleaved stereo
+        fn callback(&mut self, out: &mut [f32]) {
+            out.fill(0.0);
+            for voice in &mut self.voices {
+                for frame in out.chunks_exact_mut(2) {
+                    if voice.position >= voice.length {
+                        break;
+                    }
+                    let remaining = voice.length - voice.position;
+                    let envelope = voice.position.min(remaining).min(voice.fade) as f32
+                        / voice.fade.max(1) as f32;
+                    let sample = voice.phase.sin() * envelope;
+                    frame[0] += sample * voice.gains.0;
+                    frame[1] += sample * voice.gains.1;
+                    voice.phase = (voice.phase + voice.step) % std::f32::consts::TAU;
+                    voice.position += 1;
+                }
+            }
+            self.voices.retain(|voice| voice.position < voice.length);
+        }
+    }
+
+    pub struct Sdl2Engine {
+        _sdl: Sdl, // Audio stops when this is dropped
+        device: AudioDevice<Mixer>,
+        sample_rate: i32,
+        master_volume: f32,
+    }
+
+    impl Sdl2Engine {
+        pub fn new(master_volume: f32) -> Result<Sdl2Engine, String> {
+            let sdl = sdl2::init()?;
+            let audio = sdl.audio()?;
+            let desired = AudioSpecDesired {
+                freq: Some(SAMPLE_RATE),
+                channels: Some(2),
+                samples: None,
+            };
+            let mut sample_rate = SAMPLE_RATE;
+            let device = audio.open_playback(None, &desired, |spec| {
+                sample_rate = spec.freq;
+                Mixer { voices: Vec::new() }
+            })?;
+            device.resume();
+            Ok(Sdl2Engine {
+                _sdl: sdl,
+                device,
+                sample_rate,
+                master_volume,
+            })
+        }
+    }
+
+    impl AudioEngine for Sdl2Engine {
+        fn play_tone(&mut self, tone: Tone) {
+            let rate = self.sample_rate as f32;
+            let (left, right) = pan_gains(tone.pan);
+            let volume = tone.volume * self.master_volume;
+            let voice = Voice {
+                phase: 0.0,
+                step: std::f32::consts::TAU * tone.frequency / rate,
+                position: 0,
+                length: (tone.duration.as_secs_f32() * rate) as u32,
+                fade: (FADE_SECONDS * rate) as u32,
+                gains: (left * volume, right * volume),
+            };
+            self.device.lock().voices.push(voice);
+        }
+
+        fn set_volume(&mut self, volume: f32) {
+            self.master_volume = volume;
+        }
+    }
+}
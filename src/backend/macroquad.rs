@@ -0,0 +1,268 @@
+// Macroquad backend. Macroquad runs one loop iteration per displayed frame, passing on
+// input and showing the simulation's newest frame. It can't change the window title,
+// keep the window on top or tell when the window loses focus; those settings are
+// ignored here.
+
+use crate::cli::Options;
+use crate::display::{self, WindowMode};
+use crate::frontend::{TouchPhase, WindowEvent};
+use crate::keys::Key;
+use crate::loading;
+use crate::render::{Color, Renderer};
+use crate::settings::Settings;
+use crate::simulation::Simulation;
+use macroquad::input::{self, KeyCode, MouseButton};
+use macroquad::math::Vec2;
+use macroquad::miniquad::conf::Icon;
+use macroquad::shapes;
+use macroquad::text::{self, Font, TextParams};
+use macroquad::window::{self, Conf};
+
+pub fn run(settings: Settings, options: Options) {
+    let mode = settings.window_mode;
+    let [width, height] = mode.window_size();
+    let conf = Conf {
+        window_title: "Rusty Snake".to_string(),
+        window_width: width as i32,
+        window_height: height as i32,
+        high_dpi: true,
+        fullscreen: mode == WindowMode::Borderless,
+        window_resizable: mode != WindowMode::Mini,
+        icon: Some(icon()),
+        ..Default::default()
+    };
+    if mode == WindowMode::Mini {
+        eprintln!("The macroquad backend can't keep the window on top");
+    }
+    macroquad::Window::from_config(conf, main_loop(settings, options));
+}
+
+async fn main_loop(settings: Settings, options: Options) {
+    // The built-in font until the loaded one is ready
+    let mut font = text::load_ttf_font_from_bytes(loading::BUILT_IN_FONT).unwrap_or_else(|e| {
+        eprintln!("Error: the built-in font can't be read: {}", e);
+        std::process::exit(1);
+    });
+    input::simulate_mouse_with_touch(false);
+
+    let mut simulation = Simulation::start(settings, options);
+    let mut cursor = (f32::NAN, f32::NAN);
+
+    loop {
+        if input::is_key_pressed(KeyCode::Escape) {
+            break;
+        }
+        for key in input::get_keys_pressed() {
+            if let Some(key) = map_key(key) {
+                simulation.event(WindowEvent::Key(key));
+            }
+        }
+        while let Some(c) = input::get_char_pressed() {
+            simulation.event(WindowEvent::Text(c.to_string()));
+        }
+        if input::mouse_position() != cursor {
+            cursor = input::mouse_position();
+            simulation.event(WindowEvent::CursorMoved([cursor.0 as f64, cursor.1 as f64]));
+        }
+        if input::is_mouse_button_pressed(MouseButton::Left) {
+            simulation.event(WindowEvent::MousePressed);
+        }
+        if input::is_mouse_button_released(MouseButton::Left) {
+            simulation.event(WindowEvent::MouseReleased);
+        }
+        for touch in input::touches() {
+            let phase = match touch.phase {
+                input::TouchPhase::Started => TouchPhase::Start,
+                input::TouchPhase::Moved => TouchPhase::Move,
+                input::TouchPhase::Ended => TouchPhase::End,
+                input::TouchPhase::Cancelled => TouchPhase::Cancel,
+                input::TouchPhase::Stationary => continue,
+            };
+            let position = [touch.position.x as f64, touch.position.y as f64];
+            simulation.event(WindowEvent::Touch(touch.id as i64, phase, position));
+        }
+
+        let size = [
+            window::screen_width() as f64,
+            window::screen_height() as f64,
+        ];
+        if let Some(loaded) = simulation.new_font() {
+            match text::load_ttf_font_from_bytes(loaded) {
+                Ok(loaded) => font = loaded,
+                Err(e) => eprintln!("Error loading the font: {}", e),
+            }
+        }
+        simulation.resize(size);
+        simulation.render(&mut MacroquadRenderer { size, font: &font });
+        simulation.profiler.end_frame();
+
+        window::next_frame().await;
+    }
+}
+
+fn icon() -> Icon {
+    let mut icon = Icon {
+        small: [0; 16 * 16 * 4],
+        medium: [0; 32 * 32 * 4],
+        big: [0; 64 * 64 * 4],
+    };
+    icon.small.copy_from_slice(&display::icon_rgba(2));
+    icon.medium.copy_from_slice(&display::icon_rgba(4));
+    icon.big.copy_from_slice(&display::icon_rgba(8));
+    icon
+}
+
+fn map_key(key: KeyCode) -> Option<Key> {
+    Some(match key {
+        KeyCode::A => Key::A,
+        KeyCode::B => Key::B,
+        KeyCode::C => Key::C,
+        KeyCode::D => Key::D,
+        KeyCode::E => Key::E,
+        KeyCode::F => Key::F,
+        KeyCode::G => Key::G,
+        KeyCode::H => Key::H,
+        KeyCode::I => Key::I,
+        KeyCode::J => Key::J,
+        KeyCode::K => Key::K,
+        KeyCode::L => Key::L,
+        KeyCode::M => Key::M,
+        KeyCode::N => Key::N,
+        KeyCode::O => Key::O,
+        KeyCode::P => Key::P,
+        KeyCode::Q => Key::Q,
+        KeyCode::R => Key::R,
+        KeyCode::S => Key::S,
+        KeyCode::T => Key::T,
+        KeyCode::U => Key::U,
+        KeyCode::V => Key::V,
+        KeyCode::W => Key::W,
+        KeyCode::X => Key::X,
+        KeyCode::Y => Key::Y,
+        KeyCode::Z => Key::Z,
+        KeyCode::Key0 => Key::D0,
+        KeyCode::Key1 => Key::D1,
+        KeyCode::Key2 => Key::D2,
+        KeyCode::Key3 => Key::D3,
+        KeyCode::Key4 => Key::D4,
+        KeyCode::Key5 => Key::D5,
+        KeyCode::Key6 => Key::D6,
+        KeyCode::Key7 => Key::D7,
+        KeyCode::Key8 => Key::D8,
+        KeyCode::Key9 => Key::D9,
+        KeyCode::Kp0 => Key::NumPad0,
+        KeyCode::Kp1 => Key::NumPad1,
+        KeyCode::Kp2 => Key::NumPad2,
+        KeyCode::Kp3 => Key::NumPad3,
+        KeyCode::Kp4 => Key::NumPad4,
+        KeyCode::Kp5 => Key::NumPad5,
+        KeyCode::Kp6 => Key::NumPad6,
+        KeyCode::Kp7 => Key::NumPad7,
+        KeyCode::Kp8 => Key::NumPad8,
+        KeyCode::Kp9 => Key::NumPad9,
+        KeyCode::KpAdd => Key::NumPadPlus,
+        KeyCode::KpSubtract => Key::NumPadMinus,
+        KeyCode::Up => Key::Up,
+        KeyCode::Down => Key::Down,
+        KeyCode::Left => Key::Left,
+        KeyCode::Right => Key::Right,
+        KeyCode::Space => Key::Space,
+        KeyCode::Tab => Key::Tab,
+        KeyCode::Enter | KeyCode::KpEnter => Key::Return,
+        KeyCode::Backspace => Key::Backspace,
+        KeyCode::GraveAccent => Key::Backquote,
+        KeyCode::Equal => Key::Equals,
+        KeyCode::Minus => Key::Minus,
+        KeyCode::LeftShift => Key::LShift,
+        KeyCode::RightShift => Key::RShift,
+        KeyCode::LeftControl => Key::LCtrl,
+        KeyCode::RightControl => Key::RCtrl,
+        KeyCode::F1 => Key::F1,
+        KeyCode::F2 => Key::F2,
+        KeyCode::F3 => Key::F3,
+        KeyCode::F4 => Key::F4,
+        KeyCode::F5 => Key::F5,
+        KeyCode::F6 => Key::F6,
+        KeyCode::F7 => Key::F7,
+        KeyCode::F8 => Key::F8,
+        KeyCode::F9 => Key::F9,
+        KeyCode::F10 => Key::F10,
+        KeyCode::F11 => Key::F11,
+        KeyCode::F12 => Key::F12,
+        _ => return None,
+    })
+}
+
+struct MacroquadRenderer<'a> {
+    size: [f64; 2],
+    font: &'a Font,
+}
+
+fn color([r, g, b, a]: Color) -> macroquad::color::Color {
+    macroquad::color::Color::new(r, g, b, a)
+}
+
+// Font sizes are in points like piston's; macroquad wants pixels
+fn font_pixels(size: u32) -> u16 {
+    (size as f32 * 4.0 / 3.0).round() as u16
+}
+
+impl Renderer for MacroquadRenderer<'_> {
+    fn view_size(&self) -> [f64; 2] {
+        self.size
+    }
+
+    fn clear(&mut self, c: Color) {
+        window::clear_background(color(c));
+    }
+
+    fn rectangle(&mut self, c: Color, [x, y, w, h]: [f64; 4]) {
+        shapes::draw_rectangle(x as f32, y as f32, w as f32, h as f32, color(c));
+    }
+
+    fn line(&mut self, c: Color, width: f64, [x1, y1, x2, y2]: [f64; 4]) {
+        shapes::draw_line(
+            x1 as f32,
+            y1 as f32,
+            x2 as f32,
+            y2 as f32,
+            width as f32,
+            color(c),
+        );
+    }
+
+    // Drawn as a fan of triangles from the first point
+    fn polygon(&mut self, c: Color, points: &[[f64; 2]]) {
+        let point = |[x, y]: [f64; 2]| Vec2::new(x as f32, y as f32);
+        for pair in points.get(1..).unwrap_or_default().windows(2) {
+            shapes::draw_triangle(point(points[0]), point(pair[0]), point(pair[1]), color(c));
+        }
+    }
+
+    fn text(&mut self, text: &str, size: u32, c: Color, x: f64, y: f64) {
+        let params = TextParams {
+            font: Some(self.font),
+            font_size: font_pixels(size),
+            color: color(c),
+            ..Default::default()
+        };
+        text::draw_text_ex(text, x as f32, y as f32, params);
+    }
+
+    fn text_width(&mut self, text: &str, size: u32) -> f64 {
+        text::measure_text(text, Some(self.font), font_pixels(size), 1.0).width as f64
+    }
+
+    // Scissored in physical pixels, so the logical rectangle is scaled by the DPI
+    fn clip(&mut self, rect: Option<[f64; 4]>) {
+        let dpi = window::screen_dpi_scale() as f64;
+        let scissor = rect.map(|[x, y, w, h]| {
+            let [x, y, w, h] = [x, y, w, h].map(|v| (v * dpi).round() as i32);
+            (x, y, w, h)
+        });
+        // Safe: only called from the main loop, between frames being drawn
+        let mut gl = unsafe { window::get_internal_gl() };
+        gl.flush();
+        gl.quad_gl.scissor(scissor);
+    }
+}
@@ -0,0 +1,17 @@
+// Window, rendering and input backends, picked with a cargo feature. Piston is the
+// default; macroquad is there for systems where piston's GL context setup fails.
+
+#[cfg(all(feature = "piston", feature = "macroquad"))]
+compile_error!("enable only one of the `piston` and `macroquad` features");
+#[cfg(not(any(feature = "piston", feature = "macroquad")))]
+compile_error!("enable one of the `piston` or `macroquad` features");
+
+#[cfg(feature = "macroquad")]
+mod macroquad;
+#[cfg(feature = "piston")]
+mod piston;
+
+#[cfg(feature = "macroquad")]
+pub use self::macroquad::run;
+#[cfg(feature = "piston")]
+pub use self::piston::run;
@@ -0,0 +1,374 @@
+// Piston backend: glutin window, gfx rendering, piston events
+
+use crate::cli::Options;
+use crate::display::{self, WindowMode};
+use crate::frontend::{TouchPhase, WindowEvent};
+use crate::keys::Key;
+use crate::loading;
+use crate::profiler::Span;
+use crate::render::{Color, Renderer};
+use crate::settings::Settings;
+use crate::simulation::Simulation;
+use piston_window::character::CharacterCache;
+use piston_window::math::Matrix2d;
+use piston_window::{
+    clear, AdvancedWindow, Button, Context, DrawState, Event, G2d, Glyphs, Image, Input, Line,
+    Loop, MouseButton, PistonWindow, Polygon, Rectangle, TextureSettings, Touch, Transformed,
+    Window, WindowSettings,
+};
+use std::collections::HashMap;
+use std::time::Instant;
+use winit::window::Icon;
+
+pub fn run(settings: Settings, options: Options) {
+    let mut window = create_window(settings.window_mode);
+
+    // The built-in font until the loaded one is ready
+    let mut glyphs = load_glyphs(&mut window, loading::BUILT_IN_FONT).unwrap_or_else(|| {
+        eprintln!("Error: the built-in font can't be read");
+        std::process::exit(1);
+    });
+    let mut text = TextCache::new(window.draw_size().width / window.size().width);
+    let mut simulation = Simulation::start(settings, options);
+
+    while let Some(event) = window.next() {
+        match &event {
+            Event::Input(input, _) => {
+                if let Some(event) = translate(input) {
+                    simulation.event(event);
+                }
+            }
+            Event::Loop(Loop::Render(args)) => {
+                if let Some(font) = simulation.new_font() {
+                    if let Some(loaded) = load_glyphs(&mut window, font) {
+                        glyphs = loaded;
+                        text = TextCache::new(text.hidpi);
+                    }
+                }
+                text.set_hidpi(args.draw_size[0] as f64 / args.window_size[0]);
+                simulation.resize(args.window_size);
+                window.draw_2d(&event, |c, g, device| {
+                    let mut renderer = PistonRenderer {
+                        c,
+                        g,
+                        glyphs: &mut glyphs,
+                        text: &mut text,
+                    };
+                    simulation.render(&mut renderer);
+                    text.end_frame();
+
+                    // Update glyphs
+                    let flush_start = Instant::now();
+                    glyphs.factory.encoder.flush(device);
+                    simulation.profiler.record(Span::GlyphFlush, flush_start);
+                });
+                simulation.profiler.end_frame();
+            }
+            _ => {}
+        }
+
+        if let Some(title) = simulation.title() {
+            window.set_title(title);
+        }
+    }
+}
+
+fn load_glyphs(window: &mut PistonWindow, font: &'static [u8]) -> Option<Glyphs> {
+    let context = window.create_texture_context();
+    Glyphs::from_bytes(font, context, TextureSettings::new()).ok()
+}
+
+fn create_window(mode: WindowMode) -> PistonWindow {
+    let mut settings = WindowSettings::new("Rusty Snake", mode.window_size()).exit_on_esc(true);
+    match mode {
+        WindowMode::Windowed => {}
+        WindowMode::Borderless => settings = settings.fullscreen(true).decorated(false),
+        WindowMode::Mini => settings = settings.resizable(false),
+    }
+    let window: PistonWindow = settings.build().unwrap();
+    let winit_window = window.window.ctx.window();
+    match Icon::from_rgba(display::icon_rgba(4), 32, 32) {
+        Ok(icon) => winit_window.set_window_icon(Some(icon)),
+        Err(e) => eprintln!("Error creating window icon: {}", e),
+    }
+    if mode == WindowMode::Mini {
+        winit_window.set_always_on_top(true);
+    }
+    window
+}
+
+fn translate(input: &Input) -> Option<WindowEvent> {
+    use piston_window::{ButtonArgs, ButtonState, Motion};
+    match input {
+        Input::Button(ButtonArgs {
+            state,
+            button: Button::Keyboard(key),
+            ..
+        }) if *state == ButtonState::Press => map_key(*key).map(WindowEvent::Key),
+        Input::Button(ButtonArgs {
+            state,
+            button: Button::Mouse(MouseButton::Left),
+            ..
+        }) => Some(match state {
+            ButtonState::Press => WindowEvent::MousePressed,
+            ButtonState::Release => WindowEvent::MouseReleased,
+        }),
+        Input::Move(Motion::MouseCursor(position)) => Some(WindowEvent::CursorMoved(*position)),
+        Input::Move(Motion::Touch(touch)) => {
+            let phase = match touch.touch {
+                Touch::Start => TouchPhase::Start,
+                Touch::Move => TouchPhase::Move,
+                Touch::End => TouchPhase::End,
+                Touch::Cancel => TouchPhase::Cancel,
+            };
+            Some(WindowEvent::Touch(touch.id, phase, touch.position()))
+        }
+        Input::Text(text) => Some(WindowEvent::Text(text.clone())),
+        Input::Focus(focused) => Some(WindowEvent::Focus(*focused)),
+        _ => None,
+    }
+}
+
+fn map_key(key: piston_window::Key) -> Option<Key> {
+    use piston_window::Key as P;
+    Some(match key {
+        P::A => Key::A,
+        P::B => Key::B,
+        P::C => Key::C,
+        P::D => Key::D,
+        P::E => Key::E,
+        P::F => Key::F,
+        P::G => Key::G,
+        P::H => Key::H,
+        P::I => Key::I,
+        P::J => Key::J,
+        P::K => Key::K,
+        P::L => Key::L,
+        P::M => Key::M,
+        P::N => Key::N,
+        P::O => Key::O,
+        P::P => Key::P,
+        P::Q => Key::Q,
+        P::R => Key::R,
+        P::S => Key::S,
+        P::T => Key::T,
+        P::U => Key::U,
+        P::V => Key::V,
+        P::W => Key::W,
+        P::X => Key::X,
+        P::Y => Key::Y,
+        P::Z => Key::Z,
+        P::D0 => Key::D0,
+        P::D1 => Key::D1,
+        P::D2 => Key::D2,
+        P::D3 => Key::D3,
+        P::D4 => Key::D4,
+        P::D5 => Key::D5,
+        P::D6 => Key::D6,
+        P::D7 => Key::D7,
+        P::D8 => Key::D8,
+        P::D9 => Key::D9,
+        P::NumPad0 => Key::NumPad0,
+        P::NumPad1 => Key::NumPad1,
+        P::NumPad2 => Key::NumPad2,
+        P::NumPad3 => Key::NumPad3,
+        P::NumPad4 => Key::NumPad4,
+        P::NumPad5 => Key::NumPad5,
+        P::NumPad6 => Key::NumPad6,
+        P::NumPad7 => Key::NumPad7,
+        P::NumPad8 => Key::NumPad8,
+        P::NumPad9 => Key::NumPad9,
+        P::NumPadPlus => Key::NumPadPlus,
+        P::NumPadMinus => Key::NumPadMinus,
+        P::Up => Key::Up,
+        P::Down => Key::Down,
+        P::Left => Key::Left,
+        P::Right => Key::Right,
+        P::Space => Key::Space,
+        P::Tab => Key::Tab,
+        P::Return => Key::Return,
+        P::Backspace => Key::Backspace,
+        P::Backquote => Key::Backquote,
+        P::Equals => Key::Equals,
+        P::Minus => Key::Minus,
+        P::LShift => Key::LShift,
+        P::RShift => Key::RShift,
+        P::LCtrl => Key::LCtrl,
+        P::RCtrl => Key::RCtrl,
+        P::F1 => Key::F1,
+        P::F2 => Key::F2,
+        P::F3 => Key::F3,
+        P::F4 => Key::F4,
+        P::F5 => Key::F5,
+        P::F6 => Key::F6,
+        P::F7 => Key::F7,
+        P::F8 => Key::F8,
+        P::F9 => Key::F9,
+        P::F10 => Key::F10,
+        P::F11 => Key::F11,
+        P::F12 => Key::F12,
+        _ => return None,
+    })
+}
+
+struct PistonRenderer<'a, 'b> {
+    c: Context,
+    g: &'a mut G2d<'b>,
+    glyphs: &'a mut Glyphs,
+    text: &'a mut TextCache,
+}
+
+impl Renderer for PistonRenderer<'_, '_> {
+    fn view_size(&self) -> [f64; 2] {
+        self.c.get_view_size()
+    }
+
+    fn clear(&mut self, color: Color) {
+        clear(color, self.g);
+    }
+
+    fn rectangle(&mut self, color: Color, rect: [f64; 4]) {
+        Rectangle::new(color).draw(rect, &self.c.draw_state, self.c.transform, self.g);
+    }
+
+    fn line(&mut self, color: Color, width: f64, points: [f64; 4]) {
+        Line::new(color, width).draw(points, &self.c.draw_state, self.c.transform, self.g);
+    }
+
+    fn polygon(&mut self, color: Color, points: &[[f64; 2]]) {
+        Polygon::new(color).draw(points, &self.c.draw_state, self.c.transform, self.g);
+    }
+
+    fn text(&mut self, text: &str, size: u32, color: Color, x: f64, y: f64) {
+        let transform = self.c.transform.trans(x, y);
+        self.text
+            .draw(self.glyphs, text, size, color, transform, &self.c, self.g);
+    }
+
+    fn text_width(&mut self, text: &str, size: u32) -> f64 {
+        self.text.width(self.glyphs, text, size)
+    }
+
+    // Scissored in physical pixels, so the logical rectangle is scaled like text
+    fn clip(&mut self, rect: Option<[f64; 4]>) {
+        let hidpi = self.text.hidpi;
+        self.c.draw_state = match rect {
+            Some(rect) => {
+                let [x, y, w, h] = rect.map(|v| (v * hidpi).round().max(0.0) as u32);
+                DrawState::default().scissor([x, y, w, h])
+            }
+            None => DrawState::default(),
+        };
+    }
+}
+
+// Laying a string out means looking up and positioning every glyph, which used to
+// happen for every label on every frame. Layouts are cached per string and size; a
+// string that wasn't drawn during the last frame (an old score, say) is dropped.
+//
+// Glyphs are rasterized at physical pixel size on HiDPI displays and drawn scaled back
+// down, so text stays crisp instead of being a blown-up low resolution bitmap.
+struct TextCache {
+    hidpi: f64,                                     // Physical pixels per logical unit
+    layouts: HashMap<u32, HashMap<String, Layout>>, // By raster size, then text
+}
+
+// Glyph positions relative to the start of the baseline
+struct Layout {
+    width: f64,
+    glyphs: Vec<(char, [f64; 2])>, // Only glyphs with something to draw
+    used: bool,                    // Drawn since the last `end_frame`
+}
+
+impl Layout {
+    fn new(glyphs: &mut Glyphs, text: &str, size: u32) -> Layout {
+        let mut layout = Layout {
+            width: 0.0,
+            glyphs: Vec::new(),
+            used: true,
+        };
+        for ch in text.chars() {
+            if let Ok(character) = glyphs.character(size, ch) {
+                if character.atlas_size[0] > 0.0 && character.atlas_size[1] > 0.0 {
+                    let position = [layout.width + character.left(), -character.top()];
+                    layout.glyphs.push((ch, position));
+                }
+                layout.width += character.advance_width();
+            }
+        }
+        layout
+    }
+}
+
+impl TextCache {
+    fn new(hidpi: f64) -> TextCache {
+        TextCache {
+            hidpi,
+            layouts: HashMap::new(),
+        }
+    }
+
+    // Follow the window's scale factor, e.g. after moving to another monitor
+    fn set_hidpi(&mut self, hidpi: f64) {
+        if hidpi > 0.0 && hidpi != self.hidpi {
+            self.hidpi = hidpi;
+            self.layouts.clear();
+        }
+    }
+
+    // Size glyphs are rasterized at for a logical font size
+    fn raster_size(&self, size: u32) -> u32 {
+        (size as f64 * self.hidpi).round() as u32
+    }
+
+    // Forget layouts that weren't used this frame, call once after drawing
+    fn end_frame(&mut self) {
+        for by_text in self.layouts.values_mut() {
+            by_text.retain(|_, layout| std::mem::take(&mut layout.used));
+        }
+    }
+
+    fn layout(&mut self, glyphs: &mut Glyphs, text: &str, raster: u32) -> &Layout {
+        let by_text = self.layouts.entry(raster).or_default();
+        if !by_text.contains_key(text) {
+            by_text.insert(text.to_string(), Layout::new(glyphs, text, raster));
+        }
+        let layout = by_text.get_mut(text).unwrap();
+        layout.used = true;
+        layout
+    }
+
+    fn width(&mut self, glyphs: &mut Glyphs, text: &str, size: u32) -> f64 {
+        let hidpi = self.hidpi;
+        self.layout(glyphs, text, self.raster_size(size)).width / hidpi
+    }
+
+    // Draw a string with its baseline starting at the transform's origin
+    #[allow(clippy::too_many_arguments)]
+    fn draw(
+        &mut self,
+        glyphs: &mut Glyphs,
+        text: &str,
+        size: u32,
+        color: Color,
+        transform: Matrix2d,
+        c: &Context,
+        g: &mut G2d,
+    ) {
+        let raster = self.raster_size(size);
+        let transform = transform.scale(1.0 / self.hidpi, 1.0 / self.hidpi);
+        let layout = self.layout(glyphs, text, raster);
+        let mut image = Image::new_color(color);
+        for &(ch, [x, y]) in &layout.glyphs {
+            if let Ok(character) = glyphs.character(raster, ch) {
+                image = image.src_rect([
+                    character.atlas_offset[0],
+                    character.atlas_offset[1],
+                    character.atlas_size[0],
+                    character.atlas_size[1],
+                ]);
+                image.draw(character.texture, &c.draw_state, transform.trans(x, y), g);
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+// The magnet crane: a 2x2 boss that turns up every `BOSS_EVERY` points and chases the
+// head for a while. Touching it is fatal and it can't be hurt directly. It's beaten
+// by luring it over shiny metal, which its magnet picks up; after three pieces it
+// breaks down and pays out a bonus. If time runs out first it just leaves.
+
+use crate::rats::axis_delta;
+
+pub const BOSS_EVERY: u32 = 50;
+pub const BOSS_TICKS: u32 = 240; // About 30 seconds at normal speed
+pub const BOSS_METAL: u32 = 3; // Pieces it takes to beat it
+pub const BOSS_BONUS: u32 = 25;
+const BOSS_SIZE: i32 = 2;
+const MOVE_EVERY: u32 = 2; // Snake moves per crane move, so it can be outrun
+
+#[derive(Clone, Copy, Debug)]
+pub struct Boss {
+    pub position: (i32, i32), // Top left cell
+    pub metal: u32,           // Pieces picked up so far
+    pub ticks_left: u32,
+}
+
+impl Boss {
+    pub fn new(position: (i32, i32)) -> Boss {
+        Boss {
+            position,
+            metal: 0,
+            ticks_left: BOSS_TICKS,
+        }
+    }
+
+    // The cells under the crane. On a wrapping board it can straddle an edge.
+    pub fn cells(&self, (width, height): (i32, i32)) -> [(i32, i32); 4] {
+        let (x, y) = self.position;
+        let cell = |dx: i32, dy: i32| ((x + dx).rem_euclid(width), (y + dy).rem_euclid(height));
+        [cell(0, 0), cell(1, 0), cell(0, 1), cell(1, 1)]
+    }
+
+    pub fn covers(&self, cell: (i32, i32), board: (i32, i32)) -> bool {
+        self.cells(board).contains(&cell)
+    }
+
+    // Count down and, every other tick, take one step toward `target`. Returns false
+    // once time is up.
+    pub fn tick(&mut self, target: (i32, i32), board: (i32, i32), wrap: bool) -> bool {
+        self.ticks_left = self.ticks_left.saturating_sub(1);
+        if self.ticks_left == 0 {
+            return false;
+        }
+        if self.ticks_left.is_multiple_of(MOVE_EVERY) {
+            // Aim the middle of the crane at the target, along the longer axis first
+            let dx = axis_delta(self.position.0, target.0, board.0, wrap);
+            let dy = axis_delta(self.position.1, target.1, board.1, wrap);
+            let step = if dx.abs() >= dy.abs() {
+                (dx.signum(), 0)
+            } else {
+                (0, dy.signum())
+            };
+            let (x, y) = (self.position.0 + step.0, self.position.1 + step.1);
+            self.position = if wrap {
+                (x.rem_euclid(board.0), y.rem_euclid(board.1))
+            } else {
+                (
+                    x.clamp(0, board.0 - BOSS_SIZE),
+                    y.clamp(0, board.1 - BOSS_SIZE),
+                )
+            };
+        }
+        true
+    }
+}
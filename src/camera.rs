@@ -0,0 +1,138 @@
+// View onto the board. Normally the board (or the part of it in focus, such as the
+// room the snake is in) is scaled to fit the window and centered, which letterboxes
+// it in fullscreen; a detached camera can be panned and zoomed
+// freely (a developer tool for inspecting collisions, and photo mode's free camera).
+// Split screen gives each player a camera onto their own part of the window.
+
+use crate::BLOCK_SIZE;
+
+const MIN_ZOOM: f64 = 0.5;
+const MAX_ZOOM: f64 = 4.0;
+
+pub struct Camera {
+    pub detached: bool,
+    offset: (f64, f64), // Cells the view is panned away from the board's center
+    zoom: f64,          // On top of the fit-to-window scale
+    view: [f64; 2],     // Window size in logical units
+    origin: [f64; 2],   // Top left corner of the view in the window
+    focus: Option<[f64; 4]>, // Part of the board to frame, in board pixels
+}
+
+impl Camera {
+    pub fn new(board: (i32, i32)) -> Camera {
+        Camera {
+            detached: false,
+            offset: (0.0, 0.0),
+            zoom: 1.0,
+            view: [board.0 as f64 * BLOCK_SIZE, board.1 as f64 * BLOCK_SIZE],
+            origin: [0.0, 0.0],
+            focus: None,
+        }
+    }
+
+    pub fn set_view(&mut self, size: [f64; 2]) {
+        self.view = size;
+    }
+
+    pub fn view(&self) -> [f64; 2] {
+        self.view
+    }
+
+    // Show the board in part of the window, [x, y, width, height], instead of all of it
+    pub fn set_viewport(&mut self, [x, y, width, height]: [f64; 4]) {
+        self.origin = [x, y];
+        self.view = [width, height];
+    }
+
+    // Frame part of the board instead of all of it, None to go back to the whole board
+    pub fn set_focus(&mut self, focus: Option<[f64; 4]>) {
+        self.focus = focus;
+    }
+
+    // Board pixels being framed
+    fn framed(&self, board: (i32, i32)) -> [f64; 4] {
+        self.focus.unwrap_or([
+            0.0,
+            0.0,
+            board.0 as f64 * BLOCK_SIZE,
+            board.1 as f64 * BLOCK_SIZE,
+        ])
+    }
+
+    pub fn toggle_detached(&mut self) {
+        self.detached = !self.detached;
+        if !self.detached {
+            self.offset = (0.0, 0.0);
+            self.zoom = 1.0;
+        }
+    }
+
+    pub fn set_detached(&mut self, detached: bool) {
+        if self.detached != detached {
+            self.toggle_detached();
+        }
+    }
+
+    // Move the view by a number of cells
+    pub fn pan(&mut self, dx: f64, dy: f64) {
+        self.offset.0 += dx / self.zoom;
+        self.offset.1 += dy / self.zoom;
+    }
+
+    pub fn zoom_by(&mut self, factor: f64) {
+        self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+    }
+
+    // Window units per board pixel
+    fn scale(&self, board: (i32, i32)) -> f64 {
+        let framed = self.framed(board);
+        let fit = (self.view[0] / framed[2]).min(self.view[1] / framed[3]);
+        fit * self.zoom
+    }
+
+    // Board pixel (cell * BLOCK_SIZE) at the middle of the window
+    fn center(&self, board: (i32, i32)) -> (f64, f64) {
+        let framed = self.framed(board);
+        (
+            framed[0] + framed[2] / 2.0 + self.offset.0 * BLOCK_SIZE,
+            framed[1] + framed[3] / 2.0 + self.offset.1 * BLOCK_SIZE,
+        )
+    }
+
+    // Map a rectangle in board pixels (cell * BLOCK_SIZE) to the window
+    pub fn to_window(&self, rect: [f64; 4], board: (i32, i32)) -> [f64; 4] {
+        let center = self.center(board);
+        let scale = self.scale(board);
+        [
+            (rect[0] - center.0) * scale + self.view[0] / 2.0 + self.origin[0],
+            (rect[1] - center.1) * scale + self.view[1] / 2.0 + self.origin[1],
+            rect[2] * scale,
+            rect[3] * scale,
+        ]
+    }
+
+    // Whether any of a board cell is inside the window
+    pub fn shows(&self, cell: (i32, i32), board: (i32, i32)) -> bool {
+        let [x, y, w, h] = self.to_window(
+            [
+                cell.0 as f64 * BLOCK_SIZE,
+                cell.1 as f64 * BLOCK_SIZE,
+                BLOCK_SIZE,
+                BLOCK_SIZE,
+            ],
+            board,
+        );
+        let [left, top] = self.origin;
+        x + w > left && y + h > top && x < left + self.view[0] && y < top + self.view[1]
+    }
+
+    // Map a point in the window (e.g. the mouse cursor) to board pixels
+    pub fn to_board(&self, point: [f64; 2], board: (i32, i32)) -> [f64; 2] {
+        let center = self.center(board);
+        let scale = self.scale(board);
+        [
+            (point[0] - self.origin[0] - self.view[0] / 2.0) / scale + center.0,
+            (point[1] - self.origin[1] - self.view[1] / 2.0) / scale + center.1,
+        ]
+    }
+}
@@ -0,0 +1,92 @@
+// Simple charts for the stats screen, drawn from rectangles and lines into a box in
+// window pixels. Every chart scales to its largest value and leaves the titles and
+// axis labels to the caller.
+
+use crate::render::{Color, Renderer};
+use crate::ui::Ui;
+
+const AXIS: Color = [1.0, 1.0, 1.0, 0.4];
+const BAR_GAP: f64 = 0.2; // Share of each bar's slot left empty
+
+// `values` left to right as a line over the box, with a dot on each one
+pub fn line(r: &mut dyn Renderer, area: [f64; 4], values: &[f64], color: Color) {
+    let [left, top, width, height] = area;
+    draw_axes(r, area);
+    let most = values.iter().copied().fold(0.0, f64::max);
+    if values.is_empty() || most <= 0.0 {
+        return;
+    }
+    let step = width / (values.len().max(2) - 1) as f64;
+    let points: Vec<[f64; 2]> = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| [left + i as f64 * step, top + height * (1.0 - value / most)])
+        .collect();
+    for pair in points.windows(2) {
+        r.line(color, 1.5, [pair[0][0], pair[0][1], pair[1][0], pair[1][1]]);
+    }
+    for [x, y] in points {
+        r.rectangle(color, [x - 2.0, y - 2.0, 4.0, 4.0]);
+    }
+}
+
+// One horizontal bar per entry, labelled on the left with its name and on the right
+// with its value
+pub fn bars(r: &mut dyn Renderer, ui: &Ui, area: [f64; 4], bars: &[(String, f64, Color)]) {
+    let [left, top, width, height] = area;
+    let most = bars.iter().map(|(_, value, _)| *value).fold(0.0, f64::max);
+    if bars.is_empty() || most <= 0.0 {
+        return;
+    }
+    let size = ui.font_size(16);
+    let label_width = bars
+        .iter()
+        .map(|(label, _, _)| r.text_width(label, size))
+        .fold(0.0, f64::max)
+        + 10.0;
+    let value_width = r.text_width(&format!("{}", most), size) + 10.0;
+    let slot = (height / bars.len() as f64).min(size as f64 * 2.5);
+    let room = (width - label_width - value_width).max(0.0);
+    for (i, (label, value, color)) in bars.iter().enumerate() {
+        let y = top + i as f64 * slot;
+        let bar = slot * (1.0 - BAR_GAP);
+        let baseline = y + bar / 2.0 + size as f64 * 0.35;
+        r.text(label, size, *color, left, baseline);
+        let length = room * value / most;
+        r.rectangle(*color, [left + label_width, y, length, bar]);
+        let x = left + label_width + length + 6.0;
+        r.text(
+            &format!("{}", value),
+            size,
+            [1.0, 1.0, 1.0, 1.0],
+            x,
+            baseline,
+        );
+    }
+}
+
+// One column per entry, its parts stacked bottom up
+pub fn stacked(r: &mut dyn Renderer, area: [f64; 4], columns: &[Vec<(f64, Color)>]) {
+    let [left, top, width, height] = area;
+    draw_axes(r, area);
+    let total = |parts: &Vec<(f64, Color)>| parts.iter().map(|(value, _)| value).sum::<f64>();
+    let most = columns.iter().map(total).fold(0.0, f64::max);
+    if columns.is_empty() || most <= 0.0 {
+        return;
+    }
+    let slot = width / columns.len() as f64;
+    for (i, parts) in columns.iter().enumerate() {
+        let x = left + i as f64 * slot + slot * BAR_GAP / 2.0;
+        let mut bottom = top + height;
+        for (value, color) in parts {
+            let part = height * value / most;
+            bottom -= part;
+            r.rectangle(*color, [x, bottom, slot * (1.0 - BAR_GAP), part]);
+        }
+    }
+}
+
+fn draw_axes(r: &mut dyn Renderer, [left, top, width, height]: [f64; 4]) {
+    r.line(AXIS, 1.0, [left, top, left, top + height]);
+    r.line(AXIS, 1.0, [left, top + height, left + width, top + height]);
+}
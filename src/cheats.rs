@@ -0,0 +1,95 @@
+// Classic cheat codes, typed on the title screen. They only affect the run that is
+// about to start, and a run with any cheat active can't make the high score table.
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Cheat {
+    Invincible, // Walls, your own body and bad metal can't kill you
+    FatSnake,   // Start at length 20
+}
+
+const CODES: [(&str, Cheat); 2] = [
+    ("RUSTGOD", Cheat::Invincible),
+    ("FATSNAKE", Cheat::FatSnake),
+];
+
+impl Cheat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Cheat::Invincible => "God mode",
+            Cheat::FatSnake => "Fat snake",
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Cheats {
+    pub invincible: bool,
+    pub fat_snake: bool,
+}
+
+impl Cheats {
+    pub fn any(&self) -> bool {
+        self.invincible || self.fat_snake
+    }
+
+    pub fn activate(&mut self, cheat: Cheat) {
+        match cheat {
+            Cheat::Invincible => self.invincible = true,
+            Cheat::FatSnake => self.fat_snake = true,
+        }
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        let mut names = Vec::new();
+        if self.invincible {
+            names.push(Cheat::Invincible.name());
+        }
+        if self.fat_snake {
+            names.push(Cheat::FatSnake.name());
+        }
+        names
+    }
+}
+
+pub enum CheatInput {
+    Activated(Cheat),
+    Partial, // Part of a code, the key should not do anything else
+    NoMatch,
+}
+
+// Letters typed so far that are the start of some code
+#[derive(Default)]
+pub struct CheatBuffer {
+    typed: String,
+}
+
+impl CheatBuffer {
+    pub fn new() -> CheatBuffer {
+        CheatBuffer {
+            typed: String::new(),
+        }
+    }
+
+    pub fn push(&mut self, c: char) -> CheatInput {
+        self.typed.push(c);
+        if !is_prefix(&self.typed) {
+            // Maybe this letter starts a new code
+            self.typed = c.to_string();
+            if !is_prefix(&self.typed) {
+                self.typed.clear();
+                return CheatInput::NoMatch;
+            }
+        }
+        match CODES.iter().find(|(code, _)| *code == self.typed) {
+            Some((_, cheat)) => {
+                self.typed.clear();
+                CheatInput::Activated(*cheat)
+            }
+            None => CheatInput::Partial,
+        }
+    }
+}
+
+fn is_prefix(typed: &str) -> bool {
+    CODES.iter().any(|(code, _)| code.starts_with(typed))
+}
@@ -0,0 +1,101 @@
+// Command line flags
+
+use crate::ai::Difficulty;
+use crate::roguelite::GameMode;
+use std::path::PathBuf;
+
+const USAGE: &str =
+    "Usage: snake_game [--dev] [--profile] [--record-input <file>] [--replay-input <file>] \
+     [--score-server <port>] [--import-scores <file>] [--import-level <file>] \
+     [--bot easy|medium|hard] [--log-transitions <file>] [--verify <file>] \
+     [--best-score <mode>[:<width>x<height>]]";
+
+#[derive(Default)]
+pub struct Options {
+    pub dev: bool,                        // Enable developer tools in release builds
+    pub profile: bool,                    // Start with the frame timing overlay shown
+    pub record_input: Option<PathBuf>,    // Write every input event plus the seed to this file
+    pub replay_input: Option<PathBuf>,    // Play back a file written by --record-input
+    pub score_server: Option<u16>,        // Serve the high scores and game status on this port
+    pub import_scores: Option<PathBuf>,   // High score file from another machine to merge in
+    pub import_level: Option<PathBuf>,    // Level file to add to the community levels
+    pub bot: Option<Difficulty>,          // Let the computer player steer, to watch it play
+    pub log_transitions: Option<PathBuf>, // Write training data from bot games here and exit
+    pub verify: Option<PathBuf>, // Play back a --record-input file without a window and exit
+    pub best_score: Option<(GameMode, Option<(i32, i32)>)>, // Look up in the database and exit
+}
+
+impl Options {
+    // Parse the process arguments, exiting with a usage message if they don't make sense
+    pub fn from_args() -> Options {
+        match Options::parse(std::env::args().skip(1)) {
+            Ok(options) => options,
+            Err(e) => {
+                eprintln!("Error: {}\n{}", e, USAGE);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--dev" => options.dev = true,
+                "--profile" => options.profile = true,
+                "--record-input" => options.record_input = Some(path_value(&arg, args.next())?),
+                "--replay-input" => options.replay_input = Some(path_value(&arg, args.next())?),
+                "--log-transitions" => {
+                    options.log_transitions = Some(path_value(&arg, args.next())?)
+                }
+                "--verify" => options.verify = Some(path_value(&arg, args.next())?),
+                "--import-scores" => options.import_scores = Some(path_value(&arg, args.next())?),
+                "--import-level" => options.import_level = Some(path_value(&arg, args.next())?),
+                "--score-server" => {
+                    let port = args.next().and_then(|port| port.parse().ok());
+                    options.score_server = Some(port.ok_or("--score-server needs a port number")?);
+                }
+                "--best-score" => {
+                    let query = args.next().and_then(|value| best_score_query(&value));
+                    options.best_score =
+                        Some(query.ok_or("--best-score needs a mode, e.g. classic:30x20")?);
+                }
+                "--bot" => {
+                    let difficulty = args.next().and_then(|value| Difficulty::parse(&value));
+                    options.bot = Some(difficulty.ok_or("--bot needs easy, medium or hard")?);
+                }
+                "--help" | "-h" => {
+                    println!("{}", USAGE);
+                    std::process::exit(0);
+                }
+                _ => return Err(format!("unknown argument '{}'", arg)),
+            }
+        }
+        if options.record_input.is_some() && options.replay_input.is_some() {
+            return Err("--record-input and --replay-input can't be combined".to_string());
+        }
+        Ok(options)
+    }
+}
+
+// "roguelite" or "roguelite:15x10"
+fn best_score_query(value: &str) -> Option<(GameMode, Option<(i32, i32)>)> {
+    let (mode, board) = match value.split_once(':') {
+        Some((mode, board)) => (mode, Some(board)),
+        None => (value, None),
+    };
+    let board = match board {
+        Some(board) => {
+            let (width, height) = board.split_once('x')?;
+            Some((width.parse().ok()?, height.parse().ok()?))
+        }
+        None => None,
+    };
+    Some((GameMode::parse(mode)?, board))
+}
+
+fn path_value(flag: &str, value: Option<String>) -> Result<PathBuf, String> {
+    value
+        .map(PathBuf::from)
+        .ok_or_else(|| format!("{} needs a file name", flag))
+}
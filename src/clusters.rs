@@ -0,0 +1,29 @@
+// Scrap clusters: now and then three or four pieces of rusty scrap turn up side by
+// side. Each piece pays like any scrap, and eating the lot within CLUSTER_MOVES moves
+// pays CLUSTER_BONUS on top. Whatever is left when time runs out rusts away together.
+
+pub const CLUSTER_CHANCE: u32 = 300; // 1 in this many moves, with no cluster around
+pub const CLUSTER_SIZES: (usize, usize) = (3, 4);
+pub const CLUSTER_MOVES: u32 = 40;
+pub const CLUSTER_BONUS: u32 = 10;
+
+// The pieces on the board carry its id, see `Food::cluster`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cluster {
+    pub id: u32,
+    pub moves_left: u32,
+}
+
+impl Cluster {
+    pub fn new(id: u32) -> Cluster {
+        Cluster {
+            id,
+            moves_left: CLUSTER_MOVES,
+        }
+    }
+
+    // How much time is left, 1.0 when new down to nothing
+    pub fn time_left(&self) -> f64 {
+        self.moves_left as f64 / CLUSTER_MOVES as f64
+    }
+}
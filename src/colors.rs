@@ -0,0 +1,106 @@
+// Custom snake colors. The player picks one base color, from a preset or by mixing
+// red, green and blue, and the head, stomach and tail shades are derived from it the
+// same way the default palette's greens relate to each other. Stored in the profile
+// as `snake_color=#rrggbb`.
+
+use crate::render::Color;
+use crate::seasons::Palette;
+
+pub const PRESETS: [(&str, Color); 6] = [
+    ("Green", [0.0, 1.0, 0.0, 1.0]),
+    ("Blue", [0.2, 0.5, 1.0, 1.0]),
+    ("Red", [1.0, 0.2, 0.15, 1.0]),
+    ("Gold", [1.0, 0.8, 0.1, 1.0]),
+    ("Purple", [0.7, 0.3, 1.0, 1.0]),
+    ("Pink", [1.0, 0.45, 0.75, 1.0]),
+];
+
+// Steps of one channel in the picker, 0 to 15
+pub const CHANNEL_STEPS: u32 = 15;
+
+// Rows of the picker screen
+pub const ROWS: [&str; 4] = ["Preset", "Red", "Green", "Blue"];
+
+// `palette` with its snake shades replaced by ones derived from `base`
+pub fn derive(base: Color, palette: &Palette) -> Palette {
+    let shade = |factor: f32| [base[0] * factor, base[1] * factor, base[2] * factor, 1.0];
+    Palette {
+        background: palette.background,
+        head: shade(0.7),
+        full_stomach: shade(1.0),
+        empty_stomach: shade(0.8),
+        tail: shade(0.5),
+    }
+}
+
+// `#rrggbb`
+pub fn parse(value: &str) -> Option<Color> {
+    let hex = value.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([
+        channel(0)? as f32 / 255.0,
+        channel(2)? as f32 / 255.0,
+        channel(4)? as f32 / 255.0,
+        1.0,
+    ])
+}
+
+pub fn hex(color: Color) -> String {
+    let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        channel(color[0]),
+        channel(color[1]),
+        channel(color[2])
+    )
+}
+
+// Color picker screen state: the row being edited and the color so far. `None` is the
+// theme's own colors.
+pub struct ColorPicker {
+    pub row: usize,
+    pub color: Option<Color>,
+}
+
+impl ColorPicker {
+    pub fn new(color: Option<Color>) -> ColorPicker {
+        ColorPicker { row: 0, color }
+    }
+
+    // Name of the current preset, if the color is one
+    pub fn preset_name(&self) -> &'static str {
+        match self.color {
+            None => "Theme",
+            Some(color) => PRESETS
+                .iter()
+                .find(|(_, preset)| hex(*preset) == hex(color))
+                .map_or("Custom", |(name, _)| name),
+        }
+    }
+
+    // Left/right on the current row: step through the presets (with the theme's own
+    // colors at the start) or nudge one channel
+    pub fn adjust(&mut self, step: i32) {
+        if self.row == 0 {
+            let current = self.color.and_then(|color| {
+                PRESETS
+                    .iter()
+                    .position(|(_, preset)| hex(*preset) == hex(color))
+            });
+            // Index 0 is the theme, presets follow
+            let count = PRESETS.len() as i32 + 1;
+            let index = current.map_or(0, |i| i as i32 + 1);
+            let next = (index + step).rem_euclid(count);
+            self.color = (next > 0).then(|| PRESETS[next as usize - 1].1);
+        } else {
+            let mut color = self.color.unwrap_or(PRESETS[0].1);
+            let channel = &mut color[self.row - 1];
+            let level = (*channel * CHANNEL_STEPS as f32).round() as i32 + step;
+            *channel = level.clamp(0, CHANNEL_STEPS as i32) as f32 / CHANNEL_STEPS as f32;
+            self.color = Some(color);
+        }
+    }
+}
@@ -0,0 +1,114 @@
+// Developer console, opened with the backtick key in debug builds or with `--dev`.
+// Commands poke at the game directly to set up situations that are tedious to reach
+// by playing:
+//
+//     spawn <scrap|metal|water|pumpkin> <x> <y>
+//                                         put a food on a cell
+//     grow <n>                            add n segments
+//     speed <frames>                      frames between snake moves (lower is faster)
+//     teleport <x> <y>                    move the snake so its head is on a cell
+//     seed <n>                            restart with a fixed seed
+//     help                                list commands
+
+use crate::app::App;
+use crate::{Food, FoodType, Game};
+
+const MAX_OUTPUT_LINES: usize = 6;
+
+pub struct Console {
+    pub open: bool,
+    pub input: String,
+    pub output: Vec<String>, // Most recent last
+}
+
+impl Console {
+    pub fn new() -> Console {
+        Console {
+            open: false,
+            input: String::new(),
+            output: Vec::new(),
+        }
+    }
+
+    pub fn print(&mut self, line: String) {
+        self.output.push(line);
+        if self.output.len() > MAX_OUTPUT_LINES {
+            self.output.remove(0);
+        }
+    }
+}
+
+pub fn run(app: &mut App, command: &str) -> Result<String, String> {
+    let words: Vec<&str> = command.split_whitespace().collect();
+    let game = &mut app.game;
+    match words.as_slice() {
+        ["help"] => Ok(
+            "spawn <scrap|metal|water|pumpkin> x y, grow n, speed n, teleport x y, seed n".into(),
+        ),
+        ["spawn", food, x, y] => {
+            let food_type =
+                FoodType::parse(food).ok_or_else(|| format!("unknown food '{}'", food))?;
+            let position = cell(game, x, y)?;
+            if game.snake.body.iter().any(|seg| seg.position == position)
+                || game.foods.iter().any(|f| f.position == position)
+            {
+                return Err("cell is occupied".into());
+            }
+            game.foods.push(Food {
+                position,
+                food_type,
+                cluster: None,
+            });
+            Ok(format!("spawned {} at {} {}", food, x, y))
+        }
+        ["grow", n] => {
+            let n: usize = number(n)?;
+            for _ in 0..n {
+                game.grow();
+            }
+            Ok(format!("length {}", game.snake.body.len()))
+        }
+        ["speed", frames] => {
+            let frames: u64 = number(frames)?;
+            if frames == 0 {
+                return Err("speed must be at least 1 frame".into());
+            }
+            game.tick_interval = frames;
+            game.base_interval = frames;
+            Ok(format!("moving every {} frames", frames))
+        }
+        ["teleport", x, y] => {
+            let (x, y) = cell(game, x, y)?;
+            let (head_x, head_y) = game.snake.body[0].position;
+            // Shift the whole body so it keeps its shape
+            for segment in &mut game.snake.body {
+                let (sx, sy) = segment.position;
+                segment.position = (
+                    (sx + x - head_x).rem_euclid(game.width),
+                    (sy + y - head_y).rem_euclid(game.height),
+                );
+            }
+            Ok(format!("head at {} {}", x, y))
+        }
+        ["seed", seed] => {
+            let seed: u64 = number(seed)?;
+            app.restart_with_seed(seed);
+            Ok(format!("restarted with seed {}", seed))
+        }
+        [] => Ok(String::new()),
+        _ => Err(format!("unknown command '{}', try help", command)),
+    }
+}
+
+fn number<T: std::str::FromStr>(word: &str) -> Result<T, String> {
+    word.parse()
+        .map_err(|_| format!("'{}' is not a valid number", word))
+}
+
+fn cell(game: &Game, x: &str, y: &str) -> Result<(i32, i32), String> {
+    let (x, y): (i32, i32) = (number(x)?, number(y)?);
+    if !(0..game.width).contains(&x) || !(0..game.height).contains(&y) {
+        return Err(format!("{} {} is off the board", x, y));
+    }
+    Ok((x, y))
+}
@@ -0,0 +1,327 @@
+// Input to direction resolution for the different control schemes
+
+use crate::keys::Key;
+use crate::{Direction, BLOCK_SIZE};
+use std::time::{Duration, Instant};
+
+const SWIPE_DISTANCE: f64 = 30.0; // Pixels a finger must travel to count as a swipe
+const TAP_DISTANCE: f64 = 10.0;
+const TAP_TIME: Duration = Duration::from_millis(250);
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ControlScheme {
+    Keyboard,
+    Mouse, // Turn toward the cursor's quadrant relative to the head on every tick
+}
+
+impl ControlScheme {
+    pub fn parse(value: &str) -> Option<ControlScheme> {
+        match value {
+            "keyboard" => Some(ControlScheme::Keyboard),
+            "mouse" => Some(ControlScheme::Mouse),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ControlPreset {
+    Arrows, // Right-handed
+    Wasd,   // Left-handed
+    Numpad, // One-handed
+    Esdf,
+}
+
+pub const PRESETS: [ControlPreset; 4] = [
+    ControlPreset::Arrows,
+    ControlPreset::Wasd,
+    ControlPreset::Numpad,
+    ControlPreset::Esdf,
+];
+
+impl ControlPreset {
+    pub fn parse(value: &str) -> Option<ControlPreset> {
+        match value {
+            "arrows" => Some(ControlPreset::Arrows),
+            "wasd" => Some(ControlPreset::Wasd),
+            "numpad" => Some(ControlPreset::Numpad),
+            "esdf" => Some(ControlPreset::Esdf),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ControlPreset::Arrows => "Arrow keys (right-handed)",
+            ControlPreset::Wasd => "WASD (left-handed)",
+            ControlPreset::Numpad => "Numpad (one-handed)",
+            ControlPreset::Esdf => "ESDF",
+        }
+    }
+}
+
+// Actions that can be remapped with `bind_<action>` in the settings file
+pub const ACTIONS: [&str; 6] = ["up", "down", "left", "right", "pause", "spit"];
+
+// Keys bound to each action. Starts from a preset, individual actions can then be
+// remapped from the settings file.
+#[derive(Clone)]
+pub struct KeyBindings {
+    pub preset: ControlPreset,
+    pub up: Vec<Key>,
+    pub down: Vec<Key>,
+    pub left: Vec<Key>,
+    pub right: Vec<Key>,
+    pub pause: Vec<Key>,
+    pub spit: Vec<Key>,
+}
+
+impl KeyBindings {
+    pub fn from_preset(preset: ControlPreset) -> KeyBindings {
+        let ([up, left, down, right], pause, spit) = match preset {
+            ControlPreset::Arrows => (
+                [Key::Up, Key::Left, Key::Down, Key::Right],
+                Key::P,
+                Key::Space,
+            ),
+            ControlPreset::Wasd => ([Key::W, Key::A, Key::S, Key::D], Key::Q, Key::Space),
+            ControlPreset::Numpad => (
+                [Key::NumPad8, Key::NumPad4, Key::NumPad5, Key::NumPad6],
+                Key::NumPad0,
+                Key::NumPadPlus,
+            ),
+            ControlPreset::Esdf => ([Key::E, Key::S, Key::D, Key::F], Key::W, Key::Space),
+        };
+        KeyBindings {
+            preset,
+            up: vec![up],
+            down: vec![down],
+            left: vec![left],
+            right: vec![right],
+            pause: vec![pause],
+            spit: vec![spit],
+        }
+    }
+
+    // Replace the keys for one action, returns false for an unknown action
+    pub fn bind(&mut self, action: &str, keys: Vec<Key>) -> bool {
+        let slot = match action {
+            "up" => &mut self.up,
+            "down" => &mut self.down,
+            "left" => &mut self.left,
+            "right" => &mut self.right,
+            "pause" => &mut self.pause,
+            "spit" => &mut self.spit,
+            _ => return false,
+        };
+        *slot = keys;
+        true
+    }
+
+    pub fn direction(&self, key: Key) -> Option<Direction> {
+        if self.up.contains(&key) {
+            Some(Direction::Up)
+        } else if self.down.contains(&key) {
+            Some(Direction::Down)
+        } else if self.left.contains(&key) {
+            Some(Direction::Left)
+        } else if self.right.contains(&key) {
+            Some(Direction::Right)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_pause(&self, key: Key) -> bool {
+        self.pause.contains(&key)
+    }
+
+    pub fn is_spit(&self, key: Key) -> bool {
+        self.spit.contains(&key)
+    }
+
+    // Short description of the steering keys, e.g. "W/A/S/D"
+    pub fn steer_keys(&self) -> String {
+        [&self.up, &self.left, &self.down, &self.right]
+            .iter()
+            .filter_map(|keys| keys.first())
+            .map(|key| key_name(*key))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    // HUD hint text
+    pub fn hint(&self) -> String {
+        let pause = self.pause.first().map_or(String::new(), |k| key_name(*k));
+        let spit = self.spit.first().map_or(String::new(), |k| key_name(*k));
+        format!(
+            "Steer {}  Pause {}  Spit {}",
+            self.steer_keys(),
+            pause,
+            spit
+        )
+    }
+}
+
+// Keys that may be named in the settings file
+#[rustfmt::skip]
+const BINDABLE_KEYS: [Key; 56] = [
+    Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G, Key::H, Key::I, Key::J,
+    Key::K, Key::L, Key::M, Key::N, Key::O, Key::P, Key::Q, Key::R, Key::S, Key::T,
+    Key::U, Key::V, Key::W, Key::X, Key::Y, Key::Z,
+    Key::D0, Key::D1, Key::D2, Key::D3, Key::D4, Key::D5, Key::D6, Key::D7, Key::D8, Key::D9,
+    Key::NumPad0, Key::NumPad1, Key::NumPad2, Key::NumPad3, Key::NumPad4,
+    Key::NumPad5, Key::NumPad6, Key::NumPad7, Key::NumPad8, Key::NumPad9,
+    Key::Up, Key::Down, Key::Left, Key::Right, Key::Space, Key::Tab,
+    Key::LShift, Key::RShift, Key::LCtrl, Key::RCtrl,
+];
+
+// Look a key up by its name as written in the settings file ("W", "NumPad8", "Up", ...)
+pub fn parse_key(name: &str) -> Option<Key> {
+    BINDABLE_KEYS
+        .iter()
+        .find(|key| format!("{:?}", key).eq_ignore_ascii_case(name))
+        .copied()
+}
+
+pub fn key_name(key: Key) -> String {
+    match key {
+        Key::NumPad0
+        | Key::NumPad1
+        | Key::NumPad2
+        | Key::NumPad3
+        | Key::NumPad4
+        | Key::NumPad5
+        | Key::NumPad6
+        | Key::NumPad7
+        | Key::NumPad8
+        | Key::NumPad9 => format!("{:?}", key).replace("NumPad", "Num"),
+        Key::Up => "\u{2191}".to_string(),
+        Key::Down => "\u{2193}".to_string(),
+        Key::Left => "\u{2190}".to_string(),
+        Key::Right => "\u{2192}".to_string(),
+        _ => format!("{:?}", key),
+    }
+}
+
+// Pick the direction pointing from the head toward the cursor (in window pixels).
+// The dominant axis wins, unless that would reverse the snake into itself, in which
+// case it turns along the other axis. Returns None when the cursor is on the head.
+pub fn mouse_direction(
+    head: (i32, i32),
+    cursor: [f64; 2],
+    current: &Direction,
+) -> Option<Direction> {
+    let dx = cursor[0] / BLOCK_SIZE - (head.0 as f64 + 0.5);
+    let dy = cursor[1] / BLOCK_SIZE - (head.1 as f64 + 0.5);
+    if dx.abs() < 0.5 && dy.abs() < 0.5 {
+        return None;
+    }
+
+    let horizontal = if dx > 0.0 {
+        Direction::Right
+    } else {
+        Direction::Left
+    };
+    let vertical = if dy > 0.0 {
+        Direction::Down
+    } else {
+        Direction::Up
+    };
+    let (primary, secondary, secondary_amount) = if dx.abs() >= dy.abs() {
+        (horizontal, vertical, dy)
+    } else {
+        (vertical, horizontal, dx)
+    };
+
+    if primary != current.opposite() {
+        Some(primary)
+    } else if secondary_amount != 0.0 {
+        Some(secondary)
+    } else {
+        None
+    }
+}
+
+pub enum Gesture {
+    Swipe(Direction),
+    Tap,
+}
+
+// Turns raw press/move/release positions (touch or mouse drag) into swipes and taps.
+// Only one pointer is tracked at a time; extra fingers are ignored.
+pub struct SwipeDetector {
+    pointer: Option<i64>,
+    anchor: [f64; 2], // Where the current swipe is measured from
+    started: Instant,
+    swiped: bool,
+}
+
+impl SwipeDetector {
+    pub fn new() -> SwipeDetector {
+        SwipeDetector {
+            pointer: None,
+            anchor: [0.0, 0.0],
+            started: Instant::now(),
+            swiped: false,
+        }
+    }
+
+    pub fn begin(&mut self, pointer: i64, position: [f64; 2]) {
+        if self.pointer.is_none() {
+            self.pointer = Some(pointer);
+            self.anchor = position;
+            self.started = Instant::now();
+            self.swiped = false;
+        }
+    }
+
+    // A swipe fires as soon as the pointer has travelled far enough, then re-anchors
+    // so a single long drag can chain several turns
+    pub fn moved(&mut self, pointer: i64, position: [f64; 2]) -> Option<Gesture> {
+        if self.pointer != Some(pointer) {
+            return None;
+        }
+        let dx = position[0] - self.anchor[0];
+        let dy = position[1] - self.anchor[1];
+        if dx.abs().max(dy.abs()) < SWIPE_DISTANCE {
+            return None;
+        }
+        self.anchor = position;
+        self.swiped = true;
+        let direction = if dx.abs() >= dy.abs() {
+            if dx > 0.0 {
+                Direction::Right
+            } else {
+                Direction::Left
+            }
+        } else if dy > 0.0 {
+            Direction::Down
+        } else {
+            Direction::Up
+        };
+        Some(Gesture::Swipe(direction))
+    }
+
+    pub fn end(&mut self, pointer: i64, position: [f64; 2]) -> Option<Gesture> {
+        if self.pointer != Some(pointer) {
+            return None;
+        }
+        let gesture = self.moved(pointer, position);
+        self.pointer = None;
+        if gesture.is_some() {
+            return gesture;
+        }
+        let dx = position[0] - self.anchor[0];
+        let dy = position[1] - self.anchor[1];
+        if !self.swiped && dx.hypot(dy) < TAP_DISTANCE && self.started.elapsed() < TAP_TIME {
+            Some(Gesture::Tap)
+        } else {
+            None
+        }
+    }
+
+    pub fn cancel(&mut self) {
+        self.pointer = None;
+    }
+}
@@ -0,0 +1,361 @@
+// Everything the game keeps between sessions in one SQLite database, for questions the
+// flat files make awkward, like the best score in roguelite mode on the mini board.
+// Built with the `sqlite` feature; without it nothing is written and every query comes
+// back empty.
+//
+// The flat files stay what the game loads from, so a build without the feature still
+// finds everything. The database is brought in step with them when it opens (which is
+// also how the files get migrated the first time) and after that every change is
+// written to both. Tables:
+//
+//     high_scores  board, rank, name, score, mutators, perfect, time
+//     deaths       x, y, cause
+//     runs         time, score, mode, width, height, and one column per food
+//     profile      key, value, as in the profile file
+//     unlocks      skin
+//     replays      path, seed, season, started, runs, best
+//
+// Runs from before the database have no mode or board.
+
+use crate::events::{GameEvent, Subscriber};
+use crate::profile::Profile;
+use crate::roguelite::GameMode;
+use crate::stats::Run;
+use crate::Game;
+use std::path::Path;
+
+#[cfg(feature = "sqlite")]
+mod backend {
+    use crate::game::{self, DeathCause, HighScoreEntry, HIGH_SCORE_TABLES};
+    use crate::profile::Profile;
+    use crate::stats::{Run, Stats};
+    use rusqlite::{params, Connection, OptionalExtension};
+    use std::path::Path;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    const DATABASE_FILE: &str = "rusty_snake.db";
+
+    const SCHEMA: &str = "
+        CREATE TABLE IF NOT EXISTS meta (key TEXT PRIMARY KEY, value INTEGER NOT NULL);
+        CREATE TABLE IF NOT EXISTS high_scores (
+            board TEXT NOT NULL, rank INTEGER NOT NULL, name TEXT NOT NULL,
+            score INTEGER NOT NULL, mutators TEXT NOT NULL, perfect INTEGER NOT NULL,
+            time INTEGER NOT NULL);
+        CREATE TABLE IF NOT EXISTS deaths (
+            x INTEGER NOT NULL, y INTEGER NOT NULL, cause TEXT NOT NULL);
+        CREATE TABLE IF NOT EXISTS runs (
+            time INTEGER NOT NULL, score INTEGER NOT NULL, mode TEXT, width INTEGER,
+            height INTEGER, scrap INTEGER NOT NULL, metal INTEGER NOT NULL,
+            water INTEGER NOT NULL, pumpkin INTEGER NOT NULL);
+        CREATE INDEX IF NOT EXISTS runs_by_mode ON runs (mode, width, height, score);
+        CREATE TABLE IF NOT EXISTS profile (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+        CREATE TABLE IF NOT EXISTS unlocks (skin TEXT PRIMARY KEY);
+        CREATE TABLE IF NOT EXISTS replays (
+            path TEXT NOT NULL, seed INTEGER NOT NULL, season TEXT,
+            started INTEGER NOT NULL, runs INTEGER NOT NULL, best INTEGER NOT NULL);
+    ";
+
+    pub struct Store(Option<Connection>);
+
+    impl Store {
+        pub fn open() -> Store {
+            match open() {
+                Ok(connection) => Store(Some(connection)),
+                Err(e) => {
+                    eprintln!("Error opening {}: {}", DATABASE_FILE, e);
+                    Store(None)
+                }
+            }
+        }
+
+        // Run one change, saying so if it fails. Nothing is lost: the files have it.
+        fn write(&mut self, change: impl FnOnce(&mut Connection) -> rusqlite::Result<()>) {
+            if let Some(connection) = self.0.as_mut() {
+                if let Err(e) = change(connection) {
+                    eprintln!("Error writing {}: {}", DATABASE_FILE, e);
+                }
+            }
+        }
+
+        pub fn sync_high_scores(&mut self) {
+            self.write(sync_high_scores);
+        }
+
+        pub fn sync_profile(&mut self, profile: &Profile) {
+            self.write(|connection| sync_profile(connection, profile));
+        }
+
+        pub fn record_death(&mut self, cell: (i32, i32), cause: DeathCause) {
+            self.write(|connection| {
+                let tx = connection.transaction()?;
+                insert_death(&tx, cell, cause)?;
+                bump(&tx, "deaths", 1)?;
+                tx.commit()
+            });
+        }
+
+        pub fn record_run(&mut self, run: &Run, mode: &str, board: (i32, i32)) {
+            self.write(|connection| {
+                let tx = connection.transaction()?;
+                insert_run(&tx, run, Some((mode, board)))?;
+                bump(&tx, "runs", 1)?;
+                tx.commit()
+            });
+        }
+
+        pub fn record_replay(&mut self, path: &Path, seed: u64, season: Option<&str>) {
+            self.write(|connection| {
+                connection.execute(
+                    "INSERT INTO replays VALUES (?1, ?2, ?3, ?4, 0, 0)",
+                    params![path.to_string_lossy(), seed as i64, season, now() as i64],
+                )?;
+                Ok(())
+            });
+        }
+
+        // A run of the replay recorded last ended with `score`
+        pub fn replay_run(&mut self, score: u32) {
+            self.write(|connection| {
+                connection.execute(
+                    "UPDATE replays SET runs = runs + 1, best = MAX(best, ?1)
+                     WHERE rowid = (SELECT MAX(rowid) FROM replays)",
+                    params![score],
+                )?;
+                Ok(())
+            });
+        }
+
+        pub fn best_score(&self, mode: &str, board: Option<(i32, i32)>) -> Option<u32> {
+            let connection = self.0.as_ref()?;
+            let (width, height) = board.unzip();
+            connection
+                .query_row(
+                    "SELECT MAX(score) FROM runs WHERE mode = ?1
+                     AND (?2 IS NULL OR width = ?2) AND (?3 IS NULL OR height = ?3)",
+                    params![mode, width, height],
+                    |row| row.get(0),
+                )
+                .optional()
+                .unwrap_or_else(|e| {
+                    eprintln!("Error reading {}: {}", DATABASE_FILE, e);
+                    None
+                })
+                .flatten()
+        }
+    }
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs())
+    }
+
+    fn open() -> rusqlite::Result<Connection> {
+        let mut connection = Connection::open(DATABASE_FILE)?;
+        connection.execute_batch(SCHEMA)?;
+        sync_high_scores(&mut connection)?;
+        sync_profile(&mut connection, &Profile::load())?;
+        sync_stats(&mut connection)?;
+        Ok(connection)
+    }
+
+    // The tables are short, so each is simply replaced with what its file holds
+    fn sync_high_scores(connection: &mut Connection) -> rusqlite::Result<()> {
+        let tx = connection.transaction()?;
+        tx.execute("DELETE FROM high_scores", [])?;
+        for (board, file) in HIGH_SCORE_TABLES {
+            let entries = game::read_high_scores(Path::new(file)).unwrap_or_default();
+            for (rank, entry) in entries.iter().enumerate() {
+                insert_high_score(&tx, board, rank + 1, entry)?;
+            }
+        }
+        tx.commit()
+    }
+
+    fn insert_high_score(
+        tx: &rusqlite::Transaction,
+        board: &str,
+        rank: usize,
+        entry: &HighScoreEntry,
+    ) -> rusqlite::Result<()> {
+        tx.execute(
+            "INSERT INTO high_scores VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                board,
+                rank as i64,
+                entry.name,
+                entry.score,
+                entry.mutators.ids(),
+                entry.perfect,
+                entry.time as i64
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn sync_profile(connection: &mut Connection, profile: &Profile) -> rusqlite::Result<()> {
+        let tx = connection.transaction()?;
+        tx.execute("DELETE FROM profile", [])?;
+        for (key, value) in profile.entries() {
+            tx.execute("INSERT INTO profile VALUES (?1, ?2)", params![key, value])?;
+        }
+        tx.execute("DELETE FROM unlocks", [])?;
+        for skin in &profile.unlocked {
+            tx.execute("INSERT INTO unlocks VALUES (?1)", params![skin.id()])?;
+        }
+        tx.commit()
+    }
+
+    // The stats file only ever grows, so whatever it holds past the deaths and runs
+    // already copied is new. The first time that's all of it.
+    fn sync_stats(connection: &mut Connection) -> rusqlite::Result<()> {
+        let stats = Stats::load();
+        let tx = connection.transaction()?;
+        let copied = |name: &str| -> rusqlite::Result<usize> {
+            let count: Option<i64> = tx
+                .query_row("SELECT value FROM meta WHERE key = ?1", [name], |row| {
+                    row.get(0)
+                })
+                .optional()?;
+            Ok(count.unwrap_or(0) as usize)
+        };
+        let (deaths, runs) = (copied("deaths")?, copied("runs")?);
+        for death in stats.deaths.iter().skip(deaths) {
+            insert_death(&tx, death.cell, death.cause)?;
+        }
+        for run in stats.runs.iter().skip(runs) {
+            insert_run(&tx, run, None)?;
+        }
+        bump(&tx, "deaths", stats.deaths.len().saturating_sub(deaths))?;
+        bump(&tx, "runs", stats.runs.len().saturating_sub(runs))?;
+        tx.commit()
+    }
+
+    fn insert_death(
+        tx: &rusqlite::Transaction,
+        cell: (i32, i32),
+        cause: DeathCause,
+    ) -> rusqlite::Result<()> {
+        tx.execute(
+            "INSERT INTO deaths VALUES (?1, ?2, ?3)",
+            params![cell.0, cell.1, cause.id()],
+        )?;
+        Ok(())
+    }
+
+    fn insert_run(
+        tx: &rusqlite::Transaction,
+        run: &Run,
+        played: Option<(&str, (i32, i32))>,
+    ) -> rusqlite::Result<()> {
+        let [scrap, metal, water, pumpkin] = run.foods;
+        let (mode, board) = played.unzip();
+        let (width, height) = board.unzip();
+        tx.execute(
+            "INSERT INTO runs VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                run.time as i64,
+                run.score,
+                mode,
+                width,
+                height,
+                scrap,
+                metal,
+                water,
+                pumpkin
+            ],
+        )?;
+        Ok(())
+    }
+
+    // Count `added` more of `name` as copied from the stats file
+    fn bump(tx: &rusqlite::Transaction, name: &str, added: usize) -> rusqlite::Result<()> {
+        tx.execute(
+            "INSERT INTO meta VALUES (?1, ?2)
+             ON CONFLICT (key) DO UPDATE SET value = value + ?2",
+            params![name, added as i64],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "sqlite"))]
+mod backend {
+    use crate::game::DeathCause;
+    use crate::profile::Profile;
+    use crate::stats::Run;
+    use std::path::Path;
+
+    pub struct Store;
+
+    impl Store {
+        pub fn open() -> Store {
+            Store
+        }
+
+        pub fn sync_high_scores(&mut self) {}
+
+        pub fn sync_profile(&mut self, _profile: &Profile) {}
+
+        pub fn record_death(&mut self, _cell: (i32, i32), _cause: DeathCause) {}
+
+        pub fn record_run(&mut self, _run: &Run, _mode: &str, _board: (i32, i32)) {}
+
+        pub fn record_replay(&mut self, _path: &Path, _seed: u64, _season: Option<&str>) {}
+
+        pub fn replay_run(&mut self, _score: u32) {}
+
+        pub fn best_score(&self, _mode: &str, _board: Option<(i32, i32)>) -> Option<u32> {
+            None
+        }
+    }
+}
+
+pub struct Database {
+    store: backend::Store,
+}
+
+impl Database {
+    pub fn open() -> Database {
+        Database {
+            store: backend::Store::open(),
+        }
+    }
+
+    // After any change to a high score table; the tables are read back from their files
+    pub fn high_scores_changed(&mut self) {
+        self.store.sync_high_scores();
+    }
+
+    pub fn profile_saved(&mut self, profile: &Profile) {
+        self.store.sync_profile(profile);
+    }
+
+    // `board` is the size the run started on, before any mutator changed it
+    pub fn record_run(&mut self, run: &Run, mode: GameMode, board: (i32, i32)) {
+        self.store.record_run(run, mode.id(), board);
+    }
+
+    // An input log was started at `path`
+    pub fn record_replay(&mut self, path: &Path, seed: u64, season: Option<&str>) {
+        self.store.record_replay(path, seed, season);
+    }
+
+    // A run ended while an input log was being recorded
+    pub fn replay_run(&mut self, score: u32) {
+        self.store.replay_run(score);
+    }
+
+    // Best recorded run in `mode`, on a board of that size if one is given
+    pub fn best_score(&self, mode: GameMode, board: Option<(i32, i32)>) -> Option<u32> {
+        self.store.best_score(mode.id(), board)
+    }
+}
+
+impl Subscriber for Database {
+    fn notify(&mut self, game: &Game, event: &GameEvent) {
+        if let (GameEvent::Died(cause), true) = (event, game.save_scores) {
+            self.store.record_death(game.snake.body[0].position, *cause);
+        }
+    }
+}
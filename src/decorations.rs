@@ -0,0 +1,143 @@
+// Purely cosmetic props scattered over the board at the start of each run and drawn
+// beneath everything else. They don't collide with anything; the snake and the food
+// simply cover them. Which props appear depends on the theme.
+
+use crate::camera::Camera;
+use crate::render::Renderer;
+use crate::BLOCK_SIZE;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const CELLS_PER_PROP: i32 = 30;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Theme {
+    Plain,    // Just the gray field
+    Junkyard, // Oil stains, loose bolts and tire marks
+}
+
+impl Theme {
+    pub fn parse(value: &str) -> Option<Theme> {
+        match value {
+            "plain" => Some(Theme::Plain),
+            "junkyard" => Some(Theme::Junkyard),
+            _ => None,
+        }
+    }
+
+    // The name `parse` accepts, as written to the settings file
+    pub fn id(&self) -> &'static str {
+        match self {
+            Theme::Plain => "plain",
+            Theme::Junkyard => "junkyard",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Theme::Plain => "Plain",
+            Theme::Junkyard => "Junkyard",
+        }
+    }
+}
+
+// Positions and sizes are in board pixels (cell * BLOCK_SIZE)
+enum Prop {
+    OilStain {
+        center: (f64, f64),
+        radius: f64,
+    },
+    Bolt {
+        center: (f64, f64),
+    },
+    TireMarks {
+        start: (f64, f64),
+        length: f64,
+        vertical: bool,
+    },
+}
+
+pub struct Decorations {
+    props: Vec<Prop>,
+}
+
+impl Decorations {
+    // The same seed always gives the same layout, so a replayed run looks the same
+    pub fn scatter(theme: Theme, seed: u64, board: (i32, i32)) -> Decorations {
+        if theme == Theme::Plain {
+            return Decorations { props: Vec::new() };
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (width, height) = (board.0 as f64 * BLOCK_SIZE, board.1 as f64 * BLOCK_SIZE);
+        let count = board.0 * board.1 / CELLS_PER_PROP;
+        let props = (0..count)
+            .map(|_| {
+                let center = (rng.gen_range(0.0..width), rng.gen_range(0.0..height));
+                match rng.gen_range(0..5) {
+                    0 | 1 => Prop::OilStain {
+                        center,
+                        radius: rng.gen_range(0.4..1.2) * BLOCK_SIZE,
+                    },
+                    2 | 3 => Prop::Bolt { center },
+                    _ => Prop::TireMarks {
+                        start: center,
+                        length: rng.gen_range(2.0..5.0) * BLOCK_SIZE,
+                        vertical: rng.gen(),
+                    },
+                }
+            })
+            .collect();
+        Decorations { props }
+    }
+
+    pub fn draw(&self, camera: &Camera, board: (i32, i32), r: &mut dyn Renderer) {
+        let mut rect = |color, rect| r.rectangle(color, camera.to_window(rect, board));
+        for prop in &self.props {
+            match *prop {
+                Prop::OilStain {
+                    center: (x, y),
+                    radius,
+                } => {
+                    // No circles in the renderer, so a blob of overlapping rectangles
+                    let color = [0.1, 0.1, 0.12, 0.2];
+                    rect(
+                        color,
+                        [x - radius, y - radius * 0.6, radius * 2.0, radius * 1.2],
+                    );
+                    rect(
+                        color,
+                        [x - radius * 0.6, y - radius, radius * 1.2, radius * 2.0],
+                    );
+                    rect(
+                        color,
+                        [x - radius * 0.5, y - radius * 0.4, radius, radius * 0.8],
+                    );
+                }
+                Prop::Bolt { center: (x, y) } => {
+                    rect([0.35, 0.32, 0.28, 1.0], [x - 3.0, y - 3.0, 6.0, 6.0]);
+                    rect([0.22, 0.2, 0.18, 1.0], [x - 1.0, y - 1.0, 2.0, 2.0]);
+                }
+                Prop::TireMarks {
+                    start: (x, y),
+                    length,
+                    vertical,
+                } => {
+                    // Two tracks of tread dashes
+                    let color = [0.2, 0.2, 0.2, 0.25];
+                    let mut along = 0.0;
+                    while along < length {
+                        for track in [0.0, 10.0] {
+                            let dash = if vertical {
+                                [x + track, y + along, 4.0, 3.0]
+                            } else {
+                                [x + along, y + track, 3.0, 4.0]
+                            };
+                            rect(color, dash);
+                        }
+                        along += 6.0;
+                    }
+                }
+            }
+        }
+    }
+}
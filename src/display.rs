@@ -0,0 +1,93 @@
+// Window presentation, chosen with `window_mode` in the settings file, plus the icon
+// and the title bar
+
+use crate::{Game, BLOCK_SIZE, HEIGHT, WIDTH};
+
+// 8x8 pixel art: S is the body, H the head, F a piece of rusty scrap
+const ICON: [&str; 8] = [
+    "        ", " SSSSSH ", " S      ", " SSSSSS ", "      S ", " F SSSS ", "        ", "        ",
+];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum WindowMode {
+    Windowed,
+    Borderless, // Undecorated fullscreen window on the primary monitor
+    Mini,       // Always-on-top "desk toy" with a 15x10 board, for a corner of the screen
+}
+
+impl WindowMode {
+    pub fn parse(value: &str) -> Option<WindowMode> {
+        match value {
+            "windowed" => Some(WindowMode::Windowed),
+            "borderless" => Some(WindowMode::Borderless),
+            "mini" => Some(WindowMode::Mini),
+            _ => None,
+        }
+    }
+
+    // The name `parse` accepts, as written to the settings file
+    pub fn id(&self) -> &'static str {
+        match self {
+            WindowMode::Windowed => "windowed",
+            WindowMode::Borderless => "borderless",
+            WindowMode::Mini => "mini",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WindowMode::Windowed => "Windowed",
+            WindowMode::Borderless => "Borderless",
+            WindowMode::Mini => "Mini",
+        }
+    }
+
+    pub fn board_size(&self) -> (i32, i32) {
+        match self {
+            WindowMode::Mini => (15, 10),
+            _ => (WIDTH, HEIGHT),
+        }
+    }
+
+    // Initial window size in logical units, before any fullscreen resize
+    pub fn window_size(&self) -> [f64; 2] {
+        let (width, height) = self.board_size();
+        [width as f64 * BLOCK_SIZE, height as f64 * BLOCK_SIZE]
+    }
+}
+
+// The icon as RGBA pixels, `scale` pixels per art pixel (4 gives the usual 32x32)
+pub fn icon_rgba(scale: usize) -> Vec<u8> {
+    let size = ICON.len() * scale;
+    let mut rgba = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let pixel = match ICON[y / scale].as_bytes()[x / scale] {
+                b'S' => [0, 255, 0, 255],
+                b'H' => [0, 179, 0, 255],
+                b'F' => [153, 102, 51, 255],
+                _ => [128, 128, 128, 255],
+            };
+            rgba.extend_from_slice(&pixel);
+        }
+    }
+    rgba
+}
+
+// e.g. "Rusty Snake — Wrap-around — 42 pts"
+pub fn window_title(game: &Game) -> String {
+    if game.won {
+        format!("Rusty Snake \u{2014} Victory \u{2014} {} pts", game.score)
+    } else if game.game_over {
+        format!("Rusty Snake \u{2014} Game Over \u{2014} {} pts", game.score)
+    } else if game.game_started {
+        let mode = if game.wrap_around {
+            "Wrap-around"
+        } else {
+            "Walls"
+        };
+        format!("Rusty Snake \u{2014} {} \u{2014} {} pts", mode, game.score)
+    } else {
+        "Rusty Snake".to_string()
+    }
+}
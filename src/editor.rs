@@ -0,0 +1,181 @@
+// The level editor, without the screen: a cursor moves over a layout and, while the
+// pen is down, paints every cell it passes with the brush tile. A rectangle marked
+// between two corners can be copied and pasted elsewhere, its top left corner at the
+// cursor.
+//
+// Every change can be undone and redone without limit. Before a change the whole
+// layout goes on the undo stack; boards are small, so a snapshot is cheaper to get
+// right than a command that knows how to reverse itself. A stroke of the pen, from
+// putting it down to lifting it, is one change however many cells it paints.
+
+use crate::levels::{Layout, LevelFile, Tile, MEDALS};
+use crate::mutators::Mutators;
+use crate::puzzle::Puzzle;
+use crate::topology::Topology;
+
+pub struct LevelEditor {
+    pub layout: Layout,
+    pub name: String,
+    pub author: String,
+    pub par: [Option<u32>; MEDALS.len()], // Not edited here, but kept
+    pub topology: Option<Topology>,       // The same
+    pub puzzle: Option<Puzzle>,           // The same
+    pub cursor: (i32, i32),
+    pub brush: Tile,
+    pub pen_down: bool,
+    pub anchor: Option<(i32, i32)>, // First corner of the rectangle being marked
+    pub clipboard: Option<Layout>,
+    undo: Vec<Layout>, // The layout before each change, oldest first
+    redo: Vec<Layout>, // Undone changes, most recently undone last
+    stroke: bool,      // The pen has changed something since it went down
+}
+
+impl LevelEditor {
+    pub fn new(layout: Layout) -> LevelEditor {
+        LevelEditor {
+            cursor: layout.start(),
+            layout,
+            name: String::new(),
+            author: String::new(),
+            par: [None; MEDALS.len()],
+            topology: None,
+            puzzle: None,
+            brush: Tile::Wall,
+            pen_down: false,
+            anchor: None,
+            clipboard: None,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            stroke: false,
+        }
+    }
+
+    // Picks up where `level` left off, though not its undo history
+    pub fn open(level: LevelFile) -> LevelEditor {
+        let mut editor = LevelEditor::new(level.layout);
+        editor.name = level.name;
+        editor.author = level.author;
+        editor.par = level.par;
+        editor.topology = level.topology;
+        editor.puzzle = level.puzzle;
+        editor
+    }
+
+    // The level as it stands, to be played with `mutators`
+    pub fn level(&self, mutators: Mutators) -> LevelFile {
+        LevelFile {
+            name: self.name.clone(),
+            author: self.author.clone(),
+            mutators,
+            par: self.par,
+            topology: self.topology,
+            puzzle: self.puzzle.clone(),
+            layout: self.layout.clone(),
+        }
+    }
+
+    // Move the cursor, staying on the layout, and paint where it lands if the pen is down
+    pub fn move_cursor(&mut self, (dx, dy): (i32, i32)) {
+        self.cursor = (
+            (self.cursor.0 + dx).clamp(0, self.layout.width - 1),
+            (self.cursor.1 + dy).clamp(0, self.layout.height - 1),
+        );
+        if self.pen_down {
+            self.paint();
+        }
+    }
+
+    // Putting the pen down paints the cell under the cursor straight away
+    pub fn toggle_pen(&mut self) {
+        self.pen_down = !self.pen_down;
+        self.stroke = false;
+        if self.pen_down {
+            self.paint();
+        }
+    }
+
+    pub fn cycle_brush(&mut self) {
+        self.brush = self.brush.next();
+    }
+
+    fn paint(&mut self) {
+        let before = self.layout.clone();
+        if self.layout.set(self.cursor, self.brush) {
+            // The rest of a stroke is part of the change its first cell started
+            if !self.stroke {
+                self.changed(before);
+            }
+            self.stroke = true;
+        }
+    }
+
+    fn changed(&mut self, before: Layout) {
+        self.undo.push(before);
+        self.redo.clear();
+    }
+
+    // Returns false if there was nothing to undo
+    pub fn undo(&mut self) -> bool {
+        let Some(before) = self.undo.pop() else {
+            return false;
+        };
+        self.redo.push(std::mem::replace(&mut self.layout, before));
+        self.stroke = false;
+        true
+    }
+
+    // Returns false if there was nothing to redo
+    pub fn redo(&mut self) -> bool {
+        let Some(after) = self.redo.pop() else {
+            return false;
+        };
+        self.undo.push(std::mem::replace(&mut self.layout, after));
+        self.stroke = false;
+        true
+    }
+
+    pub fn undo_steps(&self) -> usize {
+        self.undo.len()
+    }
+
+    pub fn redo_steps(&self) -> usize {
+        self.redo.len()
+    }
+
+    // Mark the first corner of a rectangle at the cursor, or drop the mark
+    pub fn toggle_mark(&mut self) {
+        self.anchor = match self.anchor {
+            Some(_) => None,
+            None => Some(self.cursor),
+        };
+    }
+
+    // The marked rectangle as its top left and bottom right cells: from the mark to the
+    // cursor, or just the cursor's cell with nothing marked
+    pub fn selection(&self) -> ((i32, i32), (i32, i32)) {
+        let (a, b) = (self.anchor.unwrap_or(self.cursor), self.cursor);
+        ((a.0.min(b.0), a.1.min(b.1)), (a.0.max(b.0), a.1.max(b.1)))
+    }
+
+    // Copy the marked rectangle, which drops the mark
+    pub fn copy(&mut self) {
+        let (top_left, bottom_right) = self.selection();
+        self.clipboard = Some(self.layout.region(top_left, bottom_right));
+        self.anchor = None;
+    }
+
+    // Paste what was copied with its top left corner at the cursor. Returns false if
+    // that changed nothing.
+    pub fn paste(&mut self) -> bool {
+        let Some(region) = &self.clipboard else {
+            return false;
+        };
+        let before = self.layout.clone();
+        if !self.layout.paste(region, self.cursor) {
+            return false;
+        }
+        self.changed(before);
+        self.stroke = false;
+        true
+    }
+}
@@ -0,0 +1,143 @@
+// The game as a reinforcement learning environment, in the style of Gym: `reset` starts
+// a new game and returns the first observation, `step` makes one move and returns what
+// came of it. Rewards are as in the training data (`transitions`): the points a move
+// scored, or -1 for dying.
+
+use crate::game::{Direction, FoodType, Game, HEIGHT, WIDTH};
+use crate::rats::axis_delta;
+use crate::transitions;
+
+// How the board is shown to the agent
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Encoding {
+    // The full board, laid out like the training data's states (see `transitions`)
+    Grid,
+    // A short vector of hand-picked features, named in `FEATURES`
+    Features,
+}
+
+pub const FEATURES: [&str; 12] = [
+    "danger ahead",
+    "danger left",
+    "danger right",
+    "moving up",
+    "moving right",
+    "moving down",
+    "moving left",
+    "food up",
+    "food right",
+    "food down",
+    "food left",
+    "length",
+];
+
+pub type Observation = Vec<f32>;
+
+pub struct SnakeEnv {
+    pub game: Game,
+    pub encoding: Encoding,
+    pub board: (i32, i32),
+    pub max_moves: u64, // A game ends as done after this many moves, dead or not
+    pub moves: u64,     // Made in the current game
+    seed: u64,          // For the next game, counting up from the one given
+}
+
+impl SnakeEnv {
+    pub fn new(seed: u64, encoding: Encoding) -> SnakeEnv {
+        SnakeEnv {
+            game: Game::headless(seed, (WIDTH, HEIGHT)),
+            encoding,
+            board: (WIDTH, HEIGHT),
+            max_moves: 10_000,
+            moves: 0,
+            seed,
+        }
+    }
+
+    // Start a new game, seeded one on from the last
+    pub fn reset(&mut self) -> Observation {
+        self.game = Game::headless(self.seed, self.board);
+        self.seed += 1;
+        self.moves = 0;
+        self.game.tick_interval = 1;
+        let direction = self.game.snake.direction;
+        self.game.steer(direction);
+        self.observe()
+    }
+
+    // Steer and make one move. Steering straight back into the neck is ignored, like it
+    // is for a player.
+    pub fn step(&mut self, action: Direction) -> (Observation, f32, bool) {
+        if self.game.game_over {
+            return (self.observe(), 0.0, true);
+        }
+        let score = self.game.score;
+        self.game.steer(action);
+        // With indigestion a move takes more than one update
+        while !self.game.tick_due() && !self.game.game_over {
+            self.game.update();
+        }
+        self.game.update();
+        self.moves += 1;
+        let reward = transitions::reward(score, &self.game) as f32;
+        let done = self.game.game_over || self.moves >= self.max_moves;
+        (self.observe(), reward, done)
+    }
+
+    pub fn observe(&self) -> Observation {
+        match self.encoding {
+            Encoding::Grid => transitions::state(&self.game)
+                .into_iter()
+                .map(f32::from)
+                .collect(),
+            Encoding::Features => features(&self.game),
+        }
+    }
+}
+
+fn features(game: &Game) -> Observation {
+    let heading = game.snake.direction;
+    let head = game.snake.body[0].position;
+    let danger = |direction: Direction| match game.neighbor(head, direction) {
+        Some(cell) => {
+            game.snake.body.iter().any(|seg| seg.position == cell) || game.boss_covers(cell)
+        }
+        None => true,
+    };
+    let food = game
+        .foods
+        .iter()
+        .filter(|food| food.food_type != FoodType::ShinyMetal || game.can_digest_metal())
+        .map(|food| {
+            (
+                axis_delta(head.0, food.position.0, game.width, game.wrap_around),
+                axis_delta(head.1, food.position.1, game.height, game.wrap_around),
+            )
+        })
+        .min_by_key(|(dx, dy)| dx.abs() + dy.abs())
+        .unwrap_or((0, 0));
+    let flag = |value: bool| if value { 1.0 } else { 0.0 };
+    vec![
+        flag(danger(heading)),
+        flag(danger(left_of(heading))),
+        flag(danger(left_of(heading).opposite())),
+        flag(heading == Direction::Up),
+        flag(heading == Direction::Right),
+        flag(heading == Direction::Down),
+        flag(heading == Direction::Left),
+        flag(food.1 < 0),
+        flag(food.0 > 0),
+        flag(food.1 > 0),
+        flag(food.0 < 0),
+        game.snake.body.len() as f32 / (game.width * game.height) as f32,
+    ]
+}
+
+fn left_of(direction: Direction) -> Direction {
+    match direction {
+        Direction::Up => Direction::Left,
+        Direction::Left => Direction::Down,
+        Direction::Down => Direction::Right,
+        Direction::Right => Direction::Up,
+    }
+}
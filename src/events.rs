@@ -0,0 +1,39 @@
+// What happened during an update, for everything that reacts to the game without being
+// part of its rules: sound, particles, animations, stats. `Game::update` only records
+// events; after each update the frontend hands them to its subscribers, so a new kind
+// of feedback subscribes here instead of reaching into the rules.
+
+use crate::game::{DeathCause, FoodType, Game};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameEvent {
+    FoodEaten { food: FoodType, cell: (i32, i32) },
+    SegmentAdded(usize), // Body index, counting the segments added before it
+    Died(DeathCause),
+    HighScoreReached(u32), // The score that made the table, now waiting for a name
+    EffectExpired(Effect),
+    NearMiss((i32, i32)), // The wall or body cell the head came up right next to
+    ClusterCleared((i32, i32)), // Where the last piece of a scrap cluster was eaten
+}
+
+// Something that wears off on its own
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Effect {
+    Shield,      // From an extra life or a shield bought in the shop
+    Indigestion, // From metal the snake had no room for
+}
+
+pub trait Subscriber {
+    // Called once per event, in the order they happened. `game` is as the update left
+    // it.
+    fn notify(&mut self, game: &Game, event: &GameEvent);
+}
+
+// Hand every event to every subscriber
+pub fn publish(game: &Game, events: &[GameEvent], subscribers: &mut [&mut dyn Subscriber]) {
+    for event in events {
+        for subscriber in subscribers.iter_mut() {
+            subscriber.notify(game, event);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+// C interface to the rules, for embedding the game in other engines and tools. Built
+// with the `ffi` feature as a shared library:
+//
+//     cargo rustc --release --lib --features ffi --crate-type cdylib
+//
+// `include/rusty_snake.h` declares the functions. A game is an opaque pointer from
+// `snake_create`, freed with `snake_destroy`; every other function takes it and
+// treats a null pointer as an empty game. Directions and cell contents use the same
+// numbers as the training data (see `transitions`).
+
+use crate::env::{Encoding, SnakeEnv};
+use crate::game::{Direction, FoodType, SegmentType};
+use crate::transitions;
+
+pub struct SnakeGame {
+    env: SnakeEnv,
+}
+
+// A new game of `width` x `height` cells (the standard board if either is 0 or less),
+// already started and heading right
+#[no_mangle]
+pub extern "C" fn snake_create(seed: u64, width: i32, height: i32) -> Box<SnakeGame> {
+    let mut env = SnakeEnv::new(seed, Encoding::Grid);
+    if width > 0 && height > 0 {
+        env.board = (width, height);
+    }
+    env.reset();
+    Box::new(SnakeGame { env })
+}
+
+#[no_mangle]
+pub extern "C" fn snake_destroy(game: Option<Box<SnakeGame>>) {
+    drop(game);
+}
+
+// Steer (0 up, 1 right, 2 down, 3 left, anything else keeps going) and make one move.
+// Returns the reward: the points scored, or -1 if the snake died.
+#[no_mangle]
+pub extern "C" fn snake_step(game: Option<&mut SnakeGame>, direction: i32) -> i32 {
+    let Some(game) = game else {
+        return 0;
+    };
+    let direction = match direction {
+        0 => Direction::Up,
+        1 => Direction::Right,
+        2 => Direction::Down,
+        3 => Direction::Left,
+        _ => game.env.game.snake.direction,
+    };
+    let (_, reward, _) = game.env.step(direction);
+    reward as i32
+}
+
+#[no_mangle]
+pub extern "C" fn snake_game_over(game: Option<&SnakeGame>) -> bool {
+    game.is_none_or(|game| game.env.game.game_over)
+}
+
+#[no_mangle]
+pub extern "C" fn snake_score(game: Option<&SnakeGame>) -> u32 {
+    game.map_or(0, |game| game.env.game.score)
+}
+
+#[no_mangle]
+pub extern "C" fn snake_width(game: Option<&SnakeGame>) -> i32 {
+    game.map_or(0, |game| game.env.game.width)
+}
+
+#[no_mangle]
+pub extern "C" fn snake_height(game: Option<&SnakeGame>) -> i32 {
+    game.map_or(0, |game| game.env.game.height)
+}
+
+#[no_mangle]
+pub extern "C" fn snake_length(game: Option<&SnakeGame>) -> u32 {
+    game.map_or(0, |game| game.env.game.snake.body.len() as u32)
+}
+
+// Segment `index` (0 is the head): its cell and its kind, 0 head, 1 empty stomach,
+// 2 full stomach, 3 tail. False if there is no such segment.
+#[no_mangle]
+pub extern "C" fn snake_segment(
+    game: Option<&SnakeGame>,
+    index: u32,
+    x: Option<&mut i32>,
+    y: Option<&mut i32>,
+    kind: Option<&mut i32>,
+) -> bool {
+    let Some(segment) = game.and_then(|game| game.env.game.snake.body.get(index as usize)) else {
+        return false;
+    };
+    let code = match segment.segment_type {
+        SegmentType::Head => 0,
+        SegmentType::EmptyStomach => 1,
+        SegmentType::FullStomach => 2,
+        SegmentType::Tail => 3,
+    };
+    write_cell(segment.position, code, x, y, kind);
+    true
+}
+
+#[no_mangle]
+pub extern "C" fn snake_food_count(game: Option<&SnakeGame>) -> u32 {
+    game.map_or(0, |game| game.env.game.foods.len() as u32)
+}
+
+// Food `index`: its cell and its kind, 0 scrap, 1 shiny metal, 2 water, 3 pumpkin.
+// False if there is no such food.
+#[no_mangle]
+pub extern "C" fn snake_food(
+    game: Option<&SnakeGame>,
+    index: u32,
+    x: Option<&mut i32>,
+    y: Option<&mut i32>,
+    kind: Option<&mut i32>,
+) -> bool {
+    let Some(food) = game.and_then(|game| game.env.game.foods.get(index as usize)) else {
+        return false;
+    };
+    let code = match food.food_type {
+        FoodType::RustyScrap => 0,
+        FoodType::ShinyMetal => 1,
+        FoodType::Water => 2,
+        FoodType::Pumpkin => 3,
+    };
+    write_cell(food.position, code, x, y, kind);
+    true
+}
+
+// The whole board as in the training data, one byte per channel and cell, into `out`
+// if it holds at least `capacity` bytes. Returns the size needed, so a first call with
+// a null buffer asks how big it has to be.
+/// # Safety
+/// `out` must be null or point to `capacity` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn snake_observe(
+    game: Option<&SnakeGame>,
+    out: *mut u8,
+    capacity: usize,
+) -> usize {
+    let Some(game) = game else {
+        return 0;
+    };
+    let state = transitions::state(&game.env.game);
+    if !out.is_null() && capacity >= state.len() {
+        std::slice::from_raw_parts_mut(out, state.len()).copy_from_slice(&state);
+    }
+    state.len()
+}
+
+fn write_cell(
+    (cell_x, cell_y): (i32, i32),
+    code: i32,
+    x: Option<&mut i32>,
+    y: Option<&mut i32>,
+    kind: Option<&mut i32>,
+) {
+    if let Some(x) = x {
+        *x = cell_x;
+    }
+    if let Some(y) = y {
+        *y = cell_y;
+    }
+    if let Some(kind) = kind {
+        *kind = code;
+    }
+}
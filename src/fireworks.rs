@@ -0,0 +1,86 @@
+// Victory fireworks: bursts of sparks all over the window that fall and fade out.
+// Purely cosmetic, so they draw from a generator of their own, never the game's.
+
+use crate::render::{Color, Renderer};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const BURST_EVERY: u64 = 40; // Frames between bursts
+const SPARKS_PER_BURST: usize = 24;
+const SPARK_FRAMES: u32 = 90; // How long a spark glows
+const SPARK_SPEED: f64 = 0.006; // Fraction of the window per frame
+const GRAVITY: f64 = 0.0001;
+const SPARK_SIZE: f64 = 4.0;
+
+const COLORS: [Color; 4] = [
+    [1.0, 0.8, 0.0, 1.0],
+    [1.0, 0.3, 0.2, 1.0],
+    [0.4, 0.8, 1.0, 1.0],
+    [0.8, 0.8, 0.8, 1.0],
+];
+
+// Positions and velocities are fractions of the window, so resizing doesn't matter
+struct Spark {
+    position: [f64; 2],
+    velocity: [f64; 2],
+    color: Color,
+    frames_left: u32,
+}
+
+pub struct Fireworks {
+    sparks: Vec<Spark>,
+    frame: u64,
+    rng: StdRng,
+}
+
+impl Fireworks {
+    pub fn new(seed: u64) -> Fireworks {
+        Fireworks {
+            sparks: Vec::new(),
+            frame: 0,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    // One frame: launch a burst every so often, then move and age every spark
+    pub fn update(&mut self) {
+        if self.frame.is_multiple_of(BURST_EVERY) {
+            let center = [self.rng.gen_range(0.2..0.8), self.rng.gen_range(0.15..0.5)];
+            let color = COLORS[self.rng.gen_range(0..COLORS.len())];
+            for i in 0..SPARKS_PER_BURST {
+                let angle = i as f64 / SPARKS_PER_BURST as f64 * std::f64::consts::TAU;
+                let speed = SPARK_SPEED * self.rng.gen_range(0.5..1.0);
+                self.sparks.push(Spark {
+                    position: center,
+                    velocity: [angle.cos() * speed, angle.sin() * speed],
+                    color,
+                    frames_left: SPARK_FRAMES,
+                });
+            }
+        }
+        self.frame += 1;
+
+        for spark in &mut self.sparks {
+            spark.position[0] += spark.velocity[0];
+            spark.position[1] += spark.velocity[1];
+            spark.velocity[1] += GRAVITY;
+            spark.frames_left -= 1;
+        }
+        self.sparks.retain(|spark| spark.frames_left > 0);
+    }
+
+    pub fn draw(&self, r: &mut dyn Renderer) {
+        let [width, height] = r.view_size();
+        for spark in &self.sparks {
+            let fade = spark.frames_left as f32 / SPARK_FRAMES as f32;
+            let [red, green, blue, alpha] = spark.color;
+            let rect = [
+                spark.position[0] * width,
+                spark.position[1] * height,
+                SPARK_SIZE,
+                SPARK_SIZE,
+            ];
+            r.rectangle([red, green, blue, alpha * fade], rect);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+// Accessibility option: every tick one food plays a short tone, panned toward where it
+// lies relative to the head, whose pitch rises as the head gets closer. The foods take
+// turns so low-vision players can locate each pickup by ear.
+
+use crate::audio::{AudioEngine, Tone};
+use crate::sound_effects::{self, axis_delta};
+use crate::{FoodType, Game};
+use std::time::Duration;
+
+const CUE_LENGTH: Duration = Duration::from_millis(90);
+const CUE_VOLUME: f32 = 0.3;
+
+pub struct FoodCues {
+    next: usize, // Index of the food whose turn it is to beep
+}
+
+impl FoodCues {
+    pub fn new() -> FoodCues {
+        FoodCues { next: 0 }
+    }
+
+    pub fn tick(&mut self, game: &Game, audio: &mut dyn AudioEngine) {
+        if game.game_over || game.foods.is_empty() {
+            return;
+        }
+        self.next = (self.next + 1) % game.foods.len();
+        let food = &game.foods[self.next];
+        let head = game.snake.body[0].position;
+
+        let dx = axis_delta(head.0, food.position.0, game.width, game.wrap_around);
+        let dy = axis_delta(head.1, food.position.1, game.height, game.wrap_around);
+        let max_distance = if game.wrap_around {
+            (game.width / 2 + game.height / 2) as f32
+        } else {
+            (game.width + game.height) as f32
+        };
+        let closeness = 1.0 - ((dx.abs() + dy.abs()) as f32 / max_distance).min(1.0);
+
+        let base = match food.food_type {
+            FoodType::RustyScrap => 262.0, // C4
+            FoodType::Water => 330.0,      // E4
+            FoodType::ShinyMetal => 392.0, // G4
+            FoodType::Pumpkin => 440.0,    // A4
+        };
+        audio.play_tone(Tone {
+            // Up to an octave higher when right next to the head
+            frequency: base * 2f32.powf(closeness),
+            duration: CUE_LENGTH,
+            volume: CUE_VOLUME,
+            pan: sound_effects::pan(game, food.position),
+        });
+    }
+}
@@ -0,0 +1,237 @@
+// How each food looks and sounds when it's eaten: the pitch of the bite, the color of
+// the crumbs that fly off and the text that floats up from the cell. The defaults are
+// built in; a theme or mod can change any of them in `food_styles.txt`:
+//
+//     water.sound = 440          bite pitch in Hz
+//     scrap.color = #996633      crumb color
+//     pumpkin.popup = +{points}! floating text, {points} is what the bite scored
+//     plain.scrap.popup = Crunch only with the Plain theme
+//
+// Food ids are the console's: scrap, metal, water and pumpkin. The effects are only
+// drawn, they never reach the game.
+
+use crate::colors;
+use crate::decorations::Theme;
+use crate::events::{GameEvent, Subscriber};
+use crate::game::FOOD_TYPES;
+use crate::render::Color;
+use crate::{FoodType, Game};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fs;
+
+const STYLES_FILE: &str = "food_styles.txt";
+const CRUMBS_PER_BITE: usize = 8;
+const CRUMB_FRAMES: u32 = 30;
+const CRUMB_SPEED: f64 = 1.2; // Board pixels per frame
+const POPUP_FRAMES: u32 = 60;
+const POPUP_RISE: f64 = 0.5; // Board pixels per frame
+
+#[derive(Clone)]
+pub struct FoodStyle {
+    pub sound: f32,
+    pub color: Color,
+    pub popup: String, // With `{points}` standing in for the score of the bite
+}
+
+// Also the color the food is drawn in on the board
+pub fn board_color(food: &FoodType) -> Color {
+    match food {
+        FoodType::RustyScrap => [0.6, 0.4, 0.2, 1.0], // Brown
+        FoodType::ShinyMetal => [0.8, 0.8, 0.8, 1.0],
+        FoodType::Water => [0.0, 0.0, 1.0, 1.0],    // Blue
+        FoodType::Pumpkin => [1.0, 0.55, 0.0, 1.0], // Orange
+    }
+}
+
+fn default_style(food: &FoodType) -> FoodStyle {
+    let sound = match food {
+        FoodType::RustyScrap => 660.0,
+        FoodType::ShinyMetal => 990.0,
+        FoodType::Water => 440.0,
+        FoodType::Pumpkin => 784.0,
+    };
+    FoodStyle {
+        sound,
+        color: board_color(food),
+        popup: "+{points}".to_string(),
+    }
+}
+
+pub struct FoodStyles {
+    styles: Vec<(FoodType, FoodStyle)>,
+}
+
+impl FoodStyles {
+    // The built-in styles with the file's changes for `theme` applied
+    pub fn load(theme: Theme) -> FoodStyles {
+        let mut styles = FoodStyles {
+            styles: FOOD_TYPES
+                .iter()
+                .map(|food| (food.clone(), default_style(food)))
+                .collect(),
+        };
+        // Without the file every food keeps its built-in style
+        let Ok(contents) = fs::read_to_string(STYLES_FILE) else {
+            return styles;
+        };
+        let lines = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='));
+        // Lines for every theme first, so the theme's own ones win
+        let mut themed = Vec::new();
+        for (key, value) in lines {
+            let parts: Vec<&str> = key.trim().split('.').collect();
+            match parts.as_slice() {
+                [food, field] => styles.apply(food, field, value.trim()),
+                [only, food, field] if Theme::parse(only) == Some(theme) => {
+                    themed.push((*food, *field, value.trim()));
+                }
+                [only, _, _] if Theme::parse(only).is_some() => {}
+                _ => eprintln!("Unknown line in {}: {}", STYLES_FILE, key),
+            }
+        }
+        for (food, field, value) in themed {
+            styles.apply(food, field, value);
+        }
+        styles
+    }
+
+    fn apply(&mut self, food: &str, field: &str, value: &str) {
+        let Some(food) = FoodType::parse(food) else {
+            eprintln!("Unknown food in {}: {}", STYLES_FILE, food);
+            return;
+        };
+        let style = self.get_mut(&food);
+        match field {
+            "sound" => match value.parse::<f32>() {
+                Ok(hz) if hz > 0.0 => style.sound = hz,
+                _ => eprintln!("Bad sound in {}: {}", STYLES_FILE, value),
+            },
+            "color" => match colors::parse(value) {
+                Some(color) => style.color = color,
+                None => eprintln!("Bad color in {}: {}", STYLES_FILE, value),
+            },
+            "popup" => style.popup = value.to_string(),
+            _ => eprintln!("Unknown field in {}: {}", STYLES_FILE, field),
+        }
+    }
+
+    pub fn get(&self, food: &FoodType) -> &FoodStyle {
+        let (_, style) = self.styles.iter().find(|(f, _)| f == food).unwrap();
+        style
+    }
+
+    fn get_mut(&mut self, food: &FoodType) -> &mut FoodStyle {
+        let (_, style) = self.styles.iter_mut().find(|(f, _)| f == food).unwrap();
+        style
+    }
+}
+
+// Positions are board pixels from the middle of the bite's cell
+pub struct Crumb {
+    pub cell: (i32, i32),
+    pub offset: [f64; 2],
+    velocity: [f64; 2],
+    pub color: Color,
+    frames_left: u32,
+}
+
+impl Crumb {
+    pub fn fade(&self) -> f32 {
+        self.frames_left as f32 / CRUMB_FRAMES as f32
+    }
+}
+
+pub struct Popup {
+    pub cell: (i32, i32),
+    pub text: String,
+    pub color: Color,
+    frames_left: u32,
+}
+
+impl Popup {
+    // How far it has floated up, in board pixels
+    pub fn rise(&self) -> f64 {
+        (POPUP_FRAMES - self.frames_left) as f64 * POPUP_RISE
+    }
+
+    pub fn fade(&self) -> f32 {
+        self.frames_left as f32 / POPUP_FRAMES as f32
+    }
+}
+
+// The crumbs and popups of recent bites
+pub struct BiteEffects {
+    pub crumbs: Vec<Crumb>,
+    pub popups: Vec<Popup>,
+    bitten: Vec<((i32, i32), FoodType, u32)>, // Cell, food and points, since the last update
+    score: u32,                               // As of the last update
+    rng: StdRng,                              // Its own, so the effects never touch the game's
+}
+
+impl BiteEffects {
+    pub fn new(seed: u64) -> BiteEffects {
+        BiteEffects {
+            crumbs: Vec::new(),
+            popups: Vec::new(),
+            bitten: Vec::new(),
+            score: 0,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    // Call once per update, after the update's events
+    pub fn update(&mut self, game: &Game, styles: &FoodStyles) {
+        self.score = game.score;
+        if !game.paused {
+            for crumb in &mut self.crumbs {
+                crumb.offset[0] += crumb.velocity[0];
+                crumb.offset[1] += crumb.velocity[1];
+                crumb.frames_left -= 1;
+            }
+            self.crumbs.retain(|crumb| crumb.frames_left > 0);
+            for popup in &mut self.popups {
+                popup.frames_left -= 1;
+            }
+            self.popups.retain(|popup| popup.frames_left > 0);
+        }
+        for (cell, food, points) in std::mem::take(&mut self.bitten) {
+            self.burst(cell, styles.get(&food), points);
+        }
+    }
+
+    fn burst(&mut self, cell: (i32, i32), style: &FoodStyle, points: u32) {
+        for i in 0..CRUMBS_PER_BITE {
+            let angle = (i as f64 + self.rng.gen_range(0.0..1.0)) / CRUMBS_PER_BITE as f64
+                * std::f64::consts::TAU;
+            let speed = CRUMB_SPEED * self.rng.gen_range(0.5..1.0);
+            self.crumbs.push(Crumb {
+                cell,
+                offset: [0.0, 0.0],
+                velocity: [angle.cos() * speed, angle.sin() * speed],
+                color: style.color,
+                frames_left: CRUMB_FRAMES,
+            });
+        }
+        if !style.popup.is_empty() {
+            self.popups.push(Popup {
+                cell,
+                text: style.popup.replace("{points}", &points.to_string()),
+                color: style.color,
+                frames_left: POPUP_FRAMES,
+            });
+        }
+    }
+}
+
+impl Subscriber for BiteEffects {
+    fn notify(&mut self, game: &Game, event: &GameEvent) {
+        if let GameEvent::FoodEaten { food, cell } = event {
+            let points = game.score.saturating_sub(self.score);
+            self.bitten.push((*cell, food.clone(), points));
+        }
+    }
+}
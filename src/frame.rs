@@ -0,0 +1,101 @@
+// A frame drawn ahead of time as a list of draw calls. The simulation thread draws the
+// screen into one with the usual view code, and the window thread plays the newest one
+// back onto the real renderer, so neither waits on the other.
+
+use crate::render::{self, Color, Renderer};
+use rusttype::Font;
+use std::time::Duration;
+
+enum Command {
+    Clear(Color),
+    Rectangle(Color, [f64; 4]),
+    Line(Color, f64, [f64; 4]),
+    Polygon(Color, Vec<[f64; 2]>),
+    Text(String, u32, Color, f64, f64),
+    Clip(Option<[f64; 4]>),
+}
+
+#[derive(Default)]
+pub struct Frame {
+    commands: Vec<Command>,
+    pub title: Option<String>, // New window title, if it changed
+    pub ui_scale: f64,
+    pub update_time: Duration, // Spent updating since the last frame the window took
+    pub draw_time: Duration,   // Spent drawing this one
+}
+
+impl Frame {
+    pub fn play(&self, r: &mut dyn Renderer) {
+        for command in &self.commands {
+            match command {
+                Command::Clear(color) => r.clear(*color),
+                Command::Rectangle(color, rect) => r.rectangle(*color, *rect),
+                Command::Line(color, width, line) => r.line(*color, *width, *line),
+                Command::Polygon(color, points) => r.polygon(*color, points),
+                Command::Text(text, size, color, x, y) => r.text(text, *size, *color, *x, *y),
+                Command::Clip(rect) => r.clip(*rect),
+            }
+        }
+    }
+}
+
+// Renderer that records into a `Frame`. Text is measured with the game's font, the
+// same one every backend draws with.
+pub struct FrameRecorder<'a> {
+    size: [f64; 2],
+    font: Option<&'a Font<'static>>,
+    frame: Frame,
+}
+
+impl<'a> FrameRecorder<'a> {
+    pub fn new(size: [f64; 2], font: Option<&'a Font<'static>>) -> FrameRecorder<'a> {
+        FrameRecorder {
+            size,
+            font,
+            frame: Frame::default(),
+        }
+    }
+
+    pub fn finish(self) -> Frame {
+        self.frame
+    }
+}
+
+impl Renderer for FrameRecorder<'_> {
+    fn view_size(&self) -> [f64; 2] {
+        self.size
+    }
+
+    fn clear(&mut self, color: Color) {
+        self.frame.commands.push(Command::Clear(color));
+    }
+
+    fn rectangle(&mut self, color: Color, rect: [f64; 4]) {
+        self.frame.commands.push(Command::Rectangle(color, rect));
+    }
+
+    fn line(&mut self, color: Color, width: f64, line: [f64; 4]) {
+        self.frame.commands.push(Command::Line(color, width, line));
+    }
+
+    fn polygon(&mut self, color: Color, points: &[[f64; 2]]) {
+        let points = points.to_vec();
+        self.frame.commands.push(Command::Polygon(color, points));
+    }
+
+    fn text(&mut self, text: &str, size: u32, color: Color, x: f64, y: f64) {
+        let text = text.to_string();
+        self.frame
+            .commands
+            .push(Command::Text(text, size, color, x, y));
+    }
+
+    fn text_width(&mut self, text: &str, size: u32) -> f64 {
+        self.font
+            .map_or(0.0, |font| render::text_width(font, text, size))
+    }
+
+    fn clip(&mut self, rect: Option<[f64; 4]>) {
+        self.frame.commands.push(Command::Clip(rect));
+    }
+}
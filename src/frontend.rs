@@ -0,0 +1,206 @@
+// The part of the main loop shared by every backend. A backend owns the window and
+// the event loop and turns its native events into `WindowEvent`s for the
+// `Simulation`, which hands them to a `Frontend` on its own thread. There they become
+// game input, `update` runs at a fixed rate and `frame` draws the screen for the
+// window to show.
+
+use crate::app::App;
+use crate::cli::Options;
+use crate::controls::{self, ControlScheme, Gesture, SwipeDetector};
+use crate::display;
+use crate::frame::{Frame, FrameRecorder};
+use crate::input_log::InputEvent;
+use crate::keys::Key;
+use crate::settings::Settings;
+use crate::ui::Ui;
+use crate::view;
+use rusttype::Font;
+use std::time::{Duration, Instant};
+
+pub const UPDATES_PER_SECOND: u64 = 120;
+const MOUSE_POINTER: i64 = -1; // Pointer id used when feeding mouse drags to the swipe detector
+
+pub enum WindowEvent {
+    Key(Key), // Pressed
+    Text(String),
+    CursorMoved([f64; 2]),
+    MousePressed, // Left button
+    MouseReleased,
+    Touch(i64, TouchPhase, [f64; 2]),
+    // The window came to the front (true) or went to the background. Macroquad can't tell.
+    #[cfg_attr(not(feature = "piston"), allow(dead_code))]
+    Focus(bool),
+}
+
+pub enum TouchPhase {
+    Start,
+    Move,
+    End,
+    Cancel,
+}
+
+pub struct Frontend {
+    pub app: App,
+    pub ui: Ui,
+    font: Option<Font<'static>>, // For measuring text while drawing a frame
+    cursor: [f64; 2],
+    swipes: SwipeDetector,
+    // The title shows the live score, but is only refreshed once a second
+    title: String,
+    title_checked: Instant,
+}
+
+impl Frontend {
+    pub fn new(settings: Settings, options: &Options, font: &'static [u8]) -> Frontend {
+        let font = Font::try_from_bytes(font);
+        Frontend {
+            ui: Ui::new(settings.ui_scale),
+            app: App::new(settings, options),
+            font,
+            cursor: [0.0, 0.0],
+            swipes: SwipeDetector::new(),
+            title: String::new(),
+            title_checked: Instant::now(),
+        }
+    }
+
+    pub fn event(&mut self, event: WindowEvent) {
+        if let WindowEvent::CursorMoved(position) = event {
+            self.cursor = position;
+        }
+        if !self.app.replaying() {
+            for input in self.translate_input(&event) {
+                self.app.input(input);
+            }
+            if let WindowEvent::Text(text) = &event {
+                self.app.type_text(text);
+            }
+        }
+    }
+
+    // Called UPDATES_PER_SECOND times a second
+    pub fn update(&mut self) {
+        self.app.update(self.cursor);
+    }
+
+    // The window's logical size changed
+    pub fn resize(&mut self, size: [f64; 2]) {
+        self.app.camera.set_view(size);
+    }
+
+    // Draw the screen as it is now
+    pub fn frame(&mut self) -> Frame {
+        // Text size changed on the settings screen
+        if self.ui.scale != self.app.settings.ui_scale {
+            self.ui = Ui::new(self.app.settings.ui_scale);
+        }
+        let mut recorder = FrameRecorder::new(self.app.camera.view(), self.font.as_ref());
+        view::draw(&self.app, &self.ui, &mut recorder);
+        let mut frame = recorder.finish();
+        frame.title = self.title();
+        frame.ui_scale = self.ui.scale;
+        frame
+    }
+
+    // New window title, at most once a second and only when it changed
+    fn title(&mut self) -> Option<String> {
+        if self.title_checked.elapsed() < Duration::from_secs(1) {
+            return None;
+        }
+        self.title_checked = Instant::now();
+        let title = display::window_title(&self.app.game);
+        if title == self.title {
+            return None;
+        }
+        self.title = title.clone();
+        Some(title)
+    }
+
+    // Turn window events into game input. Touch screens (and mouse drags, unless the
+    // mouse is already used for steering) are run through the swipe detector.
+    fn translate_input(&mut self, event: &WindowEvent) -> Vec<InputEvent> {
+        let app = &self.app;
+        let cursor = self.cursor;
+        let mut inputs = Vec::new();
+        if let WindowEvent::Key(key) = event {
+            inputs.push(InputEvent::Key(*key));
+        }
+        // Going to the background only reaches the game when it's to pause it
+        match event {
+            WindowEvent::Focus(false) if app.settings.focus_pause => {
+                inputs.push(InputEvent::Window(false))
+            }
+            WindowEvent::Focus(true) => inputs.push(InputEvent::Window(true)),
+            _ => {}
+        }
+
+        // Menus take the pointer: hovering focuses an item, a click or tap picks it
+        if let Some(menu) = view::menu(app) {
+            let pointer = match event {
+                WindowEvent::CursorMoved(_) | WindowEvent::MousePressed => Some(cursor),
+                WindowEvent::Touch(_, TouchPhase::Start, position) => Some(*position),
+                _ => None,
+            };
+            let window_height = app.camera.view()[1];
+            let item = pointer.and_then(|[_, y]| menu.item_at(&self.ui, window_height, y));
+            if let Some(item) = item {
+                if item != menu.focus() {
+                    inputs.push(InputEvent::Focus(item));
+                }
+                if !matches!(event, WindowEvent::CursorMoved(_)) {
+                    // Values step up, like pressing right; lists pick the item
+                    let key = if menu.is_adjustable() {
+                        Key::Right
+                    } else {
+                        Key::Return
+                    };
+                    inputs.push(InputEvent::Key(key));
+                }
+            }
+            return inputs;
+        }
+
+        let mouse_steering = app.settings.control_scheme == ControlScheme::Mouse;
+        let left_pressed = matches!(event, WindowEvent::MousePressed);
+        if mouse_steering && left_pressed && !app.game.game_started {
+            // Clicking starts the game heading toward the cursor
+            let game = &app.game;
+            let cursor = app.camera.to_board(cursor, (game.width, game.height));
+            let snake = &game.snake;
+            let direction =
+                controls::mouse_direction(snake.body[0].position, cursor, &snake.direction)
+                    .unwrap_or(snake.direction);
+            inputs.push(InputEvent::Steer(direction));
+        }
+
+        let swipes = &mut self.swipes;
+        let gesture = match event {
+            WindowEvent::Touch(id, phase, position) => match phase {
+                TouchPhase::Start => {
+                    swipes.begin(*id, *position);
+                    None
+                }
+                TouchPhase::Move => swipes.moved(*id, *position),
+                TouchPhase::End => swipes.end(*id, *position),
+                TouchPhase::Cancel => {
+                    swipes.cancel();
+                    None
+                }
+            },
+            _ if mouse_steering => None,
+            WindowEvent::MousePressed => {
+                swipes.begin(MOUSE_POINTER, cursor);
+                None
+            }
+            WindowEvent::MouseReleased => swipes.end(MOUSE_POINTER, cursor),
+            WindowEvent::CursorMoved(_) => swipes.moved(MOUSE_POINTER, cursor),
+            _ => None,
+        };
+        match gesture {
+            Some(Gesture::Swipe(direction)) => inputs.push(InputEvent::Steer(direction)),
+            Some(Gesture::Tap) => inputs.push(InputEvent::Tap),
+            None => {}
+        }
+        inputs
+    }
+}
@@ -0,0 +1,2032 @@
+// The rules: the board, the snake, food and scoring. Nothing in here knows about
+// windows, input devices or drawing, so any frontend (or a test) can drive a game.
+
+use crate::adaptive::Adaptive;
+use crate::boss::{Boss, BOSS_BONUS, BOSS_EVERY, BOSS_METAL};
+use crate::cheats::{Cheat, Cheats};
+use crate::clusters::{Cluster, CLUSTER_BONUS, CLUSTER_CHANCE, CLUSTER_SIZES};
+use crate::events::{Effect, GameEvent};
+use crate::grid::GridKind;
+use crate::levels::{Layout, Tile};
+use crate::mutators::Mutators;
+use crate::puzzle::Puzzle;
+use crate::rats::{axis_delta, Rat, MAX_RATS, RAT_CHANCE, RAT_POINTS};
+use crate::rooms::Level;
+use crate::rules::{InputTiming, LengthSpeed, Rules};
+use crate::scrolling::Track;
+use crate::spit::{Shot, MAX_AMMO, SHOT_RANGE, SHOT_SPEED};
+use crate::topology::{Edge, Topology};
+use crate::weather::{Puddle, PUDDLES_PER_RAIN, PUDDLE_GROWTH, PUDDLE_POINTS, RAIN_CHANCE};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const WIDTH: i32 = 30;
+pub const HEIGHT: i32 = 20;
+pub const SNAKE_SPEED: u64 = 15;
+const HIGH_SCORE_FILE: &str = "high_scores.txt";
+// Runs on the other grids get their own tables
+const DIAGONAL_HIGH_SCORE_FILE: &str = "high_scores_diagonal.txt";
+const HEX_HIGH_SCORE_FILE: &str = "high_scores_hex.txt";
+const KIDS_HIGH_SCORE_FILE: &str = "high_scores_kids.txt";
+// Every table by name, for tools that look at them all
+pub const HIGH_SCORE_TABLES: [(&str, &str); 4] = [
+    ("square", HIGH_SCORE_FILE),
+    ("diagonal", DIAGONAL_HIGH_SCORE_FILE),
+    ("hex", HEX_HIGH_SCORE_FILE),
+    ("kids", KIDS_HIGH_SCORE_FILE),
+];
+const MAX_HIGH_SCORES: usize = 5;
+pub const SCORE_BACKUPS: usize = 3; // Earlier tables kept next to each file, `.1` the newest
+const PUMPKIN_POINTS: u32 = 3;
+const NEAR_MISS_POINTS: u32 = 1;
+const EXTRA_LIFE_SHIELD_MOVES: u32 = 10; // Grace period after an extra life is used up
+const METAL_BITE_SHRINK: usize = 3; // Stomach segments lost to a forgiven bad metal bite
+const EXPAND_EVERY: u32 = 15; // Points per extra column and row with an expanding board
+const BREAKER_CHANCE: u32 = 400; // 1 in this many ticks, with walls on and none around
+const INDIGESTION_FRAMES: u64 = 600; // About 5 seconds
+pub const SPEED_WARNING_FRAMES: u64 = 240; // About 2 seconds of warning before a speed-up
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Right,
+    Left,
+    Up,
+    Down,
+}
+
+pub const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Right,
+    Direction::Down,
+    Direction::Left,
+];
+
+impl Direction {
+    pub fn parse(name: &str) -> Option<Direction> {
+        match name {
+            "Right" => Some(Direction::Right),
+            "Left" => Some(Direction::Left),
+            "Up" => Some(Direction::Up),
+            "Down" => Some(Direction::Down),
+            _ => None,
+        }
+    }
+
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::Right => Direction::Left,
+            Direction::Left => Direction::Right,
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FoodType {
+    RustyScrap,
+    ShinyMetal,
+    Water,
+    Pumpkin, // Seasonal scrap, only on the board as a `special_food`
+}
+
+pub const FOOD_TYPES: [FoodType; 4] = [
+    FoodType::RustyScrap,
+    FoodType::ShinyMetal,
+    FoodType::Water,
+    FoodType::Pumpkin,
+];
+
+impl FoodType {
+    pub fn parse(value: &str) -> Option<FoodType> {
+        FOOD_TYPES.iter().find(|food| food.id() == value).cloned()
+    }
+
+    // Its name in the console and data files
+    pub fn id(&self) -> &'static str {
+        match self {
+            FoodType::RustyScrap => "scrap",
+            FoodType::ShinyMetal => "metal",
+            FoodType::Water => "water",
+            FoodType::Pumpkin => "pumpkin",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SegmentType {
+    Head,
+    Tail,
+    EmptyStomach,
+    FullStomach,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DeathCause {
+    Wall,
+    SelfCollision,
+    MetalTooShort,  // Ate ShinyMetal before having any stomach
+    MetalNoStomach, // Ate ShinyMetal with every stomach full, before that gave indigestion
+    Boss,           // Caught by the magnet crane
+    Scrolled,       // Carried off the bottom of a scrolling board
+}
+
+pub const DEATH_CAUSES: [DeathCause; 6] = [
+    DeathCause::Wall,
+    DeathCause::SelfCollision,
+    DeathCause::MetalTooShort,
+    DeathCause::MetalNoStomach,
+    DeathCause::Boss,
+    DeathCause::Scrolled,
+];
+
+impl DeathCause {
+    pub fn parse(value: &str) -> Option<DeathCause> {
+        DEATH_CAUSES
+            .iter()
+            .copied()
+            .find(|cause| cause.id() == value)
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            DeathCause::Wall => "wall",
+            DeathCause::SelfCollision => "self",
+            DeathCause::MetalTooShort => "metal_too_short",
+            DeathCause::MetalNoStomach => "metal_no_stomach",
+            DeathCause::Boss => "boss",
+            DeathCause::Scrolled => "scrolled",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            DeathCause::Wall => "wall collision",
+            DeathCause::SelfCollision => "ran into yourself",
+            DeathCause::MetalTooShort => "ate metal without a stomach",
+            DeathCause::MetalNoStomach => "ate metal with a full stomach",
+            DeathCause::Boss => "caught by the crane",
+            DeathCause::Scrolled => "fell behind the scroll",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Food {
+    pub position: (i32, i32),
+    pub food_type: FoodType,
+    pub cluster: Option<u32>, // The id of the scrap cluster it belongs to, if any
+}
+
+#[derive(Clone)]
+pub struct Segment {
+    pub position: (i32, i32),
+    pub segment_type: SegmentType,
+}
+
+#[derive(Clone)]
+pub struct Snake {
+    pub body: Vec<Segment>,
+    pub direction: Direction,
+}
+
+#[derive(Clone)]
+pub struct HighScoreEntry {
+    pub name: String,
+    pub score: u32,
+    pub mutators: Mutators, // What the run was played with
+    pub perfect: bool,      // Reached the goal instead of dying
+    pub assisted: bool,     // Played with an assist such as the slow start
+    pub time: u64,          // Seconds since 1970 when it was set, 0 from older files
+}
+
+impl HighScoreEntry {
+    // name,score[,mutators[,flags[,time]]] - older files have fewer columns. The flags
+    // are `perfect` and `assisted`, joined with `+`.
+    pub fn parse(line: &str) -> Option<HighScoreEntry> {
+        let parts: Vec<&str> = line.split(',').collect();
+        if !(2..=5).contains(&parts.len()) {
+            return None;
+        }
+        let time = match parts.get(4) {
+            Some(time) => time.parse().ok()?,
+            None => 0,
+        };
+        let flags: Vec<&str> = parts.get(3).map_or(Vec::new(), |f| f.split('+').collect());
+        Some(HighScoreEntry {
+            name: parts[0].to_string(),
+            score: parts[1].parse().ok()?,
+            mutators: Mutators::parse(parts.get(2).unwrap_or(&""))?,
+            perfect: flags.contains(&"perfect"),
+            assisted: flags.contains(&"assisted"),
+            time,
+        })
+    }
+
+    pub fn line(&self) -> String {
+        let mut flags = Vec::new();
+        if self.perfect {
+            flags.push("perfect");
+        }
+        if self.assisted {
+            flags.push("assisted");
+        }
+        format!(
+            "{},{},{},{},{}",
+            self.name,
+            self.score,
+            self.mutators.ids(),
+            flags.join("+"),
+            self.time
+        )
+    }
+
+    // Entries from two machines are the same entry when all of these match
+    fn key(&self) -> (&str, u32, u64) {
+        (&self.name, self.score, self.time)
+    }
+}
+
+// What merging another high score table changed
+#[derive(Debug, Default, PartialEq)]
+pub struct MergeReport {
+    pub added: Vec<(String, u32)>, // New entries that made the table
+    pub duplicates: usize,         // Already in the table
+    pub crowded_out: usize,        // New, but not good enough to make the table
+}
+
+impl MergeReport {
+    pub fn summary(&self) -> String {
+        let added: Vec<String> = self
+            .added
+            .iter()
+            .map(|(name, score)| format!("{} {}", name, score))
+            .collect();
+        let added = if added.is_empty() {
+            "no new entries".to_string()
+        } else {
+            format!("added {}", added.join(", "))
+        };
+        format!(
+            "{}; {} already there, {} too low to make the table",
+            added, self.duplicates, self.crowded_out
+        )
+    }
+}
+
+// The length rules speeding the snake up, held back so the player gets a warning
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpeedUp {
+    pub interval: u64,    // The new tick interval
+    pub frames_left: u64, // Until it takes over
+}
+
+#[derive(Clone)]
+pub struct Game {
+    pub width: i32, // Board size in cells
+    pub height: i32,
+    pub snake: Snake,
+    pub foods: Vec<Food>,
+    pub score: u32,
+    pub game_over: bool,
+    pub won: bool, // Over because the goal was reached, not because the snake died
+    pub death_cause: Option<DeathCause>,
+    pub game_started: bool,
+    pub paused: bool,
+    pub frame_count: u64,
+    pub tick_interval: u64,        // Frames between snake moves
+    pub foods_eaten: usize,        // This run, of any kind
+    pub base_interval: u64,        // The same, before the rules adjust it for length
+    pub speed_up: Option<SpeedUp>, // A faster pace that's coming, announced in advance
+    pub rules: Rules,
+    pub wrap_around: bool,
+    pub topology: Option<Topology>, // Edges that don't all just wrap, see `set_topology`
+    pub tail_length: usize,         // Keeps track of tail growth
+    pub high_scores: Vec<HighScoreEntry>,
+    pub entering_name: bool,
+    pub player_name: String,
+    pub events: Vec<GameEvent>, // What happened during the last update, in order
+    pub spawned: Vec<(i32, i32)>, // Where food turned up during the last update, for feedback
+    pub hits: Vec<(i32, i32)>,  // Where rats were squashed or shot during the last update
+    pub rng: StdRng,            // Seeded, so a game can be reproduced exactly
+    pub save_scores: bool,      // Write the high score file when a score is added
+    pub cheats: Cheats,
+    pub special_food: Option<FoodType>, // Extra food kept on the board, e.g. a seasonal one
+    pub shield_moves: u32,              // Moves left during which nothing can kill the snake
+    pub extra_lives: u32,               // Deaths forgiven before the run really ends
+    pub metal_bites: u32,               // Bad metal bites that cost length instead of the run
+    pub extra_scrap: usize,             // Rusty scrap kept on the board besides the usual one
+    pub ranked: bool,                   // Can make the high score table
+    pub mutators: Mutators,
+    pub boss: Option<Boss>,
+    pub next_boss: u32,      // Score that brings on the next boss
+    pub next_expansion: u32, // Score that grows an expanding board
+    pub rats: Vec<Rat>,
+    pub spit_ammo: u32,
+    pub shots: Vec<Shot>,
+    pub breaker: Option<(i32, i32)>, // Wall-breaker pickup lying on the board
+    pub wall_breaker: bool,          // Picked up, the next wall hit knocks the wall down
+    pub open_walls: Vec<(i32, i32)>, // Knocked down wall cells, just off the board
+    pub level: Option<Level>,        // Rooms and doors, with the rooms mutator
+    pub track: Option<Track>,        // Rocks and distance, with the scrolling mutator
+    pub layout: Option<Layout>,      // Walls of a hand-made level
+    pub puddles: Vec<Puddle>,        // Left by rain, on the ground under the food
+    pub grid: GridKind,
+    pub heading: usize,  // Where the snake is going, as a heading of the grid
+    last_heading: usize, // Heading of the last move, turns are limited relative to it
+    pressed: Option<Direction>, // Steered since the last move, the first half of a chord
+    pub indigestion_frames: u64, // Frames left slowed down by metal the snake had no room for
+    pub kids: bool,      // Forgiving rules: half speed, walls bounce, metal never hurts
+    held_turn: Option<Direction>, // Steered with indigestion or in mud since the last move
+    pub queued: Vec<Direction>, // Strict input: pressed since the last move, oldest first
+    due_turn: Option<Direction>, // Held before the last move, taken now
+    stuck: bool,         // In mud and held back the last move
+    pub adaptive: Option<Adaptive>, // Adaptive difficulty, which carries over between runs
+    metal_rest: u32,     // Adaptive: moves before eaten metal comes back
+    pub near_misses: u32, // This run, see `check_near_miss`
+    pub cluster: Option<Cluster>, // Scrap cluster on the board, see `update_cluster`
+    clusters_spawned: u32, // This run, for the clusters' ids
+    pub puzzle: Option<Puzzle>, // Played move by move, see `set_puzzle`
+    pub moves_used: u32, // Puzzle: moves made so far, taken back ones not counted
+    step: bool,          // A move was pressed for and is waiting for the update, see `hurry`
+    undo: Vec<Game>,     // Puzzle: the game before each move, oldest first
+    near: bool,          // The head was next to a wall or the body after the last move
+}
+
+impl Game {
+    pub fn new(seed: u64, size: (i32, i32)) -> Game {
+        let mut game = Game::headless(seed, size);
+        game.save_scores = true;
+        game.load_high_scores();
+        game
+    }
+
+    // A game that never touches the high score file, for bots and tests
+    pub fn headless(seed: u64, (width, height): (i32, i32)) -> Game {
+        let mut snake_body = Vec::new();
+        let head_pos = (width / 2, height / 2);
+        snake_body.push(Segment {
+            position: head_pos,
+            segment_type: SegmentType::Head,
+        });
+
+        Game {
+            width,
+            height,
+            snake: Snake {
+                body: snake_body,
+                direction: Direction::Right,
+            },
+            foods: Vec::new(),
+            score: 0,
+            game_over: false,
+            won: false,
+            death_cause: None,
+            game_started: false,
+            paused: false,
+            frame_count: 0,
+            tick_interval: SNAKE_SPEED,
+            foods_eaten: 0,
+            base_interval: SNAKE_SPEED,
+            speed_up: None,
+            rules: Rules::default(),
+            wrap_around: true,
+            topology: None,
+            tail_length: 0, // Tail starts at length 0
+            high_scores: Vec::new(),
+            entering_name: false,
+            player_name: String::new(),
+            events: Vec::new(),
+            spawned: Vec::new(),
+            hits: Vec::new(),
+            rng: StdRng::seed_from_u64(seed),
+            save_scores: false,
+            cheats: Cheats::default(),
+            special_food: None,
+            shield_moves: 0,
+            extra_lives: 0,
+            metal_bites: 0,
+            extra_scrap: 0,
+            ranked: true,
+            mutators: Mutators::default(),
+            boss: None,
+            next_boss: BOSS_EVERY,
+            next_expansion: EXPAND_EVERY,
+            rats: Vec::new(),
+            spit_ammo: 0,
+            shots: Vec::new(),
+            breaker: None,
+            wall_breaker: false,
+            open_walls: Vec::new(),
+            level: None,
+            track: None,
+            layout: None,
+            puddles: Vec::new(),
+            grid: GridKind::Square,
+            heading: 0,
+            last_heading: 0,
+            pressed: None,
+            indigestion_frames: 0,
+            kids: false,
+            held_turn: None,
+            queued: Vec::new(),
+            due_turn: None,
+            stuck: false,
+            adaptive: None,
+            metal_rest: 0,
+            near_misses: 0,
+            near: false,
+            cluster: None,
+            clusters_spawned: 0,
+            puzzle: None,
+            moves_used: 0,
+            step: false,
+            undo: Vec::new(),
+        }
+    }
+
+    fn spawn_foods(&mut self) {
+        self.foods.clear();
+        self.cluster = None;
+        for food_type in [FoodType::RustyScrap, FoodType::ShinyMetal, FoodType::Water] {
+            if food_type == FoodType::Water && self.mutators.no_water {
+                continue;
+            }
+            let food = self.generate_food(food_type);
+            self.foods.push(food);
+        }
+        if let Some(food_type) = self.special_food.clone() {
+            let food = self.generate_food(food_type);
+            self.foods.push(food);
+        }
+    }
+
+    // Cells something new can turn up on: in the room the snake is in, off the rocks,
+    // and clear of the snake, food, the wall-breaker, puddles, rats and the boss. New
+    // board features belong in here, so nothing spawns on them. Row by row, so the same
+    // seed picks the same cell.
+    pub fn free_cells(&self) -> Vec<(i32, i32)> {
+        let taken: HashSet<(i32, i32)> = self
+            .snake
+            .body
+            .iter()
+            .map(|seg| seg.position)
+            .chain(self.foods.iter().map(|food| food.position))
+            .chain(self.puddles.iter().map(|puddle| puddle.position))
+            .chain(self.rats.iter().map(|rat| rat.position))
+            .chain(self.breaker)
+            .collect();
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter(|cell| !taken.contains(cell) && !self.boss_covers(*cell))
+            .filter(|cell| {
+                self.level
+                    .as_ref()
+                    .is_none_or(|level| level.in_active_room(*cell))
+            })
+            .filter(|cell| {
+                !self
+                    .track
+                    .as_ref()
+                    .is_some_and(|track| track.is_rock(*cell))
+            })
+            .filter(|cell| !self.layout.as_ref().is_some_and(|l| l.is_wall(*cell)))
+            .collect()
+    }
+
+    // Food on a free cell, every one as likely as the next
+    fn generate_food(&mut self, food_type: FoodType) -> Food {
+        let free = self.free_cells();
+        let position = if free.is_empty() {
+            // Nowhere left; anywhere is better than never returning
+            (
+                self.rng.gen_range(0..self.width),
+                self.rng.gen_range(0..self.height),
+            )
+        } else {
+            free[self.rng.gen_range(0..free.len())]
+        };
+        self.spawned.push(position);
+        Food {
+            position,
+            food_type,
+            cluster: None,
+        }
+    }
+
+    // Count the scrap cluster's time down, clearing away what's left of it when it's
+    // up, or maybe scatter a new one
+    fn update_cluster(&mut self) {
+        match self.cluster.as_mut() {
+            Some(cluster) if cluster.moves_left > 1 => cluster.moves_left -= 1,
+            Some(cluster) => {
+                let id = cluster.id;
+                self.foods.retain(|food| food.cluster != Some(id));
+                self.cluster = None;
+            }
+            None if self.rng.gen_ratio(1, CLUSTER_CHANCE) => self.spawn_cluster(),
+            None => {}
+        }
+    }
+
+    // Three or four pieces of scrap on free cells next to each other, grown one
+    // neighbour at a time from a random first cell. Nothing if there's no room.
+    fn spawn_cluster(&mut self) {
+        let free = self.free_cells();
+        if free.is_empty() {
+            return;
+        }
+        let size = self.rng.gen_range(CLUSTER_SIZES.0..=CLUSTER_SIZES.1);
+        let grid = self.grid.grid();
+        let mut cells = vec![free[self.rng.gen_range(0..free.len())]];
+        while cells.len() < size {
+            let mut next: Vec<(i32, i32)> = Vec::new();
+            for &cell in &cells {
+                for heading in 0..grid.headings() {
+                    let neighbor = grid.step(cell, heading);
+                    if free.contains(&neighbor)
+                        && !cells.contains(&neighbor)
+                        && !next.contains(&neighbor)
+                    {
+                        next.push(neighbor);
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            cells.push(next[self.rng.gen_range(0..next.len())]);
+        }
+        if cells.len() < CLUSTER_SIZES.0 {
+            return;
+        }
+        self.clusters_spawned += 1;
+        let id = self.clusters_spawned;
+        for &position in &cells {
+            self.foods.push(Food {
+                position,
+                food_type: FoodType::RustyScrap,
+                cluster: Some(id),
+            });
+        }
+        self.spawned.extend(cells);
+        self.cluster = Some(Cluster::new(id));
+    }
+
+    pub fn update(&mut self) {
+        self.events.clear();
+        self.spawned.clear();
+        self.hits.clear();
+        if self.paused {
+            return;
+        }
+        self.frame_count += 1;
+
+        if self.game_over || !self.game_started {
+            return;
+        }
+        if let Some(adaptive) = self.adaptive.as_mut() {
+            adaptive.tick();
+        }
+        if self.indigestion_frames == 1 {
+            self.events
+                .push(GameEvent::EffectExpired(Effect::Indigestion));
+        }
+        self.indigestion_frames = self.indigestion_frames.saturating_sub(1);
+        if let Some(speed_up) = self.speed_up.as_mut() {
+            speed_up.frames_left -= 1;
+            if speed_up.frames_left == 0 {
+                self.tick_interval = speed_up.interval;
+                self.speed_up = None;
+            }
+        }
+        let due = match self.puzzle {
+            // A puzzle moves when a direction is pressed, and nothing else happens
+            Some(_) => std::mem::take(&mut self.step),
+            None => {
+                std::mem::take(&mut self.step)
+                    || self.frame_count.is_multiple_of(self.move_interval())
+            }
+        };
+        if !due {
+            return;
+        }
+        if self.puzzle.is_some() {
+            self.advance();
+            self.check_puzzle();
+            return;
+        }
+
+        if self.shield_moves == 1 {
+            self.events.push(GameEvent::EffectExpired(Effect::Shield));
+        }
+        self.shield_moves = self.shield_moves.saturating_sub(1);
+        if self.rules.length_speed != LengthSpeed::Off {
+            self.pace_for_length();
+        }
+
+        if self.update_track() {
+            return;
+        }
+
+        // Spawn foods if not already present
+        if self.foods.is_empty() {
+            self.spawn_foods();
+        }
+        while self.food_on_board(FoodType::RustyScrap) < 1 + self.extra_scrap {
+            let food = self.generate_food(FoodType::RustyScrap);
+            self.foods.push(food);
+        }
+        if let Some(adaptive) = &self.adaptive {
+            self.metal_rest = self.metal_rest.saturating_sub(1);
+            if self.metal_rest == 0
+                && self.food_on_board(FoodType::ShinyMetal) < adaptive.metal_pieces()
+            {
+                let food = self.generate_food(FoodType::ShinyMetal);
+                self.foods.push(food);
+            }
+        }
+        self.update_cluster();
+
+        if !self.wrap_around
+            && self.breaker.is_none()
+            && !self.wall_breaker
+            && self.rng.gen_ratio(1, BREAKER_CHANCE)
+        {
+            self.breaker = Some(self.generate_food(FoodType::RustyScrap).position);
+        }
+
+        if self.update_boss() {
+            return;
+        }
+        self.advance();
+    }
+
+    // The snake's move: held back by mud, turning if it was steered, then on a cell
+    // and maybe another along a speed lane
+    fn advance(&mut self) {
+        // Mud holds the head back every other move
+        self.stuck = self.in_mud() && !self.stuck;
+        if self.stuck {
+            return;
+        }
+
+        // Turns steered with indigestion or in mud come a move late
+        if let Some(direction) = self.due_turn.take() {
+            self.apply_steer(direction);
+        }
+        self.due_turn = self.held_turn.take();
+        self.apply_queued();
+
+        self.move_snake(false);
+        // A speed lane the head has just come onto going its way carries it a cell
+        // further, checked like a move of its own
+        if !self.game_over && self.on_speed_lane() {
+            self.move_snake(true);
+        }
+    }
+
+    // One cell forward: collisions, food and growing. `extra` is the second cell of a
+    // move doubled by a speed lane, which the rats, shots and puddles sit out.
+    fn move_snake(&mut self, extra: bool) {
+        // Calculate new head position
+        let head = self.snake.body[0].position;
+        let heading = self.current_heading();
+        let next = self.grid.grid().step(head, heading);
+        self.last_heading = heading;
+        self.pressed = None;
+        let new_head_pos = match self.enter(next, heading) {
+            Some(position) => position,
+            None if self.wall_breaker => self.break_wall(next),
+            None if self.kids || self.bounces_off(next) => match self.bounce(head) {
+                Some(position) => position,
+                // Boxed in, it waits for a way out
+                None => return,
+            },
+            None => {
+                // An invincible snake just waits at the wall for a turn
+                self.die(DeathCause::Wall);
+                return;
+            }
+        };
+
+        // Check for collision with self, slipping diagonally through the body included
+        if self.squeezes_through(head, next) && self.die(DeathCause::SelfCollision) {
+            return;
+        }
+        if self
+            .snake
+            .body
+            .iter()
+            .any(|seg| seg.position == new_head_pos)
+            && self.die(DeathCause::SelfCollision)
+        {
+            return;
+        }
+        if self.boss_covers(new_head_pos) && self.die(DeathCause::Boss) {
+            return;
+        }
+
+        if self.breaker == Some(new_head_pos) {
+            self.breaker = None;
+            self.wall_breaker = true;
+        }
+
+        // Check for food at new head position
+        let mut ate_food = false;
+        let mut food_type = None;
+        if let Some(index) = self.foods.iter().position(|food| food.position == new_head_pos) {
+            ate_food = true;
+            self.foods_eaten += 1;
+            food_type = Some(self.foods[index].food_type.clone());
+            self.events.push(GameEvent::FoodEaten {
+                food: self.foods[index].food_type.clone(),
+                cell: new_head_pos,
+            });
+            let food = self.foods[index].food_type.clone();
+            let cluster = self.foods[index].cluster;
+            match &self.adaptive {
+                // A cluster's or a puzzle's pieces are gone once eaten
+                _ if cluster.is_some() || self.puzzle.is_some() => {
+                    self.foods.remove(index);
+                }
+                // Adaptive difficulty tops metal up on its own schedule
+                Some(adaptive) if food == FoodType::ShinyMetal => {
+                    self.metal_rest = adaptive.metal_rest();
+                    self.foods.remove(index);
+                }
+                _ => self.foods[index] = self.generate_food(food),
+            }
+            if cluster.is_some_and(|id| !self.foods.iter().any(|f| f.cluster == Some(id))) {
+                self.cluster = None;
+                self.award(CLUSTER_BONUS);
+                self.events.push(GameEvent::ClusterCleared(new_head_pos));
+            }
+            if let Some(level) = &mut self.level {
+                level.ate();
+            }
+        }
+        if self
+            .level
+            .as_mut()
+            .is_some_and(|level| level.enter(new_head_pos))
+        {
+            // Food only turns up in the room the snake is in
+            self.spawn_foods();
+        }
+
+        // Move segments
+        let mut new_positions: Vec<(i32, i32)> = vec![new_head_pos];
+        for i in 0..self.snake.body.len() - 1 {
+            new_positions.push(self.snake.body[i].position);
+        }
+        for (segment, &new_pos) in self.snake.body.iter_mut().zip(new_positions.iter()) {
+            segment.position = new_pos;
+        }
+
+        // Update segment types if necessary
+        // Ensure the first segment is always the head
+        self.snake.body[0].segment_type = SegmentType::Head;
+
+        self.check_near_miss();
+        if !extra {
+            // Puzzles have nothing wander in or rain down
+            let world = self.puzzle.is_none();
+            if world {
+                self.update_rats();
+            }
+            self.update_shots();
+            if world {
+                self.update_puddles(new_head_pos);
+            }
+        }
+
+        // Handle food effects
+        if ate_food {
+            match food_type.unwrap() {
+                FoodType::RustyScrap => {
+                    self.award(1);
+                    self.grow();
+                }
+                FoodType::Pumpkin => {
+                    self.award(PUMPKIN_POINTS);
+                    self.grow();
+                }
+                FoodType::ShinyMetal => {
+                    // Check if snake length >= 5 (head + tail of 3 + at least one stomach segment)
+                    if self.snake.body.len() < 5 {
+                        self.bad_metal(DeathCause::MetalTooShort);
+                        return;
+                    }
+                    // Check for empty stomach segment, with room for more metal
+                    let room = self.full_stomachs() < self.rules.stomach_capacity;
+                    if let Some(empty_stomach_index) = self
+                        .snake
+                        .body
+                        .iter()
+                        .position(|seg| seg.segment_type == SegmentType::EmptyStomach)
+                        .filter(|_| room)
+                    {
+                        // Change one empty stomach segment to full stomach
+                        self.snake.body[empty_stomach_index].segment_type = SegmentType::FullStomach;
+                        self.award(2);
+                    } else {
+                        // At capacity or with every stomach full, it sits badly for a while
+                        self.indigestion_frames = INDIGESTION_FRAMES;
+                    }
+                }
+                FoodType::Water => {
+                    // With no shiny scrap stored, water does nothing
+                    if self.digest(5, 5) {
+                        self.spit_ammo = (self.spit_ammo + 1).min(MAX_AMMO);
+                    }
+                }
+            }
+        }
+
+        if let Some(goal) = self.rules.goal {
+            if goal.reached(self.score, self.snake.body.len()) {
+                self.won = true;
+                self.game_over = true;
+                self.check_high_score();
+            }
+        }
+    }
+
+    fn in_mud(&self) -> bool {
+        let head = self.snake.body[0].position;
+        self.layout
+            .as_ref()
+            .is_some_and(|layout| layout.get(head) == Tile::Mud)
+    }
+
+    // Whether the head is on a speed lane going the way the snake is
+    fn on_speed_lane(&self) -> bool {
+        let head = self.snake.body[0].position;
+        self.layout
+            .as_ref()
+            .and_then(|layout| layout.lane(head))
+            .is_some_and(|lane| self.grid.grid().heading_for(lane) == self.current_heading())
+    }
+
+    // Stomach segments holding metal, each one a Water pickup that's worth points now
+    pub fn full_stomachs(&self) -> usize {
+        self.snake
+            .body
+            .iter()
+            .filter(|seg| seg.segment_type == SegmentType::FullStomach)
+            .count()
+    }
+
+    // Wash down a piece of metal: one full stomach segment turns back to empty, and
+    // the tail grows by `growth` empty stomach segments. False if nothing was stored.
+    fn digest(&mut self, points: u32, growth: usize) -> bool {
+        let Some(full_stomach_index) = self
+            .snake
+            .body
+            .iter()
+            .position(|seg| seg.segment_type == SegmentType::FullStomach)
+        else {
+            return false;
+        };
+        self.snake.body[full_stomach_index].segment_type = SegmentType::EmptyStomach;
+        self.award(points);
+        // Grow tail by adding empty stomach segments before the tail
+        let tail_start_index = self
+            .snake
+            .body
+            .iter()
+            .position(|seg| seg.segment_type == SegmentType::Tail)
+            .unwrap();
+        let tail_pos = self.snake.body[tail_start_index].position;
+        for _ in 0..growth {
+            self.snake.body.insert(
+                tail_start_index,
+                Segment {
+                    position: tail_pos,
+                    segment_type: SegmentType::EmptyStomach,
+                },
+            );
+            self.events.push(GameEvent::SegmentAdded(tail_start_index));
+        }
+        true
+    }
+
+    // Dry the puddles out a little, maybe bring a shower, and let the snake drink the
+    // puddle its head is on if it has metal to wash down
+    fn update_puddles(&mut self, head: (i32, i32)) {
+        for puddle in &mut self.puddles {
+            puddle.moves_left = puddle.moves_left.saturating_sub(1);
+        }
+        self.puddles.retain(|puddle| puddle.moves_left > 0);
+
+        if self.puddles.is_empty() && self.rng.gen_ratio(1, RAIN_CHANCE) {
+            for _ in 0..PUDDLES_PER_RAIN {
+                let position = self.generate_food(FoodType::Water).position;
+                if !self.puddles.iter().any(|p| p.position == position) {
+                    self.puddles.push(Puddle::new(position));
+                }
+            }
+        }
+
+        if let Some(index) = self.puddles.iter().position(|p| p.position == head) {
+            if self.digest(PUDDLE_POINTS, PUDDLE_GROWTH) {
+                self.puddles.remove(index);
+            }
+        }
+    }
+
+    // The cell one step from `position`, wrapping around the edges if that's on or
+    // the wall there has been knocked down. None if the step would hit a wall.
+    pub fn neighbor(&self, position: (i32, i32), direction: Direction) -> Option<(i32, i32)> {
+        let heading = self.grid.grid().heading_for(direction);
+        self.enter(step(position, direction), heading)
+    }
+
+    // `arrive`, for a step going `heading`, which a one-way gate the other way stops
+    fn enter(&self, cell: (i32, i32), heading: usize) -> Option<(i32, i32)> {
+        let cell = self.arrive(cell)?;
+        let gate = self.layout.as_ref().and_then(|layout| layout.gate(cell));
+        match gate {
+            Some(gate) if self.grid.grid().heading_for(gate) != heading => None,
+            _ => Some(cell),
+        }
+    }
+
+    // Where a step onto `(x, y)`, possibly just off the board, ends up
+    fn arrive(&self, (x, y): (i32, i32)) -> Option<(i32, i32)> {
+        let cell = self.wrap((x, y));
+        if self
+            .layout
+            .as_ref()
+            .is_some_and(|layout| layout.is_wall(cell))
+        {
+            None
+        } else if cell != (x, y) {
+            Some(cell)
+        } else if (0..self.width).contains(&x)
+            && (0..self.height).contains(&y)
+            && !self
+                .level
+                .as_ref()
+                .is_some_and(|level| level.is_wall((x, y)))
+            && !self
+                .track
+                .as_ref()
+                .is_some_and(|track| track.is_rock((x, y)))
+        {
+            Some((x, y))
+        } else {
+            None
+        }
+    }
+
+    // `(x, y)` brought back onto the board across an edge it can be crossed at
+    fn wrap(&self, (x, y): (i32, i32)) -> (i32, i32) {
+        if self.wrap_around || self.open_walls.contains(&(x, y)) {
+            (x.rem_euclid(self.width), y.rem_euclid(self.height))
+        } else if let Some(topology) = &self.topology {
+            let board = (self.width, self.height);
+            topology.enter((x, y), board).unwrap_or((x, y))
+        } else {
+            (x, y)
+        }
+    }
+
+    // Whether a step onto `cell` goes over a bounce edge
+    fn bounces_off(&self, cell: (i32, i32)) -> bool {
+        self.topology.is_some_and(|topology| {
+            topology.crossed(cell, (self.width, self.height)) == Some(Edge::Bounce)
+        })
+    }
+
+    // Knock down the wall cell the snake is about to hit, along with the one facing it
+    // across the board so the tunnel works both ways. Returns where the snake comes out.
+    fn break_wall(&mut self, (x, y): (i32, i32)) -> (i32, i32) {
+        self.wall_breaker = false;
+        let cell = self.wrap((x, y));
+        if let Some(layout) = self
+            .layout
+            .as_mut()
+            .filter(|layout| layout.is_wall(cell) || layout.gate(cell).is_some())
+        {
+            layout.set(cell, Tile::Floor);
+            return cell;
+        }
+        if (0..self.width).contains(&x) && (0..self.height).contains(&y) {
+            // A wall between two rooms, or a rock
+            if let Some(level) = &mut self.level {
+                level.openings.push((x, y));
+            }
+            if let Some(track) = &mut self.track {
+                track.rocks.retain(|rock| *rock != (x, y));
+            }
+            return (x, y);
+        }
+        let facing = if x >= self.width {
+            (-1, y)
+        } else if x < 0 {
+            (self.width, y)
+        } else if y >= self.height {
+            (x, -1)
+        } else {
+            (x, self.height)
+        };
+        self.open_walls.push((x, y));
+        self.open_walls.push(facing);
+        (x.rem_euclid(self.width), y.rem_euclid(self.height))
+    }
+
+    // Eating shiny metal now would be safe: the snake is long enough and has room,
+    // an empty stomach and capacity left, so it doesn't bring on indigestion
+    pub fn can_digest_metal(&self) -> bool {
+        self.snake.body.len() >= 5
+            && self.full_stomachs() < self.rules.stomach_capacity
+            && self
+                .snake
+                .body
+                .iter()
+                .any(|seg| seg.segment_type == SegmentType::EmptyStomach)
+    }
+
+    // A near miss is the head coming up right next to a wall or its own body (past its
+    // neck) without hitting it. Slipping along one counts once, when the head gets there.
+    fn check_near_miss(&mut self) {
+        let mut cells: Vec<(i32, i32)> = self.snake.body.iter().map(|seg| seg.position).collect();
+        // New stomach segments start out on top of the one before
+        cells.dedup();
+        let head = cells[0];
+        let body = cells.get(2..).unwrap_or_default();
+        let grid = self.grid.grid();
+        let hazard = (0..grid.headings())
+            .map(|heading| (grid.step(head, heading), heading))
+            .filter(|(cell, _)| cells.get(1) != Some(cell))
+            .find(|&(cell, heading)| {
+                self.enter(cell, heading)
+                    .is_none_or(|cell| body.contains(&cell))
+            })
+            .map(|(cell, _)| cell);
+        let near = hazard.is_some();
+        let Some(cell) = hazard.filter(|_| !self.near) else {
+            self.near = near;
+            return;
+        };
+        self.near = true;
+        self.near_misses += 1;
+        self.award(NEAR_MISS_POINTS);
+        self.events.push(GameEvent::NearMiss(cell));
+        if let Some(adaptive) = self.adaptive.as_mut() {
+            adaptive.close_call();
+        }
+    }
+
+    // Score points, scaled by the mutators' multiplier
+    fn award(&mut self, points: u32) {
+        self.score += (points as f64 * self.mutators.multiplier()).round() as u32;
+        while self.mutators.expanding_board && self.score >= self.next_expansion {
+            self.next_expansion += EXPAND_EVERY;
+            self.expand_board();
+        }
+    }
+
+    // Add a column on the right and a row at the bottom. Knocked down walls on those
+    // edges move out with them.
+    fn expand_board(&mut self) {
+        for (x, y) in &mut self.open_walls {
+            if *x == self.width {
+                *x += 1;
+            }
+            if *y == self.height {
+                *y += 1;
+            }
+        }
+        self.width += 1;
+        self.height += 1;
+        if let Some(level) = &mut self.level {
+            level.grow();
+        }
+    }
+
+    // Pick the mutators for a run that hasn't started yet. `board` is the size the
+    // board would have without them.
+    pub fn set_mutators(&mut self, mutators: Mutators, board: (i32, i32)) {
+        if self.game_started {
+            return;
+        }
+        self.mutators = mutators;
+        (self.width, self.height) = mutators.board_size(board);
+        // Rooms are walled in all round
+        self.level = mutators
+            .rooms
+            .then(|| Level::new((self.width, self.height)));
+        self.track = mutators.scrolling.then(Track::default);
+        self.wrap_around = self.level.is_none() && self.track.is_none();
+        // Nothing has moved yet, so the whole body is stacked on the head
+        let start = match &self.level {
+            Some(level) => level.start(),
+            None => (self.width / 2, self.height / 2),
+        };
+        for segment in &mut self.snake.body {
+            segment.position = start;
+        }
+        self.base_interval = if mutators.double_speed {
+            SNAKE_SPEED / 2
+        } else {
+            SNAKE_SPEED
+        };
+        self.tick_interval = self.base_interval;
+        self.speed_up = None;
+    }
+
+    // Play on a hand-made layout, which sets the board size. Only before the run starts.
+    pub fn set_layout(&mut self, layout: Layout) {
+        if self.game_started {
+            return;
+        }
+        (self.width, self.height) = (layout.width, layout.height);
+        for segment in &mut self.snake.body {
+            segment.position = layout.start();
+        }
+        self.layout = Some(layout);
+    }
+
+    // What the edges do for a run that hasn't started yet. Rooms and the scrolling track
+    // are walled in all round whatever it says.
+    pub fn set_topology(&mut self, topology: Topology) {
+        if self.game_started || self.level.is_some() || self.track.is_some() {
+            return;
+        }
+        self.wrap_around = topology == Topology::default();
+        self.topology = Some(topology).filter(|_| !self.wrap_around);
+    }
+
+    // Where the snake will move next, as a heading of the grid
+    pub fn current_heading(&self) -> usize {
+        match self.grid {
+            GridKind::Square => self.grid.grid().heading_for(self.snake.direction),
+            GridKind::Diagonal | GridKind::Hex => self.heading,
+        }
+    }
+
+    // Two perpendicular directions steered between moves (a chord, or one of the
+    // numpad's corner keys) make a diagonal. Anything but a straight reversal goes.
+    fn steer_diagonal(&mut self, direction: Direction) -> bool {
+        let grid = self.grid.grid();
+        let first = self.pressed.replace(direction);
+        let heading = match first.filter(|d| *d != direction && *d != direction.opposite()) {
+            Some(first) => {
+                let (a, b) = (step((0, 0), first), step((0, 0), direction));
+                let sum = (a.0 + b.0, a.1 + b.1);
+                (0..grid.headings())
+                    .find(|h| grid.step((0, 0), *h) == sum)
+                    .unwrap_or(self.heading)
+            }
+            None => grid.heading_for(direction),
+        };
+        if heading == self.heading || heading == (self.last_heading + 4) % 8 {
+            return false;
+        }
+        self.heading = heading;
+        self.snake.direction = grid.direction(heading);
+        true
+    }
+
+    // A diagonal step between two body segments that follow each other cuts through
+    // the body, even though neither cell is hit
+    fn squeezes_through(&self, head: (i32, i32), next: (i32, i32)) -> bool {
+        if head.0 == next.0 || head.1 == next.1 {
+            return false;
+        }
+        let index = |cell: Option<(i32, i32)>| {
+            let cell = cell?;
+            self.snake.body.iter().position(|seg| seg.position == cell)
+        };
+        let beside = index(self.arrive((next.0, head.1)));
+        let below = index(self.arrive((head.0, next.1)));
+        matches!((beside, below), (Some(a), Some(b)) if a.abs_diff(b) == 1)
+    }
+
+    // Hex steering is relative: left and right turn a sixth of the way round, at most
+    // one turn away from the last move so the snake can't fold back into itself
+    fn turn(&mut self, direction: Direction) -> bool {
+        let headings = self.grid.grid().headings();
+        let heading = match direction {
+            Direction::Left => (self.heading + headings - 1) % headings,
+            Direction::Right => (self.heading + 1) % headings,
+            Direction::Up | Direction::Down => return false,
+        };
+        let from_last = (heading + headings - self.last_heading) % headings;
+        if from_last > 1 && from_last < headings - 1 {
+            return false;
+        }
+        self.heading = heading;
+        self.snake.direction = self.grid.grid().direction(heading);
+        true
+    }
+
+    // Turn off a wall onto the free heading closest to the current one, never straight
+    // back into the neck. Returns where that takes the head.
+    fn bounce(&mut self, head: (i32, i32)) -> Option<(i32, i32)> {
+        let grid = self.grid.grid();
+        let headings = grid.headings();
+        let current = self.current_heading();
+        for turn in 1..headings / 2 {
+            for heading in [
+                (current + turn) % headings,
+                (current + headings - turn) % headings,
+            ] {
+                let Some(cell) = self.enter(grid.step(head, heading), heading) else {
+                    continue;
+                };
+                if self.snake.body.iter().any(|seg| seg.position == cell) {
+                    continue;
+                }
+                self.heading = heading;
+                self.last_heading = heading;
+                self.snake.direction = grid.direction(heading);
+                return Some(cell);
+            }
+        }
+        None
+    }
+
+    // Kids mode for the run that just started, with its own high score table
+    pub fn set_kids(&mut self) {
+        self.kids = true;
+        self.base_interval *= 2;
+        self.tick_interval *= 2;
+        if self.save_scores {
+            self.high_scores.clear();
+            self.load_high_scores();
+        }
+    }
+
+    // Switch board geometry, which also switches to that geometry's high score table
+    pub fn set_grid(&mut self, grid: GridKind) {
+        self.grid = grid;
+        self.heading = grid.grid().heading_for(self.snake.direction);
+        self.last_heading = self.heading;
+        if self.save_scores {
+            self.high_scores.clear();
+            self.load_high_scores();
+        }
+    }
+
+    fn high_score_file(&self) -> &'static str {
+        if self.kids {
+            return KIDS_HIGH_SCORE_FILE;
+        }
+        match self.grid {
+            GridKind::Square => HIGH_SCORE_FILE,
+            GridKind::Diagonal => DIAGONAL_HIGH_SCORE_FILE,
+            GridKind::Hex => HEX_HIGH_SCORE_FILE,
+        }
+    }
+
+    pub fn toggle_pause(&mut self) {
+        if self.game_started && !self.game_over {
+            self.paused = !self.paused;
+        }
+    }
+
+    // Fingerprint of the state moves depend on, for checking that a replay plays out
+    // exactly as it was recorded. FNV-1a rather than std's hasher, whose output may
+    // change between Rust versions.
+    pub fn state_hash(&self) -> u64 {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut add = |value: i64| {
+            for byte in value.to_le_bytes() {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        };
+        add(self.frame_count as i64);
+        add(self.score as i64);
+        add(self.tick_interval as i64);
+        if let Some(speed_up) = self.speed_up {
+            add(speed_up.interval as i64);
+            add(speed_up.frames_left as i64);
+        }
+        add(self.game_over as i64);
+        if let Some(adaptive) = &self.adaptive {
+            add(adaptive.level as i64);
+        }
+        add(self.snake.direction as i64);
+        for segment in &self.snake.body {
+            add(segment.position.0 as i64);
+            add(segment.position.1 as i64);
+            add(segment.segment_type.clone() as i64);
+        }
+        for food in &self.foods {
+            add(food.position.0 as i64);
+            add(food.position.1 as i64);
+            add(food.food_type.clone() as i64);
+            add(food.cluster.unwrap_or(0) as i64);
+        }
+        for rat in &self.rats {
+            add(rat.position.0 as i64);
+            add(rat.position.1 as i64);
+        }
+        if let Some(boss) = &self.boss {
+            add(boss.position.0 as i64);
+            add(boss.position.1 as i64);
+        }
+        // Where the random numbers have got to, without using any up
+        add(self.rng.clone().gen::<i64>());
+        hash
+    }
+
+    // True if the next call to update will move the snake
+    pub fn tick_due(&self) -> bool {
+        self.game_started
+            && !self.game_over
+            && !self.paused
+            && (self.step || (self.frame_count + 1).is_multiple_of(self.move_interval()))
+    }
+
+    // Move at the next update rather than when the move comes due, like a duel player
+    // pressing a key on their turn
+    pub fn hurry(&mut self) {
+        if self.game_started && !self.game_over && self.puzzle.is_none() {
+            self.step = true;
+        }
+    }
+
+    // Apply a steering input. The first direction pressed starts the game.
+    // Returns true if the snake actually turned.
+    pub fn steer(&mut self, direction: Direction) -> bool {
+        if self.game_over || self.paused {
+            return false;
+        }
+        if !self.game_started {
+            self.game_started = true;
+            self.snake.direction = direction;
+            self.heading = self.grid.grid().heading_for(direction);
+            self.last_heading = self.heading;
+            if self.puzzle.is_none() {
+                self.spawn_foods();
+                return false;
+            }
+        }
+        if self.puzzle.is_some() {
+            return self.puzzle_steer(direction);
+        }
+        if self.indigestion_frames > 0 || self.in_mud() {
+            self.held_turn = Some(direction);
+            return false;
+        }
+        if self.rules.input_timing == InputTiming::Strict {
+            // Whether it turns is only known at the move
+            self.queued.push(direction);
+            return false;
+        }
+        self.apply_steer(direction)
+    }
+
+    // A press in a puzzle is a move, turning or going straight on, that can be taken
+    // back. Returns true if the snake turned.
+    fn puzzle_steer(&mut self, direction: Direction) -> bool {
+        let reverse = self.grid == GridKind::Square
+            && self.snake.body.len() > 1
+            && direction == self.snake.direction.opposite();
+        let out_of_moves = self
+            .puzzle
+            .as_ref()
+            .is_some_and(|puzzle| self.moves_used >= puzzle.moves);
+        if self.step || reverse || out_of_moves {
+            return false;
+        }
+        let undo = std::mem::take(&mut self.undo);
+        let before = self.clone();
+        self.undo = undo;
+        self.undo.push(before);
+        self.moves_used += 1;
+        self.step = true;
+        self.apply_steer(direction)
+    }
+
+    // Take the last move of a puzzle back, the one that ended it included. False if
+    // there's none to take back.
+    pub fn undo(&mut self) -> bool {
+        let Some(mut before) = self.undo.pop() else {
+            return false;
+        };
+        before.undo = std::mem::take(&mut self.undo);
+        *self = before;
+        true
+    }
+
+    // Lay a puzzle's food out on the waiting game, see `puzzle`
+    pub fn set_puzzle(&mut self, puzzle: Puzzle) {
+        if self.game_started {
+            return;
+        }
+        self.foods = puzzle
+            .foods
+            .iter()
+            .map(|(food_type, position)| Food {
+                position: *position,
+                food_type: food_type.clone(),
+                cluster: None,
+            })
+            .collect();
+        self.ranked = false;
+        self.puzzle = Some(puzzle);
+    }
+
+    // A puzzle is solved once its food is all eaten, and lost when its moves are up
+    fn check_puzzle(&mut self) {
+        let Some(puzzle) = &self.puzzle else {
+            return;
+        };
+        if self.game_over {
+            return;
+        }
+        if self.foods.is_empty() {
+            self.won = true;
+            self.game_over = true;
+        } else if self.moves_used >= puzzle.moves {
+            self.game_over = true;
+        }
+    }
+
+    // Stars for a solved puzzle
+    pub fn stars(&self) -> Option<u32> {
+        let puzzle = self.puzzle.as_ref().filter(|_| self.won)?;
+        Some(puzzle.stars(self.moves_used))
+    }
+
+    // Strict input: take the latest of the queued presses that is a valid turn. On the
+    // diagonal board the press before it counts as the other half of a chord.
+    fn apply_queued(&mut self) {
+        let queued = std::mem::take(&mut self.queued);
+        for (i, direction) in queued.iter().enumerate().rev() {
+            if self.grid == GridKind::Diagonal {
+                self.pressed = i.checked_sub(1).map(|before| queued[before]);
+            }
+            if self.apply_steer(*direction) {
+                break;
+            }
+        }
+    }
+
+    fn apply_steer(&mut self, direction: Direction) -> bool {
+        match self.grid {
+            GridKind::Square => {}
+            GridKind::Diagonal => return self.steer_diagonal(direction),
+            GridKind::Hex => return self.turn(direction),
+        }
+        // Reversals are checked against the last move rather than the last turn, so two
+        // quick presses between moves can't fold the snake back into its neck
+        let moved = self.grid.grid().direction(self.last_heading);
+        if direction == self.snake.direction || direction == moved.opposite() {
+            return false;
+        }
+        self.snake.direction = direction;
+        true
+    }
+
+    // Slowing down takes effect from the next move. Speeding up waits
+    // SPEED_WARNING_FRAMES, so it never comes as a surprise.
+    fn pace_for_length(&mut self) {
+        let interval = self
+            .rules
+            .tick_interval(self.base_interval, self.snake.body.len());
+        if interval >= self.tick_interval {
+            self.tick_interval = interval;
+            self.speed_up = None;
+        } else if let Some(speed_up) = self.speed_up.as_mut() {
+            // Growing again during the warning just makes the coming pace faster
+            speed_up.interval = interval;
+        } else {
+            self.speed_up = Some(SpeedUp {
+                interval,
+                frames_left: SPEED_WARNING_FRAMES,
+            });
+        }
+    }
+
+    // Frames between moves right now: half as many again with indigestion, more early
+    // in the run with the slow start, and as adaptive difficulty has it
+    pub fn move_interval(&self) -> u64 {
+        let mut interval = if self.indigestion_frames > 0 {
+            self.tick_interval + self.tick_interval / 2
+        } else {
+            self.tick_interval
+        };
+        if let Some(adaptive) = &self.adaptive {
+            interval = adaptive.interval(interval);
+        }
+        self.rules.slow_start_interval(interval, self.foods_eaten)
+    }
+
+    // End the run, unless a cheat, a shield or an extra life says otherwise. Returns
+    // true if the snake died.
+    fn die(&mut self, cause: DeathCause) -> bool {
+        if self.cheats.invincible || self.shield_moves > 0 {
+            return false;
+        }
+        if let Some(adaptive) = self.adaptive.as_mut() {
+            // An extra life used up counts too
+            adaptive.died();
+        }
+        if self.extra_lives > 0 {
+            // Shielded for a few moves, so there's time to steer away from what hit it
+            self.extra_lives -= 1;
+            self.shield_moves = EXTRA_LIFE_SHIELD_MOVES;
+            return false;
+        }
+        self.game_over = true;
+        self.death_cause = Some(cause);
+        self.events.push(GameEvent::Died(cause));
+        self.check_high_score();
+        true
+    }
+
+    // Metal with nowhere to put it. With bites to spare the snake coughs up some of its
+    // stomach instead of dying.
+    fn bad_metal(&mut self, cause: DeathCause) {
+        if self.kids {
+            return;
+        }
+        if self.metal_bites == 0 {
+            self.die(cause);
+            return;
+        }
+        self.metal_bites -= 1;
+        for _ in 0..METAL_BITE_SHRINK {
+            let stomach = self.snake.body.iter().rposition(|seg| {
+                matches!(
+                    seg.segment_type,
+                    SegmentType::EmptyStomach | SegmentType::FullStomach
+                )
+            });
+            if let Some(index) = stomach {
+                self.snake.body.remove(index);
+            }
+        }
+    }
+
+    // Bring on, move and resolve the boss. Returns true if it killed the snake.
+    fn update_boss(&mut self) -> bool {
+        let board = (self.width, self.height);
+        let head = self.snake.body[0].position;
+        if self.boss.is_none() && self.score >= self.next_boss {
+            while self.next_boss <= self.score {
+                self.next_boss += BOSS_EVERY;
+            }
+            // Start in the corner furthest from the head
+            let x = if head.0 < self.width / 2 {
+                self.width - 2
+            } else {
+                0
+            };
+            let y = if head.1 < self.height / 2 {
+                self.height - 2
+            } else {
+                0
+            };
+            self.boss = Some(Boss::new((x, y)));
+        }
+        let Some(mut boss) = self.boss.take() else {
+            return false;
+        };
+        if !boss.tick(head, board, self.wrap_around) {
+            return false; // Gave up and left
+        }
+
+        // The magnet picks up any shiny metal under the crane
+        self.boss = Some(boss);
+        let mut metal = 0;
+        for index in 0..self.foods.len() {
+            let food = &self.foods[index];
+            if food.food_type == FoodType::ShinyMetal && boss.covers(food.position, board) {
+                metal += 1;
+                self.foods[index] = self.generate_food(FoodType::ShinyMetal);
+            }
+        }
+        boss.metal += metal;
+        if boss.metal >= BOSS_METAL {
+            self.boss = None;
+            self.award(BOSS_BONUS);
+            return false;
+        }
+        self.boss = Some(boss);
+        boss.covers(head, board) && self.die(DeathCause::Boss)
+    }
+
+    // Scroll the board a row when it's due, carrying everything on it down with the
+    // rocks. Returns true if that pushed the snake off the bottom.
+    fn update_track(&mut self) -> bool {
+        let board = (self.width, self.height);
+        let Some(track) = self.track.as_mut() else {
+            return false;
+        };
+        if !track.tick() {
+            return false;
+        }
+        track.scroll(&mut self.rng, board);
+
+        let bottom = self.height - 1;
+        if self.snake.body.iter().any(|seg| seg.position.1 == bottom) {
+            if self.die(DeathCause::Scrolled) {
+                return true;
+            }
+            // Holding on: the snake stays put and the rocks give way around it
+            let body: Vec<(i32, i32)> = self.snake.body.iter().map(|s| s.position).collect();
+            if let Some(track) = self.track.as_mut() {
+                track.rocks.retain(|rock| !body.contains(rock));
+            }
+        } else {
+            for segment in &mut self.snake.body {
+                segment.position.1 += 1;
+            }
+        }
+
+        for rat in &mut self.rats {
+            rat.position.1 += 1;
+        }
+        self.rats.retain(|rat| rat.position.1 < self.height);
+        for shot in &mut self.shots {
+            shot.position.1 += 1;
+        }
+        self.shots.retain(|shot| shot.position.1 < self.height);
+        for puddle in &mut self.puddles {
+            puddle.position.1 += 1;
+        }
+        self.puddles
+            .retain(|puddle| puddle.position.1 < self.height);
+        self.breaker = self
+            .breaker
+            .map(|(x, y)| (x, y + 1))
+            .filter(|cell| cell.1 < self.height);
+        for index in 0..self.foods.len() {
+            self.foods[index].position.1 += 1;
+            if self.foods[index].position.1 == self.height {
+                // Gone off the bottom, another turns up
+                let food_type = self.foods[index].food_type.clone();
+                self.foods[index] = self.generate_food(food_type);
+            }
+        }
+        false
+    }
+
+    // Spawn, move and squash rats, after the snake has moved
+    fn update_rats(&mut self) {
+        let head = self.snake.body[0].position;
+        let board = (self.width, self.height);
+        self.squash_rats(head);
+
+        if self.rats.len() < MAX_RATS && self.rng.gen_ratio(1, RAT_CHANCE) {
+            // In from a random cell on the left or right edge
+            let x = if self.rng.gen() { 0 } else { self.width - 1 };
+            let position = (x, self.rng.gen_range(0..self.height));
+            if !self.snake.body.iter().any(|seg| seg.position == position) {
+                self.rats.push(Rat { position });
+            }
+        }
+
+        if !Rat::moves_on(self.frame_count / self.tick_interval) {
+            return;
+        }
+        let mut index = 0;
+        while index < self.rats.len() {
+            let rat = self.rats[index];
+            let nearest = self.foods.iter().enumerate().min_by_key(|(_, food)| {
+                let dx = axis_delta(
+                    rat.position.0,
+                    food.position.0,
+                    self.width,
+                    self.wrap_around,
+                );
+                let dy = axis_delta(
+                    rat.position.1,
+                    food.position.1,
+                    self.height,
+                    self.wrap_around,
+                );
+                dx.abs() + dy.abs()
+            });
+            let Some((food_index, food)) = nearest else {
+                break;
+            };
+            // Rats run onto the head (and get squashed) but around the rest of the body
+            let body = &self.snake.body[1..];
+            let step = rat.step_toward(food.position, board, self.wrap_around, |cell| {
+                body.iter().any(|seg| seg.position == cell)
+            });
+            if let Some(position) = step {
+                self.rats[index].position = position;
+            }
+            if self.rats[index].position == food.position {
+                // Got there first: the rat makes off with it and a new one turns up
+                let food_type = food.food_type.clone();
+                self.rats.remove(index);
+                self.foods[food_index] = self.generate_food(food_type);
+                continue;
+            }
+            index += 1;
+        }
+        self.squash_rats(head);
+    }
+
+    fn squash_rats(&mut self, head: (i32, i32)) {
+        let before = self.rats.len();
+        self.rats.retain(|rat| rat.position != head);
+        for _ in self.rats.len()..before {
+            self.award(RAT_POINTS);
+            self.hits.push(head);
+        }
+    }
+
+    // Spit a shot straight ahead from the head. Returns false without ammo.
+    pub fn spit(&mut self) -> bool {
+        if !self.game_started || self.game_over || self.paused || self.spit_ammo == 0 {
+            return false;
+        }
+        self.spit_ammo -= 1;
+        self.shots.push(Shot {
+            position: self.snake.body[0].position,
+            direction: self.snake.direction,
+            range_left: SHOT_RANGE,
+        });
+        true
+    }
+
+    // Move every shot a cell at a time, so nothing gets skipped over
+    fn update_shots(&mut self) {
+        let mut shots = std::mem::take(&mut self.shots);
+        shots.retain_mut(|shot| {
+            for _ in 0..SHOT_SPEED {
+                let Some(next) = self.neighbor(shot.position, shot.direction) else {
+                    return false; // Splat against the wall
+                };
+                if shot.range_left == 0 || self.boss_covers(next) {
+                    return false;
+                }
+                shot.position = next;
+                shot.range_left -= 1;
+                if let Some(index) = self.rats.iter().position(|rat| rat.position == next) {
+                    self.rats.remove(index);
+                    self.award(RAT_POINTS);
+                    self.hits.push(next);
+                    return false;
+                }
+            }
+            true
+        });
+        self.shots = shots;
+    }
+
+    // A cell is under the boss
+    pub fn boss_covers(&self, cell: (i32, i32)) -> bool {
+        self.boss
+            .is_some_and(|boss| boss.covers(cell, (self.width, self.height)))
+    }
+
+    // Not counting scrap clusters, which come and go on their own
+    fn food_on_board(&self, food_type: FoodType) -> usize {
+        self.foods
+            .iter()
+            .filter(|food| food.food_type == food_type && food.cluster.is_none())
+            .count()
+    }
+
+    // Cheats are typed on the title screen, before the run starts
+    pub fn activate_cheat(&mut self, cheat: Cheat) {
+        self.cheats.activate(cheat);
+        if cheat == Cheat::FatSnake {
+            while self.snake.body.len() < 20 {
+                self.grow();
+            }
+        }
+    }
+
+    // Add one segment: the tail grows first, after that empty stomach segments
+    // are added between head and tail
+    pub fn grow(&mut self) {
+        if self.tail_length < 3 {
+            // Growing the tail
+            self.tail_length += 1;
+            let tail_pos = self.snake.body.last().unwrap().position;
+            self.snake.body.push(Segment {
+                position: tail_pos,
+                segment_type: SegmentType::Tail,
+            });
+            self.events
+                .push(GameEvent::SegmentAdded(self.snake.body.len() - 1));
+        } else {
+            // After tail is fully grown, add empty stomach segments between head and tail
+            let stomach_insert_index = 1; // After head
+            let stomach_pos = self.snake.body[stomach_insert_index - 1].position;
+            self.snake.body.insert(
+                stomach_insert_index,
+                Segment {
+                    position: stomach_pos,
+                    segment_type: SegmentType::EmptyStomach,
+                },
+            );
+            self.events
+                .push(GameEvent::SegmentAdded(stomach_insert_index));
+        }
+    }
+
+    fn check_high_score(&mut self) {
+        if self.is_high_score() {
+            self.events.push(GameEvent::HighScoreReached(self.score));
+            self.entering_name = true;
+            self.player_name.clear();
+        }
+    }
+
+    fn load_high_scores(&mut self) {
+        // A missing file is an empty table
+        if let Ok(entries) = read_high_scores(Path::new(self.high_score_file())) {
+            self.high_scores.extend(entries);
+            // Sort high scores in descending order
+            self.high_scores.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+            // Keep only top N scores
+            self.high_scores.truncate(MAX_HIGH_SCORES);
+        }
+    }
+
+    fn save_high_scores(&self) {
+        let contents: String = self
+            .high_scores
+            .iter()
+            .map(|entry| entry.line() + "\n")
+            .collect();
+        if let Err(e) = write_with_backups(Path::new(self.high_score_file()), &contents) {
+            eprintln!("Error writing high scores: {}", e);
+        }
+    }
+
+    // The backups of this board's table, newest first, None where there isn't one
+    pub fn score_backups(&self) -> Vec<Option<Vec<HighScoreEntry>>> {
+        let path = Path::new(self.high_score_file());
+        (1..=SCORE_BACKUPS)
+            .map(|generation| read_high_scores(&backup_path(path, generation)).ok())
+            .collect()
+    }
+
+    // Put a backup back as the table, returning how many entries it holds. The table it
+    // replaces becomes the newest backup, so a restore can be undone the same way.
+    pub fn restore_high_scores(&mut self, generation: usize) -> Result<usize, String> {
+        let path = backup_path(Path::new(self.high_score_file()), generation);
+        let mut entries =
+            read_high_scores(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+        entries.truncate(MAX_HIGH_SCORES);
+        self.high_scores = entries;
+        if self.save_scores {
+            self.save_high_scores();
+        }
+        Ok(self.high_scores.len())
+    }
+
+    fn is_high_score(&self) -> bool {
+        if self.cheats.any() || !self.ranked {
+            return false;
+        }
+        if self.high_scores.len() < MAX_HIGH_SCORES {
+            return true;
+        }
+        self.score > self.high_scores.last().unwrap().score
+    }
+
+    pub fn add_high_score(&mut self) {
+        self.high_scores.push(HighScoreEntry {
+            name: self.player_name.clone(),
+            score: self.score,
+            mutators: self.mutators,
+            perfect: self.won,
+            assisted: self.rules.assisted(),
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+        });
+        // Sort and truncate
+        self.high_scores.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+        self.high_scores.truncate(MAX_HIGH_SCORES);
+        // Save to file
+        if self.save_scores {
+            self.save_high_scores();
+        }
+    }
+
+    // Remove every entry under `name`, returning how many there were
+    pub fn remove_high_scores(&mut self, name: &str) -> usize {
+        let before = self.high_scores.len();
+        self.high_scores.retain(|entry| entry.name != name);
+        let removed = before - self.high_scores.len();
+        if self.save_scores && removed > 0 {
+            self.save_high_scores();
+        }
+        removed
+    }
+
+    // Union another machine's table with this one instead of overwriting either: an
+    // entry already here is skipped, the rest are ranked in with the local ones
+    pub fn merge_high_scores(&mut self, theirs: Vec<HighScoreEntry>) -> MergeReport {
+        let mut report = MergeReport::default();
+        let mut new = Vec::new();
+        for entry in theirs {
+            if self
+                .high_scores
+                .iter()
+                .chain(&new)
+                .any(|e| e.key() == entry.key())
+            {
+                report.duplicates += 1;
+            } else {
+                new.push(entry);
+            }
+        }
+        let keys: Vec<(String, u32, u64)> = new
+            .iter()
+            .map(|e| (e.name.clone(), e.score, e.time))
+            .collect();
+        self.high_scores.extend(new);
+        self.high_scores
+            .sort_by_key(|entry| std::cmp::Reverse(entry.score));
+        self.high_scores.truncate(MAX_HIGH_SCORES);
+        for (name, score, time) in keys {
+            if self
+                .high_scores
+                .iter()
+                .any(|e| e.key() == (&name, score, time))
+            {
+                report.added.push((name, score));
+            } else {
+                report.crowded_out += 1;
+            }
+        }
+        if self.save_scores && !report.added.is_empty() {
+            self.save_high_scores();
+        }
+        report
+    }
+
+    // Merge a high score file copied over from another machine
+    pub fn import_high_scores(&mut self, path: &Path) -> Result<MergeReport, String> {
+        let theirs = read_high_scores(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(self.merge_high_scores(theirs))
+    }
+}
+
+// The entries of a high score file, skipping lines that don't parse
+pub fn read_high_scores(path: &Path) -> io::Result<Vec<HighScoreEntry>> {
+    let file = File::open(path)?;
+    Ok(BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| HighScoreEntry::parse(&line))
+        .collect())
+}
+
+// Replace the file at `path` without it ever being half written: the new contents go
+// to a temporary file first and are renamed over it. The old contents are kept as
+// `<path>.1`, shifting the earlier backups along and dropping the oldest.
+pub fn write_with_backups(path: &Path, contents: &str) -> io::Result<()> {
+    let temporary = suffixed(path, "tmp");
+    let mut file = File::create(&temporary)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    for generation in (1..SCORE_BACKUPS).rev() {
+        let older = backup_path(path, generation);
+        if older.exists() {
+            fs::rename(&older, backup_path(path, generation + 1))?;
+        }
+    }
+    // Copied rather than moved, so there's never a moment without the file
+    if path.exists() {
+        fs::copy(path, backup_path(path, 1))?;
+    }
+    fs::rename(&temporary, path)
+}
+
+pub fn backup_path(path: &Path, generation: usize) -> PathBuf {
+    suffixed(path, &generation.to_string())
+}
+
+fn suffixed(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+// One step in a direction, ignoring the edges of the board
+fn step((x, y): (i32, i32), direction: Direction) -> (i32, i32) {
+    match direction {
+        Direction::Right => (x + 1, y),
+        Direction::Left => (x - 1, y),
+        Direction::Up => (x, y - 1),
+        Direction::Down => (x, y + 1),
+    }
+}
@@ -0,0 +1,201 @@
+// Gamepad support: d-pad and stick steering, menu buttons and force-feedback rumble.
+// Only compiled in with the `gamepad` feature, otherwise every call is a no-op
+// so the main loop does not need to care whether a controller backend exists.
+
+use crate::settings::Settings;
+use crate::Direction;
+
+// What a controller did since the last poll. Only produced by the real backend.
+#[cfg_attr(not(feature = "gamepad"), allow(dead_code))]
+#[derive(Clone, Copy)]
+pub enum PadInput {
+    Direction(Direction), // D-pad, or the left stick pushed past the dead zone
+    Confirm,              // South face button (A / Cross)
+    Back,                 // East face button (B / Circle)
+    Start,
+    Connected, // Also sent for controllers already plugged in at startup
+    Disconnected,
+}
+
+#[derive(Clone, Copy)]
+pub enum Rumble {
+    Turn,  // Subtle tick when the snake changes direction
+    Metal, // Short pulse when eating ShinyMetal
+    Death, // Long pulse when the run ends
+}
+
+#[cfg(feature = "gamepad")]
+mod backend {
+    use super::{PadInput, Rumble};
+    use crate::Direction;
+    use gilrs::ff::{BaseEffect, BaseEffectType, Effect, EffectBuilder, Replay, Ticks};
+    use gilrs::{Axis, Button, EventType, Gilrs};
+    use std::time::{Duration, Instant};
+
+    const STICK_PUSHED: f32 = 0.6; // Past this the stick counts as a press
+    const STICK_CENTERED: f32 = 0.3; // Back under this on both axes it can press again
+
+    pub struct Backend {
+        gilrs: Option<Gilrs>,
+        // Effects stop when dropped, so keep them alive until they have played out
+        playing: Vec<(Effect, Instant)>,
+        stick: (f32, f32),  // Left stick position, y up
+        stick_pushed: bool, // Already reported, until it goes back to the middle
+    }
+
+    impl Backend {
+        pub fn new() -> Backend {
+            let gilrs = match Gilrs::new() {
+                Ok(g) => Some(g),
+                Err(e) => {
+                    eprintln!("Gamepad support unavailable: {}", e);
+                    None
+                }
+            };
+            Backend {
+                gilrs,
+                playing: Vec::new(),
+                stick: (0.0, 0.0),
+                stick_pushed: false,
+            }
+        }
+
+        pub fn poll(&mut self) -> Vec<PadInput> {
+            let now = Instant::now();
+            self.playing.retain(|(_, until)| *until > now);
+
+            let mut inputs = Vec::new();
+            let Some(gilrs) = self.gilrs.as_mut() else {
+                return inputs;
+            };
+            while let Some(event) = gilrs.next_event() {
+                match event.event {
+                    EventType::ButtonPressed(button, _) => {
+                        let input = match button {
+                            Button::DPadUp => PadInput::Direction(Direction::Up),
+                            Button::DPadDown => PadInput::Direction(Direction::Down),
+                            Button::DPadLeft => PadInput::Direction(Direction::Left),
+                            Button::DPadRight => PadInput::Direction(Direction::Right),
+                            Button::South => PadInput::Confirm,
+                            Button::East => PadInput::Back,
+                            Button::Start => PadInput::Start,
+                            _ => continue,
+                        };
+                        inputs.push(input);
+                    }
+                    EventType::Connected => inputs.push(PadInput::Connected),
+                    EventType::Disconnected => {
+                        self.stick = (0.0, 0.0);
+                        inputs.push(PadInput::Disconnected);
+                    }
+                    EventType::AxisChanged(Axis::LeftStickX, value, _) => self.stick.0 = value,
+                    EventType::AxisChanged(Axis::LeftStickY, value, _) => self.stick.1 = value,
+                    _ => {}
+                }
+            }
+            // The stick acts like a d-pad: one press each time it is pushed out
+            let (x, y) = self.stick;
+            if x.abs() < STICK_CENTERED && y.abs() < STICK_CENTERED {
+                self.stick_pushed = false;
+            } else if !self.stick_pushed && x.abs().max(y.abs()) > STICK_PUSHED {
+                self.stick_pushed = true;
+                let direction = match (x.abs() > y.abs(), x > 0.0, y > 0.0) {
+                    (true, true, _) => Direction::Right,
+                    (true, false, _) => Direction::Left,
+                    (false, _, true) => Direction::Up,
+                    (false, _, false) => Direction::Down,
+                };
+                inputs.push(PadInput::Direction(direction));
+            }
+            inputs
+        }
+
+        pub fn rumble(&mut self, pulse: Rumble, strength: f32) {
+            let gilrs = match self.gilrs.as_mut() {
+                Some(g) => g,
+                None => return,
+            };
+            let (kind, millis) = match pulse {
+                Rumble::Turn => (BaseEffectType::Weak { magnitude: 10_000 }, 30),
+                Rumble::Metal => (BaseEffectType::Strong { magnitude: 40_000 }, 120),
+                Rumble::Death => (BaseEffectType::Strong { magnitude: 65_000 }, 600),
+            };
+            let pads: Vec<_> = gilrs
+                .gamepads()
+                .filter(|(_, pad)| pad.is_ff_supported())
+                .map(|(id, _)| id)
+                .collect();
+            if pads.is_empty() {
+                return;
+            }
+
+            let effect = EffectBuilder::new()
+                .add_effect(BaseEffect {
+                    kind,
+                    scheduling: Replay {
+                        play_for: Ticks::from_ms(millis),
+                        ..Default::default()
+                    },
+                    envelope: Default::default(),
+                })
+                .gamepads(&pads)
+                .gain(strength)
+                .finish(gilrs);
+            match effect {
+                Ok(effect) => {
+                    if effect.play().is_ok() {
+                        let until = Instant::now() + Duration::from_millis(millis as u64);
+                        self.playing.push((effect, until));
+                    }
+                }
+                Err(e) => eprintln!("Error creating rumble effect: {}", e),
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "gamepad"))]
+mod backend {
+    use super::{PadInput, Rumble};
+
+    pub struct Backend;
+
+    impl Backend {
+        pub fn new() -> Backend {
+            Backend
+        }
+
+        pub fn poll(&mut self) -> Vec<PadInput> {
+            Vec::new()
+        }
+
+        pub fn rumble(&mut self, _pulse: Rumble, _strength: f32) {}
+    }
+}
+
+pub struct Gamepad {
+    backend: backend::Backend,
+    rumble_enabled: bool,
+    rumble_strength: f32,
+}
+
+impl Gamepad {
+    pub fn new(settings: &Settings) -> Gamepad {
+        Gamepad {
+            backend: backend::Backend::new(),
+            rumble_enabled: settings.rumble,
+            rumble_strength: settings.rumble_strength,
+        }
+    }
+
+    // Drain pending controller events
+    pub fn poll(&mut self) -> Vec<PadInput> {
+        self.backend.poll()
+    }
+
+    pub fn rumble(&mut self, pulse: Rumble) {
+        if self.rumble_enabled && self.rumble_strength > 0.0 {
+            self.backend.rumble(pulse, self.rumble_strength);
+        }
+    }
+}
@@ -0,0 +1,168 @@
+// Board geometry. The classic board is a square grid with four headings, the
+// diagonal board the same grid with eight, and the experimental hex board has six.
+// Hex cells keep plain (x, y) positions, laid out in rows with every odd row shifted
+// half a cell to the right ("odd-r" offsets), so everything that only compares cells
+// works unchanged and only stepping and drawing differ.
+
+use crate::game::Direction;
+
+pub trait Grid {
+    // Number of headings, counted clockwise from east
+    fn headings(&self) -> usize;
+    // The cell one step from `cell` going `heading`, ignoring the edges of the board
+    fn step(&self, cell: (i32, i32), heading: usize) -> (i32, i32);
+    // Heading a run starts in when it's first steered in `direction`
+    fn heading_for(&self, direction: Direction) -> usize;
+    // Closest of the four screen directions to `heading`, for what only knows those
+    fn direction(&self, heading: usize) -> Direction;
+}
+
+pub struct SquareGrid;
+
+impl Grid for SquareGrid {
+    fn headings(&self) -> usize {
+        4
+    }
+
+    fn step(&self, (x, y): (i32, i32), heading: usize) -> (i32, i32) {
+        match heading {
+            0 => (x + 1, y),
+            1 => (x, y + 1),
+            2 => (x - 1, y),
+            _ => (x, y - 1),
+        }
+    }
+
+    fn heading_for(&self, direction: Direction) -> usize {
+        match direction {
+            Direction::Right => 0,
+            Direction::Down => 1,
+            Direction::Left => 2,
+            Direction::Up => 3,
+        }
+    }
+
+    fn direction(&self, heading: usize) -> Direction {
+        match heading {
+            0 => Direction::Right,
+            1 => Direction::Down,
+            2 => Direction::Left,
+            _ => Direction::Up,
+        }
+    }
+}
+
+pub struct DiagonalGrid;
+
+impl Grid for DiagonalGrid {
+    fn headings(&self) -> usize {
+        8
+    }
+
+    // East, then clockwise round through every diagonal
+    fn step(&self, (x, y): (i32, i32), heading: usize) -> (i32, i32) {
+        let (dx, dy) = match heading {
+            0 => (1, 0),
+            1 => (1, 1),
+            2 => (0, 1),
+            3 => (-1, 1),
+            4 => (-1, 0),
+            5 => (-1, -1),
+            6 => (0, -1),
+            _ => (1, -1),
+        };
+        (x + dx, y + dy)
+    }
+
+    fn heading_for(&self, direction: Direction) -> usize {
+        match direction {
+            Direction::Right => 0,
+            Direction::Down => 2,
+            Direction::Left => 4,
+            Direction::Up => 6,
+        }
+    }
+
+    fn direction(&self, heading: usize) -> Direction {
+        match heading {
+            7 | 0 => Direction::Right,
+            1 | 2 => Direction::Down,
+            3 | 4 => Direction::Left,
+            _ => Direction::Up,
+        }
+    }
+}
+
+pub struct HexGrid;
+
+impl Grid for HexGrid {
+    fn headings(&self) -> usize {
+        6
+    }
+
+    // East, south-east, south-west, west, north-west, north-east. Going diagonally
+    // the column changes or not depending on whether the row is shifted.
+    fn step(&self, (x, y): (i32, i32), heading: usize) -> (i32, i32) {
+        let shifted = y.rem_euclid(2);
+        match heading {
+            0 => (x + 1, y),
+            1 => (x + shifted, y + 1),
+            2 => (x + shifted - 1, y + 1),
+            3 => (x - 1, y),
+            4 => (x + shifted - 1, y - 1),
+            _ => (x + shifted, y - 1),
+        }
+    }
+
+    fn heading_for(&self, direction: Direction) -> usize {
+        match direction {
+            Direction::Right => 0,
+            Direction::Down => 1,
+            Direction::Left => 3,
+            Direction::Up => 4,
+        }
+    }
+
+    fn direction(&self, heading: usize) -> Direction {
+        match heading {
+            0 => Direction::Right,
+            1 | 2 => Direction::Down,
+            3 => Direction::Left,
+            _ => Direction::Up,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GridKind {
+    Square,
+    Diagonal,
+    Hex,
+}
+
+impl GridKind {
+    pub fn parse(value: &str) -> Option<GridKind> {
+        match value {
+            "square" => Some(GridKind::Square),
+            "diagonal" => Some(GridKind::Diagonal),
+            "hex" => Some(GridKind::Hex),
+            _ => None,
+        }
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            GridKind::Square => "square",
+            GridKind::Diagonal => "diagonal",
+            GridKind::Hex => "hex",
+        }
+    }
+
+    pub fn grid(&self) -> &'static dyn Grid {
+        match self {
+            GridKind::Square => &SquareGrid,
+            GridKind::Diagonal => &DiagonalGrid,
+            GridKind::Hex => &HexGrid,
+        }
+    }
+}
@@ -0,0 +1,169 @@
+// Ranked seasons: every high score table is a leaderboard for one calendar month (UTC).
+// When the game starts in a new month, the entries set in earlier ones leave their
+// tables for the hall of fame, which keeps the best three of every board and month:
+//
+//     2026-09,square,ANN,212,,perfect,1788000000
+//
+// The month, the board's name in `HIGH_SCORE_TABLES`, then the entry as its table had
+// it. Entries from files older than the times they carry belong to no season and stay.
+
+use crate::game::{read_high_scores, write_with_backups, HighScoreEntry, HIGH_SCORE_TABLES};
+use crate::seasons;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+const HALL_OF_FAME_FILE: &str = "hall_of_fame.txt";
+const PODIUM: usize = 3; // Entries kept of every board and month
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+pub struct PastSeason {
+    pub month: (i64, u32),                          // Year and month
+    pub boards: Vec<(String, Vec<HighScoreEntry>)>, // Best first
+}
+
+impl PastSeason {
+    fn new(month: (i64, u32)) -> PastSeason {
+        PastSeason {
+            month,
+            boards: Vec::new(),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        let (year, month) = self.month;
+        format!("{} {}", MONTHS[month as usize - 1], year)
+    }
+}
+
+pub struct HallOfFame {
+    pub seasons: Vec<PastSeason>, // Newest first
+}
+
+impl HallOfFame {
+    pub fn load() -> HallOfFame {
+        let mut hall = HallOfFame {
+            seasons: Vec::new(),
+        };
+        // No file yet means no season has ended
+        if let Ok(file) = File::open(HALL_OF_FAME_FILE) {
+            for line in BufReader::new(file).lines().map_while(Result::ok) {
+                match parse(&line) {
+                    Some((month, board, entry)) => hall.add(month, board, entry),
+                    None => eprintln!("Unknown line in {}: {}", HALL_OF_FAME_FILE, line),
+                }
+            }
+        }
+        hall
+    }
+
+    // Move every entry set before this month out of the tables. Adding the same entry
+    // twice keeps one, so a table restored from a backup can be closed again.
+    pub fn close_past_seasons(&mut self) {
+        let (year, month, _) = seasons::today();
+        let mut kept_tables = Vec::new();
+        for (board, file) in HIGH_SCORE_TABLES {
+            let Ok(entries) = read_high_scores(Path::new(file)) else {
+                continue;
+            };
+            let (past, kept): (Vec<_>, Vec<_>) = entries
+                .into_iter()
+                .partition(|entry| entry.time != 0 && month_of(entry.time) < (year, month));
+            if past.is_empty() {
+                continue;
+            }
+            for entry in past {
+                self.add(month_of(entry.time), board, entry);
+            }
+            kept_tables.push((file, kept));
+        }
+        if kept_tables.is_empty() {
+            return;
+        }
+        // The tables only lose their entries once the hall of fame has them
+        if let Err(e) = self.save() {
+            eprintln!("Error writing the hall of fame: {}", e);
+            return;
+        }
+        for (file, kept) in kept_tables {
+            let contents: String = kept.iter().map(|entry| entry.line() + "\n").collect();
+            if let Err(e) = write_with_backups(Path::new(file), &contents) {
+                eprintln!("Error writing high scores: {}", e);
+            }
+        }
+    }
+
+    fn add(&mut self, month: (i64, u32), board: &str, entry: HighScoreEntry) {
+        let index = match self.seasons.iter().position(|s| s.month <= month) {
+            Some(i) if self.seasons[i].month == month => i,
+            Some(i) => {
+                self.seasons.insert(i, PastSeason::new(month));
+                i
+            }
+            None => {
+                self.seasons.push(PastSeason::new(month));
+                self.seasons.len() - 1
+            }
+        };
+        let boards = &mut self.seasons[index].boards;
+        let entries = match boards.iter().position(|(b, _)| b == board) {
+            Some(i) => &mut boards[i].1,
+            None => {
+                boards.push((board.to_string(), Vec::new()));
+                &mut boards.last_mut().unwrap().1
+            }
+        };
+        let duplicate = entries
+            .iter()
+            .any(|e| (&e.name, e.score, e.time) == (&entry.name, entry.score, entry.time));
+        if !duplicate {
+            entries.push(entry);
+            entries.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+            entries.truncate(PODIUM);
+        }
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let mut file = File::create(HALL_OF_FAME_FILE)?;
+        for season in &self.seasons {
+            let (year, month) = season.month;
+            for (board, entries) in &season.boards {
+                for entry in entries {
+                    writeln!(file, "{}-{:02},{},{}", year, month, board, entry.line())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse(line: &str) -> Option<((i64, u32), &str, HighScoreEntry)> {
+    let mut parts = line.splitn(3, ',');
+    let (year, month) = parts.next()?.split_once('-')?;
+    let month = (year.parse().ok()?, month.parse().ok()?);
+    if !(1..=12).contains(&month.1) {
+        return None;
+    }
+    let board = parts.next()?;
+    let entry = HighScoreEntry::parse(parts.next()?)?;
+    Some((month, board, entry))
+}
+
+fn month_of(time: u64) -> (i64, u32) {
+    let (year, month, _) = seasons::date(time);
+    (year, month)
+}
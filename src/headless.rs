@@ -0,0 +1,103 @@
+// Playing games without a window. A `Controller` looks at the board before every
+// move and decides where to go; `play` runs a game to the end as fast as it can.
+
+use crate::game::{DeathCause, Direction, Game, DEATH_CAUSES};
+
+pub trait Controller {
+    // Direction for the next move, None keeps going straight. Turning back into the
+    // neck is ignored, like it is for the player.
+    fn next_move(&mut self, game: &Game) -> Option<Direction>;
+}
+
+pub struct RunStats {
+    pub score: u32,
+    pub length: usize,
+    pub moves: u64,
+    pub near_misses: u32,
+    pub death_cause: Option<DeathCause>, // None if the move limit or the goal was reached first
+    pub won: bool,
+}
+
+// Play until the snake dies or has made `max_moves` moves. The limit keeps a bot
+// that can't die (or just circles forever) from hanging the caller.
+pub fn play(game: Game, controller: &mut dyn Controller, max_moves: u64) -> RunStats {
+    play_observed(game, controller, max_moves, &mut |_| {})
+}
+
+// `play`, showing the game to `observe` once before the first move and after every move
+pub fn play_observed(
+    mut game: Game,
+    controller: &mut dyn Controller,
+    max_moves: u64,
+    observe: &mut dyn FnMut(&Game),
+) -> RunStats {
+    game.tick_interval = 1;
+    let direction = game.snake.direction;
+    game.steer(direction);
+    observe(&game);
+
+    let mut moves = 0;
+    while !game.game_over && moves < max_moves {
+        if let Some(direction) = controller.next_move(&game) {
+            game.steer(direction);
+        }
+        game.update();
+        moves += 1;
+        observe(&game);
+    }
+
+    RunStats {
+        score: game.score,
+        length: game.snake.body.len(),
+        moves,
+        near_misses: game.near_misses,
+        death_cause: game.death_cause,
+        won: game.won,
+    }
+}
+
+// A few lines of statistics over a batch of runs: scores, lengths and what ended them
+pub fn summary(runs: &[RunStats]) -> String {
+    if runs.is_empty() {
+        return "no games played".to_string();
+    }
+    let count = runs.len() as f64;
+    let mean = |value: fn(&RunStats) -> f64| runs.iter().map(value).sum::<f64>() / count;
+    let best = runs.iter().map(|run| run.score).max().unwrap_or(0);
+
+    let mut lines = vec![
+        format!("games:        {}", runs.len()),
+        format!(
+            "score:        mean {:.1}, best {}",
+            mean(|run| run.score as f64),
+            best
+        ),
+        format!("length:       mean {:.1}", mean(|run| run.length as f64)),
+        format!("moves:        mean {:.0}", mean(|run| run.moves as f64)),
+        format!(
+            "near misses:  mean {:.1}",
+            mean(|run| run.near_misses as f64)
+        ),
+    ];
+    for cause in DEATH_CAUSES {
+        let deaths = runs
+            .iter()
+            .filter(|run| run.death_cause == Some(cause))
+            .count();
+        if deaths > 0 {
+            lines.push(format!("  {:<28}{}", cause.description(), deaths));
+        }
+    }
+    let won = runs.iter().filter(|run| run.won).count();
+    if won > 0 {
+        lines.push(format!("  {:<28}{}", "reached the goal", won));
+    }
+    let survived = runs
+        .iter()
+        .filter(|run| run.death_cause.is_none() && !run.won)
+        .count();
+    if survived > 0 {
+        lines.push(format!("  {:<28}{}", "hit the move limit", survived));
+    }
+    lines.join("\n")
+}
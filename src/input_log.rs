@@ -0,0 +1,486 @@
+// Recording and playback of input, so bug reports can come with an exact reproduction.
+//
+// The log is plain text. The first line holds the seed every game in the session was
+// derived from, followed by the seasonal content in play if there was any, since
+// that changes which foods spawn, and the rules and board size the session started
+// with (see `Rules::words`), which playback uses instead of its own settings. Then
+// each event is stamped with the number of updates processed before it arrived (which
+// is what playback goes by) and the wall-clock milliseconds since start (for humans
+// reading the file):
+//
+//     seed 1234567890
+//     season halloween
+//     rules length_speed:off stomach_capacity:3 goal:off input_timing:strict slow_start:off
+//     board 30x20
+//     240 2001 key Up
+//     301 2510 steer Left
+//     420 3500 tap
+//     500 4200 cmd grow 10
+//     620 5300 loadout shield extra_life
+//     620 5300 upgrades start_length:1 iron_stomach:0 extra_scrap:1
+//     620 5300 grid hex
+//     620 5300 versus
+//     620 5300 adaptive level:1 clock:36000 deaths:30100 close_calls:35020,35400
+//     700 6100 focus 2
+//     900 7600 pad off
+//     950 9000 idle
+//     990 9400 window off
+//
+// Every move of the snake also leaves a fingerprint of the game state after it (see
+// `Game::state_hash`), so a replay can tell exactly where it stopped matching:
+//
+//     242 2018 hash 3f2a9c0d11e4b587
+//
+// Every run that starts leaves what it takes to play it again (see `App::run_stamp`),
+// which playback sets the run up with before starting it, whatever the profile and
+// menus there would have made of it:
+//
+//     1800 15000 start seed=8812764 mode=classic mutators=none board=30x20
+//
+// and every run that ends leaves the same again with its score, signed with the state
+// it ended in and the fingerprint of the rules it was played by (see
+// `Rules::fingerprint`):
+//
+//     2400 21000 run seed=8812764 mode=classic mutators=none board=30x20 score=42
+//         replay=9be04c1d2a7f3e60 settings=51c0e2d84f1a9b37
+//
+// all on one line. Playback checks that each run ends with the score it claims, so a
+// log with an edited score doesn't verify, and that it was played by the rules it was
+// signed with. Older logs have no signature and only the moves are checked; the oldest
+// don't say what rules or board they had either, and play by the local settings.
+
+use crate::adaptive::Adaptive;
+use crate::grid::GridKind;
+use crate::keys::Key;
+use crate::mutators::Mutators;
+use crate::roguelite::{GameMode, Upgrades};
+use crate::rules::Rules;
+use crate::shop::Loadout;
+use crate::Direction;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+#[derive(Clone)]
+pub enum InputEvent {
+    Key(Key),
+    Steer(Direction), // Gamepad d-pad, swipe or mouse steering
+    Tap,
+    Command(String),    // Developer console command
+    Loadout(Loadout),   // Consumables handed to the run that just started
+    Upgrades(Upgrades), // Roguelite upgrades applied to the run that just started
+    Grid(GridKind),     // Board geometry of the run that just started
+    Kids,               // The run that just started is in kids mode
+    Versus,             // A second player joined the run that just started
+    Duel,               // The same, against a chess clock
+    Adaptive(Adaptive), // Adaptive difficulty as the run that just started finds it
+    Focus(usize),       // Mouse pointing at an item of the open menu
+    Pad(bool),          // A controller was connected (true) or disconnected
+    Idle,               // Nothing came in for long enough to pause the run
+    Window(bool),       // The window came to the front (true) or went to the background
+}
+
+// Outcome of checking a replay against the state hashes recorded with it
+#[derive(Clone, Copy, Default)]
+pub struct Verification {
+    pub recorded: u64,                    // Moves with a hash in the log
+    pub matched: u64,                     // Moves whose state matched, up to the divergence
+    pub divergence: Option<(u64, u64)>,   // First move that didn't match, and its update
+    pub claims: u64,                      // Runs with a signed score in the log
+    pub confirmed: u64,                   // Of those, runs that played out as signed
+    pub disputed: Option<(u64, Dispute)>, // First run that didn't, counting from 1
+}
+
+// Why a run's signed score wasn't confirmed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Dispute {
+    Settings,        // It was played by other rules than the replay
+    Score(u32, u32), // It ended with another score than claimed: claimed, played
+    Replay,          // The score matches but the run ended in another state
+}
+
+impl Dispute {
+    pub fn description(&self) -> String {
+        match self {
+            Dispute::Settings => "recorded with different settings".to_string(),
+            Dispute::Score(claimed, played) => {
+                format!("claims {} but the replay scores {}", claimed, played)
+            }
+            Dispute::Replay => "doesn't end the way it was signed".to_string(),
+        }
+    }
+}
+
+// What a run's line in the log says it ended with
+#[derive(Clone, Copy, PartialEq)]
+pub struct Claim {
+    pub score: u32,
+    pub replay: u64,   // `Game::state_hash` as the run ended
+    pub settings: u64, // `Rules::fingerprint` of the rules it was played by
+}
+
+// How a run was set up, as `App::run_stamp` writes it
+#[derive(Clone, Copy, PartialEq)]
+pub struct Stamp {
+    pub seed: u64,
+    pub mode: GameMode,
+    pub mutators: Mutators,
+    pub board: (i32, i32),
+}
+
+impl Stamp {
+    // The `name=value` fields of a start or run line, in any order, others ignored
+    fn parse(fields: &[&str]) -> Option<Stamp> {
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find_map(|field| field.strip_prefix(name)?.strip_prefix('='))
+        };
+        let mutators = match field("mutators")? {
+            "none" => Mutators::default(),
+            ids => Mutators::parse(ids)?,
+        };
+        Some(Stamp {
+            seed: field("seed")?.parse().ok()?,
+            mode: GameMode::parse(field("mode")?)?,
+            mutators,
+            board: parse_board(field("board")?)?,
+        })
+    }
+}
+
+// e.g. "30x20"
+fn parse_board(value: &str) -> Option<(i32, i32)> {
+    let (width, height) = value.trim().split_once('x')?;
+    Some((width.parse().ok()?, height.parse().ok()?))
+}
+
+pub struct InputRecorder {
+    file: BufWriter<File>,
+    start: Instant,
+}
+
+impl InputRecorder {
+    pub fn create(
+        path: &Path,
+        seed: u64,
+        season: Option<&str>,
+        rules: Rules,
+        (width, height): (i32, i32),
+    ) -> io::Result<InputRecorder> {
+        let mut file = BufWriter::new(File::create(path)?);
+        writeln!(file, "seed {}", seed)?;
+        if let Some(season) = season {
+            writeln!(file, "season {}", season)?;
+        }
+        writeln!(file, "rules {}", rules.words().join(" "))?;
+        writeln!(file, "board {}x{}", width, height)?;
+        Ok(InputRecorder {
+            file,
+            start: Instant::now(),
+        })
+    }
+
+    pub fn record(&mut self, update: u64, event: &InputEvent) {
+        let millis = self.start.elapsed().as_millis();
+        let result = match event {
+            InputEvent::Key(key) => writeln!(self.file, "{} {} key {:?}", update, millis, key),
+            InputEvent::Steer(direction) => {
+                writeln!(self.file, "{} {} steer {:?}", update, millis, direction)
+            }
+            InputEvent::Tap => writeln!(self.file, "{} {} tap", update, millis),
+            InputEvent::Command(command) => {
+                writeln!(self.file, "{} {} cmd {}", update, millis, command)
+            }
+            InputEvent::Loadout(loadout) => {
+                let words = loadout.words().join(" ");
+                writeln!(self.file, "{} {} loadout {}", update, millis, words)
+            }
+            InputEvent::Upgrades(upgrades) => {
+                let words = upgrades.words().join(" ");
+                writeln!(self.file, "{} {} upgrades {}", update, millis, words)
+            }
+            InputEvent::Grid(grid) => {
+                writeln!(self.file, "{} {} grid {}", update, millis, grid.id())
+            }
+            InputEvent::Kids => writeln!(self.file, "{} {} kids", update, millis),
+            InputEvent::Versus => writeln!(self.file, "{} {} versus", update, millis),
+            InputEvent::Duel => writeln!(self.file, "{} {} duel", update, millis),
+            InputEvent::Adaptive(adaptive) => {
+                let words = adaptive.words().join(" ");
+                writeln!(self.file, "{} {} adaptive {}", update, millis, words)
+            }
+            InputEvent::Focus(item) => writeln!(self.file, "{} {} focus {}", update, millis, item),
+            InputEvent::Pad(connected) => {
+                let state = if *connected { "on" } else { "off" };
+                writeln!(self.file, "{} {} pad {}", update, millis, state)
+            }
+            InputEvent::Idle => writeln!(self.file, "{} {} idle", update, millis),
+            InputEvent::Window(focused) => {
+                let state = if *focused { "on" } else { "off" };
+                writeln!(self.file, "{} {} window {}", update, millis, state)
+            }
+        };
+        // Flush every event so the log survives a crash, which is when it's most useful
+        if let Err(e) = result.and_then(|_| self.file.flush()) {
+            eprintln!("Error writing input log: {}", e);
+        }
+    }
+
+    // A run started, set up as `stamp` says. Flushed, like the events.
+    pub fn record_start(&mut self, update: u64, stamp: &str) {
+        let millis = self.start.elapsed().as_millis();
+        let result = writeln!(self.file, "{} {} start {}", update, millis, stamp);
+        if let Err(e) = result.and_then(|_| self.file.flush()) {
+            eprintln!("Error writing input log: {}", e);
+        }
+    }
+
+    // A run ended. Flushed, like the events.
+    pub fn record_run(&mut self, update: u64, stamp: &str, claim: Claim) {
+        let millis = self.start.elapsed().as_millis();
+        let result = writeln!(
+            self.file,
+            "{} {} run {} score={} replay={:016x} settings={:016x}",
+            update, millis, stamp, claim.score, claim.replay, claim.settings
+        );
+        if let Err(e) = result.and_then(|_| self.file.flush()) {
+            eprintln!("Error writing input log: {}", e);
+        }
+    }
+
+    // The state after a move. Not flushed: a crash loses at most a few, and the events
+    // that led up to it are still there.
+    pub fn record_hash(&mut self, update: u64, hash: u64) {
+        let millis = self.start.elapsed().as_millis();
+        if let Err(e) = writeln!(self.file, "{} {} hash {:016x}", update, millis, hash) {
+            eprintln!("Error writing input log: {}", e);
+        }
+    }
+}
+
+pub struct InputReplay {
+    pub seed: u64,
+    pub season: Option<String>,
+    pub rules: Option<Rules>, // The session started with, in logs that say
+    pub board: Option<(i32, i32)>, // The same
+    pub verification: Verification,
+    events: VecDeque<(u64, InputEvent)>,
+    hashes: VecDeque<(u64, u64)>, // Update each move happened in and the state after it
+    claims: VecDeque<Claim>,      // Signed runs, oldest first
+    starts: VecDeque<Stamp>,      // Runs started, oldest first
+}
+
+impl InputReplay {
+    pub fn load(path: &Path) -> io::Result<InputReplay> {
+        let invalid = |line: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("bad input log line '{}'", line),
+            )
+        };
+        let mut lines = BufReader::new(File::open(path)?).lines();
+        let first = lines.next().transpose()?.unwrap_or_default();
+        let seed = first
+            .strip_prefix("seed ")
+            .and_then(|s| s.trim().parse().ok())
+            .ok_or_else(|| invalid(&first))?;
+
+        let mut season = None;
+        let mut rules = None;
+        let mut board = None;
+        let mut events = VecDeque::new();
+        let mut hashes = VecDeque::new();
+        let mut claims = VecDeque::new();
+        let mut starts = VecDeque::new();
+        for line in lines {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix("season ") {
+                season = Some(name.trim().to_string());
+                continue;
+            }
+            if let Some(words) = line.strip_prefix("rules ") {
+                let words = words.split_whitespace();
+                rules = Some(Rules::parse(words).ok_or_else(|| invalid(&line))?);
+                continue;
+            }
+            if let Some(size) = line.strip_prefix("board ") {
+                board = Some(parse_board(size).ok_or_else(|| invalid(&line))?);
+                continue;
+            }
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let update: u64 = parts[0].parse().map_err(|_| invalid(&line))?;
+            if let Some(["hash", value]) = parts.get(2..) {
+                let hash = u64::from_str_radix(value, 16).map_err(|_| invalid(&line))?;
+                hashes.push_back((update, hash));
+                continue;
+            }
+            if let Some(["start", fields @ ..]) = parts.get(2..) {
+                starts.push_back(Stamp::parse(fields).ok_or_else(|| invalid(&line))?);
+                continue;
+            }
+            if let Some(["run", fields @ ..]) = parts.get(2..) {
+                let field = |name: &str| {
+                    fields
+                        .iter()
+                        .find_map(|field| field.strip_prefix(name)?.strip_prefix('='))
+                };
+                let hex = |name: &str| field(name).and_then(|v| u64::from_str_radix(v, 16).ok());
+                // Unsigned runs are only there for reading
+                if let (Some(replay), Some(settings)) = (hex("replay"), hex("settings")) {
+                    let score = field("score").and_then(|v| v.parse().ok());
+                    let score = score.ok_or_else(|| invalid(&line))?;
+                    claims.push_back(Claim {
+                        score,
+                        replay,
+                        settings,
+                    });
+                }
+                continue;
+            }
+            let event = match parts.get(2..) {
+                // Older logs also have the backend's key code before the name
+                Some(["key", .., name]) => {
+                    InputEvent::Key(Key::parse(name).ok_or_else(|| invalid(&line))?)
+                }
+                Some(["steer", direction]) => {
+                    InputEvent::Steer(Direction::parse(direction).ok_or_else(|| invalid(&line))?)
+                }
+                Some(["tap"]) => InputEvent::Tap,
+                Some(["kids"]) => InputEvent::Kids,
+                Some(["versus"]) => InputEvent::Versus,
+                Some(["duel"]) => InputEvent::Duel,
+                Some(["pad", "on"]) => InputEvent::Pad(true),
+                Some(["pad", "off"]) => InputEvent::Pad(false),
+                Some(["idle"]) => InputEvent::Idle,
+                Some(["window", "on"]) => InputEvent::Window(true),
+                Some(["window", "off"]) => InputEvent::Window(false),
+                Some(["focus", item]) => {
+                    InputEvent::Focus(item.parse().map_err(|_| invalid(&line))?)
+                }
+                Some(["loadout", words @ ..]) => InputEvent::Loadout(
+                    Loadout::parse(words.iter().copied()).ok_or_else(|| invalid(&line))?,
+                ),
+                Some(["upgrades", words @ ..]) => InputEvent::Upgrades(
+                    Upgrades::parse(words.iter().copied()).ok_or_else(|| invalid(&line))?,
+                ),
+                Some(["adaptive", words @ ..]) => InputEvent::Adaptive(
+                    Adaptive::parse(words.iter().copied()).ok_or_else(|| invalid(&line))?,
+                ),
+                Some(["grid", grid]) => {
+                    InputEvent::Grid(GridKind::parse(grid).ok_or_else(|| invalid(&line))?)
+                }
+                Some(["cmd", ..]) => {
+                    // Everything after the tag, spacing included
+                    let start = line.find(" cmd ").ok_or_else(|| invalid(&line))?;
+                    InputEvent::Command(line[start + 5..].to_string())
+                }
+                _ => return Err(invalid(&line)),
+            };
+            events.push_back((update, event));
+        }
+        Ok(InputReplay {
+            seed,
+            season,
+            rules,
+            board,
+            verification: Verification {
+                recorded: hashes.len() as u64,
+                claims: claims.len() as u64,
+                ..Verification::default()
+            },
+            events,
+            hashes,
+            claims,
+            starts,
+        })
+    }
+
+    // How the next run to start was set up, in logs that say
+    pub fn next_start(&mut self) -> Option<Stamp> {
+        self.starts.pop_front()
+    }
+
+    // Events that were recorded before update number `update` ran
+    pub fn due(&mut self, update: u64) -> Vec<InputEvent> {
+        let mut due = Vec::new();
+        while self.events.front().is_some_and(|(at, _)| *at <= update) {
+            if let Some((_, event)) = self.events.pop_front() {
+                due.push(event);
+            }
+        }
+        due
+    }
+
+    // Compare the state after a move with the hash recorded for the same move. Checking
+    // stops at the first mismatch, everything after it is bound to differ too.
+    pub fn check(&mut self, update: u64, hash: u64) {
+        let Some(recorded) = self.hashes.pop_front() else {
+            return;
+        };
+        if recorded == (update, hash) {
+            self.verification.matched += 1;
+        } else {
+            self.diverged(recorded.0);
+        }
+    }
+
+    // Compare how a run ended with the next signed one. Like the moves, checking stops
+    // at the first run that doesn't match.
+    pub fn check_claim(&mut self, played: Claim) {
+        if self.verification.disputed.is_some() {
+            return;
+        }
+        let Some(claim) = self.claims.pop_front() else {
+            return;
+        };
+        let dispute = if claim.settings != played.settings {
+            Some(Dispute::Settings)
+        } else if claim.score != played.score {
+            Some(Dispute::Score(claim.score, played.score))
+        } else if claim.replay != played.replay {
+            Some(Dispute::Replay)
+        } else {
+            None
+        };
+        match dispute {
+            Some(dispute) => {
+                let run = self.verification.confirmed + 1;
+                eprintln!("Run {} {}", run, dispute.description());
+                self.verification.disputed = Some((run, dispute));
+                self.claims.clear();
+            }
+            None => self.verification.confirmed += 1,
+        }
+    }
+
+    // Call at the start of each update: a move recorded before `update` that hasn't
+    // been checked yet never happened
+    pub fn check_missed(&mut self, update: u64) {
+        if let Some(&(recorded, _)) = self.hashes.front() {
+            if recorded < update {
+                self.diverged(recorded);
+            }
+        }
+    }
+
+    fn diverged(&mut self, update: u64) {
+        let moves = self.verification.matched + 1;
+        eprintln!(
+            "Replay diverged from the recording at move {} (update {})",
+            moves, update
+        );
+        self.verification.divergence = Some((moves, update));
+        self.hashes.clear();
+    }
+
+    // No events left to play back, and no moves left to check
+    pub fn finished(&self) -> bool {
+        self.events.is_empty() && self.hashes.is_empty()
+    }
+}
@@ -0,0 +1,93 @@
+// Keyboard keys as the game sees them. Each backend maps its own key codes onto this,
+// so bindings, settings and input logs don't depend on the windowing library.
+
+#[rustfmt::skip]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Key {
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    D0, D1, D2, D3, D4, D5, D6, D7, D8, D9,
+    NumPad0, NumPad1, NumPad2, NumPad3, NumPad4,
+    NumPad5, NumPad6, NumPad7, NumPad8, NumPad9, NumPadPlus, NumPadMinus,
+    Up, Down, Left, Right,
+    Space, Tab, Return, Backspace, Backquote, Equals, Minus,
+    LShift, RShift, LCtrl, RCtrl,
+    F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+}
+
+#[rustfmt::skip]
+const ALL_KEYS: [Key; 75] = [
+    Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G, Key::H, Key::I, Key::J,
+    Key::K, Key::L, Key::M, Key::N, Key::O, Key::P, Key::Q, Key::R, Key::S, Key::T,
+    Key::U, Key::V, Key::W, Key::X, Key::Y, Key::Z,
+    Key::D0, Key::D1, Key::D2, Key::D3, Key::D4, Key::D5, Key::D6, Key::D7, Key::D8, Key::D9,
+    Key::NumPad0, Key::NumPad1, Key::NumPad2, Key::NumPad3, Key::NumPad4,
+    Key::NumPad5, Key::NumPad6, Key::NumPad7, Key::NumPad8, Key::NumPad9,
+    Key::NumPadPlus, Key::NumPadMinus,
+    Key::Up, Key::Down, Key::Left, Key::Right,
+    Key::Space, Key::Tab, Key::Return, Key::Backspace, Key::Backquote, Key::Equals, Key::Minus,
+    Key::LShift, Key::RShift, Key::LCtrl, Key::RCtrl,
+    Key::F1, Key::F2, Key::F3, Key::F4, Key::F5, Key::F6,
+    Key::F7, Key::F8, Key::F9, Key::F10, Key::F11, Key::F12,
+];
+
+impl Key {
+    // Look a key up by its name ("W", "NumPad8", "Up", ...), ignoring case
+    pub fn parse(name: &str) -> Option<Key> {
+        ALL_KEYS
+            .iter()
+            .find(|key| format!("{:?}", key).eq_ignore_ascii_case(name))
+            .copied()
+    }
+}
+
+// Helper function to convert Key to char
+pub fn key_to_char(key: Key) -> Option<char> {
+    match key {
+        Key::A => Some('A'),
+        Key::B => Some('B'),
+        Key::C => Some('C'),
+        Key::D => Some('D'),
+        Key::E => Some('E'),
+        Key::F => Some('F'),
+        Key::G => Some('G'),
+        Key::H => Some('H'),
+        Key::I => Some('I'),
+        Key::J => Some('J'),
+        Key::K => Some('K'),
+        Key::L => Some('L'),
+        Key::M => Some('M'),
+        Key::N => Some('N'),
+        Key::O => Some('O'),
+        Key::P => Some('P'),
+        Key::Q => Some('Q'),
+        Key::R => Some('R'),
+        Key::S => Some('S'),
+        Key::T => Some('T'),
+        Key::U => Some('U'),
+        Key::V => Some('V'),
+        Key::W => Some('W'),
+        Key::X => Some('X'),
+        Key::Y => Some('Y'),
+        Key::Z => Some('Z'),
+        Key::Space => Some(' '),
+        _ => None,
+    }
+}
+
+// Digit typed with the number row or the numpad
+pub fn key_to_digit(key: Key) -> Option<char> {
+    let digits = [
+        [Key::D0, Key::NumPad0],
+        [Key::D1, Key::NumPad1],
+        [Key::D2, Key::NumPad2],
+        [Key::D3, Key::NumPad3],
+        [Key::D4, Key::NumPad4],
+        [Key::D5, Key::NumPad5],
+        [Key::D6, Key::NumPad6],
+        [Key::D7, Key::NumPad7],
+        [Key::D8, Key::NumPad8],
+        [Key::D9, Key::NumPad9],
+    ];
+    let digit = digits.iter().position(|keys| keys.contains(&key))?;
+    char::from_digit(digit as u32, 10)
+}
@@ -0,0 +1,459 @@
+// Hand-made levels. A level is one text file, so it can be passed around as it is:
+// a few `key=value` lines saying what it's called, who made it and the mutators it's
+// meant to be played with, then the layout, one line per row of the board, `#` for a
+// wall, `.` for open floor, `~` for mud, `>`, `<`, `^` or `v` for a speed lane going
+// that way and `→`, `←`, `↑` or `↓` for a one-way gate:
+//
+//     name=Crossroads
+//     author=Simon
+//     mutators=double_speed+no_water
+//     bronze=10
+//     silver=25
+//     gold=50
+//     topology=top:wall bottom:wall
+//     ..........
+//     ..####→...
+//     >>>>>..~~.
+//
+// Every row is as long as the first. A snake coming onto a speed lane the way it goes
+// is carried a cell further in the same move, and one in mud only gets every other move
+// and turns a move late. A gate can only be gone into the way its arrow points; from
+// anywhere else it's a wall. The snake starts in the middle of the board, so
+// that cell always stays open. `bronze`, `silver` and `gold` are the par scores for
+// each medal, and `topology` says what the edges of the board do (see `topology`;
+// without it every edge wraps). `moves`, `stars` and `food` make the level a puzzle
+// (see `puzzle`). All the `key=value` lines are optional; without a name
+// the level is called after its file, and a medal without a par score can't be won.
+// Keys the game doesn't know are skipped, so a level made with a newer version still
+// loads.
+//
+// The editor's level lives in `levels/`, and levels from other players are picked up
+// from `levels/community/`.
+
+use crate::game::Direction;
+use crate::mutators::Mutators;
+use crate::puzzle::Puzzle;
+use crate::topology::Topology;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const LEVELS_DIR: &str = "levels";
+pub const COMMUNITY_DIR: &str = "levels/community";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tile {
+    Floor,
+    Wall,
+    Lane(Direction), // Doubles the step of a snake going its way
+    Mud,             // Halves the pace and slows down turns
+    Gate(Direction), // Only lets the snake in going its way
+}
+
+pub const TILES: [Tile; 11] = [
+    Tile::Floor,
+    Tile::Wall,
+    Tile::Mud,
+    Tile::Lane(Direction::Right),
+    Tile::Lane(Direction::Down),
+    Tile::Lane(Direction::Left),
+    Tile::Lane(Direction::Up),
+    Tile::Gate(Direction::Right),
+    Tile::Gate(Direction::Down),
+    Tile::Gate(Direction::Left),
+    Tile::Gate(Direction::Up),
+];
+
+impl Tile {
+    pub fn symbol(self) -> char {
+        match self {
+            Tile::Floor => '.',
+            Tile::Wall => '#',
+            Tile::Mud => '~',
+            Tile::Lane(Direction::Right) => '>',
+            Tile::Lane(Direction::Left) => '<',
+            Tile::Lane(Direction::Up) => '^',
+            Tile::Lane(Direction::Down) => 'v',
+            Tile::Gate(Direction::Right) => '→',
+            Tile::Gate(Direction::Left) => '←',
+            Tile::Gate(Direction::Up) => '↑',
+            Tile::Gate(Direction::Down) => '↓',
+        }
+    }
+
+    pub fn parse(symbol: char) -> Option<Tile> {
+        TILES.into_iter().find(|tile| tile.symbol() == symbol)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Tile::Floor => "Floor",
+            Tile::Wall => "Wall",
+            Tile::Mud => "Mud",
+            Tile::Lane(Direction::Right) => "Lane right",
+            Tile::Lane(Direction::Left) => "Lane left",
+            Tile::Lane(Direction::Up) => "Lane up",
+            Tile::Lane(Direction::Down) => "Lane down",
+            Tile::Gate(Direction::Right) => "Gate right",
+            Tile::Gate(Direction::Left) => "Gate left",
+            Tile::Gate(Direction::Up) => "Gate up",
+            Tile::Gate(Direction::Down) => "Gate down",
+        }
+    }
+
+    // The one after it, for cycling through them
+    pub fn next(self) -> Tile {
+        let i = TILES.iter().position(|tile| *tile == self).unwrap_or(0);
+        TILES[(i + 1) % TILES.len()]
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Layout {
+    pub width: i32,
+    pub height: i32,
+    tiles: Vec<Tile>, // Row by row
+}
+
+impl Layout {
+    // All floor
+    pub fn new((width, height): (i32, i32)) -> Layout {
+        Layout {
+            width,
+            height,
+            tiles: vec![Tile::Floor; (width * height) as usize],
+        }
+    }
+
+    // Where the snake starts
+    pub fn start(&self) -> (i32, i32) {
+        (self.width / 2, self.height / 2)
+    }
+
+    pub fn contains(&self, (x, y): (i32, i32)) -> bool {
+        (0..self.width).contains(&x) && (0..self.height).contains(&y)
+    }
+
+    // Off the layout is open floor
+    pub fn get(&self, cell: (i32, i32)) -> Tile {
+        if self.contains(cell) {
+            self.tiles[(cell.1 * self.width + cell.0) as usize]
+        } else {
+            Tile::Floor
+        }
+    }
+
+    pub fn is_wall(&self, cell: (i32, i32)) -> bool {
+        self.get(cell) == Tile::Wall
+    }
+
+    // The way the speed lane on `cell` goes, if there is one
+    pub fn lane(&self, cell: (i32, i32)) -> Option<Direction> {
+        match self.get(cell) {
+            Tile::Lane(direction) => Some(direction),
+            _ => None,
+        }
+    }
+
+    // The way the one-way gate on `cell` lets the snake in, if there is one
+    pub fn gate(&self, cell: (i32, i32)) -> Option<Direction> {
+        match self.get(cell) {
+            Tile::Gate(direction) => Some(direction),
+            _ => None,
+        }
+    }
+
+    // Returns false if nothing changed: the cell already held `tile`, is off the layout
+    // or is the start, which stays open
+    pub fn set(&mut self, cell: (i32, i32), tile: Tile) -> bool {
+        if !self.contains(cell) || cell == self.start() || self.get(cell) == tile {
+            return false;
+        }
+        self.tiles[(cell.1 * self.width + cell.0) as usize] = tile;
+        true
+    }
+
+    // The rectangle with corners `a` and `b`, both included, as a layout of its own
+    pub fn region(&self, a: (i32, i32), b: (i32, i32)) -> Layout {
+        let (left, top) = (a.0.min(b.0), a.1.min(b.1));
+        let mut region = Layout::new(((a.0 - b.0).abs() + 1, (a.1 - b.1).abs() + 1));
+        for y in 0..region.height {
+            for x in 0..region.width {
+                let tile = self.get((left + x, top + y));
+                region.tiles[(y * region.width + x) as usize] = tile;
+            }
+        }
+        region
+    }
+
+    // Copy `region` in with its top left corner at `at`, cut off at the edges. Returns
+    // false if nothing changed.
+    pub fn paste(&mut self, region: &Layout, at: (i32, i32)) -> bool {
+        let mut changed = false;
+        for y in 0..region.height {
+            for x in 0..region.width {
+                changed |= self.set((at.0 + x, at.1 + y), region.get((x, y)));
+            }
+        }
+        changed
+    }
+
+    pub fn parse(text: &str) -> Result<Layout, String> {
+        let rows: Vec<&str> = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        Layout::from_rows(&rows)
+    }
+
+    fn from_rows(rows: &[&str]) -> Result<Layout, String> {
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        if width == 0 {
+            return Err("no rows".to_string());
+        }
+        let mut layout = Layout::new((width as i32, rows.len() as i32));
+        for (y, row) in rows.iter().enumerate() {
+            if row.chars().count() != width {
+                return Err(format!("row {} is not {} cells long", y + 1, width));
+            }
+            for (x, symbol) in row.chars().enumerate() {
+                let tile = Tile::parse(symbol)
+                    .ok_or_else(|| format!("unknown tile '{}' in row {}", symbol, y + 1))?;
+                layout.set((x as i32, y as i32), tile);
+            }
+        }
+        Ok(layout)
+    }
+
+    pub fn text(&self) -> String {
+        self.tiles
+            .chunks(self.width as usize)
+            .map(|row| row.iter().map(|tile| tile.symbol()).collect::<String>() + "\n")
+            .collect()
+    }
+}
+
+// Won by scoring at least a level's par for it; worst first
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Medal {
+    Bronze,
+    Silver,
+    Gold,
+}
+
+pub const MEDALS: [Medal; 3] = [Medal::Bronze, Medal::Silver, Medal::Gold];
+
+impl Medal {
+    pub fn id(self) -> &'static str {
+        match self {
+            Medal::Bronze => "bronze",
+            Medal::Silver => "silver",
+            Medal::Gold => "gold",
+        }
+    }
+
+    pub fn parse(id: &str) -> Option<Medal> {
+        MEDALS.into_iter().find(|medal| medal.id() == id)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Medal::Bronze => "Bronze",
+            Medal::Silver => "Silver",
+            Medal::Gold => "Gold",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LevelFile {
+    pub name: String,
+    pub author: String,                   // Empty if nobody signed it
+    pub mutators: Mutators,               // Recommended, switched on when the level is picked
+    pub par: [Option<u32>; MEDALS.len()], // Score each medal takes, in MEDALS order
+    pub topology: Option<Topology>,       // In place of the player's own, if it has one
+    pub puzzle: Option<Puzzle>,           // Played move by move, if it's a puzzle
+    pub layout: Layout,
+}
+
+impl LevelFile {
+    pub fn new(layout: Layout) -> LevelFile {
+        LevelFile {
+            name: String::new(),
+            author: String::new(),
+            mutators: Mutators::default(),
+            par: [None; MEDALS.len()],
+            topology: None,
+            puzzle: None,
+            layout,
+        }
+    }
+
+    // The best medal `score` wins here
+    pub fn medal(&self, score: u32) -> Option<Medal> {
+        MEDALS
+            .into_iter()
+            .zip(self.par)
+            .filter(|(_, par)| par.is_some_and(|par| score >= par))
+            .map(|(medal, _)| medal)
+            .max()
+    }
+
+    pub fn parse(text: &str) -> Result<LevelFile, String> {
+        let mut level = LevelFile::new(Layout::new((0, 0)));
+        let mut rows = Vec::new();
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            match line.split_once('=') {
+                Some(_) if !rows.is_empty() => {
+                    return Err(format!("'{}' after the layout", line));
+                }
+                Some(("name", value)) => level.name = value.trim().to_string(),
+                Some(("author", value)) => level.author = value.trim().to_string(),
+                Some(("mutators", value)) => {
+                    level.mutators = Mutators::parse(value.trim())
+                        .ok_or_else(|| format!("unknown mutators '{}'", value))?;
+                }
+                Some(("topology", value)) => {
+                    level.topology = Some(
+                        Topology::parse(value.trim())
+                            .ok_or_else(|| format!("unknown topology '{}'", value))?,
+                    );
+                }
+                Some(("moves", value)) => {
+                    let moves = value.trim().parse();
+                    level.puzzle.get_or_insert_default().moves =
+                        moves.map_err(|_| format!("bad moves '{}'", value))?;
+                }
+                Some(("stars", value)) => {
+                    level.puzzle.get_or_insert_default().stars = Puzzle::parse_stars(value)
+                        .ok_or_else(|| format!("bad stars '{}'", value))?;
+                }
+                Some(("food", value)) => {
+                    level.puzzle.get_or_insert_default().foods = Puzzle::parse_foods(value)
+                        .ok_or_else(|| format!("bad food '{}'", value))?;
+                }
+                Some((key, value)) => {
+                    // Anything but a par score is from a newer version
+                    if let Some(i) = MEDALS.iter().position(|medal| medal.id() == key) {
+                        let par = value.trim().parse();
+                        level.par[i] =
+                            Some(par.map_err(|_| format!("bad {} score '{}'", key, value))?);
+                    }
+                }
+                None => rows.push(line),
+            }
+        }
+        level.layout = Layout::from_rows(&rows)?;
+        if level
+            .puzzle
+            .as_ref()
+            .is_some_and(|puzzle| puzzle.moves == 0)
+        {
+            return Err("a puzzle needs moves".to_string());
+        }
+        Ok(level)
+    }
+
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        if !self.name.is_empty() {
+            text += &format!("name={}\n", self.name);
+        }
+        if !self.author.is_empty() {
+            text += &format!("author={}\n", self.author);
+        }
+        if self.mutators.any() {
+            text += &format!("mutators={}\n", self.mutators.ids());
+        }
+        for (medal, par) in MEDALS.iter().zip(self.par) {
+            if let Some(par) = par {
+                text += &format!("{}={}\n", medal.id(), par);
+            }
+        }
+        if let Some(topology) = self.topology {
+            text += &format!("topology={}\n", topology.text());
+        }
+        if let Some(puzzle) = &self.puzzle {
+            text += &format!("moves={}\n", puzzle.moves);
+            if puzzle.stars.iter().any(Option::is_some) {
+                text += &format!("stars={}\n", puzzle.stars_text());
+            }
+            if !puzzle.foods.is_empty() {
+                text += &format!("food={}\n", puzzle.foods_text());
+            }
+        }
+        text + &self.layout.text()
+    }
+
+    // A level without a name is called after its file
+    pub fn load(path: &Path) -> Result<LevelFile, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let mut level =
+            LevelFile::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+        if level.name.is_empty() {
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+            level.name = stem.replace('_', " ");
+        }
+        Ok(level)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        fs::write(path, self.text()).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    // Short form of its name, which medals are kept under: "Cross Roads!" is
+    // `cross_roads`
+    pub fn id(&self) -> String {
+        let slug: String = self
+            .name
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        let slug: Vec<&str> = slug.split('_').filter(|part| !part.is_empty()).collect();
+        if slug.is_empty() {
+            "level".to_string()
+        } else {
+            slug.join("_")
+        }
+    }
+
+    // File name to share it under
+    pub fn file_name(&self) -> String {
+        self.id() + ".txt"
+    }
+}
+
+// Every level in `dir`, skipping (and reporting) files that aren't levels. Missing is
+// the same as empty.
+pub fn scan(dir: &Path) -> Vec<LevelFile> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    // read_dir comes in no particular order
+    paths.sort();
+    paths
+        .iter()
+        .filter_map(|path| {
+            LevelFile::load(path)
+                .map_err(|e| eprintln!("Error loading level {}", e))
+                .ok()
+        })
+        .collect()
+}
+
+// Check that `path` is a level and copy it in with the community levels, returning
+// where it went. One already there with the same file name is replaced.
+pub fn import(path: &Path) -> Result<PathBuf, String> {
+    let level = LevelFile::load(path)?;
+    let destination = Path::new(COMMUNITY_DIR).join(level.file_name());
+    level.save(&destination)?;
+    Ok(destination)
+}
@@ -0,0 +1,28 @@
+// The game rules as a library, so tests, bots and other frontends can play games
+// without a window. The `snake_game` binary is the windowed frontend built on top.
+
+pub mod adaptive;
+pub mod ai;
+pub mod boss;
+pub mod cheats;
+pub mod clusters;
+pub mod editor;
+pub mod env;
+pub mod events;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod game;
+pub mod grid;
+pub mod headless;
+pub mod levels;
+pub mod mutators;
+pub mod names;
+pub mod puzzle;
+pub mod rats;
+pub mod rooms;
+pub mod rules;
+pub mod scrolling;
+pub mod spit;
+pub mod topology;
+pub mod transitions;
+pub mod weather;
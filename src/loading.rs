@@ -0,0 +1,157 @@
+// Startup. The window opens straight away on a loading screen while the font is read
+// on a background thread, and then the simulation starts up on its own (profile, high
+// scores, the audio device). The icon is drawn in code and every sound is synthesized,
+// so the font is the only file to load. If it can't be, the screen says why and offers
+// to carry on with the copy built into the game.
+
+use crate::render::{Color, Renderer};
+use crate::ui::{Line, Ui};
+use rusttype::Font;
+use std::path::PathBuf;
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+const FONT_FILE: &str = "FiraSans-Regular.ttf";
+
+// What the loading screen is drawn with, and the default if the file can't be loaded
+pub const BUILT_IN_FONT: &[u8] = include_bytes!("../assets/FiraSans-Regular.ttf");
+
+const WHITE: Color = [1.0, 1.0, 1.0, 1.0];
+const DIM: Color = [1.0, 1.0, 1.0, 0.6];
+const DONE: Color = [0.0, 1.0, 0.0, 1.0];
+const FAILED: Color = [1.0, 0.3, 0.3, 1.0];
+const TRACK_CELLS: usize = 16; // Cells the snake runs along under the steps
+const SNAKE_CELLS: usize = 4;
+const CELLS_PER_SECOND: f64 = 8.0;
+
+pub enum Status {
+    Loading,
+    Done,
+    Failed(String),
+}
+
+impl Status {
+    fn line(&self, name: &str) -> Line {
+        match self {
+            Status::Loading => Line::new(format!("{}: loading", name), 20, DIM),
+            Status::Done => Line::new(format!("{}: done", name), 20, DONE),
+            Status::Failed(e) => Line::new(format!("{}: {}", name, e), 20, FAILED),
+        }
+    }
+}
+
+pub struct Loading {
+    started: Instant, // For the animation
+    loader: Option<JoinHandle<Result<Vec<u8>, String>>>,
+    pub font: Status,
+    pub game: Status, // Done once the simulation has drawn its first frame
+}
+
+impl Loading {
+    pub fn start() -> Loading {
+        let loader = thread::Builder::new()
+            .name("loader".to_string())
+            .spawn(read_font)
+            .unwrap_or_else(|e| {
+                eprintln!("Error starting the loader thread: {}", e);
+                std::process::exit(1);
+            });
+        Loading {
+            started: Instant::now(),
+            loader: Some(loader),
+            font: Status::Loading,
+            game: Status::Loading,
+        }
+    }
+
+    // The font, once, when the loader thread has read it. It lives as long as the game.
+    pub fn poll(&mut self) -> Option<&'static [u8]> {
+        if !self
+            .loader
+            .as_ref()
+            .is_some_and(|loader| loader.is_finished())
+        {
+            return None;
+        }
+        let result = self.loader.take()?.join().unwrap_or_else(|_| {
+            // It panicked, and has said why on the way out
+            Err("the loader crashed".to_string())
+        });
+        match result {
+            Ok(bytes) => {
+                self.font = Status::Done;
+                Some(bytes.leak())
+            }
+            Err(e) => {
+                eprintln!("Error loading the font: {}", e);
+                self.font = Status::Failed(e);
+                None
+            }
+        }
+    }
+
+    pub fn failed(&self) -> bool {
+        matches!(self.font, Status::Failed(_))
+    }
+
+    pub fn draw(&self, ui: &Ui, r: &mut dyn Renderer) {
+        r.clear([0.0, 0.0, 0.0, 1.0]);
+        let mut lines = vec![
+            Line::new("Rusty Snake", 32, DONE),
+            Line::spacer(20),
+            self.font.line("Font"),
+            self.game.line("Game"),
+            Line::spacer(20),
+        ];
+        if self.failed() {
+            lines.push(Line::new(
+                "Enter to Continue with the Built-in Font",
+                16,
+                WHITE,
+            ));
+            lines.push(Line::new("Esc to Quit", 16, WHITE));
+        } else {
+            // Room for the snake
+            lines.push(Line::spacer(16));
+        }
+        ui.draw_column(r, &lines);
+        if !self.failed() {
+            self.draw_snake(ui, r);
+        }
+    }
+
+    // A short snake running round a track near the bottom, so a slow start doesn't look
+    // like a hang
+    fn draw_snake(&self, ui: &Ui, r: &mut dyn Renderer) {
+        let [width, height] = r.view_size();
+        let cell = 12.0 * ui.scale;
+        let left = (width - cell * TRACK_CELLS as f64) / 2.0;
+        let top = height * 0.8;
+        let head = (self.started.elapsed().as_secs_f64() * CELLS_PER_SECOND) as usize;
+        for i in 0..SNAKE_CELLS {
+            let x = (head + TRACK_CELLS - i) % TRACK_CELLS;
+            let shade = 1.0 - i as f32 / SNAKE_CELLS as f32 * 0.6;
+            r.rectangle(
+                [0.0, shade, 0.0, 1.0],
+                [left + x as f64 * cell, top, cell - 2.0, cell - 2.0],
+            );
+        }
+    }
+}
+
+// The font file, checked to be one rusttype can read
+pub fn read_font() -> Result<Vec<u8>, String> {
+    let path = font_path()?;
+    let bytes = std::fs::read(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    if Font::try_from_bytes(&bytes).is_none() {
+        return Err(format!("{} is not a font rusttype can read", FONT_FILE));
+    }
+    Ok(bytes)
+}
+
+fn font_path() -> Result<PathBuf, String> {
+    let assets = find_folder::Search::ParentsThenKids(3, 3)
+        .for_folder("assets")
+        .map_err(|_| "'assets' folder not found".to_string())?;
+    Ok(assets.join(FONT_FILE))
+}
@@ -0,0 +1,136 @@
+// Adaptive soundtrack, synthesized like every other sound. A looping tune is split
+// into stems (bass, chords, lead) that fade in one after another as the run gets
+// tense, i.e. as the snake gets longer and faster. Paused or on the title screen only a
+// sparse bass line is left. The intensity eases toward its target every frame, so the
+// stems crossfade instead of cutting in.
+
+use crate::audio::{AudioEngine, Tone};
+use crate::frontend::UPDATES_PER_SECOND;
+use crate::game::SNAKE_SPEED;
+use crate::Game;
+use std::time::Duration;
+
+const STEP_FRAMES: u64 = 18; // An eighth note at 200 BPM
+const STEPS: usize = 16; // Two bars, then the tune loops
+const MUSIC_VOLUME: f32 = 0.12;
+const FADE_SECONDS: f32 = 2.0; // Time for the intensity to close most of a gap
+const FULL_LENGTH: f32 = 40.0; // Snake length that counts as fully tense
+
+// Roots of the chords, one per half bar: A minor, F, C, G
+const ROOTS: [f32; 4] = [110.0, 87.31, 130.81, 98.0];
+// Semitones above the root for the broken chords, minor or major third
+const MINOR: [f32; 3] = [0.0, 3.0, 7.0];
+const MAJOR: [f32; 3] = [0.0, 4.0, 7.0];
+// Lead melody in semitones above A4, None is a rest
+const LEAD: [Option<f32>; STEPS] = [
+    Some(0.0),
+    None,
+    Some(3.0),
+    Some(7.0),
+    None,
+    Some(5.0),
+    Some(3.0),
+    None,
+    Some(2.0),
+    None,
+    Some(3.0),
+    Some(5.0),
+    Some(7.0),
+    None,
+    Some(10.0),
+    None,
+];
+const LEAD_BASE: f32 = 440.0;
+
+#[derive(Clone, Copy)]
+enum Stem {
+    Bass,
+    Chords,
+    Lead,
+}
+
+const STEMS: [Stem; 3] = [Stem::Bass, Stem::Chords, Stem::Lead];
+
+impl Stem {
+    // Intensity at which the stem starts fading in, and how much more it takes to be
+    // at full volume
+    fn threshold(&self) -> (f32, f32) {
+        match self {
+            Stem::Bass => (0.0, 0.1),
+            Stem::Chords => (0.25, 0.2),
+            Stem::Lead => (0.55, 0.25),
+        }
+    }
+
+    fn gain(&self, intensity: f32) -> f32 {
+        let (start, width) = self.threshold();
+        ((intensity - start) / width).clamp(0.0, 1.0)
+    }
+}
+
+pub struct Music {
+    frame: u64,
+    intensity: f32, // 0.0 - 1.0, eased toward `tension`
+}
+
+impl Music {
+    pub fn new() -> Music {
+        Music {
+            frame: 0,
+            intensity: 0.0,
+        }
+    }
+
+    // Call once per update
+    pub fn update(&mut self, game: &Game, audio: &mut dyn AudioEngine) {
+        if game.game_over {
+            self.intensity = 0.0;
+            return;
+        }
+        let calm = game.paused || !game.game_started;
+        let target = if calm { 0.0 } else { tension(game) };
+        let rate = 1.0 / (FADE_SECONDS * UPDATES_PER_SECOND as f32);
+        self.intensity += (target - self.intensity) * rate.min(1.0);
+
+        self.frame += 1;
+        if !self.frame.is_multiple_of(STEP_FRAMES) {
+            return;
+        }
+        let step = (self.frame / STEP_FRAMES) as usize % STEPS;
+        let root = ROOTS[step / 4];
+        let third = if step / 4 == 0 { MINOR } else { MAJOR };
+        for stem in STEMS {
+            let gain = stem.gain(self.intensity);
+            let note = match stem {
+                // Calm keeps the bass at quarter volume, on the first beat of each bar
+                Stem::Bass if calm => step.is_multiple_of(8).then_some((root, 0.25)),
+                Stem::Bass => step.is_multiple_of(2).then_some((root, gain)),
+                Stem::Chords => Some((root * 2.0 * semitones(third[step % 3]), gain)),
+                Stem::Lead => LEAD[step].map(|note| (LEAD_BASE * semitones(note), gain)),
+            };
+            if let Some((frequency, gain)) = note {
+                if gain > 0.0 {
+                    audio.play_tone(Tone {
+                        frequency,
+                        duration: Duration::from_millis(120),
+                        volume: MUSIC_VOLUME * gain,
+                        pan: 0.0,
+                    });
+                }
+            }
+        }
+    }
+}
+
+// How tense the run is, 0.0 - 1.0: half from the snake's length, half from how much
+// faster than the starting speed it moves
+fn tension(game: &Game) -> f32 {
+    let length = (game.snake.body.len() as f32 / FULL_LENGTH).min(1.0);
+    let speedup = SNAKE_SPEED as f32 / game.tick_interval.max(1) as f32;
+    let speed = (speedup - 1.0).clamp(0.0, 1.0);
+    (length + speed) / 2.0
+}
+
+fn semitones(count: f32) -> f32 {
+    2f32.powf(count / 12.0)
+}
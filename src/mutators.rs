@@ -0,0 +1,141 @@
+// Run modifiers picked on the title screen before a run. Each one makes the game
+// harder in some way and multiplies every point scored in exchange. The set a run
+// was played with is kept with its high score entry.
+
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct Mutators {
+    pub double_speed: bool,
+    pub no_water: bool,
+    pub invisible_tail: bool,  // Only the head is drawn
+    pub tiny_board: bool,      // Half the width and height
+    pub expanding_board: bool, // Starts at half size, a column and row more every 15 points
+    pub rooms: bool,           // Walled in, rooms with doors instead of one open board
+    pub scrolling: bool,       // The board slides down, with rocks coming in at the top
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Mutator {
+    DoubleSpeed,
+    NoWater,
+    InvisibleTail,
+    TinyBoard,
+    ExpandingBoard,
+    Rooms,
+    Scrolling,
+}
+
+pub const MUTATORS: [Mutator; 7] = [
+    Mutator::DoubleSpeed,
+    Mutator::NoWater,
+    Mutator::InvisibleTail,
+    Mutator::TinyBoard,
+    Mutator::ExpandingBoard,
+    Mutator::Rooms,
+    Mutator::Scrolling,
+];
+
+impl Mutator {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Mutator::DoubleSpeed => "double_speed",
+            Mutator::NoWater => "no_water",
+            Mutator::InvisibleTail => "invisible_tail",
+            Mutator::TinyBoard => "tiny_board",
+            Mutator::ExpandingBoard => "expanding_board",
+            Mutator::Rooms => "rooms",
+            Mutator::Scrolling => "scrolling",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mutator::DoubleSpeed => "Double speed",
+            Mutator::NoWater => "No water",
+            Mutator::InvisibleTail => "Invisible tail",
+            Mutator::TinyBoard => "Tiny board",
+            Mutator::ExpandingBoard => "Expanding board",
+            Mutator::Rooms => "Rooms",
+            Mutator::Scrolling => "Endless scrolling",
+        }
+    }
+
+    pub fn multiplier(&self) -> f64 {
+        match self {
+            Mutator::DoubleSpeed => 1.5,
+            Mutator::NoWater => 1.25,
+            Mutator::InvisibleTail => 1.5,
+            Mutator::TinyBoard => 1.25,
+            Mutator::ExpandingBoard => 1.1,
+            Mutator::Rooms => 1.25,
+            Mutator::Scrolling => 1.5,
+        }
+    }
+}
+
+impl Mutators {
+    pub fn is_on(&self, mutator: Mutator) -> bool {
+        match mutator {
+            Mutator::DoubleSpeed => self.double_speed,
+            Mutator::NoWater => self.no_water,
+            Mutator::InvisibleTail => self.invisible_tail,
+            Mutator::TinyBoard => self.tiny_board,
+            Mutator::ExpandingBoard => self.expanding_board,
+            Mutator::Rooms => self.rooms,
+            Mutator::Scrolling => self.scrolling,
+        }
+    }
+
+    pub fn toggle(&mut self, mutator: Mutator) {
+        let flag = match mutator {
+            Mutator::DoubleSpeed => &mut self.double_speed,
+            Mutator::NoWater => &mut self.no_water,
+            Mutator::InvisibleTail => &mut self.invisible_tail,
+            Mutator::TinyBoard => &mut self.tiny_board,
+            Mutator::ExpandingBoard => &mut self.expanding_board,
+            Mutator::Rooms => &mut self.rooms,
+            Mutator::Scrolling => &mut self.scrolling,
+        };
+        *flag = !*flag;
+    }
+
+    pub fn active(&self) -> Vec<Mutator> {
+        MUTATORS
+            .iter()
+            .copied()
+            .filter(|mutator| self.is_on(*mutator))
+            .collect()
+    }
+
+    pub fn any(&self) -> bool {
+        !self.active().is_empty()
+    }
+
+    // Every active multiplier, compounded
+    pub fn multiplier(&self) -> f64 {
+        self.active().iter().map(Mutator::multiplier).product()
+    }
+
+    // Board size at the start of a run on a board that would otherwise be `size`
+    pub fn board_size(&self, (width, height): (i32, i32)) -> (i32, i32) {
+        if self.tiny_board || self.expanding_board {
+            ((width / 2).max(8), (height / 2).max(6))
+        } else {
+            (width, height)
+        }
+    }
+
+    // e.g. "double_speed+no_water", empty with none active
+    pub fn ids(&self) -> String {
+        let ids: Vec<&str> = self.active().iter().map(Mutator::id).collect();
+        ids.join("+")
+    }
+
+    pub fn parse(value: &str) -> Option<Mutators> {
+        let mut mutators = Mutators::default();
+        for id in value.split('+').filter(|id| !id.is_empty()) {
+            let mutator = MUTATORS.iter().find(|mutator| mutator.id() == id)?;
+            mutators.toggle(*mutator);
+        }
+        Some(mutators)
+    }
+}
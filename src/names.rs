@@ -0,0 +1,44 @@
+// High score names. They are typed as free text, so lengths and cuts are counted in
+// graphemes (what a player sees as one character) rather than bytes or chars: "Åsa 🐍"
+// is five long, and backspace never splits an accent or an emoji in half.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+pub const MAX_NAME_LENGTH: usize = 10;
+
+pub fn length(name: &str) -> usize {
+    name.graphemes(true).count()
+}
+
+// Append typed text, up to `MAX_NAME_LENGTH`. Control characters are dropped, and so
+// are the separators of the high score and PIN files.
+pub fn type_into(name: &mut String, text: &str) {
+    for c in text.chars() {
+        if c.is_control() || c == ',' || c == '=' {
+            continue;
+        }
+        // A combining mark joins the last grapheme instead of adding one
+        let before = name.len();
+        name.push(c);
+        if length(name) > MAX_NAME_LENGTH {
+            name.truncate(before);
+            return;
+        }
+    }
+}
+
+// Remove the last grapheme
+pub fn backspace(name: &mut String) {
+    if let Some((start, _)) = name.grapheme_indices(true).next_back() {
+        name.truncate(start);
+    }
+}
+
+// `name` cut to at most `max` graphemes, with an ellipsis standing in for the rest
+pub fn shorten(name: &str, max: usize) -> String {
+    if length(name) <= max {
+        return name.to_string();
+    }
+    let kept: String = name.graphemes(true).take(max.saturating_sub(1)).collect();
+    kept + "…"
+}
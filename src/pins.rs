@@ -0,0 +1,101 @@
+// PINs that protect names on the high score table, so whoever else shares the machine
+// can't post scores under someone's name or clear them. Kept hashed, never as typed,
+// one name per line:
+//
+//     ANN=9d1c0f3a27b4e855
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+
+const PINS_FILE: &str = "pins.txt";
+const HASH_ROUNDS: u32 = 10_000;
+pub const PIN_LENGTH: usize = 4;
+
+// What a PIN being typed is for
+#[derive(Clone, Copy, PartialEq)]
+pub enum PinPurpose {
+    Claim, // Protect the name just entered, then save the score under it
+    Save,  // Prove the name is yours to save the score under it
+    Clear, // Prove the name is yours to delete its scores
+}
+
+pub struct PinEntry {
+    pub purpose: PinPurpose,
+    pub name: String,
+    pub digits: String,
+}
+
+impl PinEntry {
+    pub fn new(purpose: PinPurpose, name: &str) -> PinEntry {
+        PinEntry {
+            purpose,
+            name: name.to_string(),
+            digits: String::new(),
+        }
+    }
+}
+
+pub struct Pins {
+    hashes: Vec<(String, u64)>,
+}
+
+impl Pins {
+    pub fn load() -> Pins {
+        let mut hashes = Vec::new();
+        // No file yet means no name is protected
+        if let Ok(file) = File::open(PINS_FILE) {
+            for line in BufReader::new(file).lines().map_while(Result::ok) {
+                if let Some((name, hash)) = line.split_once('=') {
+                    if let Ok(hash) = u64::from_str_radix(hash.trim(), 16) {
+                        hashes.push((name.trim().to_string(), hash));
+                    }
+                }
+            }
+        }
+        Pins { hashes }
+    }
+
+    pub fn is_protected(&self, name: &str) -> bool {
+        self.hashes.iter().any(|(n, _)| n == name)
+    }
+
+    pub fn check(&self, name: &str, pin: &str) -> bool {
+        self.hashes
+            .iter()
+            .any(|(n, hash)| n == name && *hash == hash_pin(name, pin))
+    }
+
+    pub fn set(&mut self, name: &str, pin: &str) {
+        self.hashes.retain(|(n, _)| n != name);
+        self.hashes.push((name.to_string(), hash_pin(name, pin)));
+        self.save();
+    }
+
+    fn save(&self) {
+        let result = File::create(PINS_FILE).and_then(|mut file| {
+            self.hashes
+                .iter()
+                .try_for_each(|(name, hash)| writeln!(file, "{}={:016x}", name, hash))
+        });
+        if let Err(e) = result {
+            eprintln!("Error writing {}: {}", PINS_FILE, e);
+        }
+    }
+}
+
+// FNV-1a over the name and PIN, repeated a few thousand times. Salted with the name,
+// so the same PIN looks different for every name. Written out here rather than using
+// std's hasher, whose algorithm may change between releases and lock everyone out.
+// With only ten thousand PINs this keeps them out of sight, it isn't meant to stop
+// a determined attacker.
+fn hash_pin(name: &str, pin: &str) -> u64 {
+    let input = format!("{}:{}", name, pin);
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for _ in 0..HASH_ROUNDS {
+        for byte in input.bytes().chain(hash.to_le_bytes()) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
@@ -0,0 +1,238 @@
+// Per-player progress that outlives a single run: the best score, what it has
+// unlocked, scrap coins, consumables bought for the next run, the roguelite upgrades
+// and where adaptive difficulty has got to. Kept in a `key=value` file like the
+// settings, but written by the game.
+//
+//     best_score=57
+//     unlocked=striped,rust
+//     skin=rust
+//     coins=12
+//     loadout=shield
+//     mode=roguelite
+//     snake_color=#33cc66
+//     upgrade_points=4
+//     upgrades=start_length:1 iron_stomach:0 extra_scrap:1
+//     medals=crossroads:gold,the_maze:bronze
+//     stars=corner_shop:3,dead_end:1
+//     adaptive=level:-1 clock:36000 deaths:30100,34500 close_calls:35020
+
+use crate::adaptive::Adaptive;
+use crate::colors;
+use crate::levels::Medal;
+use crate::render::Color;
+use crate::roguelite::{GameMode, Upgrades};
+use crate::shop::{self, Loadout};
+use crate::skins::{Skin, SKINS};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+
+const PROFILE_FILE: &str = "profile.txt";
+
+pub struct Profile {
+    pub best_score: u32,
+    pub unlocked: Vec<Skin>, // Besides Classic, which is always available
+    pub skin: Skin,
+    pub coins: u32,
+    pub loadout: Loadout, // Bought for the next run
+    pub mode: GameMode,
+    pub upgrade_points: u32,
+    pub upgrades: Upgrades,
+    pub snake_color: Option<Color>, // Base color picked by the player, None for the theme's
+    pub medals: BTreeMap<String, Medal>, // Best won on each level, by level id
+    pub stars: BTreeMap<String, u32>, // Most earned on each puzzle, the same
+    pub adaptive: Adaptive,         // As the last run in the mode left it
+}
+
+impl Profile {
+    pub fn load() -> Profile {
+        let mut profile = Profile {
+            best_score: 0,
+            unlocked: Vec::new(),
+            skin: Skin::Classic,
+            coins: 0,
+            loadout: Loadout::default(),
+            mode: GameMode::Classic,
+            upgrade_points: 0,
+            upgrades: Upgrades::default(),
+            snake_color: None,
+            medals: BTreeMap::new(),
+            stars: BTreeMap::new(),
+            adaptive: Adaptive::default(),
+        };
+        // No file yet just means a fresh profile
+        if let Ok(file) = File::open(PROFILE_FILE) {
+            for line in BufReader::new(file).lines().map_while(Result::ok) {
+                if let Some((key, value)) = line.split_once('=') {
+                    profile.apply(key.trim(), value.trim());
+                }
+            }
+        }
+        profile
+    }
+
+    fn apply(&mut self, key: &str, value: &str) {
+        match key {
+            "best_score" => {
+                if let Ok(v) = value.parse() {
+                    self.best_score = v;
+                }
+            }
+            "unlocked" => {
+                self.unlocked = value
+                    .split(',')
+                    .filter_map(|id| Skin::parse(id.trim()))
+                    .collect();
+            }
+            "skin" => {
+                if let Some(v) = Skin::parse(value) {
+                    self.skin = v;
+                }
+            }
+            "coins" => {
+                if let Ok(v) = value.parse() {
+                    self.coins = v;
+                }
+            }
+            "loadout" => {
+                if let Some(v) = Loadout::parse(value.split_whitespace()) {
+                    self.loadout = v;
+                }
+            }
+            "mode" => {
+                if let Some(v) = GameMode::parse(value) {
+                    self.mode = v;
+                }
+            }
+            "upgrade_points" => {
+                if let Ok(v) = value.parse() {
+                    self.upgrade_points = v;
+                }
+            }
+            "upgrades" => {
+                if let Some(v) = Upgrades::parse(value.split_whitespace()) {
+                    self.upgrades = v;
+                }
+            }
+            "snake_color" => {
+                if value == "off" {
+                    self.snake_color = None;
+                } else if let Some(v) = colors::parse(value) {
+                    self.snake_color = Some(v);
+                }
+            }
+            "medals" => {
+                self.medals = value
+                    .split(',')
+                    .filter_map(|entry| entry.trim().split_once(':'))
+                    .filter_map(|(level, medal)| Some((level.to_string(), Medal::parse(medal)?)))
+                    .collect();
+            }
+            "stars" => {
+                self.stars = value
+                    .split(',')
+                    .filter_map(|entry| entry.trim().split_once(':'))
+                    .filter_map(|(level, stars)| Some((level.to_string(), stars.parse().ok()?)))
+                    .collect();
+            }
+            "adaptive" => {
+                if let Some(v) = Adaptive::parse(value.split_whitespace()) {
+                    self.adaptive = v;
+                }
+            }
+            _ => eprintln!("Unknown profile entry '{}' in {}", key, PROFILE_FILE),
+        }
+    }
+
+    // Every entry as it's written to the file
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        let unlocked: Vec<&str> = self.unlocked.iter().map(|skin| skin.id()).collect();
+        let medals: Vec<String> = self
+            .medals
+            .iter()
+            .map(|(level, medal)| format!("{}:{}", level, medal.id()))
+            .collect();
+        let stars: Vec<String> = self
+            .stars
+            .iter()
+            .map(|(level, stars)| format!("{}:{}", level, stars))
+            .collect();
+        vec![
+            ("best_score", self.best_score.to_string()),
+            ("unlocked", unlocked.join(",")),
+            ("skin", self.skin.id().to_string()),
+            ("coins", self.coins.to_string()),
+            ("loadout", self.loadout.words().join(" ")),
+            ("mode", self.mode.id().to_string()),
+            ("upgrade_points", self.upgrade_points.to_string()),
+            ("upgrades", self.upgrades.words().join(" ")),
+            (
+                "snake_color",
+                self.snake_color.map_or("off".to_string(), colors::hex),
+            ),
+            ("medals", medals.join(",")),
+            ("stars", stars.join(",")),
+            ("adaptive", self.adaptive.words().join(" ")),
+        ]
+    }
+
+    pub fn save(&self) {
+        let contents: String = self
+            .entries()
+            .iter()
+            .map(|(key, value)| format!("{}={}\n", key, value))
+            .collect();
+        let result = File::create(PROFILE_FILE).and_then(|mut f| f.write_all(contents.as_bytes()));
+        if let Err(e) = result {
+            eprintln!("Error writing profile: {}", e);
+        }
+    }
+
+    pub fn is_unlocked(&self, skin: Skin) -> bool {
+        skin == Skin::Classic || self.unlocked.contains(&skin)
+    }
+
+    // Record a finished run: pay out its coins, returns the skins it unlocked
+    pub fn finish_run(&mut self, score: u32) -> Vec<Skin> {
+        self.best_score = self.best_score.max(score);
+        self.coins += shop::coins_for(score);
+        let newly: Vec<Skin> = SKINS
+            .iter()
+            .copied()
+            .filter(|skin| !self.is_unlocked(*skin) && score >= skin.milestone())
+            .collect();
+        self.unlocked.extend(&newly);
+        newly
+    }
+
+    // Keep `medal` for the level `id` if it beats the one won there before. Returns
+    // whether it did.
+    pub fn award_medal(&mut self, id: &str, medal: Medal) -> bool {
+        if self.medals.get(id).is_some_and(|best| *best >= medal) {
+            return false;
+        }
+        self.medals.insert(id.to_string(), medal);
+        true
+    }
+
+    // The same for `stars` on the puzzle `id`
+    pub fn award_stars(&mut self, id: &str, stars: u32) -> bool {
+        if self.stars.get(id).is_some_and(|best| *best >= stars) {
+            return false;
+        }
+        self.stars.insert(id.to_string(), stars);
+        true
+    }
+
+    // Switch to the next unlocked skin, wrapping around
+    pub fn cycle_skin(&mut self) {
+        let current = SKINS.iter().position(|s| *s == self.skin).unwrap_or(0);
+        for offset in 1..=SKINS.len() {
+            let skin = SKINS[(current + offset) % SKINS.len()];
+            if self.is_unlocked(skin) {
+                self.skin = skin;
+                return;
+            }
+        }
+    }
+}
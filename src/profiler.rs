@@ -0,0 +1,137 @@
+// Frame timing overlay, toggled with F12 or started with `--profile`. Each rendered
+// frame is split into spans so a stutter report can say which part of the loop was
+// slow. Times are CPU time: updates and most of the drawing on the simulation thread,
+// the rest on the main thread. The GPU works asynchronously, so a GPU-bound stall
+// shows up in whichever span ends up waiting on it.
+
+use crate::render::{Color, Renderer};
+use crate::ui::{Line, Ui};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+const HISTORY: usize = 120; // Frames kept for the graph and percentiles
+const GRAPH_HEIGHT: f64 = 60.0;
+const GRAPH_MS: f64 = 33.3; // Frame time at the top of the graph
+const FRAME_BUDGET_MS: f64 = 1000.0 / 60.0;
+
+#[derive(Clone, Copy)]
+pub enum Span {
+    Input,
+    Update,
+    Draw,
+    GlyphFlush,
+}
+
+const SPANS: [(Span, &str, Color); 4] = [
+    (Span::Input, "input", [0.3, 0.6, 1.0, 1.0]),
+    (Span::Update, "update", [0.3, 1.0, 0.3, 1.0]),
+    (Span::Draw, "draw", [1.0, 0.8, 0.2, 1.0]),
+    (Span::GlyphFlush, "flush", [1.0, 0.3, 0.3, 1.0]),
+];
+
+pub struct Profiler {
+    pub visible: bool,
+    current: [Duration; 4], // Spans measured since the last rendered frame
+    frames: VecDeque<[Duration; 4]>,
+}
+
+impl Profiler {
+    pub fn new(visible: bool) -> Profiler {
+        Profiler {
+            visible,
+            current: [Duration::ZERO; 4],
+            frames: VecDeque::with_capacity(HISTORY),
+        }
+    }
+
+    pub fn record(&mut self, span: Span, start: Instant) {
+        self.add(span, start.elapsed());
+    }
+
+    // Time measured elsewhere, e.g. on the simulation thread
+    pub fn add(&mut self, span: Span, time: Duration) {
+        self.current[span as usize] += time;
+    }
+
+    // Close the current frame, called after each render
+    pub fn end_frame(&mut self) {
+        if self.frames.len() == HISTORY {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(std::mem::take(&mut self.current));
+    }
+
+    // Frame time in milliseconds at percentile p (0.0 - 1.0) over the history
+    fn percentile(&self, p: f64) -> f64 {
+        let mut totals: Vec<f64> = self.frames.iter().map(total_ms).collect();
+        if totals.is_empty() {
+            return 0.0;
+        }
+        totals.sort_by(|a, b| a.total_cmp(b));
+        totals[((totals.len() - 1) as f64 * p).round() as usize]
+    }
+
+    fn average_ms(&self, span: Span) -> f64 {
+        if self.frames.is_empty() {
+            return 0.0;
+        }
+        let sum: Duration = self.frames.iter().map(|f| f[span as usize]).sum();
+        sum.as_secs_f64() * 1000.0 / self.frames.len() as f64
+    }
+
+    // Stacked bar per frame along the bottom right, with the readouts above it
+    pub fn draw(&self, ui: &Ui, r: &mut dyn Renderer) {
+        let bar_width = 2.0;
+        let width = HISTORY as f64 * bar_width;
+        let [view_width, view_height] = r.view_size();
+        let left = view_width - width - 10.0;
+        let bottom = view_height - 30.0;
+        let scale = GRAPH_HEIGHT / GRAPH_MS;
+
+        r.rectangle(
+            [0.0, 0.0, 0.0, 0.6],
+            [left, bottom - GRAPH_HEIGHT, width, GRAPH_HEIGHT],
+        );
+        for (i, frame) in self.frames.iter().enumerate() {
+            let x = left + i as f64 * bar_width;
+            let mut y = bottom;
+            for (span, _, color) in SPANS {
+                let height = (ms(frame[span as usize]) * scale).min(y - (bottom - GRAPH_HEIGHT));
+                y -= height;
+                r.rectangle(color, [x, y, bar_width, height]);
+            }
+        }
+        let budget_y = bottom - FRAME_BUDGET_MS * scale;
+        r.line(
+            [1.0, 1.0, 1.0, 0.5],
+            0.5,
+            [left, budget_y, left + width, budget_y],
+        );
+
+        // Text positions are given unscaled, the graph itself stays at its pixel size
+        let text_x = left / ui.scale;
+        let readout = format!(
+            "p50 {:.1}  p95 {:.1}  p99 {:.1} ms",
+            self.percentile(0.5),
+            self.percentile(0.95),
+            self.percentile(0.99)
+        );
+        let line = Line::new(readout, 12, [1.0, 1.0, 1.0, 1.0]);
+        ui.draw_text(r, &line, text_x, (bottom - GRAPH_HEIGHT) / ui.scale - 20.0);
+        let spans = SPANS
+            .iter()
+            .map(|(span, name, _)| format!("{} {:.1}", name, self.average_ms(*span)))
+            .collect::<Vec<_>>()
+            .join("  ");
+        let line = Line::new(spans, 12, [0.8, 0.8, 0.8, 1.0]);
+        ui.draw_text(r, &line, text_x, (bottom - GRAPH_HEIGHT) / ui.scale - 6.0);
+    }
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn total_ms(frame: &[Duration; 4]) -> f64 {
+    frame.iter().copied().map(ms).sum()
+}
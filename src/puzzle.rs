@@ -0,0 +1,74 @@
+// Puzzles: hand-made levels with their food laid out in advance and a limit on moves.
+// The snake only moves when a direction is pressed, one cell a press, and the puzzle
+// is solved by eating every piece before the moves run out. Any move can be taken
+// back, the one that lost the puzzle included. A level is a puzzle when it says how
+// many moves it allows (see `levels`):
+//
+//     moves=30
+//     stars=18,24
+//     food=scrap:3,4 metal:6,2 water:7,7
+//
+// Solving it at all is worth a star, in 24 moves or fewer two and in 18 three.
+
+use crate::game::FoodType;
+
+pub const MAX_STARS: u32 = 3;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Puzzle {
+    pub moves: u32,                         // Allowed
+    pub stars: [Option<u32>; 2],            // Most moves for three stars, and for two
+    pub foods: Vec<(FoodType, (i32, i32))>, // Laid out at the start, never replaced
+}
+
+impl Puzzle {
+    // Stars for solving it in `moves`
+    pub fn stars(&self, moves: u32) -> u32 {
+        match self.stars {
+            [Some(most), _] if moves <= most => MAX_STARS,
+            [_, Some(most)] if moves <= most => 2,
+            _ => 1,
+        }
+    }
+
+    // e.g. "18,24", or ",24" for no three stars
+    pub fn parse_stars(value: &str) -> Option<[Option<u32>; 2]> {
+        let mut stars = [None; 2];
+        for (i, most) in value.split(',').map(str::trim).enumerate() {
+            if !most.is_empty() {
+                *stars.get_mut(i)? = Some(most.parse().ok()?);
+            }
+        }
+        Some(stars)
+    }
+
+    pub fn stars_text(&self) -> String {
+        let stars: Vec<String> = self
+            .stars
+            .iter()
+            .map(|most| most.map_or(String::new(), |most| most.to_string()))
+            .collect();
+        stars.join(",")
+    }
+
+    // e.g. "scrap:3,4 metal:6,2"
+    pub fn parse_foods(value: &str) -> Option<Vec<(FoodType, (i32, i32))>> {
+        value
+            .split_whitespace()
+            .map(|word| {
+                let (food, cell) = word.split_once(':')?;
+                let (x, y) = cell.split_once(',')?;
+                Some((FoodType::parse(food)?, (x.parse().ok()?, y.parse().ok()?)))
+            })
+            .collect()
+    }
+
+    pub fn foods_text(&self) -> String {
+        let foods: Vec<String> = self
+            .foods
+            .iter()
+            .map(|(food, (x, y))| format!("{}:{},{}", food.id(), x, y))
+            .collect();
+        foods.join(" ")
+    }
+}
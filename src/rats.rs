@@ -0,0 +1,62 @@
+// Rats: small scavengers that scurry in from the edges, head for the nearest food and
+// run off with it if they get there first. They can't hurt the snake; running the
+// head into one squashes it for points.
+
+pub const MAX_RATS: usize = 3;
+pub const RAT_CHANCE: u32 = 60; // One in this many ticks brings a new rat
+pub const RAT_POINTS: u32 = 3;
+const MOVE_EVERY: u64 = 2; // Snake moves per rat move
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rat {
+    pub position: (i32, i32),
+}
+
+impl Rat {
+    // Whether the rat moves on this tick
+    pub fn moves_on(tick: u64) -> bool {
+        tick.is_multiple_of(MOVE_EVERY)
+    }
+
+    // Next cell toward `target`, trying the longer axis first and then the other.
+    // `blocked` cells (the snake's body) are never stepped on; None if both are.
+    pub fn step_toward(
+        &self,
+        target: (i32, i32),
+        (width, height): (i32, i32),
+        wrap: bool,
+        blocked: impl Fn((i32, i32)) -> bool,
+    ) -> Option<(i32, i32)> {
+        let (x, y) = self.position;
+        let dx = axis_delta(x, target.0, width, wrap);
+        let dy = axis_delta(y, target.1, height, wrap);
+        let horizontal = (x + dx.signum(), y);
+        let vertical = (x, y + dy.signum());
+        let options = if dx.abs() >= dy.abs() {
+            [(dx, horizontal), (dy, vertical)]
+        } else {
+            [(dy, vertical), (dx, horizontal)]
+        };
+        options
+            .into_iter()
+            .filter(|(delta, _)| *delta != 0)
+            .map(|(_, (x, y))| {
+                if wrap {
+                    (x.rem_euclid(width), y.rem_euclid(height))
+                } else {
+                    (x, y)
+                }
+            })
+            .find(|cell| !blocked(*cell))
+    }
+}
+
+// Signed distance along one axis, the short way round when the board wraps
+pub fn axis_delta(from: i32, to: i32, size: i32, wrap: bool) -> i32 {
+    let delta = to - from;
+    if wrap && delta.abs() > size / 2 {
+        delta - size * delta.signum()
+    } else {
+        delta
+    }
+}
@@ -0,0 +1,35 @@
+// Drawing primitives every backend provides. Coordinates are logical window units
+// with the origin in the top left corner, so screens look the same on every backend
+// and on HiDPI displays.
+
+use rusttype::{point, Font, Scale};
+
+pub type Color = [f32; 4]; // RGBA, 0.0 - 1.0
+
+pub trait Renderer {
+    // Window size in logical units
+    fn view_size(&self) -> [f64; 2];
+    fn clear(&mut self, color: Color);
+    // [x, y, width, height]
+    fn rectangle(&mut self, color: Color, rect: [f64; 4]);
+    // [x1, y1, x2, y2]
+    fn line(&mut self, color: Color, width: f64, line: [f64; 4]);
+    // Filled convex polygon through the points, in order
+    fn polygon(&mut self, color: Color, points: &[[f64; 2]]);
+    // Text with its baseline starting at (x, y). `size` is in points, like piston's.
+    fn text(&mut self, text: &str, size: u32, color: Color, x: f64, y: f64);
+    fn text_width(&mut self, text: &str, size: u32) -> f64;
+    // Only draw inside [x, y, width, height] from now on, None for the whole window.
+    // Split screen draws each player's board as a pass of its own, clipped to its half.
+    fn clip(&mut self, rect: Option<[f64; 4]>);
+}
+
+// Width of `text` set in `font`, for renderers that lay text out themselves
+pub fn text_width(font: &Font, text: &str, size: u32) -> f64 {
+    font.layout(text, Scale::uniform(size as f32), point(0.0, 0.0))
+        .last()
+        .map_or(0.0, |glyph| {
+            let advance = glyph.unpositioned().h_metrics().advance_width;
+            (glyph.position().x + advance) as f64
+        })
+}
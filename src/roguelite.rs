@@ -0,0 +1,199 @@
+// Roguelite mode: every death pays out upgrade points, which buy permanent upgrades
+// that carry over to every later run in the mode. The upgrades form a small tree;
+// a longer start has to come first. Runs in this mode don't go on the high score
+// table.
+
+use crate::profile::Profile;
+use crate::Game;
+
+const SEGMENTS_PER_START_LEVEL: u32 = 2;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum GameMode {
+    Classic,
+    Roguelite,
+    Diagonal, // Eight-way steering, with its own high score table
+    Hex,      // Experimental six-way board, with its own high score table
+    Kids,     // Forgiving rules and chunky sprites, with its own high score table
+    Adaptive, // Pace and metal follow how the player is doing, see `adaptive`
+    Versus,   // Two players side by side, see `versus`
+    Duel,     // Versus taking turns against a chess clock, the same
+}
+
+impl GameMode {
+    pub fn parse(value: &str) -> Option<GameMode> {
+        match value {
+            "classic" => Some(GameMode::Classic),
+            "roguelite" => Some(GameMode::Roguelite),
+            "diagonal" => Some(GameMode::Diagonal),
+            "hex" => Some(GameMode::Hex),
+            "kids" => Some(GameMode::Kids),
+            "adaptive" => Some(GameMode::Adaptive),
+            "versus" => Some(GameMode::Versus),
+            "duel" => Some(GameMode::Duel),
+            _ => None,
+        }
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            GameMode::Classic => "classic",
+            GameMode::Roguelite => "roguelite",
+            GameMode::Diagonal => "diagonal",
+            GameMode::Hex => "hex",
+            GameMode::Kids => "kids",
+            GameMode::Adaptive => "adaptive",
+            GameMode::Versus => "versus",
+            GameMode::Duel => "duel",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Classic => "Classic",
+            GameMode::Roguelite => "Roguelite",
+            GameMode::Diagonal => "Diagonal",
+            GameMode::Hex => "Hex",
+            GameMode::Kids => "Kids",
+            GameMode::Adaptive => "Adaptive",
+            GameMode::Versus => "Versus",
+            GameMode::Duel => "Duel",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Upgrade {
+    StartLength,
+    IronStomach, // Bad metal bites cost stomach segments instead of the run
+    ExtraScrap,  // More rusty scrap on the board at once
+}
+
+pub const UPGRADES: [Upgrade; 3] = [
+    Upgrade::StartLength,
+    Upgrade::IronStomach,
+    Upgrade::ExtraScrap,
+];
+
+impl Upgrade {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Upgrade::StartLength => "start_length",
+            Upgrade::IronStomach => "iron_stomach",
+            Upgrade::ExtraScrap => "extra_scrap",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Upgrade> {
+        UPGRADES
+            .iter()
+            .copied()
+            .find(|upgrade| upgrade.id() == value)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Upgrade::StartLength => "Longer start",
+            Upgrade::IronStomach => "Iron stomach",
+            Upgrade::ExtraScrap => "Extra scrap",
+        }
+    }
+
+    pub fn max_level(&self) -> u32 {
+        match self {
+            Upgrade::StartLength => 3,
+            Upgrade::IronStomach | Upgrade::ExtraScrap => 2,
+        }
+    }
+
+    // Points for the next level, each one costs more than the last
+    pub fn cost(&self, level: u32) -> u32 {
+        level + 1
+    }
+
+    // The upgrade and level this one branches off from
+    pub fn requires(&self) -> Option<(Upgrade, u32)> {
+        match self {
+            Upgrade::StartLength => None,
+            Upgrade::IronStomach | Upgrade::ExtraScrap => Some((Upgrade::StartLength, 1)),
+        }
+    }
+}
+
+// Upgrade levels, as bought in the profile and as applied to a run
+#[derive(Clone, Copy, Default, PartialEq)]
+pub struct Upgrades {
+    pub start_length: u32,
+    pub iron_stomach: u32,
+    pub extra_scrap: u32,
+}
+
+impl Upgrades {
+    pub fn level(&self, upgrade: Upgrade) -> u32 {
+        match upgrade {
+            Upgrade::StartLength => self.start_length,
+            Upgrade::IronStomach => self.iron_stomach,
+            Upgrade::ExtraScrap => self.extra_scrap,
+        }
+    }
+
+    fn level_mut(&mut self, upgrade: Upgrade) -> &mut u32 {
+        match upgrade {
+            Upgrade::StartLength => &mut self.start_length,
+            Upgrade::IronStomach => &mut self.iron_stomach,
+            Upgrade::ExtraScrap => &mut self.extra_scrap,
+        }
+    }
+
+    // e.g. "start_length:2 iron_stomach:1 extra_scrap:0", for the profile and input log
+    pub fn words(&self) -> Vec<String> {
+        UPGRADES
+            .iter()
+            .map(|upgrade| format!("{}:{}", upgrade.id(), self.level(*upgrade)))
+            .collect()
+    }
+
+    pub fn parse<'a>(words: impl IntoIterator<Item = &'a str>) -> Option<Upgrades> {
+        let mut upgrades = Upgrades::default();
+        for word in words {
+            let (id, level) = word.split_once(':')?;
+            let upgrade = Upgrade::parse(id)?;
+            *upgrades.level_mut(upgrade) = level.parse::<u32>().ok()?.min(upgrade.max_level());
+        }
+        Some(upgrades)
+    }
+
+    // Set up a run that's just starting
+    pub fn apply(&self, game: &mut Game) {
+        game.ranked = false;
+        for _ in 0..self.start_length * SEGMENTS_PER_START_LEVEL {
+            game.grow();
+        }
+        game.metal_bites = self.iron_stomach;
+        game.extra_scrap = self.extra_scrap as usize;
+    }
+}
+
+// Upgrade points a death with this score pays out
+pub fn points_for(score: u32) -> u32 {
+    1 + score / 20
+}
+
+pub fn buy(profile: &mut Profile, upgrade: Upgrade) -> Result<(), &'static str> {
+    let level = profile.upgrades.level(upgrade);
+    if level >= upgrade.max_level() {
+        return Err("Already at the top level");
+    }
+    if let Some((required, required_level)) = upgrade.requires() {
+        if profile.upgrades.level(required) < required_level {
+            return Err("Needs a longer start first");
+        }
+    }
+    let cost = upgrade.cost(level);
+    if profile.upgrade_points < cost {
+        return Err("Not enough upgrade points");
+    }
+    profile.upgrade_points -= cost;
+    *profile.upgrades.level_mut(upgrade) += 1;
+    Ok(())
+}
@@ -0,0 +1,109 @@
+// Multi-room levels: the board is cut into rooms side by side, separated by wall
+// columns with one door each. Food only turns up in the room the snake is in, and
+// the door on its far side opens once enough of it has been eaten.
+
+pub const ROOMS: i32 = 3;
+pub const ROOM_QUOTA: u32 = 5; // Food to eat in a room before its door opens
+
+#[derive(Clone, Debug)]
+pub struct Room {
+    pub left: i32,
+    pub right: i32, // Exclusive
+    pub eaten: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct Level {
+    pub rooms: Vec<Room>,
+    pub walls: Vec<i32>,           // Column of each dividing wall, left to right
+    pub doors: Vec<(i32, i32)>,    // Door cell in each wall
+    pub openings: Vec<(i32, i32)>, // Wall cells that can be passed: open doors and holes
+    pub active: usize,             // Room the snake is in
+    height: i32,
+}
+
+impl Level {
+    pub fn new((width, height): (i32, i32)) -> Level {
+        let walls: Vec<i32> = (1..ROOMS).map(|i| width * i / ROOMS).collect();
+        let mut left = 0;
+        let mut rooms = Vec::new();
+        for right in walls.iter().copied().chain([width]) {
+            rooms.push(Room {
+                left,
+                right,
+                eaten: 0,
+            });
+            left = right + 1;
+        }
+        Level {
+            rooms,
+            doors: walls.iter().map(|x| (*x, height / 2)).collect(),
+            walls,
+            openings: Vec::new(),
+            active: 0,
+            height,
+        }
+    }
+
+    // Where the snake starts: the middle of the first room
+    pub fn start(&self) -> (i32, i32) {
+        let room = &self.rooms[0];
+        ((room.left + room.right) / 2, self.height / 2)
+    }
+
+    pub fn is_wall(&self, cell: (i32, i32)) -> bool {
+        self.walls.contains(&cell.0) && !self.openings.contains(&cell)
+    }
+
+    pub fn is_open(&self, door: usize) -> bool {
+        self.openings.contains(&self.doors[door])
+    }
+
+    pub fn room_at(&self, (x, _): (i32, i32)) -> Option<usize> {
+        self.rooms
+            .iter()
+            .position(|room| (room.left..room.right).contains(&x))
+    }
+
+    pub fn in_active_room(&self, cell: (i32, i32)) -> bool {
+        self.room_at(cell) == Some(self.active)
+    }
+
+    // Count a bite in the active room, opening its door when the quota is met
+    pub fn ate(&mut self) {
+        let room = &mut self.rooms[self.active];
+        room.eaten += 1;
+        if room.eaten == ROOM_QUOTA && self.active < self.doors.len() {
+            self.openings.push(self.doors[self.active]);
+        }
+    }
+
+    // The head is on `cell`; returns true if that moved the snake into another room
+    pub fn enter(&mut self, cell: (i32, i32)) -> bool {
+        match self.room_at(cell) {
+            Some(room) if room != self.active => {
+                self.active = room;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // The board got a column and row bigger; the last room takes the new column
+    pub fn grow(&mut self) {
+        self.rooms.last_mut().unwrap().right += 1;
+        self.height += 1;
+    }
+
+    // Columns the camera shows for the active room, the walls around it included
+    pub fn view_columns(&self) -> (i32, i32) {
+        let room = &self.rooms[self.active];
+        let left = if self.active == 0 { 0 } else { room.left - 1 };
+        let right = if self.active + 1 == self.rooms.len() {
+            room.right
+        } else {
+            room.right + 1
+        };
+        (left, right)
+    }
+}
@@ -0,0 +1,219 @@
+// Optional rule tweaks a frontend can switch on for every run. The defaults are the
+// classic game.
+
+use crate::topology::{Edge, Topology};
+
+const SEGMENTS_PER_FRAME: u64 = 6; // Length that adds or takes a frame between moves
+pub const SLOW_START_FOODS: usize = 10; // Eaten before the slow start is back to full pace
+const SLOW_START_PACE: u64 = 60; // Percent of full speed before the first food
+pub const STOMACH_CAPACITY: usize = 3;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LengthSpeed {
+    Off,
+    Heavy, // A longer snake moves slower, up to half speed
+    Hard,  // A longer snake moves faster, up to double speed
+}
+
+impl LengthSpeed {
+    pub fn parse(value: &str) -> Option<LengthSpeed> {
+        match value {
+            "off" => Some(LengthSpeed::Off),
+            "heavy" => Some(LengthSpeed::Heavy),
+            "hard" => Some(LengthSpeed::Hard),
+            _ => None,
+        }
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            LengthSpeed::Off => "off",
+            LengthSpeed::Heavy => "heavy",
+            LengthSpeed::Hard => "hard",
+        }
+    }
+}
+
+// When steering takes effect
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum InputTiming {
+    Immediate, // As soon as the key is pressed
+    Strict,    // At the next move: of the presses since the last one, the latest valid turn
+}
+
+impl InputTiming {
+    pub fn parse(value: &str) -> Option<InputTiming> {
+        match value {
+            "immediate" => Some(InputTiming::Immediate),
+            "strict" => Some(InputTiming::Strict),
+            _ => None,
+        }
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            InputTiming::Immediate => "immediate",
+            InputTiming::Strict => "strict",
+        }
+    }
+}
+
+// What wins a run, instead of only ever ending it by dying
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Goal {
+    Score(u32),
+    Length(usize),
+}
+
+impl Goal {
+    // "score:200" or "length:50"
+    pub fn parse(value: &str) -> Option<Goal> {
+        let (kind, target) = value.split_once(':')?;
+        match kind.trim() {
+            "score" => target.trim().parse().ok().map(Goal::Score),
+            "length" => target.trim().parse().ok().map(Goal::Length),
+            _ => None,
+        }
+    }
+
+    // In the form `parse` reads
+    pub fn text(&self) -> String {
+        match self {
+            Goal::Score(target) => format!("score:{}", target),
+            Goal::Length(target) => format!("length:{}", target),
+        }
+    }
+
+    pub fn reached(&self, score: u32, length: usize) -> bool {
+        match *self {
+            Goal::Score(target) => score >= target,
+            Goal::Length(target) => length >= target,
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Goal::Score(target) => format!("score {}", target),
+            Goal::Length(target) => format!("length {}", target),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Rules {
+    pub length_speed: LengthSpeed,
+    pub stomach_capacity: usize, // Full stomachs the snake can carry before indigestion
+    pub goal: Option<Goal>,
+    pub input_timing: InputTiming,
+    pub slow_start: bool, // Assist: the first foods of a run come at a gentler pace
+    pub topology: Topology, // What the edges of the board do, unless a level says
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules {
+            length_speed: LengthSpeed::Off,
+            stomach_capacity: STOMACH_CAPACITY,
+            goal: None,
+            input_timing: InputTiming::Immediate,
+            slow_start: false,
+            topology: Topology::default(),
+        }
+    }
+}
+
+impl Rules {
+    // Fingerprint of everything here, signed into every run's score in input logs so
+    // that checking a replay can tell other settings from a forged score. FNV-1a, like
+    // `Game::state_hash`.
+    pub fn fingerprint(&self) -> u64 {
+        let goal = match self.goal {
+            None => [0, 0],
+            Some(Goal::Score(target)) => [1, target as u64],
+            Some(Goal::Length(target)) => [2, target as u64],
+        };
+        let values = [
+            self.length_speed as u64,
+            self.stomach_capacity as u64,
+            goal[0],
+            goal[1],
+            self.input_timing as u64,
+            self.slow_start as u64,
+            self.topology.left as u64,
+            self.topology.right as u64,
+            self.topology.top as u64,
+            self.topology.bottom as u64,
+        ];
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in values.iter().flat_map(|value| value.to_le_bytes()) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        hash
+    }
+
+    // e.g. "length_speed:heavy stomach_capacity:3 goal:score:200 input_timing:strict
+    // slow_start:on left:twist right:twist", for the input log. The edges are the
+    // topology's own words, the ones that wrap left out.
+    pub fn words(&self) -> Vec<String> {
+        let goal = self.goal.map_or("off".to_string(), |goal| goal.text());
+        let mut words = vec![
+            format!("length_speed:{}", self.length_speed.id()),
+            format!("stomach_capacity:{}", self.stomach_capacity),
+            format!("goal:{}", goal),
+            format!("input_timing:{}", self.input_timing.id()),
+            format!("slow_start:{}", if self.slow_start { "on" } else { "off" }),
+        ];
+        words.extend(self.topology.text().split_whitespace().map(str::to_string));
+        words
+    }
+
+    pub fn parse<'a>(words: impl IntoIterator<Item = &'a str>) -> Option<Rules> {
+        let mut rules = Rules::default();
+        for word in words {
+            let (key, value) = word.split_once(':')?;
+            let edge = || Edge::parse(value);
+            match key {
+                "length_speed" => rules.length_speed = LengthSpeed::parse(value)?,
+                "stomach_capacity" => rules.stomach_capacity = value.parse().ok()?,
+                "goal" if value == "off" => rules.goal = None,
+                "goal" => rules.goal = Some(Goal::parse(value)?),
+                "input_timing" => rules.input_timing = InputTiming::parse(value)?,
+                "slow_start" => rules.slow_start = value == "on",
+                "left" => rules.topology.left = edge()?,
+                "right" => rules.topology.right = edge()?,
+                "top" => rules.topology.top = edge()?,
+                "bottom" => rules.topology.bottom = edge()?,
+                _ => return None,
+            }
+        }
+        Some(rules)
+    }
+
+    // Frames between moves for a snake `length` segments long, when it would move
+    // every `base` frames regardless of length
+    pub fn tick_interval(&self, base: u64, length: usize) -> u64 {
+        let change = (length as u64).saturating_sub(1) / SEGMENTS_PER_FRAME;
+        match self.length_speed {
+            LengthSpeed::Off => base,
+            LengthSpeed::Heavy => (base + change).min(base * 2),
+            LengthSpeed::Hard => base.saturating_sub(change).max(base.div_ceil(2)),
+        }
+    }
+
+    // Whether any assist is on, which the high score table notes
+    pub fn assisted(&self) -> bool {
+        self.slow_start
+    }
+
+    // `interval` stretched by the slow start after `eaten` foods: SLOW_START_PACE
+    // percent of full speed at first, rising evenly back to full speed
+    pub fn slow_start_interval(&self, interval: u64, eaten: usize) -> u64 {
+        if !self.slow_start || eaten >= SLOW_START_FOODS {
+            return interval;
+        }
+        let pace =
+            SLOW_START_PACE + (100 - SLOW_START_PACE) * eaten as u64 / SLOW_START_FOODS as u64;
+        (interval * 100).div_ceil(pace)
+    }
+}
@@ -0,0 +1,172 @@
+// Read-only web view of the high score table and the game in progress, for LAN
+// parties and streaming overlays. Started with `--score-server <port>`, it answers on
+// localhost from a thread of its own:
+//
+//     /             HTML page that refreshes itself every couple of seconds
+//     /scores.json  the high score table
+//     /status.json  what the game is doing right now
+
+use crate::Game;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+#[derive(Clone, Default, PartialEq)]
+struct Snapshot {
+    state: &'static str,
+    score: u32,
+    length: usize,
+    scores: Vec<(String, u32, bool)>, // Name, score, perfect run
+}
+
+impl Snapshot {
+    fn of(game: &Game) -> Snapshot {
+        let state = if game.won {
+            "victory"
+        } else if game.game_over {
+            "game over"
+        } else if !game.game_started {
+            "waiting"
+        } else if game.paused {
+            "paused"
+        } else {
+            "playing"
+        };
+        Snapshot {
+            state,
+            score: game.score,
+            length: game.snake.body.len(),
+            scores: game
+                .high_scores
+                .iter()
+                .map(|entry| (entry.name.clone(), entry.score, entry.perfect))
+                .collect(),
+        }
+    }
+
+    fn scores_json(&self) -> String {
+        let entries: Vec<String> = self
+            .scores
+            .iter()
+            .map(|(name, score, perfect)| {
+                format!(
+                    "{{\"name\":\"{}\",\"score\":{},\"perfect\":{}}}",
+                    json_escape(name),
+                    score,
+                    perfect
+                )
+            })
+            .collect();
+        format!("[{}]", entries.join(","))
+    }
+
+    fn status_json(&self) -> String {
+        format!(
+            "{{\"state\":\"{}\",\"score\":{},\"length\":{}}}",
+            self.state, self.score, self.length
+        )
+    }
+
+    fn html(&self) -> String {
+        let rows: String = self
+            .scores
+            .iter()
+            .enumerate()
+            .map(|(i, (name, score, perfect))| {
+                let mark = if *perfect { " (perfect)" } else { "" };
+                format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}{}</td></tr>",
+                    i + 1,
+                    html_escape(name),
+                    score,
+                    mark
+                )
+            })
+            .collect();
+        format!(
+            "<!DOCTYPE html><html><head><meta charset=\"utf-8\">\
+             <meta http-equiv=\"refresh\" content=\"2\"><title>Rusty Snake</title></head>\
+             <body><h1>Rusty Snake</h1><p>{} - score {}, length {}</p>\
+             <h2>High Scores</h2><table>{}</table></body></html>",
+            self.state, self.score, self.length, rows
+        )
+    }
+}
+
+pub struct ScoreServer {
+    shared: Arc<Mutex<Snapshot>>,
+    last: Snapshot, // What was last handed to the server thread
+}
+
+impl ScoreServer {
+    // None (with the reason printed) if the port can't be had
+    pub fn start(port: u16) -> Option<ScoreServer> {
+        let listener = match TcpListener::bind(("127.0.0.1", port)) {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("Error starting the score server on port {}: {}", port, e);
+                return None;
+            }
+        };
+        println!("Score server on http://localhost:{}/", port);
+        let shared = Arc::new(Mutex::new(Snapshot::default()));
+        let snapshots = Arc::clone(&shared);
+        thread::spawn(move || {
+            for stream in listener.incoming().map_while(Result::ok) {
+                let snapshot = snapshots.lock().map(|s| s.clone()).unwrap_or_default();
+                if let Err(e) = respond(stream, &snapshot) {
+                    eprintln!("Score server: {}", e);
+                }
+            }
+        });
+        Some(ScoreServer {
+            shared,
+            last: Snapshot::default(),
+        })
+    }
+
+    // Called every frame; only takes the lock when something changed
+    pub fn publish(&mut self, game: &Game) {
+        let now = Snapshot::of(game);
+        if now == self.last {
+            return;
+        }
+        if let Ok(mut shared) = self.shared.lock() {
+            *shared = now.clone();
+        }
+        self.last = now;
+    }
+}
+
+fn respond(mut stream: TcpStream, snapshot: &Snapshot) -> std::io::Result<()> {
+    let mut request = String::new();
+    BufReader::new(&stream).read_line(&mut request)?;
+    // e.g. "GET /scores.json HTTP/1.1"
+    let path = request.split_whitespace().nth(1).unwrap_or("/");
+    let (status, kind, body) = match path {
+        "/" => ("200 OK", "text/html", snapshot.html()),
+        "/scores.json" => ("200 OK", "application/json", snapshot.scores_json()),
+        "/status.json" => ("200 OK", "application/json", snapshot.status_json()),
+        _ => ("404 Not Found", "text/plain", "not found".to_string()),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}; charset=utf-8\r\nContent-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n{}",
+        status,
+        kind,
+        body.len(),
+        body
+    )
+}
+
+fn json_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,236 @@
+// Score cards: a picture of the board as the run ended with the score, mode and date
+// along the bottom, saved as a PNG to share. Drawn offscreen through the same view
+// code as the window, onto a plain pixel buffer. The run's stamp (seed, mode, mutators
+// and board) is printed under the score and kept in the PNG as a `Comment` text chunk,
+// so whoever gets the card can play the run again.
+//
+// Photo mode saves through the same canvas: just the board, as the camera frames it,
+// at several times the window's size.
+
+use crate::app::App;
+use crate::loading;
+use crate::render::{self, Color, Renderer};
+use crate::seasons;
+use crate::view;
+use rusttype::{point, Font, Scale};
+use std::fs::File;
+use std::io::BufWriter;
+
+const BANNER_HEIGHT: f64 = 50.0;
+const PHOTO_SCALE: f64 = 3.0; // Photo size next to the window's
+
+// Software renderer over an RGBA buffer, one pixel per logical unit
+struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<[f32; 4]>,
+    font: Option<Font<'static>>,
+    clip: Option<[f64; 4]>, // Pixels outside are left alone
+}
+
+impl Canvas {
+    fn new([width, height]: [f64; 2]) -> Canvas {
+        let (width, height) = (width.round() as usize, height.round() as usize);
+        let font = loading::read_font()
+            .ok()
+            .and_then(Font::try_from_vec)
+            .or_else(|| Font::try_from_bytes(loading::BUILT_IN_FONT));
+        Canvas {
+            width,
+            height,
+            pixels: vec![[0.0, 0.0, 0.0, 1.0]; width * height],
+            font,
+            clip: None,
+        }
+    }
+
+    // Blend `color` over one pixel, `coverage` scaling its alpha
+    fn blend(&mut self, x: i64, y: i64, color: Color, coverage: f32) {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return;
+        }
+        if let Some([left, top, width, height]) = self.clip {
+            let (x, y) = (x as f64 + 0.5, y as f64 + 0.5);
+            if x < left || y < top || x >= left + width || y >= top + height {
+                return;
+            }
+        }
+        let alpha = color[3] * coverage.clamp(0.0, 1.0);
+        let pixel = &mut self.pixels[y as usize * self.width + x as usize];
+        for channel in 0..3 {
+            pixel[channel] = color[channel] * alpha + pixel[channel] * (1.0 - alpha);
+        }
+    }
+
+    // Blend `color` over every pixel in the bounding box whose center passes `inside`
+    fn fill(&mut self, color: Color, bounds: [f64; 4], inside: impl Fn(f64, f64) -> bool) {
+        let [left, top, right, bottom] = bounds;
+        for y in top.floor() as i64..bottom.ceil() as i64 {
+            for x in left.floor() as i64..right.ceil() as i64 {
+                if inside(x as f64 + 0.5, y as f64 + 0.5) {
+                    self.blend(x, y, color, 1.0);
+                }
+            }
+        }
+    }
+
+    fn save(&self, path: &str, comment: &str) -> Result<(), String> {
+        let file = File::create(path).map_err(|e| e.to_string())?;
+        let mut encoder =
+            png::Encoder::new(BufWriter::new(file), self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .add_text_chunk("Comment".to_string(), comment.to_string())
+            .map_err(|e| e.to_string())?;
+        let data: Vec<u8> = self
+            .pixels
+            .iter()
+            .flat_map(|pixel| {
+                let [r, g, b, _] = pixel.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+                [r, g, b, 255]
+            })
+            .collect();
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&data))
+            .map_err(|e| e.to_string())
+    }
+}
+
+impl Renderer for Canvas {
+    fn view_size(&self) -> [f64; 2] {
+        [self.width as f64, self.height as f64]
+    }
+
+    fn clear(&mut self, color: Color) {
+        self.pixels.fill(color);
+    }
+
+    fn rectangle(&mut self, color: Color, [x, y, w, h]: [f64; 4]) {
+        self.fill(color, [x, y, x + w, y + h], |_, _| true);
+    }
+
+    fn line(&mut self, color: Color, width: f64, [x1, y1, x2, y2]: [f64; 4]) {
+        let radius = width / 2.0;
+        let bounds = [
+            x1.min(x2) - radius,
+            y1.min(y2) - radius,
+            x1.max(x2) + radius,
+            y1.max(y2) + radius,
+        ];
+        let (dx, dy) = (x2 - x1, y2 - y1);
+        let length = dx * dx + dy * dy;
+        self.fill(color, bounds, |x, y| {
+            // Distance to the closest point of the segment
+            let t = if length == 0.0 {
+                0.0
+            } else {
+                (((x - x1) * dx + (y - y1) * dy) / length).clamp(0.0, 1.0)
+            };
+            (x - x1 - t * dx).hypot(y - y1 - t * dy) <= radius
+        });
+    }
+
+    fn polygon(&mut self, color: Color, points: &[[f64; 2]]) {
+        if points.len() < 3 {
+            return;
+        }
+        let bounds = points.iter().fold(
+            [f64::MAX, f64::MAX, f64::MIN, f64::MIN],
+            |[l, t, r, b], [x, y]| [l.min(*x), t.min(*y), r.max(*x), b.max(*y)],
+        );
+        self.fill(color, bounds, |x, y| {
+            // Inside a convex polygon every edge turns the same way as seen from the point
+            let sides: Vec<f64> = points
+                .iter()
+                .zip(points.iter().cycle().skip(1))
+                .map(|([ax, ay], [bx, by])| (bx - ax) * (y - ay) - (by - ay) * (x - ax))
+                .collect();
+            sides.iter().all(|s| *s >= 0.0) || sides.iter().all(|s| *s <= 0.0)
+        });
+    }
+
+    fn text(&mut self, text: &str, size: u32, color: Color, x: f64, y: f64) {
+        let Some(font) = self.font.take() else {
+            return;
+        };
+        let scale = Scale::uniform(size as f32);
+        for glyph in font.layout(text, scale, point(x as f32, y as f32)) {
+            if let Some(bounds) = glyph.pixel_bounding_box() {
+                glyph.draw(|gx, gy, coverage| {
+                    let px = bounds.min.x as i64 + gx as i64;
+                    let py = bounds.min.y as i64 + gy as i64;
+                    self.blend(px, py, color, coverage);
+                });
+            }
+        }
+        self.font = Some(font);
+    }
+
+    fn text_width(&mut self, text: &str, size: u32) -> f64 {
+        self.font
+            .as_ref()
+            .map_or(0.0, |font| render::text_width(font, text, size))
+    }
+
+    fn clip(&mut self, rect: Option<[f64; 4]>) {
+        self.clip = rect;
+    }
+}
+
+// Draw the card for the run that just ended and save it next to the high scores.
+// Returns the file name.
+pub fn export(app: &App) -> Result<String, String> {
+    let game = &app.game;
+    let [width, height] = app.camera.view();
+    let mut canvas = Canvas::new([width, height]);
+    canvas.clear(app.palette().background);
+    view::draw_board(app, &mut canvas);
+
+    let (year, month, day) = seasons::today();
+    let date = format!("{}-{:02}-{:02}", year, month, day);
+    let banner = [0.0, height - BANNER_HEIGHT, width, BANNER_HEIGHT];
+    canvas.rectangle([0.0, 0.0, 0.0, 0.7], banner);
+    let mut text = format!(
+        "Rusty Snake  Score {}  {}",
+        game.score,
+        app.profile.mode.name()
+    );
+    if game.won {
+        text += "  Victory";
+    }
+    canvas.text(&text, 18, [1.0, 0.8, 0.0, 1.0], 10.0, height - 28.0);
+    let date_width = canvas.text_width(&date, 16);
+    let date_x = width - 10.0 - date_width;
+    canvas.text(&date, 16, [1.0, 1.0, 1.0, 0.8], date_x, height - 28.0);
+    let stamp = app.run_stamp();
+    canvas.text(&stamp, 12, [1.0, 1.0, 1.0, 0.6], 10.0, height - 10.0);
+
+    let path = format!("score_card_{}_{}.png", date, game.score);
+    canvas.save(
+        &path,
+        &format!("Rusty Snake score={} {}", game.score, stamp),
+    )?;
+    Ok(path)
+}
+
+// Save the board as photo mode frames it, without the HUD. Returns the file name.
+pub fn export_photo(app: &mut App) -> Result<String, String> {
+    let view = app.camera.view();
+    let size = view.map(|side| side * PHOTO_SCALE);
+    let mut canvas = Canvas::new(size);
+    canvas.clear(app.palette().background);
+    // The camera frames the same part of the board at the bigger size
+    app.camera.set_view(size);
+    view::draw_board(app, &mut canvas);
+    app.camera.set_view(view);
+
+    let (year, month, day) = seasons::today();
+    let path = format!(
+        "photo_{}-{:02}-{:02}_{}.png",
+        year, month, day, app.game.frame_count
+    );
+    canvas.save(&path, &format!("Rusty Snake photo {}", app.run_stamp()))?;
+    Ok(path)
+}
@@ -0,0 +1,48 @@
+// Endless scrolling: the board is a window onto a track that keeps sliding down a row
+// at a time. Each new row comes in at the top, sometimes with a run of rocks on it,
+// and anything carried past the bottom edge is gone - the snake included.
+
+use rand::rngs::StdRng;
+use rand::Rng;
+
+pub const SCROLL_EVERY: u32 = 4; // Snake moves per row scrolled
+const ROCK_CHANCE: u32 = 3; // One in this many rows brings rocks
+const MAX_ROCK_RUN: i32 = 4; // Longest run of rocks in one row
+
+#[derive(Clone, Debug, Default)]
+pub struct Track {
+    pub rocks: Vec<(i32, i32)>,
+    pub rows: u64, // Rows scrolled so far, the distance travelled
+    moves: u32,    // Snake moves since the last scroll
+}
+
+impl Track {
+    // Count a snake move, true when that's enough for the next row
+    pub fn tick(&mut self) -> bool {
+        self.moves += 1;
+        if self.moves < SCROLL_EVERY {
+            return false;
+        }
+        self.moves = 0;
+        true
+    }
+
+    // Slide the rocks down a row, dropping the ones that fall off the bottom, and
+    // generate the new top row. The run never covers the whole row.
+    pub fn scroll(&mut self, rng: &mut StdRng, (width, height): (i32, i32)) {
+        self.rows += 1;
+        for rock in &mut self.rocks {
+            rock.1 += 1;
+        }
+        self.rocks.retain(|rock| rock.1 < height);
+        if rng.gen_ratio(1, ROCK_CHANCE) {
+            let run = rng.gen_range(1..=MAX_ROCK_RUN.min(width - 1));
+            let start = rng.gen_range(0..=width - run);
+            self.rocks.extend((start..start + run).map(|x| (x, 0)));
+        }
+    }
+
+    pub fn is_rock(&self, cell: (i32, i32)) -> bool {
+        self.rocks.contains(&cell)
+    }
+}
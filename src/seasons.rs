@@ -0,0 +1,113 @@
+// Seasonal content: around some holidays the game swaps in a different palette and
+// puts a special food on the board. Which season is active comes from today's date
+// (UTC), unless `seasonal=off` is set. Seasons are plain data in `SEASONS`, adding
+// one is a matter of adding an entry.
+
+use crate::render::Color;
+use crate::{FoodType, SegmentType};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, Copy)]
+pub struct Palette {
+    pub background: Color,
+    pub head: Color,
+    pub full_stomach: Color,
+    pub empty_stomach: Color,
+    pub tail: Color,
+}
+
+impl Palette {
+    pub fn segment(&self, segment_type: &SegmentType) -> Color {
+        match segment_type {
+            SegmentType::Head => self.head,
+            SegmentType::FullStomach => self.full_stomach,
+            SegmentType::EmptyStomach => self.empty_stomach,
+            SegmentType::Tail => self.tail,
+        }
+    }
+}
+
+pub const DEFAULT_PALETTE: Palette = Palette {
+    background: [0.5, 0.5, 0.5, 1.0],
+    head: [0.0, 0.7, 0.0, 1.0],          // Dark green
+    full_stomach: [0.0, 1.0, 0.0, 1.0],  // Bright green
+    empty_stomach: [0.0, 0.8, 0.0, 1.0], // Medium green
+    tail: [0.0, 0.5, 0.0, 1.0],          // Darker green
+};
+
+pub struct Season {
+    pub name: &'static str, // Also what input logs record
+    from: (u32, u32),       // Month and day, both ends inclusive
+    to: (u32, u32),
+    pub palette: Palette,
+    pub special_food: Option<FoodType>,
+}
+
+pub const SEASONS: [Season; 2] = [
+    Season {
+        name: "halloween",
+        from: (10, 20),
+        to: (10, 31),
+        palette: Palette {
+            background: [0.25, 0.2, 0.3, 1.0], // Dusk purple
+            head: [1.0, 0.5, 0.0, 1.0],
+            full_stomach: [1.0, 0.7, 0.2, 1.0],
+            empty_stomach: [0.9, 0.45, 0.0, 1.0],
+            tail: [0.6, 0.3, 0.0, 1.0],
+        },
+        special_food: Some(FoodType::Pumpkin),
+    },
+    Season {
+        name: "winter",
+        from: (12, 15),
+        to: (12, 31),
+        palette: Palette {
+            background: [0.75, 0.8, 0.85, 1.0], // Snow
+            head: [0.7, 0.0, 0.0, 1.0],
+            full_stomach: [1.0, 0.2, 0.2, 1.0],
+            empty_stomach: [0.85, 0.1, 0.1, 1.0],
+            tail: [0.5, 0.0, 0.0, 1.0],
+        },
+        special_food: None,
+    },
+];
+
+// The season today falls in, if any
+pub fn current() -> Option<&'static Season> {
+    let (_, month, day) = today();
+    let today = (month, day);
+    SEASONS
+        .iter()
+        .find(|season| season.from <= today && today <= season.to)
+}
+
+pub fn by_name(name: &str) -> Option<&'static Season> {
+    SEASONS.iter().find(|season| season.name == name)
+}
+
+pub fn today() -> (i64, u32, u32) {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    date(seconds)
+}
+
+// (year, month, day) in UTC, from the days since 1970 (Howard Hinnant's civil_from_days)
+pub fn date(seconds: u64) -> (i64, u32, u32) {
+    let days = (seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153; // March is 0
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    // The year starts in March here, so January and February belong to the next one
+    let year = era * 400 + year_of_era + i64::from(month <= 2);
+    (year, month as u32, day as u32)
+}
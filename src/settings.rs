@@ -0,0 +1,454 @@
+use crate::audio::AudioBackend;
+use crate::controls::{self, ControlPreset, ControlScheme, KeyBindings};
+use crate::decorations::Theme;
+use crate::display::WindowMode;
+use crate::keys::Key;
+use crate::rules::{Goal, InputTiming, LengthSpeed, Rules, STOMACH_CAPACITY};
+use crate::topology::Topology;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+
+const SETTINGS_FILE: &str = "settings.txt";
+const VOLUME_STEP: f32 = 0.1;
+const UI_SCALE_STEP: f64 = 0.25;
+
+// Settings that can be changed in game on the settings screen. Changes are written
+// back to the settings file, so they stick.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Adjustable {
+    Volume,
+    Mute,
+    Music,
+    Theme,
+    UiScale,
+    ReducedMotion,
+    SlowStart,
+    WindowMode,
+}
+
+pub const ADJUSTABLE: [Adjustable; 8] = [
+    Adjustable::Volume,
+    Adjustable::Mute,
+    Adjustable::Music,
+    Adjustable::Theme,
+    Adjustable::UiScale,
+    Adjustable::ReducedMotion,
+    Adjustable::SlowStart,
+    Adjustable::WindowMode,
+];
+
+impl Adjustable {
+    // Its key in the settings file
+    pub fn key(&self) -> &'static str {
+        match self {
+            Adjustable::Volume => "volume",
+            Adjustable::Mute => "mute",
+            Adjustable::Music => "music",
+            Adjustable::Theme => "theme",
+            Adjustable::UiScale => "ui_scale",
+            Adjustable::ReducedMotion => "reduced_motion",
+            Adjustable::SlowStart => "slow_start",
+            Adjustable::WindowMode => "window_mode",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Adjustable::Volume => "Volume",
+            Adjustable::Mute => "Mute",
+            Adjustable::Music => "Music",
+            Adjustable::Theme => "Theme",
+            Adjustable::UiScale => "Text Size",
+            Adjustable::ReducedMotion => "Reduced Motion",
+            Adjustable::SlowStart => "Slow Start (Assist)",
+            // The window is only created once
+            Adjustable::WindowMode => "Window (next launch)",
+        }
+    }
+}
+
+// User-tweakable options, read from a simple `key=value` file next to the high scores
+pub struct Settings {
+    pub rumble: bool,
+    pub rumble_strength: f32, // 0.0 - 1.0, scales every rumble pulse
+    pub control_scheme: ControlScheme,
+    pub control_preset: ControlPreset,
+    pub key_overrides: Vec<(String, Vec<Key>)>, // `bind_<action>` entries, applied over the preset
+    pub volume: f32,                            // Master volume, 0.0 - 1.0
+    pub mute: bool,                             // Silences everything, keeping the volume
+    pub music: bool,                            // The adaptive soundtrack
+    pub audio_backend: AudioBackend,            // Preferred when several are compiled in
+    pub food_cues: bool,                        // Accessibility: tones that help locate food by ear
+    pub ui_scale: f64,                          // Text size multiplier, 1.0 - 2.0
+    pub screen_reader: bool, // Accessibility: speak state changes via text-to-speech
+    pub reduced_motion: bool, // Accessibility: no flashing, particles or pulsing
+    pub window_mode: WindowMode,
+    pub theme: Theme,
+    pub seasonal: bool,            // Holiday palettes and foods around their dates
+    pub length_speed: LengthSpeed, // Whether snake length changes its speed
+    pub stomach_capacity: usize,   // Full stomachs carried before metal gives indigestion
+    pub goal: Option<Goal>,        // Target that wins the run, None to play until death
+    pub input_timing: InputTiming, // Strict collects turns and applies them on the move
+    pub topology: Topology,        // Edges of the plain board, see `topology`
+    pub slow_start: bool,          // Assist: a gentler pace for the first foods of a run
+    pub check_updates: bool,       // Look for a newer release at startup (`update-check`)
+    pub idle_pause: Option<u64>,   // Seconds without input before a run pauses itself
+    pub focus_pause: bool,         // Pause when the window goes to the background
+    dirty: Vec<Adjustable>,        // Changed in game since the file was last written
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            rumble: true,
+            rumble_strength: 1.0,
+            control_scheme: ControlScheme::Keyboard,
+            control_preset: ControlPreset::Arrows,
+            key_overrides: Vec::new(),
+            volume: 0.8,
+            mute: false,
+            music: true,
+            audio_backend: AudioBackend::Rodio,
+            food_cues: false,
+            ui_scale: 1.0,
+            screen_reader: false,
+            reduced_motion: false,
+            window_mode: WindowMode::Windowed,
+            theme: Theme::Junkyard,
+            seasonal: true,
+            length_speed: LengthSpeed::Off,
+            stomach_capacity: STOMACH_CAPACITY,
+            goal: None,
+            input_timing: InputTiming::Immediate,
+            topology: Topology::default(),
+            slow_start: false,
+            check_updates: false,
+            idle_pause: Some(30),
+            focus_pause: true,
+            dirty: Vec::new(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Settings {
+        let mut settings = Settings::default();
+        // A missing settings file simply means defaults
+        if let Ok(file) = File::open(SETTINGS_FILE) {
+            let reader = BufReader::new(file);
+            for line in reader.lines().map_while(Result::ok) {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                if let Some((key, value)) = line.split_once('=') {
+                    settings.apply(key.trim(), value.trim());
+                }
+            }
+        }
+        settings
+    }
+
+    // Bindings for a preset with the user's own remappings applied on top
+    pub fn key_bindings(&self, preset: ControlPreset) -> KeyBindings {
+        let mut bindings = KeyBindings::from_preset(preset);
+        for (action, keys) in &self.key_overrides {
+            bindings.bind(action, keys.clone());
+        }
+        bindings
+    }
+
+    // Rule tweaks every run is played with
+    pub fn rules(&self) -> Rules {
+        Rules {
+            length_speed: self.length_speed,
+            stomach_capacity: self.stomach_capacity,
+            goal: self.goal,
+            input_timing: self.input_timing,
+            topology: self.topology,
+            slow_start: self.slow_start,
+        }
+    }
+
+    // Play by `rules` from now on, without writing them to the file, as a replay does
+    pub fn set_rules(&mut self, rules: Rules) {
+        self.length_speed = rules.length_speed;
+        self.stomach_capacity = rules.stomach_capacity;
+        self.goal = rules.goal;
+        self.input_timing = rules.input_timing;
+        self.topology = rules.topology;
+        self.slow_start = rules.slow_start;
+    }
+
+    // Whether to draw the purely decorative motion: the flashing start screen, bursts of
+    // particles, fireworks and pulsing or growing segments. Everything that draws one
+    // asks here, and shows a still version instead when it's off.
+    pub fn effects_enabled(&self) -> bool {
+        !self.reduced_motion
+    }
+
+    // Volume sounds are actually played at
+    pub fn output_volume(&self) -> f32 {
+        if self.mute {
+            0.0
+        } else {
+            self.volume
+        }
+    }
+
+    // Step a setting up or down (toggle or cycle it, for the ones without a range)
+    pub fn adjust(&mut self, setting: Adjustable, step: i32) {
+        match setting {
+            Adjustable::Volume => {
+                let volume = self.volume + step as f32 * VOLUME_STEP;
+                // Rounded to the step, so repeated steps don't drift
+                self.volume = ((volume / VOLUME_STEP).round() * VOLUME_STEP).clamp(0.0, 1.0);
+            }
+            Adjustable::Mute => self.mute = !self.mute,
+            Adjustable::ReducedMotion => self.reduced_motion = !self.reduced_motion,
+            Adjustable::SlowStart => self.slow_start = !self.slow_start,
+            Adjustable::Music => self.music = !self.music,
+            Adjustable::Theme => {
+                self.theme = match self.theme {
+                    Theme::Plain => Theme::Junkyard,
+                    Theme::Junkyard => Theme::Plain,
+                };
+            }
+            Adjustable::UiScale => {
+                self.ui_scale = (self.ui_scale + step as f64 * UI_SCALE_STEP).clamp(1.0, 2.0);
+            }
+            Adjustable::WindowMode => {
+                let modes = [
+                    WindowMode::Windowed,
+                    WindowMode::Borderless,
+                    WindowMode::Mini,
+                ];
+                let current = modes
+                    .iter()
+                    .position(|m| *m == self.window_mode)
+                    .unwrap_or(0);
+                let next = (current as i32 + step).rem_euclid(modes.len() as i32);
+                self.window_mode = modes[next as usize];
+            }
+        }
+        if !self.dirty.contains(&setting) {
+            self.dirty.push(setting);
+        }
+    }
+
+    // The setting as shown on the settings screen
+    pub fn describe(&self, setting: Adjustable) -> String {
+        match setting {
+            Adjustable::Volume => format!("{}%", (self.volume * 100.0).round()),
+            Adjustable::Mute => if self.mute { "On" } else { "Off" }.to_string(),
+            Adjustable::Music => if self.music { "On" } else { "Off" }.to_string(),
+            Adjustable::Theme => self.theme.name().to_string(),
+            Adjustable::UiScale => format!("{}%", (self.ui_scale * 100.0).round()),
+            Adjustable::ReducedMotion => if self.reduced_motion { "On" } else { "Off" }.to_string(),
+            Adjustable::SlowStart => if self.slow_start { "On" } else { "Off" }.to_string(),
+            Adjustable::WindowMode => self.window_mode.name().to_string(),
+        }
+    }
+
+    // The setting as written to the file, in a form `apply` reads back
+    fn value(&self, setting: Adjustable) -> String {
+        match setting {
+            Adjustable::Volume => format!("{:.1}", self.volume),
+            Adjustable::Mute => if self.mute { "on" } else { "off" }.to_string(),
+            Adjustable::Music => if self.music { "on" } else { "off" }.to_string(),
+            Adjustable::Theme => self.theme.id().to_string(),
+            Adjustable::UiScale => format!("{}%", (self.ui_scale * 100.0).round()),
+            Adjustable::ReducedMotion => if self.reduced_motion { "on" } else { "off" }.to_string(),
+            Adjustable::SlowStart => if self.slow_start { "on" } else { "off" }.to_string(),
+            Adjustable::WindowMode => self.window_mode.id().to_string(),
+        }
+    }
+
+    // Write the settings changed in game back to the file. Their lines are replaced in
+    // place, so comments, key bindings and everything else in the file stay as they are.
+    pub fn save(&mut self) {
+        if self.dirty.is_empty() {
+            return;
+        }
+        let existing = fs::read_to_string(SETTINGS_FILE).unwrap_or_default();
+        let mut pending = std::mem::take(&mut self.dirty);
+        let mut lines: Vec<String> = existing
+            .lines()
+            .map(|line| {
+                let key = line.split_once('=').map(|(key, _)| key.trim());
+                match pending.iter().position(|s| Some(s.key()) == key) {
+                    Some(i) => {
+                        let setting = pending.remove(i);
+                        format!("{}={}", setting.key(), self.value(setting))
+                    }
+                    None => line.to_string(),
+                }
+            })
+            .collect();
+        for setting in pending {
+            lines.push(format!("{}={}", setting.key(), self.value(setting)));
+        }
+        let contents = lines.join("\n") + "\n";
+        if let Err(e) = fs::write(SETTINGS_FILE, contents) {
+            eprintln!("Error writing settings: {}", e);
+        }
+    }
+
+    fn apply(&mut self, key: &str, value: &str) {
+        match key {
+            "rumble" => {
+                if let Some(v) = parse_bool(value) {
+                    self.rumble = v;
+                }
+            }
+            "rumble_strength" => {
+                if let Ok(v) = value.parse::<f32>() {
+                    self.rumble_strength = v.clamp(0.0, 1.0);
+                }
+            }
+            "controls" => {
+                if let Some(v) = ControlScheme::parse(value) {
+                    self.control_scheme = v;
+                }
+            }
+            "volume" => {
+                if let Ok(v) = value.parse::<f32>() {
+                    self.volume = v.clamp(0.0, 1.0);
+                }
+            }
+            "audio_backend" => {
+                if let Some(v) = AudioBackend::parse(value) {
+                    self.audio_backend = v;
+                }
+            }
+            "mute" => {
+                if let Some(v) = parse_bool(value) {
+                    self.mute = v;
+                }
+            }
+            "music" => {
+                if let Some(v) = parse_bool(value) {
+                    self.music = v;
+                }
+            }
+            "food_cues" => {
+                if let Some(v) = parse_bool(value) {
+                    self.food_cues = v;
+                }
+            }
+            "ui_scale" => {
+                // Accepts "150%" as well as "150"
+                if let Ok(v) = value.trim_end_matches('%').parse::<f64>() {
+                    self.ui_scale = (v / 100.0).clamp(1.0, 2.0);
+                }
+            }
+            "screen_reader" => {
+                if let Some(v) = parse_bool(value) {
+                    self.screen_reader = v;
+                }
+            }
+            "reduced_motion" => {
+                if let Some(v) = parse_bool(value) {
+                    self.reduced_motion = v;
+                }
+            }
+            "slow_start" => {
+                if let Some(v) = parse_bool(value) {
+                    self.slow_start = v;
+                }
+            }
+            "window_mode" => {
+                if let Some(v) = WindowMode::parse(value) {
+                    self.window_mode = v;
+                }
+            }
+            "theme" => {
+                if let Some(v) = Theme::parse(value) {
+                    self.theme = v;
+                }
+            }
+            "check_updates" => {
+                if let Some(v) = parse_bool(value) {
+                    self.check_updates = v;
+                }
+            }
+            "idle_pause" => {
+                // Seconds, or `off`
+                if value == "off" {
+                    self.idle_pause = None;
+                } else if let Ok(v) = value.parse::<u64>() {
+                    self.idle_pause = Some(v.max(1));
+                }
+            }
+            "focus_pause" => {
+                if let Some(v) = parse_bool(value) {
+                    self.focus_pause = v;
+                }
+            }
+            "seasonal" => {
+                if let Some(v) = parse_bool(value) {
+                    self.seasonal = v;
+                }
+            }
+            "length_speed" => {
+                if let Some(v) = LengthSpeed::parse(value) {
+                    self.length_speed = v;
+                }
+            }
+            "stomach_capacity" => {
+                if let Ok(v) = value.parse::<usize>() {
+                    self.stomach_capacity = v.max(1);
+                }
+            }
+            "goal" => {
+                // e.g. `goal = score:200`, `goal = length:50` or `goal = off`
+                if value == "off" {
+                    self.goal = None;
+                } else if let Some(v) = Goal::parse(value) {
+                    self.goal = Some(v);
+                }
+            }
+            "input_timing" => {
+                if let Some(v) = InputTiming::parse(value) {
+                    self.input_timing = v;
+                }
+            }
+            "topology" => {
+                // e.g. `topology = left:twist right:twist top:wall bottom:bounce`
+                if let Some(v) = Topology::parse(value) {
+                    self.topology = v;
+                }
+            }
+            "controls_preset" => {
+                if let Some(v) = ControlPreset::parse(value) {
+                    self.control_preset = v;
+                }
+            }
+            _ if key.starts_with("bind_") => {
+                // e.g. `bind_up = W, Up`
+                let keys: Vec<Key> = value
+                    .split(',')
+                    .filter_map(|name| controls::parse_key(name.trim()))
+                    .collect();
+                let action = &key["bind_".len()..];
+                if keys.is_empty() || !controls::ACTIONS.contains(&action) {
+                    eprintln!(
+                        "Invalid key binding '{}={}' in {}",
+                        key, value, SETTINGS_FILE
+                    );
+                } else {
+                    self.key_overrides.push((action.to_string(), keys));
+                }
+            }
+            _ => eprintln!("Unknown setting '{}' in {}", key, SETTINGS_FILE),
+        }
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "true" | "on" | "yes" | "1" => Some(true),
+        "false" | "off" | "no" | "0" => Some(false),
+        _ => None,
+    }
+}
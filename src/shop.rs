@@ -0,0 +1,119 @@
+// Scrap coins are earned at the end of every honest run and spent here, on skins that
+// haven't been unlocked yet and on consumables for the next run. Consumables are
+// bought one at a time and used up by the run they're armed for.
+
+use crate::profile::Profile;
+use crate::skins::{Skin, SKINS};
+
+const POINTS_PER_COIN: u32 = 2;
+const SHIELD_PRICE: u32 = 20;
+const EXTRA_LIFE_PRICE: u32 = 40;
+pub const START_SHIELD_MOVES: u32 = 40; // How long a starting shield lasts
+
+// Coins a run with this score earns
+pub fn coins_for(score: u32) -> u32 {
+    score / POINTS_PER_COIN
+}
+
+// Consumables armed for a run
+#[derive(Clone, Copy, Default, PartialEq)]
+pub struct Loadout {
+    pub shield: bool,
+    pub extra_life: bool,
+}
+
+impl Loadout {
+    pub fn any(&self) -> bool {
+        self.shield || self.extra_life
+    }
+
+    // Words for the input log, e.g. "shield extra_life"
+    pub fn words(&self) -> Vec<&'static str> {
+        let mut words = Vec::new();
+        if self.shield {
+            words.push("shield");
+        }
+        if self.extra_life {
+            words.push("extra_life");
+        }
+        words
+    }
+
+    pub fn parse<'a>(words: impl IntoIterator<Item = &'a str>) -> Option<Loadout> {
+        let mut loadout = Loadout::default();
+        for word in words {
+            match word {
+                "shield" => loadout.shield = true,
+                "extra_life" => loadout.extra_life = true,
+                _ => return None,
+            }
+        }
+        Some(loadout)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Item {
+    Skin(Skin),
+    Shield,
+    ExtraLife,
+}
+
+impl Item {
+    pub fn name(&self) -> String {
+        match self {
+            Item::Skin(skin) => format!("{} skin", skin.name()),
+            Item::Shield => format!("Starting shield ({} moves)", START_SHIELD_MOVES),
+            Item::ExtraLife => "Extra life".to_string(),
+        }
+    }
+
+    pub fn price(&self) -> u32 {
+        match self {
+            // Buying a skin outright costs a bit more than the score that unlocks it
+            Item::Skin(skin) => skin.milestone() * 3 / 2,
+            Item::Shield => SHIELD_PRICE,
+            Item::ExtraLife => EXTRA_LIFE_PRICE,
+        }
+    }
+
+    // Already has it, so there's nothing to buy
+    pub fn owned(&self, profile: &Profile) -> bool {
+        match self {
+            Item::Skin(skin) => profile.is_unlocked(*skin),
+            Item::Shield => profile.loadout.shield,
+            Item::ExtraLife => profile.loadout.extra_life,
+        }
+    }
+}
+
+// Everything on the shelves: the consumables, then every skin that's still locked
+pub fn items(profile: &Profile) -> Vec<Item> {
+    let mut items = vec![Item::Shield, Item::ExtraLife];
+    items.extend(
+        SKINS
+            .iter()
+            .filter(|skin| !profile.is_unlocked(**skin))
+            .map(|skin| Item::Skin(*skin)),
+    );
+    items
+}
+
+pub fn buy(profile: &mut Profile, item: Item) -> Result<(), &'static str> {
+    if item.owned(profile) {
+        return Err("Already owned");
+    }
+    if profile.coins < item.price() {
+        return Err("Not enough scrap coins");
+    }
+    profile.coins -= item.price();
+    match item {
+        Item::Skin(skin) => {
+            profile.unlocked.push(skin);
+            profile.skin = skin;
+        }
+        Item::Shield => profile.loadout.shield = true,
+        Item::ExtraLife => profile.loadout.extra_life = true,
+    }
+    Ok(())
+}
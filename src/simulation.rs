@@ -0,0 +1,222 @@
+// The game runs on its own thread, so a slow frame on the window's side (a big board,
+// fireworks, a busy GPU) never holds up a move or the input that steers it. The window
+// thread forwards its events over a channel; the simulation thread applies them at the
+// next update, runs updates on its own fixed clock and after each one draws a `Frame`
+// into a one-slot mailbox. The window shows whichever frame is newest, and keeps
+// showing the last one until another arrives. Until the first one does, the window
+// shows the loading screen.
+
+use crate::cli::Options;
+use crate::frame::Frame;
+use crate::frontend::{Frontend, WindowEvent, UPDATES_PER_SECOND};
+use crate::keys::Key;
+use crate::loading::{self, Loading, Status};
+use crate::profiler::{Profiler, Span};
+use crate::render::Renderer;
+use crate::settings::Settings;
+use crate::ui::Ui;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+const MAX_LAG: Duration = Duration::from_millis(250); // Don't try to catch up after a long stall
+
+enum Message {
+    Window(WindowEvent),
+    Resize([f64; 2]),
+}
+
+type Mailbox = Arc<Mutex<Option<Frame>>>;
+
+pub struct Simulation {
+    pub profiler: Profiler,
+    ui: Ui, // For the profiler overlay, at the game's text size
+    prewarmed: bool,
+    loading: Loading,
+    pending: Option<(Settings, Options)>, // Until the font is loaded and the thread starts
+    font: Option<&'static [u8]>,          // For the backend to switch to
+    messages: Option<Sender<Message>>,    // Dropped to stop the thread
+    mailbox: Mailbox,
+    frame: Frame, // Shown until a newer one arrives
+    size: [f64; 2],
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Simulation {
+    pub fn start(settings: Settings, options: Options) -> Simulation {
+        Simulation {
+            profiler: Profiler::new(options.profile),
+            ui: Ui::new(settings.ui_scale),
+            prewarmed: false,
+            loading: Loading::start(),
+            pending: Some((settings, options)),
+            font: None,
+            messages: None,
+            mailbox: Mailbox::default(),
+            frame: Frame::default(),
+            size: [0.0, 0.0],
+            thread: None,
+        }
+    }
+
+    fn spawn(&mut self, font: &'static [u8]) {
+        let Some((settings, options)) = self.pending.take() else {
+            return;
+        };
+        if font != loading::BUILT_IN_FONT {
+            self.font = Some(font);
+            self.prewarmed = false;
+        }
+        let (sender, receiver) = mpsc::channel();
+        // The size came in while loading
+        let _ = sender.send(Message::Resize(self.size));
+        let mailbox = self.mailbox.clone();
+        let thread = thread::Builder::new()
+            .name("simulation".to_string())
+            .spawn(move || run(settings, options, font, receiver, mailbox))
+            .unwrap_or_else(|e| {
+                eprintln!("Error starting the simulation thread: {}", e);
+                std::process::exit(1);
+            });
+        self.messages = Some(sender);
+        self.thread = Some(thread);
+    }
+
+    // A font the backend should draw with from now on, if the loaded one isn't the
+    // built-in one it started with
+    pub fn new_font(&mut self) -> Option<&'static [u8]> {
+        self.font.take()
+    }
+
+    pub fn event(&mut self, event: WindowEvent) {
+        let start = Instant::now();
+        match event {
+            WindowEvent::Key(Key::F12) => self.profiler.visible = !self.profiler.visible,
+            WindowEvent::Key(Key::Return) if self.loading.failed() => {
+                self.spawn(loading::BUILT_IN_FONT)
+            }
+            _ => {}
+        }
+        self.send(Message::Window(event));
+        self.profiler.record(Span::Input, start);
+    }
+
+    // The window's logical size, checked every frame and passed on when it changed
+    pub fn resize(&mut self, size: [f64; 2]) {
+        if size != self.size {
+            self.size = size;
+            self.send(Message::Resize(size));
+        }
+    }
+
+    pub fn render(&mut self, r: &mut dyn Renderer) {
+        if self
+            .thread
+            .as_ref()
+            .is_some_and(|thread| thread.is_finished())
+        {
+            // It panicked, and has said why on the way out
+            std::process::exit(1);
+        }
+        if let Some(font) = self.loading.poll() {
+            self.spawn(font);
+        }
+        let newer = self.mailbox.lock().ok().and_then(|mut slot| slot.take());
+        if let Some(frame) = newer {
+            self.profiler.add(Span::Update, frame.update_time);
+            self.profiler.add(Span::Draw, frame.draw_time);
+            self.frame = frame;
+            self.loading.game = Status::Done;
+        }
+        let start = Instant::now();
+        // Text size changed on the settings screen
+        if self.ui.scale != self.frame.ui_scale && self.frame.ui_scale > 0.0 {
+            self.ui = Ui::new(self.frame.ui_scale);
+            self.prewarmed = false;
+        }
+        if !self.prewarmed {
+            self.ui.prewarm(r);
+            self.prewarmed = true;
+        }
+        if let Status::Done = self.loading.game {
+            self.frame.play(r);
+        } else {
+            self.loading.draw(&self.ui, r);
+        }
+        if self.profiler.visible {
+            self.profiler.draw(&self.ui, r);
+        }
+        self.profiler.record(Span::Draw, start);
+    }
+
+    // New window title, if the last frame brought one
+    #[cfg_attr(not(feature = "piston"), allow(dead_code))] // Macroquad can't set it
+    pub fn title(&mut self) -> Option<String> {
+        self.frame.title.take()
+    }
+
+    fn send(&self, message: Message) {
+        if let Some(messages) = &self.messages {
+            // Only fails if the thread is gone, which `render` deals with
+            let _ = messages.send(message);
+        }
+    }
+}
+
+impl Drop for Simulation {
+    // Let the current update finish, so nothing is cut off halfway through saving
+    fn drop(&mut self) {
+        self.messages = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn run(
+    settings: Settings,
+    options: Options,
+    font: &'static [u8],
+    messages: Receiver<Message>,
+    mailbox: Mailbox,
+) {
+    let mut frontend = Frontend::new(settings, &options, font);
+    let step = Duration::from_secs(1) / UPDATES_PER_SECOND as u32;
+    let mut next = Instant::now();
+    loop {
+        loop {
+            match messages.try_recv() {
+                Ok(Message::Window(event)) => frontend.event(event),
+                Ok(Message::Resize(size)) => frontend.resize(size),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return,
+            }
+        }
+
+        let start = Instant::now();
+        frontend.update();
+        let update_time = start.elapsed();
+        let start = Instant::now();
+        let mut frame = frontend.frame();
+        frame.draw_time = start.elapsed();
+        frame.update_time = update_time;
+        if let Ok(mut slot) = mailbox.lock() {
+            // The window hasn't taken the last one: it's replaced, but its update time
+            // and title still count
+            if let Some(old) = slot.take() {
+                frame.update_time += old.update_time;
+                frame.title = frame.title.or(old.title);
+            }
+            *slot = Some(frame);
+        }
+
+        next += step;
+        let now = Instant::now();
+        if next > now {
+            thread::sleep(next - now);
+        } else if now - next > MAX_LAG {
+            next = now;
+        }
+    }
+}
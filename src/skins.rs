@@ -0,0 +1,122 @@
+// Cosmetic snake skins. Classic follows the palette (so it changes with the seasons),
+// the others bring their own colors, pattern and segment shape. Every skin but
+// Classic is unlocked by reaching a score milestone in a run.
+
+use crate::render::Color;
+use crate::seasons::Palette;
+use crate::SegmentType;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Skin {
+    Classic,
+    Striped,
+    Rust,
+    Chrome,
+    Neon,
+}
+
+pub const SKINS: [Skin; 5] = [
+    Skin::Classic,
+    Skin::Striped,
+    Skin::Rust,
+    Skin::Chrome,
+    Skin::Neon,
+];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Shape {
+    Block,   // Plain square
+    Rounded, // Square with the corners cut off
+    Plated,  // Square with a darker rim
+}
+
+impl Skin {
+    pub fn parse(value: &str) -> Option<Skin> {
+        match value {
+            "classic" => Some(Skin::Classic),
+            "striped" => Some(Skin::Striped),
+            "rust" => Some(Skin::Rust),
+            "chrome" => Some(Skin::Chrome),
+            "neon" => Some(Skin::Neon),
+            _ => None,
+        }
+    }
+
+    // The name `parse` accepts, as stored in the profile
+    pub fn id(&self) -> &'static str {
+        match self {
+            Skin::Classic => "classic",
+            Skin::Striped => "striped",
+            Skin::Rust => "rust",
+            Skin::Chrome => "chrome",
+            Skin::Neon => "neon",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Skin::Classic => "Classic",
+            Skin::Striped => "Striped",
+            Skin::Rust => "Rust Bucket",
+            Skin::Chrome => "Chrome",
+            Skin::Neon => "Neon",
+        }
+    }
+
+    // Score a single run has to reach to unlock the skin
+    pub fn milestone(&self) -> u32 {
+        match self {
+            Skin::Classic => 0,
+            Skin::Striped => 25,
+            Skin::Rust => 50,
+            Skin::Chrome => 100,
+            Skin::Neon => 200,
+        }
+    }
+
+    pub fn shape(&self) -> Shape {
+        match self {
+            Skin::Classic | Skin::Striped => Shape::Block,
+            Skin::Rust | Skin::Neon => Shape::Rounded,
+            Skin::Chrome => Shape::Plated,
+        }
+    }
+
+    // Color of the segment at `index` (0 is the head)
+    pub fn color(&self, index: usize, segment_type: &SegmentType, palette: &Palette) -> Color {
+        let base = palette.segment(segment_type);
+        let stomach_full = *segment_type == SegmentType::FullStomach;
+        match self {
+            Skin::Classic => base,
+            Skin::Striped if index % 2 == 1 && *segment_type != SegmentType::Head => {
+                [1.0, 0.85, 0.1, 1.0]
+            }
+            Skin::Striped => base,
+            Skin::Rust => match segment_type {
+                SegmentType::Head => [0.55, 0.25, 0.1, 1.0],
+                SegmentType::Tail => [0.4, 0.2, 0.1, 1.0],
+                _ if stomach_full => [0.85, 0.45, 0.15, 1.0],
+                _ => [0.7, 0.35, 0.12, 1.0],
+            },
+            Skin::Chrome => match segment_type {
+                SegmentType::Head => [0.95, 0.95, 1.0, 1.0],
+                _ if stomach_full => [0.85, 0.88, 0.95, 1.0],
+                _ => [0.7, 0.72, 0.78, 1.0],
+            },
+            Skin::Neon => {
+                // Hue cycles down the body
+                let hues = [
+                    [1.0, 0.1, 0.8, 1.0],
+                    [0.2, 0.9, 1.0, 1.0],
+                    [0.6, 1.0, 0.1, 1.0],
+                ];
+                let color: Color = hues[index % hues.len()];
+                if stomach_full {
+                    color
+                } else {
+                    [color[0] * 0.8, color[1] * 0.8, color[2] * 0.8, 1.0]
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,161 @@
+// Sound effects for what happens on the board: bites, food turning up and rats being
+// squashed. A bite is pitched by what was eaten (see `food_styles`). Each is panned by
+// where it happens left or right of the head, and played quieter when it happens
+// outside the view (on a board bigger than the window, or in another room), so the
+// sound alone tells roughly where to look. A coming speed-up is announced with a
+// rising run of beeps, a shield or indigestion wearing off with a low blip, a near miss
+// with a quick falling whoosh from the side of what the head just scraped past, and a
+// scrap cluster eaten in time with a bright chime.
+
+use crate::audio::{AudioEngine, Tone};
+use crate::camera::Camera;
+use crate::events::{GameEvent, Subscriber};
+use crate::food_styles::FoodStyles;
+use crate::game::SPEED_WARNING_FRAMES;
+use crate::{FoodType, Game};
+use std::time::Duration;
+
+const EFFECT_VOLUME: f32 = 0.25;
+const OFFSCREEN_GAIN: f32 = 0.4;
+const WARNING_BEEPS: u64 = 4; // Spread over the speed-up warning
+const WARNING_BEEP_FRAMES: u64 = 60;
+const WHOOSH_STEPS: u64 = 6;
+const WHOOSH_STEP_FRAMES: u64 = 3;
+
+enum Effect {
+    Bite(FoodType), // At the eaten food's pitch
+    Spawn,
+    Squash,
+    Expired,
+    Cluster,
+}
+
+impl Effect {
+    fn tone(&self, styles: &FoodStyles) -> (f32, Duration) {
+        match self {
+            Effect::Bite(food) => (styles.get(food).sound, Duration::from_millis(60)),
+            Effect::Spawn => (1320.0, Duration::from_millis(40)),
+            Effect::Squash => (150.0, Duration::from_millis(90)),
+            Effect::Expired => (220.0, Duration::from_millis(120)),
+            Effect::Cluster => (1760.0, Duration::from_millis(150)),
+        }
+    }
+}
+
+pub struct SoundEffects {
+    heard: Vec<(Effect, (i32, i32))>, // From the events of the frame that just ran
+    whoosh: Option<(u64, f32)>,       // Frames since a near miss, and its pan
+}
+
+impl SoundEffects {
+    pub fn new() -> SoundEffects {
+        SoundEffects {
+            heard: Vec::new(),
+            whoosh: None,
+        }
+    }
+
+    // Play the effects of the frame that just ran, after its events
+    pub fn play(
+        &mut self,
+        game: &Game,
+        camera: &Camera,
+        styles: &FoodStyles,
+        audio: &mut dyn AudioEngine,
+    ) {
+        let mut effects = std::mem::take(&mut self.heard);
+        // Nothing on the title screen, where the starting food is laid out
+        if !game.game_started {
+            return;
+        }
+        effects.extend(game.spawned.iter().map(|cell| (Effect::Spawn, *cell)));
+        effects.extend(game.hits.iter().map(|cell| (Effect::Squash, *cell)));
+        if let Some(speed_up) = game.speed_up.filter(|_| !game.paused) {
+            speed_warning(SPEED_WARNING_FRAMES - speed_up.frames_left, audio);
+        }
+        if let Some((frame, pan)) = self.whoosh.as_mut() {
+            whoosh(*frame, *pan, audio);
+            *frame += 1;
+            if *frame == WHOOSH_STEPS * WHOOSH_STEP_FRAMES {
+                self.whoosh = None;
+            }
+        }
+        let board = (game.width, game.height);
+        for (effect, cell) in effects {
+            let (frequency, duration) = effect.tone(styles);
+            let gain = if camera.shows(cell, board) {
+                1.0
+            } else {
+                OFFSCREEN_GAIN
+            };
+            audio.play_tone(Tone {
+                frequency,
+                duration,
+                volume: EFFECT_VOLUME * gain,
+                pan: pan(game, cell),
+            });
+        }
+    }
+}
+
+impl Subscriber for SoundEffects {
+    fn notify(&mut self, game: &Game, event: &GameEvent) {
+        match event {
+            GameEvent::FoodEaten { food, cell } => {
+                self.heard.push((Effect::Bite(food.clone()), *cell));
+            }
+            GameEvent::NearMiss(cell) => self.whoosh = Some((0, pan(game, *cell))),
+            GameEvent::ClusterCleared(cell) => self.heard.push((Effect::Cluster, *cell)),
+            GameEvent::EffectExpired(_) => {
+                self.heard
+                    .push((Effect::Expired, game.snake.body[0].position));
+            }
+            _ => {}
+        }
+    }
+}
+
+// One beep every WARNING_BEEP_FRAMES from when the warning started, each a step higher
+fn speed_warning(frame: u64, audio: &mut dyn AudioEngine) {
+    if !frame.is_multiple_of(WARNING_BEEP_FRAMES) || frame / WARNING_BEEP_FRAMES >= WARNING_BEEPS {
+        return;
+    }
+    let step = (frame / WARNING_BEEP_FRAMES) as f32;
+    audio.play_tone(Tone {
+        frequency: 440.0 * 2f32.powf(step / WARNING_BEEPS as f32),
+        duration: Duration::from_millis(80),
+        volume: EFFECT_VOLUME,
+        pan: 0.0,
+    });
+}
+
+// One short tone every WHOOSH_STEP_FRAMES, each lower and quieter than the one before
+fn whoosh(frame: u64, pan: f32, audio: &mut dyn AudioEngine) {
+    if !frame.is_multiple_of(WHOOSH_STEP_FRAMES) {
+        return;
+    }
+    let step = (frame / WHOOSH_STEP_FRAMES) as f32;
+    audio.play_tone(Tone {
+        frequency: 1200.0 * 0.8f32.powf(step),
+        duration: Duration::from_millis(30),
+        volume: EFFECT_VOLUME * (1.0 - step / WHOOSH_STEPS as f32),
+        pan,
+    });
+}
+
+// Stereo position of `cell`, by how far it lies left or right of the head
+pub fn pan(game: &Game, cell: (i32, i32)) -> f32 {
+    let head = game.snake.body[0].position;
+    let dx = axis_delta(head.0, cell.0, game.width, game.wrap_around);
+    (dx as f32 / (game.width / 2) as f32).clamp(-1.0, 1.0)
+}
+
+// Signed distance along one axis, taking the short way round when the board wraps
+pub fn axis_delta(from: i32, to: i32, size: i32, wrap: bool) -> i32 {
+    let delta = to - from;
+    if wrap && delta.abs() > size / 2 {
+        delta - size * delta.signum()
+    } else {
+        delta
+    }
+}
@@ -0,0 +1,16 @@
+// Rust spit: digesting shiny metal (washing it down with water) leaves the snake a
+// gob of rust it can spit straight ahead. Shots fly two cells a tick and take out
+// the first rat in their path; the crane's armour just soaks them up.
+
+use crate::game::Direction;
+
+pub const MAX_AMMO: u32 = 5;
+pub const SHOT_SPEED: u32 = 2; // Cells per tick
+pub const SHOT_RANGE: u32 = 12; // Cells before a shot falls apart
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Shot {
+    pub position: (i32, i32),
+    pub direction: Direction,
+    pub range_left: u32,
+}
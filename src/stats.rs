@@ -0,0 +1,192 @@
+// Lifetime statistics across runs, for the stats screen: where and how each run
+// ended, and what each run scored and ate. One line per death or finished run in the
+// stats file, a run with the time it ended, the foods eaten in `FOOD_TYPES` order and
+// its near misses:
+//
+//     0,7,wall
+//     run,1760000000,42,12,3,1,0,5
+//
+// Files from before runs were recorded only hold deaths, and runs from before near
+// misses were counted end with the foods.
+
+use crate::events::{GameEvent, Subscriber};
+use crate::game::{DeathCause, DEATH_CAUSES, FOOD_TYPES};
+use crate::Game;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+
+const STATS_FILE: &str = "stats.txt";
+
+// The pages of the stats screen, flipped with left/right
+#[derive(Clone, Copy, PartialEq)]
+pub enum StatsPage {
+    Deaths, // Where runs ended, over the board
+    Scores,
+    Causes,
+    Foods,
+}
+
+pub const STATS_PAGES: [StatsPage; 4] = [
+    StatsPage::Deaths,
+    StatsPage::Scores,
+    StatsPage::Causes,
+    StatsPage::Foods,
+];
+
+impl StatsPage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatsPage::Deaths => "Deaths",
+            StatsPage::Scores => "Score per Run",
+            StatsPage::Causes => "Deaths by Cause",
+            StatsPage::Foods => "Food per Run",
+        }
+    }
+
+    pub fn step(&self, step: i32) -> StatsPage {
+        let index = STATS_PAGES
+            .iter()
+            .position(|page| page == self)
+            .unwrap_or(0);
+        let next = (index as i32 + step).rem_euclid(STATS_PAGES.len() as i32);
+        STATS_PAGES[next as usize]
+    }
+}
+
+pub struct Death {
+    pub cell: (i32, i32), // Where the head was
+    pub cause: DeathCause,
+}
+
+pub struct Run {
+    pub time: u64, // When it ended, seconds since 1970
+    pub score: u32,
+    pub foods: [u32; FOOD_TYPES.len()], // Eaten, in `FOOD_TYPES` order
+    pub near_misses: u32,
+}
+
+impl Run {
+    fn parse(parts: &[&str]) -> Option<Run> {
+        let [time, score, foods @ ..] = parts else {
+            return None;
+        };
+        let mut run = Run {
+            time: time.parse().ok()?,
+            score: score.parse().ok()?,
+            foods: [0; FOOD_TYPES.len()],
+            near_misses: 0,
+        };
+        // Foods added later are missing from older lines
+        for (count, part) in run.foods.iter_mut().zip(foods) {
+            *count = part.parse().ok()?;
+        }
+        if let Some(near_misses) = foods.get(FOOD_TYPES.len()) {
+            run.near_misses = near_misses.parse().ok()?;
+        }
+        Some(run)
+    }
+
+    fn line(&self) -> String {
+        let foods: Vec<String> = self.foods.iter().map(|count| count.to_string()).collect();
+        format!(
+            "run,{},{},{},{}",
+            self.time,
+            self.score,
+            foods.join(","),
+            self.near_misses
+        )
+    }
+}
+
+pub struct Stats {
+    pub deaths: Vec<Death>,
+    pub runs: Vec<Run>, // Oldest first
+}
+
+impl Stats {
+    pub fn load() -> Stats {
+        let mut stats = Stats {
+            deaths: Vec::new(),
+            runs: Vec::new(),
+        };
+        // No file yet just means nobody has played
+        if let Ok(file) = File::open(STATS_FILE) {
+            for line in BufReader::new(file).lines().map_while(Result::ok) {
+                let parts: Vec<&str> = line.trim().split(',').collect();
+                match parts[..] {
+                    ["run", ref run @ ..] => stats.runs.extend(Run::parse(run)),
+                    [x, y, cause] => {
+                        if let (Ok(x), Ok(y), Some(cause)) =
+                            (x.parse(), y.parse(), DeathCause::parse(cause))
+                        {
+                            stats.deaths.push(Death {
+                                cell: (x, y),
+                                cause,
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        stats
+    }
+
+    pub fn record_death(&mut self, cell: (i32, i32), cause: DeathCause) {
+        append(&format!("{},{},{}", cell.0, cell.1, cause.id()));
+        self.deaths.push(Death { cell, cause });
+    }
+
+    pub fn record_run(&mut self, run: Run) {
+        append(&run.line());
+        self.runs.push(run);
+    }
+
+    // Deaths per cell of a `width` x `height` board, row by row. Deaths on cells
+    // outside it (from bigger boards) are left out.
+    pub fn heatmap(&self, (width, height): (i32, i32)) -> Vec<u32> {
+        let mut counts = vec![0; (width * height) as usize];
+        for death in &self.deaths {
+            let (x, y) = death.cell;
+            if (0..width).contains(&x) && (0..height).contains(&y) {
+                counts[(y * width + x) as usize] += 1;
+            }
+        }
+        counts
+    }
+
+    // How many runs each cause ended, most common first, leaving out the ones that never did
+    pub fn causes(&self) -> Vec<(DeathCause, usize)> {
+        let mut causes: Vec<(DeathCause, usize)> = DEATH_CAUSES
+            .iter()
+            .map(|cause| {
+                let count = self.deaths.iter().filter(|d| d.cause == *cause).count();
+                (*cause, count)
+            })
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        causes.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        causes
+    }
+}
+
+// Deaths are counted as they happen, runs when the app wraps one up
+impl Subscriber for Stats {
+    fn notify(&mut self, game: &Game, event: &GameEvent) {
+        if let (GameEvent::Died(cause), true) = (event, game.save_scores) {
+            self.record_death(game.snake.body[0].position, *cause);
+        }
+    }
+}
+
+// Appended straight to the file, there's no need to rewrite what's already there
+fn append(line: &str) {
+    let result = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(STATS_FILE)
+        .and_then(|mut f| writeln!(f, "{}", line));
+    if let Err(e) = result {
+        eprintln!("Error writing stats: {}", e);
+    }
+}
@@ -0,0 +1,101 @@
+// Tips for the game over screen, picked by what ended the run. The built-in ones are
+// English; `tips.txt` replaces them a cause at a time, e.g. with a translation:
+//
+//     wall = Turn early: a key pressed too late only counts on the next move
+//     wall = The snake can't reverse, so plan a U-turn two cells ahead
+//
+// Every line adds a tip for its cause, and a cause with any lines in the file loses its
+// built-in ones. Cause ids are the death stats': wall, self, metal_too_short,
+// metal_no_stomach, boss and scrolled. The tip shown moves on each time the same cause
+// ends a run.
+
+use crate::game::{DeathCause, DEATH_CAUSES};
+use std::fs;
+
+const TIPS_FILE: &str = "tips.txt";
+
+pub struct Tips {
+    tips: Vec<(DeathCause, Vec<String>)>,
+}
+
+impl Tips {
+    pub fn load() -> Tips {
+        let mut tips = Tips {
+            tips: DEATH_CAUSES
+                .iter()
+                .map(|cause| (*cause, built_in(*cause).map(String::from).to_vec()))
+                .collect(),
+        };
+        // Without the file every cause keeps its built-in tips
+        let Ok(contents) = fs::read_to_string(TIPS_FILE) else {
+            return tips;
+        };
+        let mut replaced = Vec::new();
+        let lines = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+        for line in lines {
+            let parsed = line
+                .split_once('=')
+                .and_then(|(id, tip)| Some((DeathCause::parse(id.trim())?, tip.trim())));
+            let Some((cause, tip)) = parsed else {
+                eprintln!("Unknown line in {}: {}", TIPS_FILE, line);
+                continue;
+            };
+            let Some((_, list)) = tips.tips.iter_mut().find(|(c, _)| *c == cause) else {
+                continue;
+            };
+            if !replaced.contains(&cause) {
+                replaced.push(cause);
+                list.clear();
+            }
+            list.push(tip.to_string());
+        }
+        tips
+    }
+
+    // The tip for a run ended by `cause`, which has ended `deaths` runs before it
+    pub fn for_death(&self, cause: DeathCause, deaths: usize) -> Option<&str> {
+        let (_, list) = self.tips.iter().find(|(c, _)| *c == cause)?;
+        if list.is_empty() {
+            return None;
+        }
+        Some(&list[deaths % list.len()])
+    }
+}
+
+fn built_in(cause: DeathCause) -> [&'static str; 3] {
+    match cause {
+        DeathCause::Wall => [
+            "Turn early: a key pressed too late only counts on the next move",
+            "The snake can't reverse, so start a U-turn two cells from the wall",
+            "The Slow Start assist in the settings gives you time to learn the turns",
+        ],
+        DeathCause::SelfCollision => [
+            "Leave yourself a way out: don't coil into a space shorter than you",
+            "Your tail moves on as you do, so following it is always safe",
+            "A long snake is safest running laps along the edge of the board",
+        ],
+        DeathCause::MetalTooShort => [
+            "Metal needs a stomach: eat scrap until the snake is five long",
+            "Early on, steer around metal until you've eaten some scrap",
+            "Metal is worth the wait: a stored piece turns water into five segments",
+        ],
+        DeathCause::MetalNoStomach => [
+            "Water converts a full stomach and grows you by five - keep one metal stored",
+            "Count your hollow segments: metal only fits while one is empty",
+            "With every stomach full, find water before touching more metal",
+        ],
+        DeathCause::Boss => [
+            "Lure the crane over shiny metal: three pieces break it down",
+            "The crane is slower than you, so it can always be outrun",
+            "If the crane's timer runs out it just leaves - keep your distance",
+        ],
+        DeathCause::Scrolled => [
+            "The board keeps scrolling down: stay in the upper half",
+            "Food near the bottom edge is rarely worth the risk",
+            "Watch the top edge for rocks coming in on the next row",
+        ],
+    }
+}
@@ -0,0 +1,128 @@
+// Board topology: what each edge of the board does to a snake going over it. A wrap
+// edge brings it back in at the opposite edge, a wall ends the run, a bounce turns it
+// off the edge the way kids mode does, and a twist wraps it round mirrored along the
+// edge, like a Möbius strip: off the right edge near the top, back in on the left near
+// the bottom. Written edge by edge, in the settings or a level:
+//
+//     left:twist right:twist top:wall bottom:bounce
+//
+// Edges left out wrap, so the classic board is the empty topology. Rooms and the
+// scrolling track keep their own walls whatever it says.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Edge {
+    Wrap,
+    Wall,
+    Bounce,
+    Twist,
+}
+
+pub const EDGE_KINDS: [Edge; 4] = [Edge::Wrap, Edge::Wall, Edge::Bounce, Edge::Twist];
+
+impl Edge {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Edge::Wrap => "wrap",
+            Edge::Wall => "wall",
+            Edge::Bounce => "bounce",
+            Edge::Twist => "twist",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Edge> {
+        EDGE_KINDS.into_iter().find(|edge| edge.id() == value)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Topology {
+    pub left: Edge,
+    pub right: Edge,
+    pub top: Edge,
+    pub bottom: Edge,
+}
+
+impl Default for Topology {
+    fn default() -> Topology {
+        Topology {
+            left: Edge::Wrap,
+            right: Edge::Wrap,
+            top: Edge::Wrap,
+            bottom: Edge::Wrap,
+        }
+    }
+}
+
+impl Topology {
+    // e.g. "left:twist right:twist", see the top of the file
+    pub fn parse(value: &str) -> Option<Topology> {
+        let mut topology = Topology::default();
+        for word in value.split_whitespace() {
+            let (side, edge) = word.split_once(':')?;
+            let edge = Edge::parse(edge)?;
+            match side {
+                "left" => topology.left = edge,
+                "right" => topology.right = edge,
+                "top" => topology.top = edge,
+                "bottom" => topology.bottom = edge,
+                _ => return None,
+            }
+        }
+        Some(topology)
+    }
+
+    // Every edge that doesn't wrap, in the form `parse` reads
+    pub fn text(&self) -> String {
+        let sides = [
+            ("left", self.left),
+            ("right", self.right),
+            ("top", self.top),
+            ("bottom", self.bottom),
+        ];
+        let words: Vec<String> = sides
+            .iter()
+            .filter(|(_, edge)| *edge != Edge::Wrap)
+            .map(|(side, edge)| format!("{}:{}", side, edge.id()))
+            .collect();
+        words.join(" ")
+    }
+
+    // The edge a step onto `(x, y)` goes over, if it leaves a `width` x `height`
+    // board. Off a corner, the side edges count first.
+    pub fn crossed(&self, (x, y): (i32, i32), (width, height): (i32, i32)) -> Option<Edge> {
+        if x < 0 {
+            Some(self.left)
+        } else if x >= width {
+            Some(self.right)
+        } else if y < 0 {
+            Some(self.top)
+        } else if y >= height {
+            Some(self.bottom)
+        } else {
+            None
+        }
+    }
+
+    // Where a step onto `(x, y)` comes back onto the board, the cell itself if it's on
+    // the board already, None off a wall or bounce edge
+    pub fn enter(&self, (x, y): (i32, i32), (width, height): (i32, i32)) -> Option<(i32, i32)> {
+        let (mut x, mut y) = (x, y);
+        if !(0..width).contains(&x) {
+            match if x < 0 { self.left } else { self.right } {
+                Edge::Wrap => {}
+                Edge::Twist => y = height - 1 - y,
+                Edge::Wall | Edge::Bounce => return None,
+            }
+            x = x.rem_euclid(width);
+        }
+        if !(0..height).contains(&y) {
+            match if y < 0 { self.top } else { self.bottom } {
+                Edge::Wrap => {}
+                Edge::Twist => x = width - 1 - x,
+                Edge::Wall | Edge::Bounce => return None,
+            }
+            y = y.rem_euclid(height);
+        }
+        Some((x, y))
+    }
+}
@@ -0,0 +1,130 @@
+// Training data for machine learning: every move of a headless game as a (state,
+// action, reward, next state, done) transition, one JSON object per line.
+//
+// The first line describes the rest:
+//
+//     {"format":"rusty_snake transitions 1","channels":["head",...],"width":30,"height":20}
+//
+// and every line after it is one move:
+//
+//     {"game":0,"state":"0010...","action":1,"reward":0,"next_state":"0100...","done":false}
+//
+// - `state` and `next_state` are the board before and after the move as a string of 0s
+//   and 1s (a list of numbers would make the file several times bigger), channels ×
+//   height × width in row-major order: `state[(c * height + y) * width + x]` is '1' if
+//   channel `c` is present at cell (x, y). The channels are listed in the header.
+// - `action` is the direction the snake moved in: 0 up, 1 right, 2 down, 3 left.
+// - `reward` is the points the move scored, or -1 if it killed the snake.
+// - `game` counts the games in the file from 0.
+// - `done` is true on the last move of a game, whether it died, reached its goal or hit
+//   the move limit.
+
+use crate::game::{Direction, FoodType, Game};
+use crate::headless::{self, Controller, RunStats};
+use std::io::{self, Write};
+
+pub const FORMAT: &str = "rusty_snake transitions 1";
+
+pub const CHANNELS: [&str; 7] = ["head", "body", "scrap", "metal", "water", "boss", "rat"];
+
+// The board as 0s and 1s, laid out as described above
+pub fn state(game: &Game) -> Vec<u8> {
+    let (width, height) = (game.width as usize, game.height as usize);
+    let mut tensor = vec![0; CHANNELS.len() * width * height];
+    let mut mark = |channel: usize, (x, y): (i32, i32)| {
+        if x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < height {
+            tensor[(channel * height + y as usize) * width + x as usize] = 1;
+        }
+    };
+    for (i, segment) in game.snake.body.iter().enumerate() {
+        mark(if i == 0 { 0 } else { 1 }, segment.position);
+    }
+    for food in &game.foods {
+        let channel = match food.food_type {
+            FoodType::RustyScrap | FoodType::Pumpkin => 2,
+            FoodType::ShinyMetal => 3,
+            FoodType::Water => 4,
+        };
+        mark(channel, food.position);
+    }
+    if let Some(boss) = &game.boss {
+        for cell in boss.cells((game.width, game.height)) {
+            mark(5, cell);
+        }
+    }
+    for rat in &game.rats {
+        mark(6, rat.position);
+    }
+    tensor
+}
+
+pub fn action(direction: Direction) -> u8 {
+    match direction {
+        Direction::Up => 0,
+        Direction::Right => 1,
+        Direction::Down => 2,
+        Direction::Left => 3,
+    }
+}
+
+// Points scored since the score was `before`, or -1 if the snake just died
+pub fn reward(before: u32, game: &Game) -> i64 {
+    if game.game_over && !game.won {
+        -1
+    } else {
+        game.score as i64 - before as i64
+    }
+}
+
+pub fn header(game: &Game) -> String {
+    let channels: Vec<String> = CHANNELS.iter().map(|c| format!("\"{}\"", c)).collect();
+    format!(
+        "{{\"format\":\"{}\",\"channels\":[{}],\"width\":{},\"height\":{}}}",
+        FORMAT,
+        channels.join(","),
+        game.width,
+        game.height
+    )
+}
+
+// Play one game with `controller`, numbered `index`,, writing a line per move to `out`
+pub fn record(
+    index: u64,
+    game: Game,
+    controller: &mut dyn Controller,
+    max_moves: u64,
+    out: &mut dyn Write,
+) -> io::Result<RunStats> {
+    let mut before: Option<(Vec<u8>, u32)> = None;
+    let mut moves = 0;
+    let mut result = Ok(());
+    let stats = headless::play_observed(game, controller, max_moves, &mut |game| {
+        let after = state(game);
+        if let Some((state, score)) = before.take() {
+            moves += 1;
+            let reward = reward(score, game);
+            let done = game.game_over || moves == max_moves;
+            let line = format!(
+                "{{\"game\":{},\"state\":\"{}\",\"action\":{},\"reward\":{},\"next_state\":\"{}\",\"done\":{}}}",
+                index,
+                bits(&state),
+                action(game.snake.direction),
+                reward,
+                bits(&after),
+                done
+            );
+            if result.is_ok() {
+                result = writeln!(out, "{}", line);
+            }
+        }
+        before = Some((after, game.score));
+    });
+    result.map(|_| stats)
+}
+
+fn bits(tensor: &[u8]) -> String {
+    tensor
+        .iter()
+        .map(|v| if *v == 0 { '0' } else { '1' })
+        .collect()
+}
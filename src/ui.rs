@@ -0,0 +1,236 @@
+// Text drawing helpers that honour the UI scale setting. Layouts are computed from
+// measured text widths instead of hard-coded pixel offsets, so they re-flow when
+// the text gets bigger. Also the list screens' menu and how keys move through it.
+
+use crate::controls::KeyBindings;
+use crate::keys::Key;
+use crate::render::{Color, Renderer};
+use crate::Direction;
+
+const LINE_SPACING: f64 = 1.4; // Line height as a multiple of the font size
+const FOCUS: Color = [0.0, 1.0, 0.0, 1.0]; // The menu item keys and clicks act on
+const FOCUS_BAR: Color = [0.0, 1.0, 0.0, 0.15]; // Behind it, across the window
+
+// Every unscaled font size the screens use, and the characters worth rasterizing up
+// front so the first score change or game over doesn't hitch
+const FONT_SIZES: [u32; 7] = [12, 14, 16, 20, 24, 28, 32];
+const COMMON_CHARS: &str = "0123456789 ABCDEFGHIJKLMNOPQRSTUVWXYZ\
+                            abcdefghijklmnopqrstuvwxyz:!?.,-/<>()_'\u{2190}\u{2191}\u{2192}\u{2193}";
+
+#[derive(Clone)]
+pub struct Line {
+    pub text: String,
+    pub size: u32, // Unscaled font size
+    pub color: Color,
+}
+
+impl Line {
+    pub fn new(text: impl Into<String>, size: u32, color: Color) -> Line {
+        Line {
+            text: text.into(),
+            size,
+            color,
+        }
+    }
+
+    // Empty line, used to put some air between groups of lines
+    pub fn spacer(size: u32) -> Line {
+        Line::new("", size, [0.0; 4])
+    }
+}
+
+pub struct Ui {
+    pub scale: f64, // 1.0 - 2.0
+}
+
+impl Ui {
+    pub fn new(scale: f64) -> Ui {
+        Ui { scale }
+    }
+
+    // Measuring text makes the backend rasterize its glyphs, call once at startup
+    pub fn prewarm(&self, r: &mut dyn Renderer) {
+        for size in FONT_SIZES {
+            r.text_width(COMMON_CHARS, self.font_size(size));
+        }
+    }
+
+    pub fn font_size(&self, base: u32) -> u32 {
+        (base as f64 * self.scale).round() as u32
+    }
+
+    // Draw text with its baseline starting at (x, y). The offset is scaled as well, so
+    // HUD text anchored to a corner keeps its margin.
+    pub fn draw_text(&self, r: &mut dyn Renderer, line: &Line, x: f64, y: f64) {
+        let size = self.font_size(line.size);
+        r.text(&line.text, size, line.color, x * self.scale, y * self.scale);
+    }
+
+    // Draw text horizontally centered in the window with its baseline at y (unscaled)
+    pub fn draw_centered(&self, r: &mut dyn Renderer, line: &Line, y: f64) {
+        self.draw_centered_at(r, line, self.font_size(line.size), y);
+    }
+
+    // Stack lines vertically, centered in the window. If the column would not fit at the
+    // current scale it is shrunk until it does.
+    pub fn draw_column(&self, r: &mut dyn Renderer, lines: &[Line]) {
+        let window_height = r.view_size()[1];
+        let (scale, boxes) = self.column_layout(window_height, lines);
+        for (line, [top, height]) in lines.iter().zip(boxes) {
+            let size = (line.size as f64 * scale).round() as u32;
+            // Baseline sits roughly three quarters down the line box
+            let baseline = top + height * 0.75;
+            if !line.text.is_empty() {
+                self.draw_centered_at(r, line, size, baseline);
+            }
+        }
+    }
+
+    // Scale `draw_column` ends up using and the top and height of each line's box
+    fn column_layout(&self, window_height: f64, lines: &[Line]) -> (f64, Vec<[f64; 2]>) {
+        let unscaled: f64 = lines.iter().map(|l| l.size as f64 * LINE_SPACING).sum();
+        let scale = self.scale.min(window_height * 0.95 / unscaled);
+        let mut y = (window_height - unscaled * scale) / 2.0;
+        let boxes = lines
+            .iter()
+            .map(|line| {
+                let height = line.size as f64 * LINE_SPACING * scale;
+                y += height;
+                [y - height, height]
+            })
+            .collect();
+        (scale, boxes)
+    }
+
+    fn draw_centered_at(&self, r: &mut dyn Renderer, line: &Line, size: u32, y: f64) {
+        let width = r.text_width(&line.text, size);
+        let x = (r.view_size()[0] - width) / 2.0;
+        r.text(&line.text, size, line.color, x.max(0.0), y);
+    }
+}
+
+// What a key does on a list screen. Every screen reads its keys through this, so the
+// arrows and the steering keys move through all of them alike, Enter (or the pad's
+// confirm button) picks and Backspace (or the pad's back button) goes back.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Nav {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Back,
+}
+
+impl Nav {
+    pub fn from_key(key: Key, bindings: &KeyBindings) -> Option<Nav> {
+        match (key, bindings.direction(key)) {
+            (Key::Up, _) | (_, Some(Direction::Up)) => Some(Nav::Up),
+            (Key::Down, _) | (_, Some(Direction::Down)) => Some(Nav::Down),
+            (Key::Left, _) | (_, Some(Direction::Left)) => Some(Nav::Left),
+            (Key::Right, _) | (_, Some(Direction::Right)) => Some(Nav::Right),
+            (Key::Return, _) => Some(Nav::Confirm),
+            (Key::Backspace, _) => Some(Nav::Back),
+            _ => None,
+        }
+    }
+}
+
+// Focus after `nav` on a list of `count` items. Up and down wrap around at the ends;
+// anything else keeps the focus, pulled back onto the list if the list got shorter.
+pub fn step_focus(focus: usize, count: usize, nav: Nav) -> usize {
+    if count == 0 {
+        return 0;
+    }
+    match nav {
+        Nav::Up => (focus + count - 1) % count,
+        Nav::Down => (focus + 1) % count,
+        _ => focus.min(count - 1),
+    }
+}
+
+// A screen with a list of choices: lines above and below, the items in between and
+// the one that has focus. Laid out like `draw_column`, so the mouse can point at
+// items as well as the keys and d-pad moving the focus.
+pub struct Menu {
+    header: Vec<Line>,
+    items: Vec<String>,
+    item_size: u32,
+    focus: usize,
+    adjustable: bool, // Items change with left/right rather than being picked
+    footer: Vec<Line>,
+}
+
+impl Menu {
+    pub fn new(header: Vec<Line>, item_size: u32, focus: usize) -> Menu {
+        Menu {
+            header,
+            items: Vec::new(),
+            item_size,
+            focus,
+            adjustable: false,
+            footer: Vec::new(),
+        }
+    }
+
+    // Show the focused item as a value that can be changed
+    pub fn adjustable(mut self) -> Menu {
+        self.adjustable = true;
+        self
+    }
+
+    pub fn focus(&self) -> usize {
+        self.focus
+    }
+
+    pub fn is_adjustable(&self) -> bool {
+        self.adjustable
+    }
+
+    pub fn item(&mut self, text: impl Into<String>) {
+        self.items.push(text.into());
+    }
+
+    pub fn footer(&mut self, line: Line) {
+        self.footer.push(line);
+    }
+
+    fn lines(&self) -> Vec<Line> {
+        let items = self.items.iter().enumerate().map(|(i, text)| {
+            if i != self.focus {
+                Line::new(text.as_str(), self.item_size, [1.0, 1.0, 1.0, 1.0])
+            } else if self.adjustable {
+                Line::new(format!("< {} >", text), self.item_size, FOCUS)
+            } else {
+                Line::new(format!("> {} <", text), self.item_size, FOCUS)
+            }
+        });
+        let mut lines: Vec<Line> = self.header.to_vec();
+        lines.extend(items);
+        lines.extend(self.footer.iter().cloned());
+        lines
+    }
+
+    // The focused item gets a bar behind it as well as its markers, so it stands out
+    // at a glance whatever its color
+    pub fn draw(&self, ui: &Ui, r: &mut dyn Renderer) {
+        let lines = self.lines();
+        if self.focus < self.items.len() {
+            let [width, height] = r.view_size();
+            let (_, boxes) = ui.column_layout(height, &lines);
+            let [top, line_height] = boxes[self.header.len() + self.focus];
+            r.rectangle(FOCUS_BAR, [0.0, top, width, line_height]);
+        }
+        ui.draw_column(r, &lines);
+    }
+
+    // Item whose line is at height `y` in a window `window_height` tall. The whole
+    // width of the line counts, so short items are as easy to hit as long ones.
+    pub fn item_at(&self, ui: &Ui, window_height: f64, y: f64) -> Option<usize> {
+        let (_, boxes) = ui.column_layout(window_height, &self.lines());
+        let items = &boxes[self.header.len()..self.header.len() + self.items.len()];
+        items
+            .iter()
+            .position(|[top, height]| y >= *top && y < top + height)
+    }
+}
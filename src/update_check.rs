@@ -0,0 +1,145 @@
+// Opt-in check for a newer release. Built with the `update-check` feature and turned
+// on with `check_updates=on` in the settings file, it asks GitHub for the latest
+// release from a thread of its own at startup, through the system's `curl`. The answer
+// is cached in `update_check.txt` for a day, so the game asks at most once a day. When
+// the release is newer than this build, the title screen shows a one-line banner.
+// Offline, or without curl, nothing shows and nothing waits.
+
+use std::fs;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const CACHE_FILE: &str = "update_check.txt";
+const CACHE_SECONDS: u64 = 24 * 60 * 60;
+#[cfg_attr(not(feature = "update-check"), allow(dead_code))]
+const LATEST_RELEASE: &str = "https://api.github.com/repos/simonalsn/rusty_snake/releases/latest";
+const SUMMARY_LENGTH: usize = 80; // Characters of the changelog shown in the banner
+
+#[derive(Clone)]
+pub struct Release {
+    pub version: String, // As tagged, e.g. "v0.2.0"
+    pub summary: String, // First line of the release notes
+}
+
+pub struct UpdateCheck {
+    found: Arc<Mutex<Option<Release>>>,
+}
+
+impl UpdateCheck {
+    pub fn start(enabled: bool) -> UpdateCheck {
+        let found = Arc::new(Mutex::new(None));
+        if enabled && cfg!(feature = "update-check") {
+            let slot = found.clone();
+            thread::spawn(move || {
+                let newer = latest().filter(|release| is_newer(&release.version));
+                if let Ok(mut slot) = slot.lock() {
+                    *slot = newer;
+                }
+            });
+        }
+        UpdateCheck { found }
+    }
+
+    // A newer release, once the check has found one
+    pub fn available(&self) -> Option<Release> {
+        self.found.lock().ok()?.clone()
+    }
+}
+
+// The latest release, from the cache while it's fresh
+fn latest() -> Option<Release> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+    let cached = fs::read_to_string(CACHE_FILE).unwrap_or_default();
+    let mut lines = cached.lines();
+    let checked: Option<u64> = lines.next().and_then(|line| line.parse().ok());
+    if checked.is_some_and(|checked| now.saturating_sub(checked) < CACHE_SECONDS) {
+        let version = lines.next()?.to_string();
+        let summary = lines.next().unwrap_or_default().to_string();
+        return Some(Release { version, summary });
+    }
+    let json = fetch()?;
+    let release = Release {
+        version: json_string(&json, "tag_name")?,
+        summary: summary(&json_string(&json, "body").unwrap_or_default()),
+    };
+    let contents = format!("{}\n{}\n{}\n", now, release.version, release.summary);
+    if let Err(e) = fs::write(CACHE_FILE, contents) {
+        eprintln!("Error writing {}: {}", CACHE_FILE, e);
+    }
+    Some(release)
+}
+
+#[cfg(feature = "update-check")]
+fn fetch() -> Option<String> {
+    let output = std::process::Command::new("curl")
+        .args(["--silent", "--fail", "--location", "--max-time", "10"])
+        .args(["--header", "Accept: application/vnd.github+json"])
+        .arg(LATEST_RELEASE)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+#[cfg(not(feature = "update-check"))]
+fn fetch() -> Option<String> {
+    None
+}
+
+// Whether a tag like "v0.2.0" is a later version than this build
+fn is_newer(tag: &str) -> bool {
+    let parse = |version: &str| -> Vec<u64> {
+        version
+            .trim_start_matches('v')
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect()
+    };
+    parse(tag) > parse(env!("CARGO_PKG_VERSION"))
+}
+
+// First line of the release notes with something in it, without Markdown heading or
+// list marks
+fn summary(notes: &str) -> String {
+    let line = notes
+        .lines()
+        .map(|line| line.trim_start_matches(['#', '-', '*', ' ']).trim())
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    if line.chars().count() > SUMMARY_LENGTH {
+        line.chars().take(SUMMARY_LENGTH - 1).collect::<String>() + "…"
+    } else {
+        line.to_string()
+    }
+}
+
+// The string value of `key` in a JSON object, found by scanning rather than parsing the
+// whole document, which is all the release check needs
+fn json_string(json: &str, key: &str) -> Option<String> {
+    let start = json.find(&format!("\"{}\"", key))? + key.len() + 2;
+    let rest = json[start..].trim_start().strip_prefix(':')?.trim_start();
+    let mut chars = rest.strip_prefix('"')?.chars();
+    let mut value = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return Some(value),
+            '\\' => match chars.next()? {
+                'n' => value.push('\n'),
+                't' => value.push('\t'),
+                'r' => {}
+                'u' => {
+                    let code: String = chars.by_ref().take(4).collect();
+                    let code = u32::from_str_radix(&code, 16).ok()?;
+                    // Surrogate pairs (emoji) come out as the replacement character
+                    value.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                }
+                other => value.push(other),
+            },
+            c => value.push(c),
+        }
+    }
+    None
+}
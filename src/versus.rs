@@ -0,0 +1,174 @@
+// Local versus: two players race on boards of their own, grown from the same seed so
+// they start out alike, side by side in one window. Player one steers with WASD and
+// player two with the arrow keys, whatever the bindings; the match is over once both
+// snakes are. Each half of the window follows its own snake, under a strip with both
+// scores.
+//
+// A duel is versus against a chess clock. The snakes take turns, a move each, and only
+// the one whose turn it is runs: its player's time bank drains until it has moved,
+// which happens when the move comes due or at once if that player presses a key. A
+// player whose time runs out is out. The clock stops once either snake is.
+
+use crate::camera::Camera;
+use crate::controls::{ControlPreset, KeyBindings};
+use crate::frontend::UPDATES_PER_SECOND;
+use crate::keys::Key;
+use crate::{Direction, Game, BLOCK_SIZE};
+
+pub const HUD_HEIGHT: f64 = 30.0; // The strip with the scores, above both boards
+pub const PLAYERS: [&str; 2] = ["Player One", "Player Two"];
+pub const KEYS: [&str; 2] = ["WASD", "Arrows"];
+pub const BANK_FRAMES: u64 = 90 * UPDATES_PER_SECOND; // Each player's time in a duel
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Clock {
+    pub banks: [u64; 2],        // Frames left, each player's
+    pub turn: usize,            // The player whose snake moves next
+    pub flagged: Option<usize>, // The player whose time ran out
+}
+
+impl Clock {
+    pub fn new() -> Clock {
+        Clock {
+            banks: [BANK_FRAMES; 2],
+            turn: 0,
+            flagged: None,
+        }
+    }
+}
+
+pub struct Versus {
+    pub rival: Game,          // Player two's run, player one's is the app's
+    pub clock: Option<Clock>, // In a duel
+    keys: [KeyBindings; 2],
+}
+
+impl Versus {
+    // Player two's run, set up like `game`, player one's, which has just started on a
+    // board of `board` before mutators
+    pub fn new(game: &Game, seed: u64, board: (i32, i32)) -> Versus {
+        let mut rival = Game::headless(seed, board);
+        rival.rules = game.rules;
+        rival.special_food = game.special_food.clone();
+        rival.set_mutators(game.mutators, board);
+        if let Some(layout) = &game.layout {
+            rival.set_layout(layout.clone());
+        }
+        rival.set_topology(game.topology.unwrap_or_default());
+        rival.steer(game.snake.direction);
+        Versus {
+            rival,
+            clock: None,
+            keys: [
+                KeyBindings::from_preset(ControlPreset::Wasd),
+                KeyBindings::from_preset(ControlPreset::Arrows),
+            ],
+        }
+    }
+
+    // The player a key steers, 0 or 1, and where to
+    pub fn steering(&self, key: Key) -> Option<(usize, Direction)> {
+        self.keys
+            .iter()
+            .enumerate()
+            .find_map(|(player, keys)| Some((player, keys.direction(key)?)))
+    }
+
+    // Either snake still going, with `game` player one's run
+    pub fn running(&self, game: &Game) -> bool {
+        !game.game_over || !self.rival.game_over
+    }
+
+    // Which of the two runs get this frame: both, except in a duel with both snakes
+    // going, when only the one whose turn it is does
+    pub fn turns(&self, game: &Game) -> [bool; 2] {
+        match self.clock {
+            Some(clock) if !game.game_over && !self.rival.game_over => {
+                [clock.turn == 0, clock.turn == 1]
+            }
+            _ => [true, true],
+        }
+    }
+
+    // Player two's part of the frame, after player one's: `turns` as `turns` gave them
+    // before it, and `moved` whether player one's snake moved
+    pub fn update(&mut self, game: &mut Game, turns: [bool; 2], moved: bool) {
+        // Pausing stops both snakes
+        self.rival.paused = game.paused;
+        let rival_moved = turns[1] && self.rival.tick_due();
+        if turns[1] {
+            self.rival.update();
+        }
+        let Some(clock) = self.clock.as_mut() else {
+            return;
+        };
+        let mut games = [game, &mut self.rival];
+        let turn = clock.turn;
+        let playing = &mut games[turn];
+        if playing.game_over || !playing.game_started || playing.paused || !turns[turn] {
+            return;
+        }
+        if [moved, rival_moved][turn] {
+            clock.turn = 1 - turn;
+            return;
+        }
+        clock.banks[turn] -= 1;
+        if clock.banks[turn] == 0 {
+            playing.game_over = true;
+            clock.flagged = Some(turn);
+        }
+    }
+
+    // A key of `player`'s was pressed. On their turn in a duel it moves their snake.
+    pub fn hurry(&mut self, game: &mut Game, player: usize) {
+        if self.clock.is_some_and(|clock| clock.turn == player) {
+            [game, &mut self.rival][player].hurry();
+        }
+    }
+
+    // How the match ended, for the game over screen
+    pub fn result(&self, game: &Game) -> String {
+        if let Some(flagged) = self.clock.and_then(|clock| clock.flagged) {
+            return format!(
+                "{} ran out of time, {} wins",
+                PLAYERS[flagged],
+                PLAYERS[1 - flagged]
+            );
+        }
+        let scores = [game.score, self.rival.score];
+        if scores[0] == scores[1] {
+            return format!("A draw at {}", scores[0]);
+        }
+        let winner = usize::from(scores[1] > scores[0]);
+        format!(
+            "{} wins, {} to {}",
+            PLAYERS[winner],
+            scores[winner],
+            scores[1 - winner]
+        )
+    }
+}
+
+// Each player's part of a window `size` big, below the scores, in window units
+pub fn viewports([width, height]: [f64; 2], scale: f64) -> [[f64; 4]; 2] {
+    let top = HUD_HEIGHT * scale;
+    let half = width / 2.0;
+    [
+        [0.0, top, half, height - top],
+        [half, top, half, height - top],
+    ]
+}
+
+// Camera on one player's board in `viewport`. It frames the whole height of the board
+// and as many columns as fit, centered on the head as far as the board allows.
+pub fn camera(game: &Game, viewport: [f64; 4]) -> Camera {
+    let mut camera = Camera::new((game.width, game.height));
+    camera.set_viewport(viewport);
+    let board_width = game.width as f64 * BLOCK_SIZE;
+    let height = game.height as f64 * BLOCK_SIZE;
+    let width = (height * viewport[2] / viewport[3]).min(board_width);
+    let head = (game.snake.body[0].position.0 as f64 + 0.5) * BLOCK_SIZE;
+    let left = (head - width / 2.0).clamp(0.0, board_width - width);
+    camera.set_focus(Some([left, 0.0, width, height]));
+    camera
+}
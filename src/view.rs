@@ -0,0 +1,1948 @@
+// Every screen of the game, drawn through the backend-neutral `Renderer`
+
+use crate::ai::Plan;
+use crate::animation;
+use crate::app::{App, AutoPause, LevelField, LEVEL_CHOICES};
+use crate::boss::{Boss, BOSS_METAL};
+use crate::camera::Camera;
+use crate::charts;
+use crate::clusters::Cluster;
+use crate::colors::{self, ColorPicker};
+use crate::console::Console;
+use crate::controls::{ControlScheme, PRESETS};
+use crate::editor::LevelEditor;
+use crate::food_styles;
+use crate::frontend::UPDATES_PER_SECOND;
+use crate::game::{FoodType, FOOD_TYPES};
+use crate::grid::GridKind;
+use crate::levels::{Layout, Medal, Tile, MEDALS};
+use crate::mutators::MUTATORS;
+use crate::names::{self, MAX_NAME_LENGTH};
+use crate::pins::{PinPurpose, PIN_LENGTH};
+use crate::puzzle::MAX_STARS;
+use crate::render::{Color, Renderer};
+use crate::roguelite::{GameMode, UPGRADES};
+use crate::rooms::{Level, ROOM_QUOTA};
+use crate::rules::{LengthSpeed, SLOW_START_FOODS};
+use crate::seasons;
+use crate::settings::ADJUSTABLE;
+use crate::shop;
+use crate::skins::Shape;
+use crate::stats::StatsPage;
+use crate::topology::{Edge, Topology};
+use crate::ui::{Line, Menu, Ui};
+use crate::versus::{self, Clock, Versus, HUD_HEIGHT, KEYS, PLAYERS};
+use crate::{Direction, Game, SegmentType, BLOCK_SIZE};
+
+const CHART_RUNS: usize = 100; // Most recent runs on the score chart
+const FOOD_CHART_RUNS: usize = 30;
+const SPEED_FLASH_FRAMES: u64 = 15; // On and off this often while a speed-up is coming
+const WALL: Color = [0.45, 0.45, 0.42, 1.0];
+const MUD: Color = [0.4, 0.28, 0.15, 1.0];
+const MUD_CLOD: Color = [0.28, 0.19, 0.1, 1.0];
+const GATE_ARROW: Color = [1.0, 0.6, 0.2, 0.9];
+const LANE: Color = [0.3, 0.8, 0.9, 0.18];
+const LANE_ARROW: Color = [0.4, 0.9, 1.0, 0.8];
+
+pub fn draw(app: &App, ui: &Ui, r: &mut dyn Renderer) {
+    let game = &app.game;
+    let bindings = &app.bindings;
+    let [view_width, view_height] = r.view_size();
+    r.clear(app.palette().background);
+
+    if let Some(zen) = &app.zen {
+        zen.draw(r);
+    } else if app.path_view {
+        draw_path(app, ui, r);
+    } else if app.stats_screen {
+        draw_stats(app, ui, r);
+    } else if let Some(editor) = &app.editor {
+        draw_editor(app, ui, r, editor);
+    } else if app.photo_mode {
+        // Nothing over the board but a faint reminder, which photos leave out
+        draw_board(app, r);
+        let text = if app.menu_message.is_empty() {
+            "Arrows Pan, +/- Zoom, Enter Save Photo, C Leave"
+        } else {
+            app.menu_message.as_str()
+        };
+        let line = Line::new(text, 14, [1.0, 1.0, 1.0, 0.5]);
+        ui.draw_text(r, &line, 10.0, view_height / ui.scale - 10.0);
+    } else if let Some(menu) = menu(app) {
+        menu.draw(ui, r);
+    } else if game.game_over && !app.versus_running() {
+        if game.won && app.settings.effects_enabled() {
+            app.fireworks.draw(r);
+        }
+        if let Some(entry) = &app.pin_entry {
+            let prompt = match entry.purpose {
+                PinPurpose::Claim => {
+                    format!("Choose a {}-digit PIN for {}", PIN_LENGTH, entry.name)
+                }
+                PinPurpose::Save => format!("{} is protected, enter its PIN", entry.name),
+                PinPurpose::Clear => format!("Enter the PIN of {} to clear its scores", entry.name),
+            };
+            // Typed digits stay hidden
+            let typed = "*".repeat(entry.digits.len());
+            let blanks = "_".repeat(PIN_LENGTH - entry.digits.len());
+            let lines = [
+                Line::new(prompt, 24, [1.0, 1.0, 1.0, 1.0]),
+                Line::new(typed + &blanks, 32, [0.0, 1.0, 0.0, 1.0]),
+                Line::new(
+                    "Enter to Confirm, Backspace to Go Back",
+                    16,
+                    [1.0, 1.0, 1.0, 0.8],
+                ),
+            ];
+            ui.draw_column(r, &lines);
+        } else if let Some(name) = &app.clearing {
+            let lines = [
+                Line::new("Clear the High Scores of:", 24, [1.0, 1.0, 1.0, 1.0]),
+                Line::new(name.as_str(), 32, [1.0, 0.3, 0.3, 1.0]),
+                Line::new(
+                    "Enter to Clear, Backspace to Cancel",
+                    16,
+                    [1.0, 1.0, 1.0, 0.8],
+                ),
+            ];
+            ui.draw_column(r, &lines);
+        } else if game.entering_name {
+            // Display 'Enter Your Name' and the name being entered
+            let lines = [
+                Line::new("New High Score! Enter Your Name:", 24, [1.0, 1.0, 1.0, 1.0]),
+                Line::new(game.player_name.as_str(), 32, [0.0, 1.0, 0.0, 1.0]),
+                Line::new(
+                    "Enter to Save, Tab to Protect a New Name with a PIN",
+                    16,
+                    [1.0, 1.0, 1.0, 0.8],
+                ),
+                Line::new(app.menu_message.as_str(), 16, [1.0, 0.3, 0.3, 1.0]),
+            ];
+            ui.draw_column(r, &lines);
+        } else {
+            // Display 'Game Over', the final score and the high scores
+            let title = if game.won {
+                Line::new("Victory!", 32, [1.0, 0.8, 0.0, 1.0])
+            } else {
+                Line::new("Game Over", 32, [1.0, 0.0, 0.0, 1.0])
+            };
+            let mut lines = vec![
+                title,
+                Line::new(
+                    format!("Final Score: {}", game.score),
+                    24,
+                    [1.0, 1.0, 1.0, 1.0],
+                ),
+                Line::new("High Scores This Month", 28, [1.0, 0.8, 0.0, 1.0]),
+            ];
+            if let Some(versus) = &app.versus {
+                lines[1] = Line::new(versus.result(game), 24, [1.0, 1.0, 1.0, 1.0]);
+            }
+            if let Some(cause) = game.death_cause.filter(|_| !game.won) {
+                // Moves on to the next tip every time the same cause ends a run
+                let deaths = app.stats.deaths.iter().filter(|d| d.cause == cause).count();
+                if let Some(tip) = app.tips.for_death(cause, deaths) {
+                    let text = format!("Tip: {}", tip);
+                    lines.insert(2, Line::new(text, 16, [0.8, 0.9, 1.0, 1.0]));
+                }
+            }
+            if game.cheats.any() {
+                lines.insert(
+                    2,
+                    Line::new("Cheats used - score not recorded", 16, [1.0, 0.3, 0.3, 1.0]),
+                );
+            }
+            if app.points_earned > 0 {
+                let text = format!("+{} upgrade points", app.points_earned);
+                lines.insert(2, Line::new(text, 20, [0.4, 0.8, 1.0, 1.0]));
+            }
+            if game.puzzle.is_some() {
+                let text = match game.stars() {
+                    Some(stars) => {
+                        let best = if app.stars_earned.is_some() {
+                            ", a new best"
+                        } else {
+                            ""
+                        };
+                        format!(
+                            "Solved in {} moves: {}/{} stars{}",
+                            game.moves_used, stars, MAX_STARS, best
+                        )
+                    }
+                    None => "Backspace takes the last move back".to_string(),
+                };
+                lines.insert(2, Line::new(text, 20, [1.0, 0.8, 0.0, 1.0]));
+            }
+            if let Some(medal) = app.medal_earned {
+                let text = format!("{} medal!", medal.name());
+                lines.insert(2, Line::new(text, 20, medal_color(medal)));
+            }
+            if app.coins_earned > 0 {
+                let text = format!("+{} scrap coins", app.coins_earned);
+                lines.insert(2, Line::new(text, 20, [0.9, 0.6, 0.3, 1.0]));
+            }
+            for skin in app.unlocks.iter().rev() {
+                let text = format!("Unlocked skin: {}", skin.name());
+                lines.insert(2, Line::new(text, 20, [1.0, 0.8, 0.0, 1.0]));
+            }
+            for (i, entry) in game.high_scores.iter().enumerate() {
+                // Imported tables can hold names longer than can be typed here
+                let name = names::shorten(&entry.name, MAX_NAME_LENGTH);
+                let mut text = format!("{}: {} - {}", i + 1, name, entry.score);
+                let mutators: Vec<&str> =
+                    entry.mutators.active().iter().map(|m| m.name()).collect();
+                if !mutators.is_empty() {
+                    text += &format!(" ({})", mutators.join(", "));
+                }
+                if entry.assisted {
+                    text += " - assisted";
+                }
+                if entry.perfect {
+                    text += " - perfect run";
+                }
+                lines.push(Line::new(text, 24, [1.0, 1.0, 1.0, 1.0]));
+            }
+            lines.push(Line::spacer(20));
+            lines.push(Line::new(
+                "Press Enter or Tap to Restart, V to View Your Path",
+                20,
+                [1.0, 1.0, 1.0, 1.0],
+            ));
+            lines.push(Line::new(
+                "E to Export a Score Card, D to Delete a Name's Scores, R to Restore a Backup",
+                16,
+                [1.0, 1.0, 1.0, 0.8],
+            ));
+            if !app.menu_message.is_empty() {
+                let line = Line::new(app.menu_message.as_str(), 16, [1.0, 0.8, 0.0, 1.0]);
+                lines.push(line);
+            }
+            ui.draw_column(r, &lines);
+            let stamp = Line::new(app.run_stamp(), 12, [1.0, 1.0, 1.0, 0.5]);
+            ui.draw_text(r, &stamp, 10.0, view_height / ui.scale - 10.0);
+        }
+    } else if !game.game_started {
+        let flash = if app.settings.effects_enabled() {
+            (game.frame_count as f64 / 30.0).sin() * 0.5 + 0.5
+        } else {
+            0.5
+        };
+        r.rectangle(
+            [0.0, 0.0, 1.0, flash as f32],
+            [0.0, 0.0, view_width, view_height],
+        );
+
+        // Display the start prompt for the active key bindings
+        let prompt = match app.settings.control_scheme {
+            ControlScheme::Keyboard => format!("Press {} to Start", bindings.steer_keys()),
+            ControlScheme::Mouse => {
+                format!("Click or Press {} to Start", bindings.steer_keys())
+            }
+        };
+        let middle = view_height / 2.0;
+        let alpha = if app.settings.effects_enabled() {
+            flash as f32
+        } else {
+            1.0
+        };
+        let line = Line::new(prompt, 24, [1.0, 1.0, 1.0, alpha]);
+        ui.draw_centered(r, &line, middle);
+        if let Some(release) = app.update_check.available() {
+            let text = format!("{} available", release.version);
+            let line = Line::new(text, 16, [1.0, 0.8, 0.2, 1.0]);
+            ui.draw_centered(r, &line, 30.0 * ui.scale);
+            if !release.summary.is_empty() {
+                let line = Line::new(release.summary, 14, [1.0, 1.0, 1.0, 0.8]);
+                ui.draw_centered(r, &line, 50.0 * ui.scale);
+            }
+        }
+        // Two short lines rather than one, which would run off the sides at larger scales
+        let text = format!(
+            "C for Controls, G for Settings, O for Color, B for Shop ({} coins)",
+            app.profile.coins
+        );
+        let line = Line::new(text, 16, [1.0, 1.0, 1.0, 0.8]);
+        ui.draw_centered(r, &line, middle + 40.0 * ui.scale);
+        let line = Line::new(
+            "H for Deaths, K for Hall of Fame, Z for Zen",
+            16,
+            [1.0, 1.0, 1.0, 0.8],
+        );
+        ui.draw_centered(r, &line, middle + 60.0 * ui.scale);
+        let skin = format!("Skin: {} (Tab to change)", app.profile.skin.name());
+        let line = Line::new(skin, 16, [1.0, 1.0, 1.0, 0.8]);
+        ui.draw_centered(r, &line, middle + 85.0 * ui.scale);
+        let mode = match app.profile.mode {
+            GameMode::Classic => "Mode: Classic (M to change)".to_string(),
+            GameMode::Roguelite => format!(
+                "Mode: Roguelite (M to change), U for Upgrades ({} points)",
+                app.profile.upgrade_points
+            ),
+            GameMode::Diagonal => {
+                "Mode: Diagonal, chord two keys or use the numpad (M to change)".to_string()
+            }
+            GameMode::Hex => "Mode: Hex grid, turn with left/right (M to change)".to_string(),
+            GameMode::Kids => "Mode: Kids, slow and forgiving (M to change)".to_string(),
+            GameMode::Adaptive => format!(
+                "Mode: Adaptive, follows how you're doing (M to change), now at {:+}",
+                app.profile.adaptive.level
+            ),
+            GameMode::Versus => {
+                "Mode: Versus, WASD against the arrows side by side (M to change)".to_string()
+            }
+            GameMode::Duel => {
+                "Mode: Duel, versus taking turns against the clock (M to change)".to_string()
+            }
+        };
+        let line = Line::new(mode, 16, [1.0, 1.0, 1.0, 0.8]);
+        ui.draw_centered(r, &line, middle + 110.0 * ui.scale);
+        let mutators = if app.mutators.any() {
+            let names: Vec<&str> = app.mutators.active().iter().map(|m| m.name()).collect();
+            format!(
+                "Mutators: {} (x{:.2}), X to change",
+                names.join(", "),
+                app.mutators.multiplier()
+            )
+        } else {
+            "X for Mutators".to_string()
+        };
+        let line = Line::new(mutators, 16, [1.0, 1.0, 1.0, 0.8]);
+        ui.draw_centered(r, &line, middle + 135.0 * ui.scale);
+        let level = match &app.level {
+            Some(level) => format!("Level: {} (L to change)", level.name),
+            None => "L for Levels".to_string(),
+        };
+        let line = Line::new(level, 16, [1.0, 1.0, 1.0, 0.8]);
+        ui.draw_centered(r, &line, middle + 160.0 * ui.scale);
+        if game.cheats.any() {
+            let text = format!("Cheats: {}", game.cheats.names().join(", "));
+            let line = Line::new(text, 16, [1.0, 0.3, 0.3, 1.0]);
+            ui.draw_centered(r, &line, middle + 190.0 * ui.scale);
+        }
+    } else if let Some(versus) = &app.versus {
+        draw_versus(app, ui, r, versus);
+    } else {
+        draw_board(app, r);
+
+        // Draw score
+        let score = Line::new(format!("Score: {}", game.score), 20, [1.0, 1.0, 1.0, 1.0]);
+        ui.draw_text(r, &score, 10.0, 20.0);
+        if game.cheats.any() {
+            let cheats = Line::new("CHEATS", 14, [1.0, 0.3, 0.3, 1.0]);
+            ui.draw_text(r, &cheats, 10.0, 38.0);
+        }
+        // How many Water pickups in a row would pay, one per full stomach
+        let chain = game.full_stomachs();
+        if chain > 0 {
+            let line = Line::new(format!("WATER x{}", chain), 14, [0.4, 0.6, 1.0, 1.0]);
+            let x = 10.0 + r.text_width(&score.text, ui.font_size(20)) / ui.scale + 12.0;
+            ui.draw_text(r, &line, x, 20.0);
+        }
+        let mut protection = Vec::new();
+        if game.shield_moves > 0 {
+            protection.push(format!("SHIELD {}", game.shield_moves));
+        }
+        if game.extra_lives > 0 {
+            protection.push(format!("+{} LIFE", game.extra_lives));
+        }
+        if game.metal_bites > 0 {
+            protection.push(format!("IRON {}", game.metal_bites));
+        }
+        if game.spit_ammo > 0 {
+            protection.push(format!("SPIT {}", game.spit_ammo));
+        }
+        if game.wall_breaker {
+            protection.push("BREAKER".to_string());
+        }
+        if !protection.is_empty() {
+            let line = Line::new(protection.join("  "), 14, [0.4, 0.8, 1.0, 1.0]);
+            let x = view_width / ui.scale
+                - 10.0
+                - r.text_width(&line.text, ui.font_size(14)) / ui.scale;
+            ui.draw_text(r, &line, x, 20.0);
+        }
+        if game.indigestion_frames > 0 {
+            let seconds = game.indigestion_frames.div_ceil(UPDATES_PER_SECOND);
+            let line = Line::new(
+                format!("INDIGESTION {}s", seconds),
+                14,
+                [0.6, 0.9, 0.2, 1.0],
+            );
+            let x = view_width / ui.scale
+                - 10.0
+                - r.text_width(&line.text, ui.font_size(14)) / ui.scale;
+            ui.draw_text(r, &line, x, 40.0);
+        }
+        if game.rules.length_speed != LengthSpeed::Off {
+            draw_speed(app, ui, r, view_width);
+        }
+        if let Some(boss) = &game.boss {
+            let seconds = boss.ticks_left as u64 * game.tick_interval / UPDATES_PER_SECOND;
+            let text = format!("CRANE  metal {}/{}  {}s", boss.metal, BOSS_METAL, seconds);
+            let line = Line::new(text, 16, [1.0, 0.8, 0.0, 1.0]);
+            ui.draw_centered(r, &line, 20.0 * ui.scale);
+        }
+        let mut progress = Vec::new();
+        if let Some(level) = &game.level {
+            let room = &level.rooms[level.active];
+            let text = if level.active + 1 == level.rooms.len() {
+                format!("ROOM {}/{}", level.active + 1, level.rooms.len())
+            } else if level.is_open(level.active) {
+                format!("ROOM {}/{}  door open", level.active + 1, level.rooms.len())
+            } else {
+                format!(
+                    "ROOM {}/{}  food {}/{}",
+                    level.active + 1,
+                    level.rooms.len(),
+                    room.eaten,
+                    ROOM_QUOTA
+                )
+            };
+            progress.push(text);
+        }
+        if let Some(track) = &game.track {
+            progress.push(format!("DISTANCE {}", track.rows));
+        }
+        if let Some(goal) = game.rules.goal {
+            progress.push(format!("GOAL {}", goal.description().to_uppercase()));
+        }
+        if game.rules.slow_start && game.foods_eaten < SLOW_START_FOODS {
+            let text = format!("SLOW START {}/{}", game.foods_eaten, SLOW_START_FOODS);
+            progress.push(text);
+        }
+        if let Some(adaptive) = &game.adaptive {
+            progress.push(format!("DIFFICULTY {:+}", adaptive.level));
+        }
+        if let Some(puzzle) = &game.puzzle {
+            let text = format!(
+                "MOVES {}/{}  Backspace to undo",
+                game.moves_used, puzzle.moves
+            );
+            progress.push(text);
+        }
+        if let Some(cluster) = &game.cluster {
+            let left = game.foods.iter().filter(|f| f.cluster.is_some()).count();
+            progress.push(format!(
+                "CLUSTER {} left  {} moves",
+                left, cluster.moves_left
+            ));
+        }
+        if !progress.is_empty() {
+            let line = Line::new(progress.join("  "), 14, [0.9, 0.9, 0.9, 1.0]);
+            ui.draw_centered(r, &line, 40.0 * ui.scale);
+        }
+        if app.dev {
+            let flags = app.dev_flags();
+            if !flags.is_empty() {
+                let dev = Line::new(flags, 14, [1.0, 1.0, 0.0, 1.0]);
+                ui.draw_text(r, &dev, 10.0, 56.0);
+            }
+        }
+        let hint = Line::new(bindings.hint(), 14, [1.0, 1.0, 1.0, 0.6]);
+        ui.draw_text(r, &hint, 10.0, view_height / ui.scale - 10.0);
+
+        if game.paused && app.auto_pause == Some(AutoPause::PadLost) {
+            r.rectangle([0.0, 0.0, 0.0, 0.5], [0.0, 0.0, view_width, view_height]);
+            let lines = [
+                Line::new("Controller Disconnected", 32, [1.0, 0.3, 0.3, 1.0]),
+                Line::new(
+                    "Reconnect it or press any key to resume",
+                    20,
+                    [1.0, 1.0, 1.0, 1.0],
+                ),
+            ];
+            ui.draw_column(r, &lines);
+        } else if let (true, Some(reason)) = (game.paused, app.auto_pause) {
+            r.rectangle([0.0, 0.0, 0.0, 0.5], [0.0, 0.0, view_width, view_height]);
+            let title = match reason {
+                AutoPause::Idle => "Paused (idle)",
+                _ => "Paused",
+            };
+            // Keys don't reach a window in the background, so no prompt until it's back
+            let prompt = if app.in_background {
+                ""
+            } else {
+                "Press any key to resume"
+            };
+            let lines = [
+                Line::new(title, 32, [1.0, 1.0, 1.0, 1.0]),
+                Line::new(prompt, 20, [1.0, 1.0, 1.0, 1.0]),
+            ];
+            ui.draw_column(r, &lines);
+        } else if game.paused {
+            r.rectangle([0.0, 0.0, 0.0, 0.5], [0.0, 0.0, view_width, view_height]);
+            let lines = [
+                Line::new("Paused", 32, [1.0, 1.0, 1.0, 1.0]),
+                Line::new("C for Photo Mode", 16, [1.0, 1.0, 1.0, 0.8]),
+            ];
+            ui.draw_column(r, &lines);
+        }
+    }
+
+    if app.console.open {
+        draw_console(&app.console, ui, r);
+    }
+}
+
+// Everything on the board: the terrain, the snake, food, critters and shots. Shared
+// by the game screen and the score card.
+pub fn draw_board(app: &App, r: &mut dyn Renderer) {
+    draw_scene(Scene::of(app), r);
+}
+
+// One board and the camera it's seen through: the run being played, or in versus
+// either player's half of the window. Only the lead scene, the app's own run, has the
+// bite effects, animations and bot plan, which follow that run.
+#[derive(Clone, Copy)]
+struct Scene<'a> {
+    app: &'a App,
+    game: &'a Game,
+    camera: &'a Camera,
+    lead: bool,
+}
+
+impl Scene<'_> {
+    fn of(app: &App) -> Scene<'_> {
+        Scene {
+            app,
+            game: &app.game,
+            camera: &app.camera,
+            lead: true,
+        }
+    }
+}
+
+// One scene's board, see `draw_board`
+fn draw_scene(scene: Scene, r: &mut dyn Renderer) {
+    let (app, game) = (scene.app, scene.game);
+    // Animations follow the app's own run
+    let effects = app.settings.effects_enabled() && scene.lead;
+    let board = (game.width, game.height);
+    app.decorations.draw(scene.camera, board, r);
+    if let Some(level) = &game.level {
+        draw_level(scene, r, level);
+    }
+    if let Some(layout) = &game.layout {
+        draw_layout(scene, r, layout);
+    }
+    if let Some(track) = &game.track {
+        // Rocks: dark boulders a little smaller than their cell
+        for &rock in &track.rocks {
+            let (x, y) = cell_origin(game, rock);
+            let rect = [x + 1.0, y + 1.0, BLOCK_SIZE - 2.0, BLOCK_SIZE - 2.0];
+            r.rectangle([0.3, 0.27, 0.25, 1.0], scene.camera.to_window(rect, board));
+        }
+    }
+    let invisible_tail = game.mutators.invisible_tail;
+    let hex = game.grid == GridKind::Hex;
+    for puddle in &game.puddles {
+        // Translucent, fading as it dries up
+        let color = [0.3, 0.5, 1.0, 0.15 + 0.35 * puddle.depth() as f32];
+        if hex {
+            draw_hexagon(scene, r, color, puddle.position, BLOCK_SIZE);
+            continue;
+        }
+        let (x, y) = cell_origin(game, puddle.position);
+        let rect = [x, y, BLOCK_SIZE, BLOCK_SIZE];
+        r.rectangle(color, scene.camera.to_window(rect, board));
+    }
+    if game.wrap_around && !invisible_tail && !hex {
+        draw_wrap_links(scene, r);
+    }
+    if let Some(topology) = &game.topology {
+        draw_edges(scene, r, topology);
+    }
+
+    // Draw snake
+    let visible = if invisible_tail {
+        1
+    } else {
+        game.snake.body.len()
+    };
+    for (i, segment) in game.snake.body.iter().enumerate().take(visible) {
+        let (x, y) = cell_origin(game, segment.position);
+        let size = match segment.segment_type {
+            // Kids mode keeps every segment big and chunky
+            _ if game.kids => BLOCK_SIZE,
+            SegmentType::Head | SegmentType::FullStomach => BLOCK_SIZE,
+            SegmentType::EmptyStomach => 20.0,
+            SegmentType::Tail => 15.0,
+        };
+        let skin = app.profile.skin;
+        let color = skin.color(i, &segment.segment_type, &app.palette());
+        let size = if effects {
+            size * app.animations.scale(i, game.frame_count)
+        } else {
+            size
+        };
+
+        if hex {
+            draw_hexagon(scene, r, color, segment.position, size);
+            match segment.segment_type {
+                SegmentType::FullStomach => {
+                    draw_hexagon(scene, r, METAL, segment.position, size * 0.4)
+                }
+                SegmentType::EmptyStomach => {
+                    let hollow = app.palette().background;
+                    draw_hexagon(scene, r, hollow, segment.position, size * 0.7)
+                }
+                _ => {}
+            }
+            continue;
+        }
+        if i == 0 && game.grid == GridKind::Diagonal {
+            let angle = if effects {
+                app.animations.head_angle()
+            } else {
+                animation::heading_angle(game)
+            };
+            draw_turned_head(scene, r, color, segment.position, size, angle);
+            continue;
+        }
+        // Center the smaller segments within the grid cell
+        let rect_x = x + (BLOCK_SIZE - size) / 2.0;
+        let rect_y = y + (BLOCK_SIZE - size) / 2.0;
+
+        let rect = scene.camera.to_window([rect_x, rect_y, size, size], board);
+        draw_segment(r, skin.shape(), color, rect);
+        draw_stomach(r, &segment.segment_type, rect, app.palette().background);
+    }
+
+    if game.wrap_around && !hex {
+        draw_wrap_preview(scene, r);
+    }
+
+    if let Some(boss) = &game.boss {
+        draw_boss(scene, r, boss);
+    }
+    draw_breaches(scene, r);
+
+    // With metal stored, Water pays: a soft halo round it that breathes with the frames
+    if game.full_stomachs() > 0 {
+        let breath = if effects {
+            (game.frame_count as f64 * 0.05).sin() * 0.5 + 0.5
+        } else {
+            0.5
+        };
+        let color = [0.4, 0.6, 1.0, 0.2 + 0.15 * breath as f32];
+        let spread = 3.0 + 2.0 * breath;
+        for food in game.foods.iter().filter(|f| f.food_type == FoodType::Water) {
+            if hex {
+                draw_hexagon(scene, r, color, food.position, BLOCK_SIZE + 2.0 * spread);
+                continue;
+            }
+            let (x, y) = cell_origin(game, food.position);
+            let rect = [
+                x - spread,
+                y - spread,
+                BLOCK_SIZE + 2.0 * spread,
+                BLOCK_SIZE + 2.0 * spread,
+            ];
+            r.rectangle(color, scene.camera.to_window(rect, board));
+        }
+    }
+
+    // Draw food
+    for food in &game.foods {
+        let color = food_styles::board_color(&food.food_type);
+        if hex {
+            draw_hexagon(scene, r, color, food.position, BLOCK_SIZE);
+            continue;
+        }
+        let (x, y) = cell_origin(game, food.position);
+        let rect = [x, y, BLOCK_SIZE, BLOCK_SIZE];
+        r.rectangle(color, scene.camera.to_window(rect, board));
+    }
+
+    if let Some(cluster) = &game.cluster {
+        draw_cluster(scene, r, cluster);
+    }
+
+    if scene.lead {
+        draw_bites(scene, r);
+    }
+
+    // Wall-breaker: a brick with a lighter mortar line
+    if let Some(cell) = game.breaker {
+        let (x, y) = cell_origin(game, cell);
+        let brick = [x + 2.0, y + 5.0, BLOCK_SIZE - 4.0, BLOCK_SIZE - 10.0];
+        let mortar = [x + 2.0, y + 11.5, BLOCK_SIZE - 4.0, 2.0];
+        r.rectangle([0.7, 0.2, 0.15, 1.0], scene.camera.to_window(brick, board));
+        r.rectangle([0.85, 0.8, 0.7, 1.0], scene.camera.to_window(mortar, board));
+    }
+
+    // Rats: a gray body with a pink tail
+    for rat in &game.rats {
+        let (x, y) = cell_origin(game, rat.position);
+        let body = [x + 5.0, y + 8.0, 15.0, 10.0];
+        let tail = [x + 1.0, y + 12.0, 5.0, 2.0];
+        r.rectangle([0.35, 0.33, 0.32, 1.0], scene.camera.to_window(body, board));
+        r.rectangle([0.9, 0.6, 0.65, 1.0], scene.camera.to_window(tail, board));
+    }
+
+    // Rust spit, a small orange blob in its cell
+    for shot in &game.shots {
+        let (x, y) = cell_origin(game, shot.position);
+        let rect = [x + 7.5, y + 7.5, 10.0, 10.0];
+        r.rectangle([0.8, 0.35, 0.1, 1.0], scene.camera.to_window(rect, board));
+    }
+
+    if app.dev && scene.lead {
+        if let Some(bot) = &app.bot {
+            draw_plan(scene, r, &bot.plan);
+        }
+    }
+}
+
+// Versus: each player's board in their half of the window, drawn as a pass of its own
+// so neither spills into the other, under a strip with both scores
+fn draw_versus(app: &App, ui: &Ui, r: &mut dyn Renderer, versus: &Versus) {
+    let [view_width, view_height] = r.view_size();
+    let games = [&app.game, &versus.rival];
+    let viewports = versus::viewports([view_width, view_height], ui.scale);
+    for (player, viewport) in viewports.into_iter().enumerate() {
+        let game = games[player];
+        let camera = versus::camera(game, viewport);
+        let scene = Scene {
+            app,
+            game,
+            camera: &camera,
+            lead: player == 0,
+        };
+        r.clip(Some(viewport));
+        draw_scene(scene, r);
+        if game.game_over {
+            r.rectangle([0.0, 0.0, 0.0, 0.5], viewport);
+        }
+        r.clip(None);
+    }
+    let [seam, top, _, _] = viewports[1];
+    r.line([1.0, 1.0, 1.0, 0.6], 1.0, [seam, top, seam, view_height]);
+    r.rectangle([0.0, 0.0, 0.0, 0.6], [0.0, 0.0, view_width, top]);
+    for (player, game) in games.into_iter().enumerate() {
+        let mut text = format!("{} ({}): {}", PLAYERS[player], KEYS[player], game.score);
+        if game.game_over {
+            text += " - out";
+        }
+        let line = Line::new(text, 18, [1.0, 1.0, 1.0, 1.0]);
+        let x = viewports[player][0] / ui.scale + 10.0;
+        ui.draw_text(r, &line, x, HUD_HEIGHT - 9.0);
+        if let Some(clock) = versus.clock {
+            draw_clock(ui, r, &clock, player, viewports[player]);
+        }
+    }
+    if app.game.paused {
+        r.rectangle([0.0, 0.0, 0.0, 0.5], [0.0, 0.0, view_width, view_height]);
+        let line = Line::new("Paused", 32, [1.0, 1.0, 1.0, 1.0]);
+        ui.draw_column(r, &[line]);
+    }
+}
+
+// A duel player's time bank, at the right of their half of the score strip: bright
+// while it's running, red in the last ten seconds
+fn draw_clock(ui: &Ui, r: &mut dyn Renderer, clock: &Clock, player: usize, viewport: [f64; 4]) {
+    let seconds = clock.banks[player].div_ceil(UPDATES_PER_SECOND);
+    let text = format!("{}:{:02}", seconds / 60, seconds % 60);
+    let running = clock.turn == player && clock.flagged.is_none();
+    let mut color = if seconds <= 10 {
+        [1.0, 0.3, 0.3, 1.0]
+    } else {
+        [1.0, 1.0, 1.0, 1.0]
+    };
+    if !running {
+        color[3] = 0.5;
+    }
+    let line = Line::new(text, 18, color);
+    let x = (viewport[0] + viewport[2]) / ui.scale - 60.0;
+    if running {
+        let [_, top, ..] = viewport;
+        r.rectangle(
+            [1.0, 1.0, 1.0, 0.15],
+            [(x - 6.0) * ui.scale, 0.0, 62.0 * ui.scale, top],
+        );
+    }
+    ui.draw_text(r, &line, x, HUD_HEIGHT - 9.0);
+}
+
+// Dev: what the computer player looked at for its last move, faint, and the path it
+// took from there, brighter
+fn draw_plan(scene: Scene, r: &mut dyn Renderer, plan: &Plan) {
+    let game = scene.game;
+    let board = (game.width, game.height);
+    let highlight = |r: &mut dyn Renderer, cell, color| {
+        let (x, y) = cell_origin(game, cell);
+        let rect = [x, y, BLOCK_SIZE, BLOCK_SIZE];
+        r.rectangle(color, scene.camera.to_window(rect, board));
+    };
+    for &cell in &plan.considered {
+        highlight(r, cell, [0.3, 0.6, 1.0, 0.12]);
+    }
+    for &cell in &plan.path {
+        highlight(r, cell, [1.0, 1.0, 0.2, 0.35]);
+    }
+}
+
+// The route the head took over the run, going from blue at the start to red at the end
+fn draw_path(app: &App, ui: &Ui, r: &mut dyn Renderer) {
+    let [_, view_height] = r.view_size();
+    let game = &app.game;
+    let board = (game.width, game.height);
+    app.decorations.draw(&app.camera, board, r);
+    let center = |cell| {
+        let (x, y) = cell_origin(game, cell);
+        let rect = [x + BLOCK_SIZE / 2.0, y + BLOCK_SIZE / 2.0, 0.0, 0.0];
+        app.camera.to_window(rect, board)
+    };
+    let steps = app.trail.len().saturating_sub(1).max(1);
+    for (i, pair) in app.trail.windows(2).enumerate() {
+        let (from, to) = (pair[0], pair[1]);
+        // Wrapping around an edge would streak across the whole board
+        if (from.0 - to.0).abs() > 1 || (from.1 - to.1).abs() > 1 {
+            continue;
+        }
+        let time = i as f32 / steps as f32;
+        let color = [time, 0.2, 1.0 - time, 0.9];
+        let ([x1, y1, ..], [x2, y2, ..]) = (center(from), center(to));
+        r.line(color, 2.0, [x1, y1, x2, y2]);
+    }
+    if let Some(&end) = app.trail.last() {
+        let [x, y, ..] = center(end);
+        r.rectangle([1.0, 0.2, 0.0, 1.0], [x - 4.0, y - 4.0, 8.0, 8.0]);
+    }
+
+    let text = format!("Your Path ({} cells)", app.trail.len());
+    ui.draw_centered(
+        r,
+        &Line::new(text, 24, [1.0, 0.8, 0.0, 1.0]),
+        30.0 * ui.scale,
+    );
+    let line = Line::new("V or Backspace to go back", 16, [1.0, 1.0, 1.0, 0.8]);
+    ui.draw_centered(r, &line, view_height - 15.0 * ui.scale);
+}
+
+// Where past runs ended, as a heatmap over the board, with what ended them
+fn draw_stats(app: &App, ui: &Ui, r: &mut dyn Renderer) {
+    let [view_width, view_height] = r.view_size();
+    // Marked like an adjustable menu item, as left/right turns the page
+    let title = match app.stats_page {
+        StatsPage::Deaths => format!("< Deaths ({}) >", app.stats.deaths.len()),
+        page => format!("< {} ({} runs) >", page.name(), app.stats.runs.len()),
+    };
+    ui.draw_centered(
+        r,
+        &Line::new(title, 32, [1.0, 0.8, 0.0, 1.0]),
+        40.0 * ui.scale,
+    );
+    // Below the title and above the two lines at the bottom
+    let margin = 60.0 * ui.scale;
+    let area = [
+        margin,
+        80.0 * ui.scale,
+        view_width - 2.0 * margin,
+        view_height - 160.0 * ui.scale,
+    ];
+    let runs = &app.stats.runs;
+    let caption = match app.stats_page {
+        StatsPage::Deaths => {
+            draw_death_map(app, r);
+            let causes: Vec<String> = app
+                .stats
+                .causes()
+                .iter()
+                .map(|(cause, count)| format!("{} {}", cause.description(), count))
+                .collect();
+            causes.join("  ")
+        }
+        _ if runs.is_empty() && app.stats_page != StatsPage::Causes => {
+            "No finished runs yet".to_string()
+        }
+        StatsPage::Scores => {
+            let recent = &runs[runs.len().saturating_sub(CHART_RUNS)..];
+            let scores: Vec<f64> = recent.iter().map(|run| run.score as f64).collect();
+            charts::line(r, area, &scores, [0.3, 1.0, 0.3, 1.0]);
+            let best = recent.iter().map(|run| run.score).max().unwrap_or(0);
+            let average = scores.iter().sum::<f64>() / scores.len() as f64;
+            let near_misses = recent.iter().map(|run| run.near_misses).sum::<u32>() as f64;
+            let (year, month, day) = seasons::date(recent[0].time);
+            format!(
+                "Last {} runs since {}-{:02}-{:02}: best {}, average {:.0}, {:.1} near misses a run",
+                recent.len(),
+                year,
+                month,
+                day,
+                best,
+                average,
+                near_misses / recent.len() as f64
+            )
+        }
+        StatsPage::Causes => {
+            let bars: Vec<(String, f64, Color)> = app
+                .stats
+                .causes()
+                .iter()
+                .map(|(cause, count)| {
+                    let label = cause.description().to_string();
+                    (label, *count as f64, [1.0, 0.5, 0.2, 1.0])
+                })
+                .collect();
+            charts::bars(r, ui, area, &bars);
+            if bars.is_empty() {
+                "Nobody has died yet".to_string()
+            } else {
+                String::new()
+            }
+        }
+        StatsPage::Foods => {
+            let recent = &runs[runs.len().saturating_sub(FOOD_CHART_RUNS)..];
+            let columns: Vec<Vec<(f64, Color)>> = recent
+                .iter()
+                .map(|run| {
+                    FOOD_TYPES
+                        .iter()
+                        .zip(run.foods)
+                        .map(|(food, count)| (count as f64, food_styles::board_color(food)))
+                        .collect()
+                })
+                .collect();
+            charts::stacked(r, area, &columns);
+            let legend: Vec<&str> = FOOD_TYPES.iter().map(|food| food.id()).collect();
+            format!(
+                "Last {} runs, bottom up: {}",
+                recent.len(),
+                legend.join(", ")
+            )
+        }
+    };
+    let line = Line::new(caption, 16, [1.0, 1.0, 1.0, 1.0]);
+    ui.draw_centered(r, &line, view_height - 40.0 * ui.scale);
+    let line = Line::new(
+        "Left/Right for More Charts, H or Backspace to Go Back",
+        16,
+        [1.0, 1.0, 1.0, 0.8],
+    );
+    ui.draw_centered(r, &line, view_height - 15.0 * ui.scale);
+}
+
+// Where past runs ended, as a heatmap over the board
+fn draw_death_map(app: &App, r: &mut dyn Renderer) {
+    let board = (app.game.width, app.game.height);
+    let counts = app.stats.heatmap(board);
+    let most = counts.iter().copied().max().unwrap_or(0).max(1);
+    for y in 0..board.1 {
+        for x in 0..board.0 {
+            let count = counts[(y * board.0 + x) as usize];
+            // Faint for the cells nobody died on, yellow through to red for the worst
+            let color = if count == 0 {
+                [1.0, 1.0, 1.0, 0.05]
+            } else {
+                let heat = count as f32 / most as f32;
+                [1.0, 0.9 * (1.0 - heat), 0.0, 0.3 + 0.6 * heat]
+            };
+            let cell = [
+                x as f64 * BLOCK_SIZE,
+                y as f64 * BLOCK_SIZE,
+                BLOCK_SIZE,
+                BLOCK_SIZE,
+            ];
+            r.rectangle(color, app.camera.to_window(cell, board));
+        }
+    }
+}
+
+// The menu on screen, if one is open. The frontend also asks for it to find the item
+// under the mouse.
+pub fn menu(app: &App) -> Option<Menu> {
+    if app.path_view || app.stats_screen {
+        None
+    } else if let Some(selected) = app.settings_menu {
+        Some(settings_menu(app, selected))
+    } else if let Some(selected) = app.restore_menu {
+        Some(restore_menu(app, selected))
+    } else if let Some(selected) = app.hall_of_fame_menu {
+        Some(hall_of_fame_menu(app, selected))
+    } else if let Some(picker) = &app.color_picker {
+        Some(color_menu(app, picker))
+    } else if let Some(selected) = app.mutators_menu {
+        Some(mutators_menu(app, selected))
+    } else if let Some(selected) = app.shop {
+        Some(shop_menu(app, selected))
+    } else if let Some(selected) = app.upgrades_menu {
+        Some(upgrades_menu(app, selected))
+    } else if let Some(selected) = app.levels_menu {
+        Some(levels_menu(app, selected))
+    } else {
+        app.controls_menu
+            .map(|selected| controls_menu(app, selected))
+    }
+}
+
+fn levels_menu(app: &App, selected: usize) -> Menu {
+    let header = vec![
+        Line::new("Levels", 32, [1.0, 0.8, 0.0, 1.0]),
+        Line::spacer(12),
+    ];
+    let mut menu = Menu::new(header, 20, selected);
+    menu.item("Plain Board");
+    menu.item("Your Level (Editor)");
+    for level in &app.community {
+        let mut text = match (app.profile.medals.get(&level.id()), &level.puzzle) {
+            (_, Some(_)) => match app.profile.stars.get(&level.id()) {
+                Some(stars) => format!("[{}/{} stars] {}", stars, MAX_STARS, level.name),
+                None => format!("[Puzzle] {}", level.name),
+            },
+            (Some(medal), None) => format!("[{}] {}", medal.name(), level.name),
+            (None, None) => level.name.clone(),
+        };
+        if !level.author.is_empty() {
+            text += &format!(" by {}", level.author);
+        }
+        text += &format!(" ({}x{}", level.layout.width, level.layout.height);
+        let mutators: Vec<&str> = level.mutators.active().iter().map(|m| m.name()).collect();
+        if !mutators.is_empty() {
+            text += &format!(", {}", mutators.join(", "));
+        }
+        menu.item(text + ")");
+    }
+    menu.footer(Line::spacer(12));
+    let par: Vec<String> = selected
+        .checked_sub(LEVEL_CHOICES)
+        .and_then(|i| app.community.get(i))
+        .map(|level| {
+            MEDALS
+                .iter()
+                .zip(level.par)
+                .filter_map(|(medal, par)| Some(format!("{} {}", medal.name(), par?)))
+                .collect()
+        })
+        .unwrap_or_default();
+    if !par.is_empty() {
+        menu.footer(Line::new(
+            format!("Par: {}", par.join(", ")),
+            16,
+            [1.0, 0.8, 0.0, 1.0],
+        ));
+    }
+    let puzzle = selected
+        .checked_sub(LEVEL_CHOICES)
+        .and_then(|i| app.community.get(i))
+        .and_then(|level| level.puzzle.as_ref());
+    if let Some(puzzle) = puzzle {
+        let mut text = format!("Puzzle: {} moves", puzzle.moves);
+        for (stars, most) in (2..=MAX_STARS).rev().zip(puzzle.stars) {
+            if let Some(most) = most {
+                text += &format!(", {} stars in {}", stars, most);
+            }
+        }
+        menu.footer(Line::new(text, 16, [1.0, 0.8, 0.0, 1.0]));
+    }
+    if app.community.is_empty() {
+        menu.footer(Line::new(
+            "Levels shared with you go in levels/community",
+            16,
+            [0.8, 0.8, 0.8, 1.0],
+        ));
+    }
+    menu.footer(Line::new(
+        format!(
+            "Up/Down to Choose, Enter to Play, Tab to Sort by {}, Backspace to Go Back",
+            app.level_sort.next().name()
+        ),
+        16,
+        [1.0, 1.0, 1.0, 1.0],
+    ));
+    menu
+}
+
+fn controls_menu(app: &App, selected: usize) -> Menu {
+    let header = vec![
+        Line::new("Controls", 32, [1.0, 0.8, 0.0, 1.0]),
+        Line::spacer(12),
+    ];
+    let mut menu = Menu::new(header, 24, selected);
+    for preset in PRESETS {
+        menu.item(preset.name());
+    }
+    menu.footer(Line::spacer(12));
+    menu.footer(Line::new(
+        app.settings.key_bindings(PRESETS[selected]).hint(),
+        20,
+        [0.8, 0.8, 0.8, 1.0],
+    ));
+    menu.footer(Line::new(
+        "Up/Down to Choose, Enter to Confirm, Backspace to Go Back",
+        16,
+        [1.0, 1.0, 1.0, 1.0],
+    ));
+    menu
+}
+
+fn shop_menu(app: &App, selected: usize) -> Menu {
+    let header = vec![
+        Line::new("Scrap Shop", 32, [1.0, 0.8, 0.0, 1.0]),
+        Line::new(
+            format!("{} scrap coins", app.profile.coins),
+            20,
+            [0.9, 0.6, 0.3, 1.0],
+        ),
+        Line::spacer(12),
+    ];
+    let mut menu = Menu::new(header, 20, selected);
+    for item in shop::items(&app.profile) {
+        let price = if item.owned(&app.profile) {
+            "owned".to_string()
+        } else {
+            item.price().to_string()
+        };
+        menu.item(format!("{} - {}", item.name(), price));
+    }
+    menu.footer(Line::spacer(12));
+    menu.footer(Line::new(
+        app.menu_message.as_str(),
+        16,
+        [1.0, 0.8, 0.0, 1.0],
+    ));
+    menu.footer(Line::new(
+        "Up/Down to Choose, Enter to Buy, Backspace to Go Back",
+        16,
+        [1.0, 1.0, 1.0, 1.0],
+    ));
+    menu
+}
+
+// Toggles with their multipliers and the combined one
+fn mutators_menu(app: &App, selected: usize) -> Menu {
+    let header = vec![
+        Line::new("Mutators", 32, [1.0, 0.8, 0.0, 1.0]),
+        Line::new(
+            format!("Score x{:.2}", app.mutators.multiplier()),
+            20,
+            [0.4, 0.8, 1.0, 1.0],
+        ),
+        Line::spacer(12),
+    ];
+    let mut menu = Menu::new(header, 20, selected);
+    for mutator in MUTATORS {
+        let state = if app.mutators.is_on(mutator) {
+            "ON"
+        } else {
+            "off"
+        };
+        menu.item(format!(
+            "{} (x{}) - {}",
+            mutator.name(),
+            mutator.multiplier(),
+            state
+        ));
+    }
+    menu.footer(Line::spacer(12));
+    menu.footer(Line::new(
+        "Up/Down to Choose, Enter to Toggle, Backspace to Go Back",
+        16,
+        [1.0, 1.0, 1.0, 1.0],
+    ));
+    menu
+}
+
+// Settings that can be changed in game, one line each with its current value
+fn settings_menu(app: &App, selected: usize) -> Menu {
+    let header = vec![
+        Line::new("Settings", 32, [1.0, 0.8, 0.0, 1.0]),
+        Line::spacer(12),
+    ];
+    let mut menu = Menu::new(header, 24, selected).adjustable();
+    for setting in ADJUSTABLE {
+        menu.item(format!(
+            "{}: {}",
+            setting.name(),
+            app.settings.describe(setting)
+        ));
+    }
+    menu.footer(Line::spacer(12));
+    menu.footer(Line::new(
+        "Up/Down to Choose, Left/Right to Change, Backspace to Go Back",
+        16,
+        [1.0, 1.0, 1.0, 1.0],
+    ));
+    menu
+}
+
+// The backups of the high score table, newest first, each with its best entry
+fn restore_menu(app: &App, selected: usize) -> Menu {
+    let header = vec![
+        Line::new("Restore High Scores", 32, [1.0, 0.8, 0.0, 1.0]),
+        Line::new(
+            "The current table becomes backup 1, so a restore can be undone",
+            16,
+            [1.0, 1.0, 1.0, 0.8],
+        ),
+        Line::spacer(12),
+    ];
+    let mut menu = Menu::new(header, 20, selected);
+    for (i, backup) in app.backups.iter().enumerate() {
+        let text = match backup.as_deref() {
+            None => format!("Backup {}: none", i + 1),
+            Some([]) => format!("Backup {}: empty", i + 1),
+            Some(entries) => {
+                let best = &entries[0];
+                let name = names::shorten(&best.name, MAX_NAME_LENGTH);
+                format!(
+                    "Backup {}: {} scores, best {} - {}",
+                    i + 1,
+                    entries.len(),
+                    name,
+                    best.score
+                )
+            }
+        };
+        menu.item(text);
+    }
+    menu.footer(Line::spacer(12));
+    menu.footer(Line::new(
+        "Up/Down to Choose, Enter to Restore, Backspace to Go Back",
+        16,
+        [1.0, 1.0, 1.0, 1.0],
+    ));
+    menu
+}
+
+// Past seasons, newest first, with the best three of every board in the one picked
+fn hall_of_fame_menu(app: &App, selected: usize) -> Menu {
+    let header = vec![
+        Line::new("Hall of Fame", 32, [1.0, 0.8, 0.0, 1.0]),
+        Line::spacer(12),
+    ];
+    let seasons = &app.hall_of_fame.seasons;
+    let mut menu = Menu::new(header, 20, selected);
+    for season in seasons {
+        menu.item(season.name());
+    }
+    menu.footer(Line::spacer(12));
+    match seasons.get(selected) {
+        Some(season) => {
+            for (board, entries) in &season.boards {
+                let podium: Vec<String> = entries
+                    .iter()
+                    .map(|entry| {
+                        let name = names::shorten(&entry.name, MAX_NAME_LENGTH);
+                        format!("{} {}", name, entry.score)
+                    })
+                    .collect();
+                let text = format!("{}: {}", board, podium.join(", "));
+                menu.footer(Line::new(text, 16, [1.0, 0.8, 0.0, 1.0]));
+            }
+        }
+        None => menu.footer(Line::new(
+            "No season has ended yet, this month's high scores will be the first",
+            16,
+            [0.8, 0.8, 0.8, 1.0],
+        )),
+    }
+    menu.footer(Line::spacer(12));
+    menu.footer(Line::new(
+        "Up/Down to Choose a Season, Backspace to Go Back",
+        16,
+        [1.0, 1.0, 1.0, 1.0],
+    ));
+    menu
+}
+
+// Crumbs flying off recent bites, and the text floating up from them. With reduced
+// motion there are no crumbs and the text fades where it appeared.
+fn draw_bites(scene: Scene, r: &mut dyn Renderer) {
+    let game = scene.game;
+    let board = (game.width, game.height);
+    let effects = scene.app.settings.effects_enabled();
+    for crumb in scene.app.bites.crumbs.iter().filter(|_| effects) {
+        let (x, y) = cell_origin(game, crumb.cell);
+        let center = BLOCK_SIZE / 2.0;
+        let rect = [
+            x + center + crumb.offset[0],
+            y + center + crumb.offset[1],
+            3.0,
+            3.0,
+        ];
+        let [red, green, blue, alpha] = crumb.color;
+        let color = [red, green, blue, alpha * crumb.fade()];
+        r.rectangle(color, scene.camera.to_window(rect, board));
+    }
+    for popup in &scene.app.bites.popups {
+        let (x, y) = cell_origin(game, popup.cell);
+        let rise = if effects { popup.rise() } else { 0.0 };
+        let [left, baseline, _, _] = scene.camera.to_window([x, y - rise, 0.0, 0.0], board);
+        let [red, green, blue, alpha] = popup.color;
+        r.text(
+            &popup.text,
+            14,
+            [red, green, blue, alpha * popup.fade()],
+            left,
+            baseline,
+        );
+    }
+}
+
+// The snake color rows, each with its value, and a sample of the derived shades
+fn color_menu(app: &App, picker: &ColorPicker) -> Menu {
+    let header = vec![
+        Line::new("Snake Color", 32, [1.0, 0.8, 0.0, 1.0]),
+        Line::spacer(12),
+    ];
+    let mut menu = Menu::new(header, 24, picker.row).adjustable();
+    for (i, row) in colors::ROWS.iter().enumerate() {
+        let value = match (i, picker.color) {
+            (0, _) => picker.preset_name().to_string(),
+            // Channels are shown as the picker's steps
+            (_, Some(color)) => {
+                let level = (color[i - 1] * colors::CHANNEL_STEPS as f32).round();
+                format!("{} / {}", level, colors::CHANNEL_STEPS)
+            }
+            (_, None) => "-".to_string(),
+        };
+        menu.item(format!("{}: {}", row, value));
+    }
+    menu.footer(Line::spacer(12));
+    if let Some(color) = picker.color {
+        let shades = colors::derive(color, &app.palette());
+        menu.footer(Line::new("Head", 20, shades.head));
+        menu.footer(Line::new("Full Stomach", 20, shades.full_stomach));
+        menu.footer(Line::new("Empty Stomach", 20, shades.empty_stomach));
+        menu.footer(Line::new("Tail", 20, shades.tail));
+    }
+    menu.footer(Line::new(
+        "Used by the Classic and Striped skins",
+        16,
+        [0.8, 0.8, 0.8, 1.0],
+    ));
+    menu.footer(Line::new(
+        "Up/Down to Choose, Left/Right to Change, Enter to Keep, Backspace to Go Back",
+        16,
+        [1.0, 1.0, 1.0, 1.0],
+    ));
+    menu
+}
+
+// The roguelite upgrade tree, one line per upgrade with its level and next cost
+fn upgrades_menu(app: &App, selected: usize) -> Menu {
+    let upgrades = &app.profile.upgrades;
+    let header = vec![
+        Line::new("Upgrades", 32, [1.0, 0.8, 0.0, 1.0]),
+        Line::new(
+            format!("{} upgrade points", app.profile.upgrade_points),
+            20,
+            [0.4, 0.8, 1.0, 1.0],
+        ),
+        Line::spacer(12),
+    ];
+    let mut menu = Menu::new(header, 20, selected);
+    for upgrade in UPGRADES {
+        let level = upgrades.level(upgrade);
+        let next = if level >= upgrade.max_level() {
+            "max".to_string()
+        } else {
+            format!("next {} pts", upgrade.cost(level))
+        };
+        // Branches of the tree are indented under what they need
+        let indent = if upgrade.requires().is_some() {
+            "  "
+        } else {
+            ""
+        };
+        menu.item(format!(
+            "{}{} {}/{} - {}",
+            indent,
+            upgrade.name(),
+            level,
+            upgrade.max_level(),
+            next
+        ));
+    }
+    menu.footer(Line::spacer(12));
+    menu.footer(Line::new(
+        app.menu_message.as_str(),
+        16,
+        [1.0, 0.8, 0.0, 1.0],
+    ));
+    menu.footer(Line::new(
+        "Up/Down to Choose, Enter to Buy, Backspace to Go Back",
+        16,
+        [1.0, 1.0, 1.0, 1.0],
+    ));
+    menu
+}
+
+// Top left of a cell in board pixels. Odd rows of a hex board sit half a cell right.
+fn cell_origin(game: &Game, (x, y): (i32, i32)) -> (f64, f64) {
+    let shift = if game.grid == GridKind::Hex && y.rem_euclid(2) == 1 {
+        0.5
+    } else {
+        0.0
+    };
+    ((x as f64 + shift) * BLOCK_SIZE, y as f64 * BLOCK_SIZE)
+}
+
+// A pointy-topped hexagon `size` board pixels across, centered on a cell
+fn draw_hexagon(scene: Scene, r: &mut dyn Renderer, color: Color, cell: (i32, i32), size: f64) {
+    let board = (scene.game.width, scene.game.height);
+    let (x, y) = cell_origin(scene.game, cell);
+    let center = (x + BLOCK_SIZE / 2.0, y + BLOCK_SIZE / 2.0);
+    let radius = size / 3f64.sqrt();
+    let points: Vec<[f64; 2]> = (0..6)
+        .map(|corner| {
+            let angle = (60.0 * corner as f64 - 90.0).to_radians();
+            let point = [
+                center.0 + radius * angle.cos(),
+                center.1 + radius * angle.sin(),
+                0.0,
+                0.0,
+            ];
+            let window = scene.camera.to_window(point, board);
+            [window[0], window[1]]
+        })
+        .collect();
+    r.polygon(color, &points);
+}
+
+// The head on the diagonal board: a square turned to face `angle` (radians clockwise
+// from east), with a dark eye toward the front
+fn draw_turned_head(
+    scene: Scene,
+    r: &mut dyn Renderer,
+    color: Color,
+    cell: (i32, i32),
+    size: f64,
+    angle: f64,
+) {
+    let board = (scene.game.width, scene.game.height);
+    let (x, y) = cell_origin(scene.game, cell);
+    let center = (x + BLOCK_SIZE / 2.0, y + BLOCK_SIZE / 2.0);
+    let to_window = |dx: f64, dy: f64| {
+        let (sin, cos) = angle.sin_cos();
+        let point = [
+            center.0 + dx * cos - dy * sin,
+            center.1 + dx * sin + dy * cos,
+            0.0,
+            0.0,
+        ];
+        let window = scene.camera.to_window(point, board);
+        [window[0], window[1]]
+    };
+    let half = size / 2.0;
+    let corners = [
+        to_window(half, -half),
+        to_window(half, half),
+        to_window(-half, half),
+        to_window(-half, -half),
+    ];
+    r.polygon(color, &corners);
+    let eye = to_window(half * 0.5, 0.0);
+    let eye_size = scene.camera.to_window([0.0, 0.0, 5.0, 5.0], board)[2];
+    r.rectangle(
+        [0.1, 0.1, 0.1, 1.0],
+        [
+            eye[0] - eye_size / 2.0,
+            eye[1] - eye_size / 2.0,
+            eye_size,
+            eye_size,
+        ],
+    );
+}
+
+// Room walls in concrete gray, closed doors as a rust-red bar across the gap
+fn draw_level(scene: Scene, r: &mut dyn Renderer, level: &Level) {
+    let board = (scene.game.width, scene.game.height);
+    for &x in &level.walls {
+        for y in 0..scene.game.height {
+            if !level.is_wall((x, y)) {
+                continue;
+            }
+            let (cell_x, cell_y) = (x as f64 * BLOCK_SIZE, y as f64 * BLOCK_SIZE);
+            let rect = if level.doors.contains(&(x, y)) {
+                [cell_x + 2.0, cell_y, BLOCK_SIZE - 4.0, BLOCK_SIZE]
+            } else {
+                [cell_x, cell_y, BLOCK_SIZE, BLOCK_SIZE]
+            };
+            let color = if level.doors.contains(&(x, y)) {
+                [0.6, 0.2, 0.1, 1.0]
+            } else {
+                WALL
+            };
+            r.rectangle(color, scene.camera.to_window(rect, board));
+        }
+    }
+}
+
+fn draw_layout(scene: Scene, r: &mut dyn Renderer, layout: &Layout) {
+    let board = (layout.width, layout.height);
+    for y in 0..layout.height {
+        for x in 0..layout.width {
+            let rect = [
+                x as f64 * BLOCK_SIZE,
+                y as f64 * BLOCK_SIZE,
+                BLOCK_SIZE,
+                BLOCK_SIZE,
+            ];
+            match layout.get((x, y)) {
+                Tile::Floor => {}
+                Tile::Wall => r.rectangle(WALL, scene.camera.to_window(rect, board)),
+                // Mud: brown with a couple of darker clods
+                Tile::Mud => {
+                    r.rectangle(MUD, scene.camera.to_window(rect, board));
+                    let (left, top) = (rect[0], rect[1]);
+                    for (dx, dy) in [(0.2, 0.3), (0.6, 0.65)] {
+                        let clod = [
+                            left + dx * BLOCK_SIZE,
+                            top + dy * BLOCK_SIZE,
+                            BLOCK_SIZE * 0.2,
+                            BLOCK_SIZE * 0.15,
+                        ];
+                        r.rectangle(MUD_CLOD, scene.camera.to_window(clod, board));
+                    }
+                }
+                // One-way gates: posts either side of the way through, and an arrow
+                Tile::Gate(direction) => {
+                    let post = BLOCK_SIZE * 0.2;
+                    let posts = match direction {
+                        Direction::Right | Direction::Left => [
+                            [rect[0], rect[1], BLOCK_SIZE, post],
+                            [rect[0], rect[1] + BLOCK_SIZE - post, BLOCK_SIZE, post],
+                        ],
+                        Direction::Up | Direction::Down => [
+                            [rect[0], rect[1], post, BLOCK_SIZE],
+                            [rect[0] + BLOCK_SIZE - post, rect[1], post, BLOCK_SIZE],
+                        ],
+                    };
+                    for post in posts {
+                        r.rectangle(WALL, scene.camera.to_window(post, board));
+                    }
+                    draw_arrow(scene, r, board, GATE_ARROW, (x, y), direction);
+                }
+                // Speed lanes: a pale strip with a chevron the way they go
+                Tile::Lane(direction) => {
+                    r.rectangle(LANE, scene.camera.to_window(rect, board));
+                    draw_arrow(scene, r, board, LANE_ARROW, (x, y), direction);
+                }
+            }
+        }
+    }
+}
+
+// The layout being edited with faint floor cells, the start in green, the marked
+// rectangle in yellow and the cursor as a white frame, filled with the brush while the
+// pen is down
+fn draw_editor(app: &App, ui: &Ui, r: &mut dyn Renderer, editor: &LevelEditor) {
+    let [_, view_height] = r.view_size();
+    let layout = &editor.layout;
+    let board = (layout.width, layout.height);
+    let cell_rect = |(x, y): (i32, i32)| {
+        [
+            x as f64 * BLOCK_SIZE,
+            y as f64 * BLOCK_SIZE,
+            BLOCK_SIZE,
+            BLOCK_SIZE,
+        ]
+    };
+    for y in 0..layout.height {
+        for x in 0..layout.width {
+            let [left, top, size, _] = cell_rect((x, y));
+            let inset = [left + 1.0, top + 1.0, size - 2.0, size - 2.0];
+            r.rectangle([1.0, 1.0, 1.0, 0.05], app.camera.to_window(inset, board));
+        }
+    }
+    draw_layout(Scene::of(app), r, layout);
+    let [left, top, size, _] = cell_rect(layout.start());
+    let start = [left + 6.0, top + 6.0, size - 12.0, size - 12.0];
+    r.rectangle([0.0, 0.8, 0.0, 1.0], app.camera.to_window(start, board));
+    if editor.anchor.is_some() {
+        let (top_left, bottom_right) = editor.selection();
+        let [left, top, _, _] = cell_rect(top_left);
+        let width = (bottom_right.0 - top_left.0 + 1) as f64 * BLOCK_SIZE;
+        let height = (bottom_right.1 - top_left.1 + 1) as f64 * BLOCK_SIZE;
+        let rect = [left, top, width, height];
+        r.rectangle([1.0, 0.8, 0.0, 0.25], app.camera.to_window(rect, board));
+    }
+    let [left, top, size, _] = cell_rect(editor.cursor);
+    if editor.pen_down {
+        let color = match editor.brush {
+            Tile::Floor => [1.0, 1.0, 1.0, 0.3],
+            Tile::Wall => WALL,
+            Tile::Mud => MUD,
+            Tile::Lane(_) => LANE_ARROW,
+            Tile::Gate(_) => GATE_ARROW,
+        };
+        r.rectangle(color, app.camera.to_window([left, top, size, size], board));
+    }
+    for side in [
+        [left, top, size, 2.0],
+        [left, top + size - 2.0, size, 2.0],
+        [left, top, 2.0, size],
+        [left + size - 2.0, top, 2.0, size],
+    ] {
+        r.rectangle([1.0, 1.0, 1.0, 1.0], app.camera.to_window(side, board));
+    }
+
+    let name = |text: &str, field| {
+        if app.level_field == Some(field) {
+            format!("{}_", text)
+        } else if text.is_empty() {
+            "-".to_string()
+        } else {
+            text.to_string()
+        }
+    };
+    let status = format!(
+        "{} by {}  Brush: {}  Pen {}  Undo {}  Redo {}{}",
+        name(&editor.name, LevelField::Name),
+        name(&editor.author, LevelField::Author),
+        editor.brush.name(),
+        if editor.pen_down { "down" } else { "up" },
+        editor.undo_steps(),
+        editor.redo_steps(),
+        if editor.clipboard.is_some() {
+            "  Clipboard full"
+        } else {
+            ""
+        }
+    );
+    let line = Line::new(status, 16, [1.0, 1.0, 1.0, 1.0]);
+    ui.draw_text(r, &line, 10.0, 20.0);
+    let line = Line::new(app.menu_message.as_str(), 16, [1.0, 0.8, 0.0, 1.0]);
+    ui.draw_text(r, &line, 10.0, 40.0);
+    let help = if app.level_field.is_some() {
+        "Type, Enter when Done"
+    } else {
+        "Space Pen, Tab Brush, Z/Y Undo/Redo, M Mark, C Copy, V Paste, N Name, B Author, \
+         X Export, Enter Play, Backspace Leave"
+    };
+    let line = Line::new(help, 14, [1.0, 1.0, 1.0, 0.8]);
+    ui.draw_centered(r, &line, view_height - 15.0 * ui.scale);
+}
+
+fn medal_color(medal: Medal) -> Color {
+    match medal {
+        Medal::Bronze => [0.8, 0.5, 0.2, 1.0],
+        Medal::Silver => [0.75, 0.75, 0.8, 1.0],
+        Medal::Gold => [1.0, 0.8, 0.0, 1.0],
+    }
+}
+
+// Knocked down walls: a rubble strip along the inside of the board edge, where the
+// snake can now pass through to the other side
+fn draw_breaches(scene: Scene, r: &mut dyn Renderer) {
+    let game = scene.game;
+    let board = (game.width, game.height);
+    let (right, bottom) = (
+        game.width as f64 * BLOCK_SIZE,
+        game.height as f64 * BLOCK_SIZE,
+    );
+    for &(x, y) in &game.open_walls {
+        let (cell_x, cell_y) = (x as f64 * BLOCK_SIZE, y as f64 * BLOCK_SIZE);
+        let rect = if x < 0 {
+            [0.0, cell_y, 4.0, BLOCK_SIZE]
+        } else if x >= game.width {
+            [right - 4.0, cell_y, 4.0, BLOCK_SIZE]
+        } else if y < 0 {
+            [cell_x, 0.0, BLOCK_SIZE, 4.0]
+        } else {
+            [cell_x, bottom - 4.0, BLOCK_SIZE, 4.0]
+        };
+        r.rectangle([0.7, 0.2, 0.15, 1.0], scene.camera.to_window(rect, board));
+    }
+}
+
+// The magnet crane: a yellow cab with a red magnet, one block per covered cell so it
+// splits properly across a wrapping edge
+fn draw_boss(scene: Scene, r: &mut dyn Renderer, boss: &Boss) {
+    let board = (scene.game.width, scene.game.height);
+    for (i, (x, y)) in boss.cells(board).into_iter().enumerate() {
+        let rect = [
+            x as f64 * BLOCK_SIZE,
+            y as f64 * BLOCK_SIZE,
+            BLOCK_SIZE,
+            BLOCK_SIZE,
+        ];
+        r.rectangle([0.85, 0.65, 0.1, 1.0], scene.camera.to_window(rect, board));
+        // The bottom row carries the magnet
+        if i >= 2 {
+            let magnet = [
+                rect[0] + BLOCK_SIZE * 0.15,
+                rect[1] + BLOCK_SIZE * 0.35,
+                BLOCK_SIZE * 0.7,
+                BLOCK_SIZE * 0.5,
+            ];
+            r.rectangle([0.8, 0.1, 0.1, 1.0], scene.camera.to_window(magnet, board));
+        }
+    }
+}
+
+// One snake segment filling `rect` (window units)
+fn draw_segment(r: &mut dyn Renderer, shape: Shape, color: Color, rect: [f64; 4]) {
+    let [x, y, w, h] = rect;
+    match shape {
+        Shape::Block => r.rectangle(color, rect),
+        Shape::Rounded => {
+            // A wide and a tall rectangle, which leaves the corners cut off
+            let (cut_x, cut_y) = (w * 0.2, h * 0.2);
+            r.rectangle(color, [x, y + cut_y, w, h - 2.0 * cut_y]);
+            r.rectangle(color, [x + cut_x, y, w - 2.0 * cut_x, h]);
+        }
+        Shape::Plated => {
+            let rim = [color[0] * 0.6, color[1] * 0.6, color[2] * 0.6, color[3]];
+            let (inset_x, inset_y) = (w * 0.15, h * 0.15);
+            r.rectangle(rim, rect);
+            r.rectangle(
+                color,
+                [
+                    x + inset_x,
+                    y + inset_y,
+                    w - 2.0 * inset_x,
+                    h - 2.0 * inset_y,
+                ],
+            );
+        }
+    }
+}
+
+// Shiny metal, in the food and inside full stomachs
+const METAL: Color = [0.8, 0.8, 0.8, 1.0];
+
+// What a stomach segment holds: a full one shows the metal it carries, an empty one
+// is hollowed out to an outline, still waiting for its water
+fn draw_stomach(r: &mut dyn Renderer, segment_type: &SegmentType, rect: [f64; 4], hollow: Color) {
+    let [x, y, w, h] = rect;
+    match segment_type {
+        SegmentType::FullStomach => {
+            let (inset_x, inset_y) = (w * 0.3, h * 0.3);
+            r.rectangle(METAL, [x + inset_x, y + inset_y, w * 0.4, h * 0.4]);
+            // Glint in the top left corner
+            r.rectangle(
+                [1.0, 1.0, 1.0, 1.0],
+                [x + inset_x, y + inset_y, w * 0.12, h * 0.12],
+            );
+        }
+        SegmentType::EmptyStomach => {
+            let (inset_x, inset_y) = (w * 0.2, h * 0.2);
+            r.rectangle(hollow, [x + inset_x, y + inset_y, w * 0.6, h * 0.6]);
+        }
+        _ => {}
+    }
+}
+
+// How close to the edge ahead the head gets before the wrap-around hints appear
+const WRAP_HINT_CELLS: i32 = 2;
+
+// Drawn under the snake: body links that cross an edge get a stub out to each edge,
+// so the snake visibly carries on at the other side instead of breaking in two
+fn draw_wrap_links(scene: Scene, r: &mut dyn Renderer) {
+    let game = scene.game;
+    let board = (game.width, game.height);
+
+    for pair in game.snake.body.windows(2) {
+        let (a, b) = (pair[0].position, pair[1].position);
+        let side = if a.0 - b.0 > 1 {
+            Direction::Right
+        } else if b.0 - a.0 > 1 {
+            Direction::Left
+        } else if a.1 - b.1 > 1 {
+            Direction::Down
+        } else if b.1 - a.1 > 1 {
+            Direction::Up
+        } else {
+            continue;
+        };
+        // `a` sits against the `side` edge and `b` against the opposite one
+        let color = [0.0, 0.6, 0.0, 1.0];
+        r.rectangle(
+            color,
+            scene.camera.to_window(edge_stub(b, side.opposite()), board),
+        );
+        r.rectangle(color, scene.camera.to_window(edge_stub(a, side), board));
+    }
+}
+
+// A scrap cluster's pieces get a gold frame that fades as its time runs out, and
+// blinks for the last few moves
+fn draw_cluster(scene: Scene, r: &mut dyn Renderer, cluster: &Cluster) {
+    let game = scene.game;
+    let board = (game.width, game.height);
+    let time_left = cluster.time_left();
+    if time_left < 0.25 && (game.frame_count / 15).is_multiple_of(2) {
+        return;
+    }
+    let color = [1.0, 0.8, 0.2, 0.3 + 0.7 * time_left as f32];
+    let line = 2.0;
+    for food in game.foods.iter().filter(|f| f.cluster == Some(cluster.id)) {
+        if game.grid == GridKind::Hex {
+            draw_hexagon(scene, r, color, food.position, BLOCK_SIZE * 0.4);
+            continue;
+        }
+        let (x, y) = cell_origin(game, food.position);
+        let sides = [
+            [x, y, BLOCK_SIZE, line],
+            [x, y + BLOCK_SIZE - line, BLOCK_SIZE, line],
+            [x, y, line, BLOCK_SIZE],
+            [x + BLOCK_SIZE - line, y, line, BLOCK_SIZE],
+        ];
+        for side in sides {
+            r.rectangle(color, scene.camera.to_window(side, board));
+        }
+    }
+}
+
+// A custom topology marks every edge that isn't a plain wall with a thin band: blue
+// to wrap, yellow to bounce and purple to twist
+fn draw_edges(scene: Scene, r: &mut dyn Renderer, topology: &Topology) {
+    let game = scene.game;
+    let board = (game.width, game.height);
+    let (width, height) = (
+        game.width as f64 * BLOCK_SIZE,
+        game.height as f64 * BLOCK_SIZE,
+    );
+    let band = BLOCK_SIZE / 6.0;
+    let edges = [
+        (topology.left, [0.0, 0.0, band, height]),
+        (topology.right, [width - band, 0.0, band, height]),
+        (topology.top, [0.0, 0.0, width, band]),
+        (topology.bottom, [0.0, height - band, width, band]),
+    ];
+    for (edge, rect) in edges {
+        let color = match edge {
+            Edge::Wall => continue,
+            Edge::Wrap => [0.3, 0.6, 1.0, 0.5],
+            Edge::Bounce => [1.0, 0.85, 0.2, 0.6],
+            Edge::Twist => [0.75, 0.35, 1.0, 0.6],
+        };
+        r.rectangle(color, scene.camera.to_window(rect, board));
+    }
+}
+
+// When the head is about to leave the board, a ghost head marks where it comes back
+// in, with arrows on both edges
+fn draw_wrap_preview(scene: Scene, r: &mut dyn Renderer) {
+    let game = scene.game;
+    let board = (game.width, game.height);
+    let head = game.snake.body[0].position;
+    let direction = game.snake.direction;
+    let (right, bottom) = (game.width - 1, game.height - 1);
+    // Cells left before the edge, the last cell before it and the cell the head comes
+    // back in on
+    let (ahead, exit, entry) = match direction {
+        Direction::Right => (right - head.0, (right, head.1), (0, head.1)),
+        Direction::Left => (head.0, (0, head.1), (right, head.1)),
+        Direction::Up => (head.1, (head.0, 0), (head.0, bottom)),
+        Direction::Down => (bottom - head.1, (head.0, bottom), (head.0, 0)),
+    };
+    if ahead >= WRAP_HINT_CELLS || game.game_over {
+        return;
+    }
+    let ghost = [
+        entry.0 as f64 * BLOCK_SIZE,
+        entry.1 as f64 * BLOCK_SIZE,
+        BLOCK_SIZE,
+        BLOCK_SIZE,
+    ];
+    r.rectangle([0.0, 0.7, 0.0, 0.35], scene.camera.to_window(ghost, board));
+
+    // One arrow just inside the edge the head is heading for, one just inside the edge
+    // it comes back in through, both pointing the way it moves
+    let color = [1.0, 1.0, 1.0, 0.7];
+    draw_arrow(scene, r, board, color, exit, direction);
+    draw_arrow(scene, r, board, color, entry, direction);
+}
+
+// Half a cell from the middle of `cell` to its edge on `side`, in board pixels
+fn edge_stub(cell: (i32, i32), side: Direction) -> [f64; 4] {
+    let (x, y) = (cell.0 as f64 * BLOCK_SIZE, cell.1 as f64 * BLOCK_SIZE);
+    let (half, thin, inset) = (BLOCK_SIZE / 2.0, BLOCK_SIZE * 0.6, BLOCK_SIZE * 0.2);
+    match side {
+        Direction::Right => [x + half, y + inset, half, thin],
+        Direction::Left => [x, y + inset, half, thin],
+        Direction::Up => [x + inset, y, thin, half],
+        Direction::Down => [x + inset, y + half, thin, half],
+    }
+}
+
+// Chevron in the middle of a cell pointing in `direction`
+fn draw_arrow(
+    scene: Scene,
+    r: &mut dyn Renderer,
+    board: (i32, i32),
+    color: Color,
+    cell: (i32, i32),
+    direction: Direction,
+) {
+    let center = (
+        (cell.0 as f64 + 0.5) * BLOCK_SIZE,
+        (cell.1 as f64 + 0.5) * BLOCK_SIZE,
+    );
+    let (dx, dy) = match direction {
+        Direction::Right => (1.0, 0.0),
+        Direction::Left => (-1.0, 0.0),
+        Direction::Up => (0.0, -1.0),
+        Direction::Down => (0.0, 1.0),
+    };
+    let size = BLOCK_SIZE * 0.3;
+    let tip = (center.0 + dx * size, center.1 + dy * size);
+    let wings = [
+        (
+            center.0 - dx * size + dy * size,
+            center.1 - dy * size + dx * size,
+        ),
+        (
+            center.0 - dx * size - dy * size,
+            center.1 - dy * size - dx * size,
+        ),
+    ];
+    let to_window = |(x, y): (f64, f64)| scene.camera.to_window([x, y, 0.0, 0.0], board);
+    let tip = to_window(tip);
+    for wing in wings {
+        let wing = to_window(wing);
+        r.line(color, 2.0, [wing[0], wing[1], tip[0], tip[1]]);
+    }
+}
+
+// How fast the snake goes next to how fast it started, top right under the timers.
+// A speed-up on its way flashes with the pace it's about to reach.
+fn draw_speed(app: &App, ui: &Ui, r: &mut dyn Renderer, view_width: f64) {
+    let game = &app.game;
+    let effects = app.settings.effects_enabled();
+    let pace = |interval: u64| game.base_interval as f64 / interval.max(1) as f64;
+    let (text, color) = match game.speed_up {
+        Some(speed_up) => {
+            let text = format!(
+                "SPEED x{:.2} > x{:.2}",
+                pace(game.tick_interval),
+                pace(speed_up.interval)
+            );
+            // Held lit with reduced motion
+            let lit = (speed_up.frames_left / SPEED_FLASH_FRAMES).is_multiple_of(2) || !effects;
+            let color = if lit {
+                [1.0, 0.8, 0.0, 1.0]
+            } else {
+                [1.0, 1.0, 1.0, 0.5]
+            };
+            (text, color)
+        }
+        None => (
+            format!("SPEED x{:.2}", pace(game.tick_interval)),
+            [0.9, 0.9, 0.9, 1.0],
+        ),
+    };
+    let line = Line::new(text, 14, color);
+    let x = view_width / ui.scale - 10.0 - r.text_width(&line.text, ui.font_size(14)) / ui.scale;
+    ui.draw_text(r, &line, x, 60.0);
+}
+
+// Translucent strip across the top of the window with recent output and the prompt
+fn draw_console(console: &Console, ui: &Ui, r: &mut dyn Renderer) {
+    let line_height = 18.0;
+    let height = (console.output.len() as f64 + 1.0) * line_height + 8.0;
+    let width = r.view_size()[0];
+    r.rectangle([0.0, 0.0, 0.0, 0.75], [0.0, 0.0, width, height * ui.scale]);
+    let mut y = line_height;
+    for text in &console.output {
+        let line = Line::new(text.as_str(), 14, [0.8, 0.8, 0.8, 1.0]);
+        ui.draw_text(r, &line, 6.0, y);
+        y += line_height;
+    }
+    let prompt = Line::new(format!("> {}_", console.input), 14, [0.0, 1.0, 0.0, 1.0]);
+    ui.draw_text(r, &prompt, 6.0, y);
+}
@@ -0,0 +1,28 @@
+// Rain: now and then a shower leaves a few puddles on the board. Passing over one
+// drinks it like a small water, and whatever isn't drunk dries up after a while.
+
+pub const RAIN_CHANCE: u32 = 500; // 1 in this many ticks, with no puddles around
+pub const PUDDLES_PER_RAIN: usize = 4;
+pub const PUDDLE_MOVES: u32 = 60; // Snake moves before a puddle has dried up
+pub const PUDDLE_POINTS: u32 = 2;
+pub const PUDDLE_GROWTH: usize = 2; // Segments grown from a puddle, against 5 for water
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Puddle {
+    pub position: (i32, i32),
+    pub moves_left: u32,
+}
+
+impl Puddle {
+    pub fn new(position: (i32, i32)) -> Puddle {
+        Puddle {
+            position,
+            moves_left: PUDDLE_MOVES,
+        }
+    }
+
+    // How much is left, 1.0 when fresh down to nothing
+    pub fn depth(&self) -> f64 {
+        self.moves_left as f64 / PUDDLE_MOVES as f64
+    }
+}
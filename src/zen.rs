@@ -0,0 +1,100 @@
+// Zen: an ambient screensaver. The Hard bot plays an endless game on a board of its
+// own under zen rules (kids mode's slow pace and harmless metal, with the walls
+// wrapping), and a run that ends anyway quietly starts over. It's drawn without a HUD,
+// in soft colors that drift slowly round the wheel. Any input ends it.
+
+use crate::ai::{Ai, Difficulty};
+use crate::headless::Controller;
+use crate::render::{Color, Renderer};
+use crate::Game;
+
+const DRIFT_FRAMES: f64 = 7200.0; // Once round the color wheel, about a minute
+const BODY_SPREAD: f64 = 0.35; // Of the wheel, from head to tail
+const CELL_INSET: f64 = 0.12; // Of a cell, left between neighbouring squares
+
+pub struct Zen {
+    game: Game,
+    bot: Ai,
+    seed: u64,
+    board: (i32, i32),
+    frame: u64,
+}
+
+impl Zen {
+    pub fn new(seed: u64, board: (i32, i32)) -> Zen {
+        Zen {
+            game: zen_game(seed, board),
+            bot: Ai::new(Difficulty::Hard, seed),
+            seed,
+            board,
+            frame: 0,
+        }
+    }
+
+    // One frame: the bot steers when a move is due, and a finished run starts over
+    pub fn update(&mut self) {
+        self.frame += 1;
+        if self.game.game_over {
+            self.seed = self.seed.wrapping_add(1);
+            self.game = zen_game(self.seed, self.board);
+        }
+        if self.game.tick_due() {
+            if let Some(direction) = self.bot.next_move(&self.game) {
+                self.game.steer(direction);
+            }
+        }
+        self.game.update();
+    }
+
+    pub fn draw(&self, r: &mut dyn Renderer) {
+        let [view_width, view_height] = r.view_size();
+        let phase = self.frame as f64 / DRIFT_FRAMES;
+        let background = tint(phase + 0.5, 0.12);
+        r.clear(background);
+        let (width, height) = (self.game.width as f64, self.game.height as f64);
+        let cell = (view_width / width).min(view_height / height);
+        let origin = [
+            (view_width - cell * width) / 2.0,
+            (view_height - cell * height) / 2.0,
+        ];
+        let square = |(x, y): (i32, i32), scale: f64| {
+            let inset = cell * (1.0 - scale * (1.0 - 2.0 * CELL_INSET)) / 2.0;
+            [
+                origin[0] + x as f64 * cell + inset,
+                origin[1] + y as f64 * cell + inset,
+                cell - 2.0 * inset,
+                cell - 2.0 * inset,
+            ]
+        };
+        for food in &self.game.foods {
+            let mut color = tint(phase + 0.25, 0.75);
+            color[3] = 0.6;
+            r.rectangle(color, square(food.position, 0.5));
+        }
+        let length = self.game.snake.body.len() as f64;
+        for (i, segment) in self.game.snake.body.iter().enumerate().rev() {
+            let along = i as f64 / length;
+            let mut color = tint(phase + along * BODY_SPREAD, 0.7);
+            color[3] = (1.0 - along * 0.6) as f32;
+            r.rectangle(color, square(segment.position, 1.0 - along * 0.3));
+        }
+    }
+}
+
+fn zen_game(seed: u64, board: (i32, i32)) -> Game {
+    let mut game = Game::headless(seed, board);
+    game.set_kids();
+    game.ranked = false;
+    let direction = game.snake.direction;
+    game.steer(direction);
+    game
+}
+
+// A pastel at `phase` round the color wheel, as light as `lightness`
+fn tint(phase: f64, lightness: f64) -> Color {
+    let channel = |offset: f64| {
+        let wave = 0.5 + 0.5 * ((phase + offset) * std::f64::consts::TAU).cos();
+        (lightness * (0.6 + 0.4 * wave)) as f32
+    };
+    [channel(0.0), channel(1.0 / 3.0), channel(2.0 / 3.0), 1.0]
+}
@@ -0,0 +1,1547 @@
+// Scripted scenarios for the game rules, run against the headless `Game`. Each test
+// lays out the board by hand, moves the snake one tick and checks the exact result.
+
+use snake_game::adaptive::{Adaptive, MAX_LEVEL};
+use snake_game::ai::{Ai, Difficulty};
+use snake_game::boss::Boss;
+use snake_game::clusters::{Cluster, CLUSTER_BONUS};
+use snake_game::editor::LevelEditor;
+use snake_game::env::{Encoding, SnakeEnv, FEATURES};
+use snake_game::events::{Effect, GameEvent};
+use snake_game::game::{
+    self, DeathCause, Direction, Food, FoodType, Game, HighScoreEntry, Segment, SegmentType,
+    HEIGHT, SCORE_BACKUPS, WIDTH,
+};
+use snake_game::grid::GridKind;
+use snake_game::headless;
+use snake_game::levels::{Layout, LevelFile, Medal, Tile};
+use snake_game::mutators::Mutators;
+use snake_game::names::{self, MAX_NAME_LENGTH};
+use snake_game::puzzle::Puzzle;
+use snake_game::rats::Rat;
+use snake_game::rooms::ROOM_QUOTA;
+use snake_game::rules::{Goal, InputTiming, LengthSpeed, Rules, SLOW_START_FOODS};
+use snake_game::scrolling::SCROLL_EVERY;
+use snake_game::topology::{Edge, Topology};
+use snake_game::transitions;
+use snake_game::weather::{Puddle, PUDDLE_GROWTH, PUDDLE_MOVES, PUDDLE_POINTS};
+
+const SEED: u64 = 42;
+
+// A started game with the given body (head first) and foods, moving one cell per update
+fn game(body: &[((i32, i32), SegmentType)], direction: Direction, foods: Vec<Food>) -> Game {
+    let mut game = Game::headless(SEED, (WIDTH, HEIGHT));
+    game.tick_interval = 1;
+    game.steer(direction);
+    game.snake.body = body
+        .iter()
+        .map(|(position, segment_type)| Segment {
+            position: *position,
+            segment_type: segment_type.clone(),
+        })
+        .collect();
+    game.foods = foods;
+    game
+}
+
+fn food(position: (i32, i32), food_type: FoodType) -> Food {
+    Food {
+        position,
+        food_type,
+        cluster: None,
+    }
+}
+
+fn segment_types(game: &Game) -> Vec<SegmentType> {
+    game.snake
+        .body
+        .iter()
+        .map(|s| s.segment_type.clone())
+        .collect()
+}
+
+fn positions(game: &Game) -> Vec<(i32, i32)> {
+    game.snake.body.iter().map(|s| s.position).collect()
+}
+
+#[test]
+fn shiny_metal_without_a_stomach_kills() {
+    let mut game = game(
+        &[((5, 5), SegmentType::Head)],
+        Direction::Right,
+        vec![food((6, 5), FoodType::ShinyMetal)],
+    );
+    game.update();
+
+    assert!(game.game_over);
+    assert_eq!(game.death_cause, Some(DeathCause::MetalTooShort));
+    assert_eq!(game.score, 0);
+}
+
+#[test]
+fn shiny_metal_with_every_stomach_full_gives_indigestion() {
+    let mut game = game(
+        &[
+            ((5, 5), SegmentType::Head),
+            ((4, 5), SegmentType::FullStomach),
+            ((3, 5), SegmentType::Tail),
+            ((2, 5), SegmentType::Tail),
+            ((1, 5), SegmentType::Tail),
+        ],
+        Direction::Right,
+        vec![food((6, 5), FoodType::ShinyMetal)],
+    );
+    game.update();
+
+    // Like metal past the stomach capacity, though there's only one stomach to fill
+    assert!(!game.game_over);
+    assert!(game.indigestion_frames > 0);
+    assert_eq!(game.score, 0);
+}
+
+#[test]
+fn shiny_metal_fills_an_empty_stomach() {
+    let mut game = game(
+        &[
+            ((5, 5), SegmentType::Head),
+            ((4, 5), SegmentType::EmptyStomach),
+            ((3, 5), SegmentType::Tail),
+            ((2, 5), SegmentType::Tail),
+            ((1, 5), SegmentType::Tail),
+        ],
+        Direction::Right,
+        vec![food((6, 5), FoodType::ShinyMetal)],
+    );
+    game.update();
+
+    assert!(!game.game_over);
+    assert_eq!(game.score, 2);
+    assert_eq!(
+        segment_types(&game),
+        [
+            SegmentType::Head,
+            SegmentType::FullStomach,
+            SegmentType::Tail,
+            SegmentType::Tail,
+            SegmentType::Tail,
+        ]
+    );
+    assert_eq!(positions(&game), [(6, 5), (5, 5), (4, 5), (3, 5), (2, 5)]);
+}
+
+#[test]
+fn water_with_a_full_stomach_scores_five_and_grows_five() {
+    let mut game = game(
+        &[
+            ((5, 5), SegmentType::Head),
+            ((4, 5), SegmentType::FullStomach),
+            ((3, 5), SegmentType::Tail),
+            ((2, 5), SegmentType::Tail),
+            ((1, 5), SegmentType::Tail),
+        ],
+        Direction::Right,
+        vec![food((6, 5), FoodType::Water)],
+    );
+    game.update();
+
+    assert!(!game.game_over);
+    assert_eq!(game.score, 5);
+    let mut expected = vec![SegmentType::Head, SegmentType::EmptyStomach];
+    expected.extend(std::iter::repeat_n(SegmentType::EmptyStomach, 5));
+    expected.extend(std::iter::repeat_n(SegmentType::Tail, 3));
+    assert_eq!(segment_types(&game), expected);
+    // The new segments start out stacked on the first tail segment and unfold as the
+    // snake moves on
+    assert_eq!(
+        positions(&game),
+        [
+            (6, 5),
+            (5, 5),
+            (4, 5),
+            (4, 5),
+            (4, 5),
+            (4, 5),
+            (4, 5),
+            (4, 5),
+            (3, 5),
+            (2, 5)
+        ]
+    );
+}
+
+#[test]
+fn water_with_nothing_to_wash_down_does_nothing() {
+    let mut game = game(
+        &[((5, 5), SegmentType::Head)],
+        Direction::Right,
+        vec![food((6, 5), FoodType::Water)],
+    );
+    game.update();
+
+    assert!(!game.game_over);
+    assert_eq!(game.score, 0);
+    assert_eq!(positions(&game), [(6, 5)]);
+}
+
+#[test]
+fn an_update_reports_what_happened_in_order() {
+    let mut game = game(
+        &[((5, 5), SegmentType::Head), ((4, 5), SegmentType::Tail)],
+        Direction::Right,
+        vec![food((6, 5), FoodType::RustyScrap)],
+    );
+    game.shield_moves = 1;
+    game.update();
+    assert_eq!(
+        game.events,
+        [
+            GameEvent::EffectExpired(Effect::Shield),
+            GameEvent::FoodEaten {
+                food: FoodType::RustyScrap,
+                cell: (6, 5)
+            },
+            GameEvent::SegmentAdded(2),
+        ]
+    );
+
+    // Each update starts a fresh list
+    game.wrap_around = false;
+    game.snake.body[0].position = (WIDTH - 1, 5);
+    game.update();
+    assert_eq!(
+        game.events,
+        [
+            GameEvent::Died(DeathCause::Wall),
+            GameEvent::HighScoreReached(1),
+        ]
+    );
+}
+
+#[test]
+fn wrap_around_crosses_every_edge() {
+    let cases = [
+        ((WIDTH - 1, 7), Direction::Right, (0, 7)),
+        ((0, 7), Direction::Left, (WIDTH - 1, 7)),
+        ((7, 0), Direction::Up, (7, HEIGHT - 1)),
+        ((7, HEIGHT - 1), Direction::Down, (7, 0)),
+    ];
+    for (start, direction, end) in cases {
+        let mut game = game(&[(start, SegmentType::Head)], direction, Vec::new());
+        assert!(game.wrap_around);
+        game.update();
+
+        assert!(
+            !game.game_over,
+            "died crossing the edge going {:?}",
+            direction
+        );
+        assert_eq!(positions(&game), [end], "going {:?}", direction);
+    }
+}
+
+#[test]
+fn an_extra_life_forgives_one_wall_hit() {
+    let mut game = game(
+        &[((WIDTH - 1, 7), SegmentType::Head)],
+        Direction::Right,
+        Vec::new(),
+    );
+    game.wrap_around = false;
+    game.extra_lives = 1;
+    game.update();
+
+    // The snake waits at the wall, shielded for long enough to turn away
+    assert!(!game.game_over);
+    assert_eq!(game.extra_lives, 0);
+    assert!(game.shield_moves > 0);
+    assert_eq!(positions(&game), [(WIDTH - 1, 7)]);
+
+    game.steer(Direction::Down);
+    game.update();
+    assert!(!game.game_over);
+    assert_eq!(positions(&game), [(WIDTH - 1, 8)]);
+}
+
+#[test]
+fn kids_mode_bounces_off_walls_and_shrugs_off_metal() {
+    let mut walled = game(
+        &[((WIDTH - 1, 7), SegmentType::Head)],
+        Direction::Right,
+        Vec::new(),
+    );
+    walled.wrap_around = false;
+    walled.set_kids();
+    assert_eq!(walled.tick_interval, 2, "half speed");
+    walled.tick_interval = 1;
+    walled.update();
+
+    // Turned along the wall instead of crashing into it
+    assert!(!walled.game_over);
+    let head = positions(&walled)[0];
+    assert!(head == (WIDTH - 1, 6) || head == (WIDTH - 1, 8));
+
+    // It bounces off the way it was steered, not the way it started out
+    let mut turned = game(
+        &[
+            ((5, 1), SegmentType::Head),
+            ((4, 1), SegmentType::Tail),
+            ((3, 1), SegmentType::Tail),
+        ],
+        Direction::Right,
+        Vec::new(),
+    );
+    turned.wrap_around = false;
+    turned.set_kids();
+    turned.tick_interval = 1;
+    turned.steer(Direction::Up);
+    turned.update();
+    assert_eq!(positions(&turned)[0], (5, 0));
+    turned.update();
+    let head = positions(&turned)[0];
+    assert!(
+        head == (4, 0) || head == (6, 0),
+        "along the wall, not stuck"
+    );
+    turned.update();
+    assert_ne!(positions(&turned)[0], head, "and it keeps going");
+
+    // Metal with no stomach to hold it just goes down
+    let mut game = game(
+        &[((5, 5), SegmentType::Head)],
+        Direction::Right,
+        vec![food((6, 5), FoodType::ShinyMetal)],
+    );
+    game.set_kids();
+    game.tick_interval = 1;
+    game.update();
+    assert!(!game.game_over);
+    assert_eq!(positions(&game), [(6, 5)]);
+}
+
+#[test]
+fn each_edge_of_a_topology_does_its_own_thing() {
+    let topology = Topology::parse("left:twist right:twist top:bounce bottom:wall").unwrap();
+    assert_eq!(
+        topology.text(),
+        "left:twist right:twist top:bounce bottom:wall"
+    );
+    assert_eq!(Topology::parse("").unwrap(), Topology::default());
+    assert!(Topology::parse("left:sideways").is_none());
+
+    let mut fresh = Game::headless(SEED, (WIDTH, HEIGHT));
+    fresh.set_topology(topology);
+    assert!(!fresh.wrap_around);
+    assert_eq!(fresh.topology, Some(topology));
+
+    // Off the right edge near the top, back in on the left near the bottom
+    let mut twisted = game(
+        &[((WIDTH - 1, 2), SegmentType::Head)],
+        Direction::Right,
+        Vec::new(),
+    );
+    twisted.set_topology(Topology::default());
+    assert_eq!(twisted.topology, None, "too late once the run has started");
+    twisted.wrap_around = false;
+    twisted.topology = Some(topology);
+    twisted.update();
+    assert!(!twisted.game_over);
+    assert_eq!(positions(&twisted), [(0, HEIGHT - 3)]);
+
+    // The top turns the snake along it, the bottom is a wall
+    let mut bounced = game(&[((7, 0), SegmentType::Head)], Direction::Up, Vec::new());
+    bounced.wrap_around = false;
+    bounced.topology = Some(topology);
+    bounced.update();
+    assert!(!bounced.game_over);
+    let head = positions(&bounced)[0];
+    assert!(head == (6, 0) || head == (8, 0));
+
+    // Steered onto a bounce edge, it turns along it from the way it was going
+    let mut steered = game(
+        &[
+            ((7, 1), SegmentType::Head),
+            ((6, 1), SegmentType::Tail),
+            ((5, 1), SegmentType::Tail),
+        ],
+        Direction::Right,
+        Vec::new(),
+    );
+    steered.wrap_around = false;
+    steered.topology = Some(topology);
+    steered.steer(Direction::Up);
+    steered.update();
+    steered.update();
+    assert!(!steered.game_over);
+    let head = positions(&steered)[0];
+    assert!(
+        head == (6, 0) || head == (8, 0),
+        "along the edge, not stuck"
+    );
+    steered.update();
+    assert_ne!(positions(&steered)[0], head, "and it keeps going");
+
+    let mut walled = game(
+        &[((7, HEIGHT - 1), SegmentType::Head)],
+        Direction::Down,
+        Vec::new(),
+    );
+    walled.wrap_around = false;
+    walled.topology = Some(topology);
+    walled.update();
+    assert!(walled.game_over);
+
+    let level = LevelFile::parse("topology=left:bounce right:bounce\n....\n").unwrap();
+    assert_eq!(level.topology.map(|t| t.right), Some(Edge::Bounce));
+    assert_eq!(level.text(), "topology=left:bounce right:bounce\n....\n");
+    assert!(LevelFile::parse("topology=left\n....\n").is_err());
+}
+
+#[test]
+fn the_water_chain_counts_down_as_the_snake_drinks() {
+    let mut game = game(
+        &[
+            ((5, 5), SegmentType::Head),
+            ((4, 5), SegmentType::FullStomach),
+            ((3, 5), SegmentType::FullStomach),
+            ((2, 5), SegmentType::Tail),
+        ],
+        Direction::Right,
+        vec![food((6, 5), FoodType::Water), food((7, 5), FoodType::Water)],
+    );
+    assert_eq!(game.full_stomachs(), 2);
+    game.update();
+    assert_eq!(game.full_stomachs(), 1);
+    game.update();
+    assert_eq!(game.full_stomachs(), 0);
+    assert_eq!(game.score, 10);
+}
+
+#[test]
+fn a_scrap_cluster_pays_a_bonus_in_time_and_rusts_away_together_if_not() {
+    let piece = |position| Food {
+        position,
+        food_type: FoodType::RustyScrap,
+        cluster: Some(1),
+    };
+    let mut eaten = game(
+        &[((5, 5), SegmentType::Head)],
+        Direction::Right,
+        vec![piece((6, 5)), piece((7, 5))],
+    );
+    eaten.cluster = Some(Cluster::new(1));
+    eaten.update();
+    assert_eq!(eaten.score, 1);
+    assert!(eaten.cluster.is_some(), "a piece is still out");
+    eaten.update();
+    assert_eq!(eaten.score, 2 + CLUSTER_BONUS);
+    assert!(eaten.cluster.is_none());
+    assert!(eaten.events.contains(&GameEvent::ClusterCleared((7, 5))));
+
+    // Time runs out on the last move, and every piece goes at once
+    let mut late = game(
+        &[((5, 5), SegmentType::Head)],
+        Direction::Right,
+        vec![piece((5, 9)), piece((6, 9)), piece((7, 9))],
+    );
+    late.cluster = Some(Cluster {
+        id: 1,
+        moves_left: 1,
+    });
+    late.update();
+    assert!(late.cluster.is_none());
+    assert!(late.foods.iter().all(|food| food.cluster.is_none()));
+    assert_eq!(late.score, 0);
+}
+
+#[test]
+fn a_spare_metal_bite_costs_stomach_instead_of_the_run() {
+    // Too short for metal, which would otherwise be the end of it
+    let mut game = game(
+        &[
+            ((5, 5), SegmentType::Head),
+            ((4, 5), SegmentType::FullStomach),
+            ((3, 5), SegmentType::EmptyStomach),
+            ((2, 5), SegmentType::Tail),
+        ],
+        Direction::Right,
+        vec![food((6, 5), FoodType::ShinyMetal)],
+    );
+    game.metal_bites = 1;
+    game.update();
+
+    assert!(!game.game_over);
+    assert_eq!(game.metal_bites, 0);
+    assert_eq!(game.score, 0);
+    assert_eq!(segment_types(&game), [SegmentType::Head, SegmentType::Tail]);
+}
+
+#[test]
+fn metal_past_stomach_capacity_gives_indigestion() {
+    let mut game = game(
+        &[
+            ((5, 5), SegmentType::Head),
+            ((4, 5), SegmentType::FullStomach),
+            ((3, 5), SegmentType::FullStomach),
+            ((2, 5), SegmentType::FullStomach),
+            ((1, 5), SegmentType::EmptyStomach),
+            ((0, 5), SegmentType::Tail),
+        ],
+        Direction::Right,
+        vec![food((6, 5), FoodType::ShinyMetal)],
+    );
+    assert!(!game.can_digest_metal(), "an empty stomach but no capacity");
+    game.update();
+
+    assert!(!game.game_over);
+    assert_eq!(game.score, 0);
+    assert!(game.indigestion_frames > 0);
+    assert_eq!(segment_types(&game)[4], SegmentType::EmptyStomach);
+
+    // Turns come a move late while it lasts
+    game.steer(Direction::Down);
+    game.update();
+    assert_eq!(positions(&game)[0], (7, 5));
+    game.update();
+    assert_eq!(positions(&game)[0], (7, 6));
+
+    // The bite still counts towards a goal like any other
+    let mut goal = self::game(
+        &[
+            ((5, 5), SegmentType::Head),
+            ((4, 5), SegmentType::FullStomach),
+            ((3, 5), SegmentType::EmptyStomach),
+            ((2, 5), SegmentType::Tail),
+            ((1, 5), SegmentType::Tail),
+        ],
+        Direction::Right,
+        vec![food((6, 5), FoodType::ShinyMetal)],
+    );
+    goal.rules.stomach_capacity = 1;
+    goal.rules.goal = Goal::parse("length:5");
+    goal.update();
+    assert!(goal.indigestion_frames > 0);
+    assert!(goal.won);
+}
+
+#[test]
+fn a_puddle_is_a_small_drink_of_water() {
+    let mut game = game(
+        &[
+            ((5, 5), SegmentType::Head),
+            ((4, 5), SegmentType::FullStomach),
+            ((3, 5), SegmentType::Tail),
+            ((2, 5), SegmentType::Tail),
+            ((1, 5), SegmentType::Tail),
+        ],
+        Direction::Right,
+        Vec::new(),
+    );
+    game.puddles = vec![Puddle::new((6, 5)), Puddle::new((10, 10))];
+    game.update();
+
+    assert_eq!(game.score, PUDDLE_POINTS);
+    assert_eq!(game.snake.body.len(), 5 + PUDDLE_GROWTH);
+    assert_eq!(segment_types(&game)[1], SegmentType::EmptyStomach);
+    assert_eq!(game.puddles.len(), 1);
+    assert_eq!(game.puddles[0].moves_left, PUDDLE_MOVES - 1);
+}
+
+#[test]
+fn reaching_the_goal_wins_the_run() {
+    let mut game = game(
+        &[((5, 5), SegmentType::Head)],
+        Direction::Right,
+        vec![food((6, 5), FoodType::RustyScrap)],
+    );
+    game.rules.goal = Goal::parse("length:2");
+    game.update();
+
+    assert!(game.game_over);
+    assert!(game.won);
+    assert_eq!(game.death_cause, None);
+}
+
+#[test]
+fn mutators_multiply_points() {
+    let mut game = game(
+        &[((5, 5), SegmentType::Head)],
+        Direction::Right,
+        vec![food((6, 5), FoodType::RustyScrap)],
+    );
+    game.mutators = Mutators {
+        double_speed: true,
+        tiny_board: true,
+        ..Mutators::default()
+    };
+    game.update();
+
+    // 1 point x 1.5 x 1.25, rounded
+    assert_eq!(game.score, 2);
+}
+
+#[test]
+fn the_crane_breaks_down_on_its_third_piece_of_metal() {
+    let mut game = game(
+        &[((5, 5), SegmentType::Head)],
+        Direction::Right,
+        vec![food((11, 10), FoodType::ShinyMetal)],
+    );
+    game.boss = Some(Boss {
+        position: (10, 10),
+        metal: 2,
+        ticks_left: 100,
+    });
+    game.update();
+
+    assert!(!game.game_over);
+    assert!(game.boss.is_none());
+    assert_eq!(game.score, 25);
+}
+
+#[test]
+fn running_into_a_rat_squashes_it_for_points() {
+    let mut game = game(&[((5, 5), SegmentType::Head)], Direction::Right, Vec::new());
+    game.rats = vec![Rat { position: (6, 5) }];
+    game.update();
+
+    assert!(!game.game_over);
+    assert!(!game.rats.iter().any(|rat| rat.position == (6, 5)));
+    assert_eq!(game.score, 3);
+}
+
+#[test]
+fn spit_shot_takes_out_a_rat_down_the_line() {
+    let mut game = game(&[((5, 5), SegmentType::Head)], Direction::Right, Vec::new());
+    game.rats = vec![Rat { position: (7, 5) }];
+    game.spit_ammo = 1;
+    assert!(game.spit());
+    assert!(!game.spit(), "out of ammo");
+    game.update();
+
+    // The head only reached (6, 5), the shot flew on ahead
+    assert!(game.shots.is_empty());
+    assert!(!game.rats.iter().any(|rat| rat.position == (7, 5)));
+    assert_eq!(game.score, 3);
+    // Reported where it happened, for the sound to come from there
+    assert_eq!(game.hits, [(7, 5)]);
+}
+
+#[test]
+fn wall_breaker_knocks_a_permanent_hole_in_the_wall() {
+    let mut game = game(
+        &[((WIDTH - 2, 7), SegmentType::Head)],
+        Direction::Right,
+        Vec::new(),
+    );
+    game.wrap_around = false;
+    game.breaker = Some((WIDTH - 1, 7));
+    game.update();
+    assert!(game.wall_breaker);
+    assert_eq!(game.breaker, None);
+
+    game.update();
+    assert!(!game.game_over);
+    assert!(!game.wall_breaker);
+    assert_eq!(positions(&game), [(0, 7)]);
+
+    // The hole stays open, both ways
+    assert_eq!(
+        game.neighbor((WIDTH - 1, 7), Direction::Right),
+        Some((0, 7))
+    );
+    assert_eq!(game.neighbor((0, 7), Direction::Left), Some((WIDTH - 1, 7)));
+    assert_eq!(game.neighbor((0, 8), Direction::Left), None);
+}
+
+#[test]
+fn layout_walls_block_even_with_wrap_around() {
+    let mut layout = Layout::new((WIDTH, HEIGHT));
+    layout.set((0, 5), Tile::Wall);
+    layout.set((3, 5), Tile::Wall);
+    let mut game = game(
+        &[((WIDTH - 1, 5), SegmentType::Head)],
+        Direction::Right,
+        Vec::new(),
+    );
+    game.layout = Some(layout);
+    assert!(!game.free_cells().contains(&(0, 5)));
+    game.update();
+    assert_eq!(game.death_cause, Some(DeathCause::Wall));
+
+    // A wall-breaker knocks the tile down for good
+    let mut game = game_with_breaker(game.layout.take().unwrap());
+    game.update();
+    assert!(!game.game_over);
+    assert_eq!(positions(&game), [(3, 5)]);
+    assert!(!game.layout.as_ref().unwrap().is_wall((3, 5)));
+}
+
+#[test]
+fn a_one_way_gate_is_a_wall_unless_gone_into_its_way() {
+    let layout = Layout::parse("...\n.→.\n...\n...\n").unwrap();
+    assert_eq!(layout.gate((1, 1)), Some(Direction::Right));
+    assert_eq!(layout.text(), "...\n.→.\n...\n...\n");
+
+    let cases = [
+        ((0, 1), Direction::Right, None),
+        ((2, 1), Direction::Left, Some(DeathCause::Wall)),
+        ((1, 0), Direction::Down, Some(DeathCause::Wall)),
+    ];
+    for (start, direction, death) in cases {
+        let mut game = game(&[(start, SegmentType::Head)], direction, Vec::new());
+        game.layout = Some(layout.clone());
+        assert_eq!(game.neighbor(start, direction).is_some(), death.is_none());
+        game.update();
+        assert_eq!(game.death_cause, death, "going {:?}", direction);
+    }
+}
+
+#[test]
+fn mud_halves_the_pace_and_turns_a_move_late() {
+    let mut game = game(&[((1, 2), SegmentType::Head)], Direction::Right, Vec::new());
+    game.layout = Some(Layout::parse(".....\n.....\n.~~..\n").unwrap());
+    game.update();
+    assert_eq!(positions(&game), [(1, 2)], "held back");
+    game.update();
+    assert_eq!(positions(&game), [(2, 2)]);
+
+    game.steer(Direction::Up);
+    game.update();
+    game.update();
+    assert_eq!(positions(&game), [(3, 2)], "out of the mud before the turn");
+    game.update();
+    assert_eq!(positions(&game), [(3, 1)]);
+}
+
+#[test]
+fn a_speed_lane_doubles_the_step_going_its_way_only() {
+    let mut layout = Layout::parse(">>.#\n....\n").unwrap();
+    layout.set((1, 1), Tile::Lane(Direction::Up));
+    assert_eq!(layout.text(), ">>.#\n.^..\n");
+
+    // Onto the lane and a cell further, eating what's on the cell in between
+    let mut along = game(
+        &[((0, 1), SegmentType::Head)],
+        Direction::Up,
+        vec![food((1, 0), FoodType::RustyScrap)],
+    );
+    along.layout = Some(layout.clone());
+    along.update();
+    assert_eq!(positions(&along)[0], (0, 0), "the lane goes right, not up");
+    along.steer(Direction::Right);
+    along.update();
+    assert_eq!(positions(&along)[0], (2, 0));
+    assert_eq!(along.foods_eaten, 1);
+
+    // The second cell is checked like any move
+    layout.set((2, 0), Tile::Lane(Direction::Right));
+    let mut game = game(&[((1, 0), SegmentType::Head)], Direction::Right, Vec::new());
+    game.layout = Some(layout);
+    game.update();
+    assert_eq!(game.death_cause, Some(DeathCause::Wall));
+}
+
+fn game_with_breaker(layout: Layout) -> Game {
+    let mut game = game(&[((2, 5), SegmentType::Head)], Direction::Right, Vec::new());
+    game.layout = Some(layout);
+    game.wall_breaker = true;
+    game
+}
+
+#[test]
+fn layouts_round_trip_through_text() {
+    let text = "##..\n....\n...#\n";
+    let layout = Layout::parse(text).unwrap();
+    assert_eq!((layout.width, layout.height), (4, 3));
+    assert!(layout.is_wall((1, 0)) && layout.is_wall((3, 2)));
+    // The start stays open whatever the file says
+    assert!(!Layout::parse("....\n..#.\n").unwrap().is_wall((2, 1)));
+    assert_eq!(layout.text(), text);
+    assert!(Layout::parse("...\n..\n").is_err());
+    assert!(Layout::parse("..x\n").is_err());
+}
+
+#[test]
+fn level_files_carry_their_name_author_and_mutators() {
+    let text = "name=Cross Roads!\nauthor=Simon\nmutators=double_speed+no_water\n#...\n....\n";
+    let level = LevelFile::parse(text).unwrap();
+    assert_eq!(
+        (level.name.as_str(), level.author.as_str()),
+        ("Cross Roads!", "Simon")
+    );
+    assert_eq!(
+        level.mutators,
+        Mutators::parse("double_speed+no_water").unwrap()
+    );
+    assert!(level.layout.is_wall((0, 0)));
+    assert_eq!(level.text(), text);
+    assert_eq!(level.file_name(), "cross_roads.txt");
+
+    // Keys from newer versions are skipped and a bare layout is a level too
+    let level = LevelFile::parse("rating=5\n....\n").unwrap();
+    assert_eq!(level.layout, Layout::parse("....\n").unwrap());
+    assert!(level.name.is_empty() && !level.mutators.any());
+    assert!(LevelFile::parse("....\nname=Late\n").is_err());
+    assert!(LevelFile::parse("mutators=nonsense\n....\n").is_err());
+}
+
+#[test]
+fn a_puzzle_moves_a_press_at_a_time_and_can_take_moves_back() {
+    let text = "moves=4\nstars=2,3\nfood=scrap:5,1 water:6,1\n....\n";
+    let level = LevelFile::parse(text).unwrap();
+    assert_eq!(level.text(), text);
+    let puzzle = level.puzzle.unwrap();
+    assert_eq!([2, 3, 4].map(|moves| puzzle.stars(moves)), [3, 2, 1]);
+    assert!(LevelFile::parse("stars=2\n....\n").is_err(), "no moves");
+
+    let mut game = Game::headless(SEED, (WIDTH, HEIGHT));
+    let (x, y) = game.snake.body[0].position;
+    game.set_puzzle(Puzzle {
+        moves: 3,
+        stars: [Some(2), None],
+        foods: vec![
+            (FoodType::RustyScrap, (x + 1, y)),
+            (FoodType::RustyScrap, (x + 2, y)),
+        ],
+    });
+    game.steer(Direction::Right);
+    for _ in 0..20 {
+        game.update();
+    }
+    assert_eq!(positions(&game)[0], (x + 1, y), "one cell a press");
+    assert_eq!(game.moves_used, 1);
+
+    game.steer(Direction::Up);
+    game.update();
+    assert_eq!(positions(&game)[0], (x + 1, y - 1));
+    assert!(game.undo());
+    assert_eq!(positions(&game)[0], (x + 1, y));
+    assert_eq!(game.moves_used, 1);
+
+    game.steer(Direction::Right);
+    game.update();
+    assert!(game.won && game.game_over);
+    assert_eq!(game.stars(), Some(3));
+
+    // Out of moves with food left is a loss, which can be taken back as well
+    assert!(game.undo());
+    game.steer(Direction::Up);
+    game.update();
+    game.steer(Direction::Right);
+    game.update();
+    assert!(game.game_over && !game.won);
+    assert_eq!(game.stars(), None);
+    assert!(game.undo());
+    assert!(!game.game_over);
+}
+
+#[test]
+fn a_hurried_snake_moves_at_the_next_update() {
+    let mut hurried = game(&[((5, 5), SegmentType::Head)], Direction::Right, vec![]);
+    hurried.tick_interval = 5;
+    while !hurried.tick_due() {
+        hurried.update();
+    }
+    hurried.update();
+    assert_eq!(hurried.snake.body[0].position, (6, 5));
+
+    // Straight away, instead of four updates on
+    hurried.hurry();
+    assert!(hurried.tick_due());
+    hurried.update();
+    assert_eq!(hurried.snake.body[0].position, (7, 5));
+    assert!(!hurried.tick_due(), "only the one move");
+}
+
+#[test]
+fn the_best_medal_whose_par_is_reached_is_won() {
+    let level = LevelFile::parse("bronze=10\ngold=50\n....\n").unwrap();
+    assert_eq!(level.medal(9), None);
+    assert_eq!(level.medal(10), Some(Medal::Bronze));
+    // No silver par, so nothing between bronze and gold
+    assert_eq!(level.medal(49), Some(Medal::Bronze));
+    assert_eq!(level.medal(80), Some(Medal::Gold));
+    assert_eq!(level.text(), "bronze=10\ngold=50\n....\n");
+    assert!(LevelFile::parse("silver=lots\n....\n").is_err());
+}
+
+#[test]
+fn a_pen_stroke_is_one_undo_step() {
+    let mut editor = LevelEditor::new(Layout::new((10, 10)));
+    editor.cursor = (1, 1);
+    editor.toggle_pen();
+    editor.move_cursor((1, 0));
+    editor.move_cursor((1, 0));
+    editor.toggle_pen();
+    editor.move_cursor((0, 1));
+    editor.toggle_pen();
+    editor.toggle_pen();
+    let painted = editor.layout.clone();
+    assert!([(1, 1), (2, 1), (3, 1), (3, 2)]
+        .iter()
+        .all(|cell| painted.is_wall(*cell)));
+    assert_eq!(editor.undo_steps(), 2);
+
+    assert!(editor.undo());
+    assert!(!editor.layout.is_wall((3, 2)));
+    assert!(editor.undo());
+    assert_eq!(editor.layout, Layout::new((10, 10)));
+    assert!(!editor.undo());
+    assert!(editor.redo() && editor.redo());
+    assert_eq!(editor.layout, painted);
+
+    // A new change drops what was undone
+    editor.undo();
+    editor.toggle_pen();
+    assert_eq!(editor.redo_steps(), 0);
+}
+
+#[test]
+fn a_copied_rectangle_pastes_at_the_cursor() {
+    let mut layout = Layout::new((10, 10));
+    layout.set((1, 1), Tile::Wall);
+    layout.set((2, 2), Tile::Wall);
+    let mut editor = LevelEditor::new(layout);
+    editor.cursor = (2, 2);
+    editor.toggle_mark();
+    editor.move_cursor((-1, -1));
+    editor.copy();
+    assert!(editor.anchor.is_none());
+
+    editor.cursor = (8, 8);
+    assert!(editor.paste());
+    // Cut off at the edge
+    assert!(editor.layout.is_wall((8, 8)) && editor.layout.is_wall((9, 9)));
+    assert!(!editor.layout.is_wall((9, 8)));
+    assert!(!editor.paste(), "pasting the same again changes nothing");
+    assert!(editor.undo());
+    assert!(!editor.layout.is_wall((8, 8)));
+}
+
+#[test]
+fn an_expanding_board_grows_every_fifteen_points() {
+    let mut game = Game::headless(SEED, (WIDTH, HEIGHT));
+    let mutators = Mutators {
+        expanding_board: true,
+        ..Mutators::default()
+    };
+    game.set_mutators(mutators, (WIDTH, HEIGHT));
+    assert_eq!((game.width, game.height), (WIDTH / 2, HEIGHT / 2));
+
+    game.tick_interval = 1;
+    game.steer(Direction::Right);
+    let head = game.snake.body[0].position;
+    game.foods = vec![food((head.0 + 1, head.1), FoodType::Pumpkin)];
+    game.score = 13;
+    game.update();
+
+    // 3 points x 1.1, rounded, takes the score past 15
+    assert_eq!(game.score, 16);
+    assert_eq!((game.width, game.height), (WIDTH / 2 + 1, HEIGHT / 2 + 1));
+}
+
+#[test]
+fn a_room_door_opens_once_the_food_quota_is_met() {
+    let mut game = Game::headless(SEED, (WIDTH, HEIGHT));
+    let mutators = Mutators {
+        rooms: true,
+        ..Mutators::default()
+    };
+    game.set_mutators(mutators, (WIDTH, HEIGHT));
+    game.tick_interval = 1;
+    game.steer(Direction::Right);
+    let level = game.level.as_mut().unwrap();
+    let door = level.doors[0];
+    let before_door = (door.0 - 1, door.1);
+    assert!(game.foods.iter().all(|f| f.position.0 < door.0));
+    assert_eq!(game.neighbor(before_door, Direction::Right), None);
+
+    let level = game.level.as_mut().unwrap();
+    for _ in 0..ROOM_QUOTA {
+        level.ate();
+    }
+    assert_eq!(game.neighbor(before_door, Direction::Right), Some(door));
+
+    // Through the door, the food moves on to the next room
+    game.snake.body = vec![Segment {
+        position: door,
+        segment_type: SegmentType::Head,
+    }];
+    game.update();
+    let level = game.level.as_ref().unwrap();
+    assert!(!game.game_over);
+    assert_eq!(level.active, 1);
+    assert!(game.foods.iter().all(|f| level.in_active_room(f.position)));
+}
+
+#[test]
+fn a_scrolling_board_carries_the_snake_off_the_bottom() {
+    let mut game = Game::headless(SEED, (WIDTH, HEIGHT));
+    let mutators = Mutators {
+        scrolling: true,
+        ..Mutators::default()
+    };
+    game.set_mutators(mutators, (WIDTH, HEIGHT));
+    game.tick_interval = 1;
+    game.steer(Direction::Right);
+    game.snake.body = vec![Segment {
+        position: (5, HEIGHT - 2),
+        segment_type: SegmentType::Head,
+    }];
+    game.foods = vec![food((0, 0), FoodType::RustyScrap)];
+
+    for _ in 1..SCROLL_EVERY {
+        game.update();
+    }
+    assert!(!game.game_over);
+    assert_eq!(
+        positions(&game),
+        [(5 + SCROLL_EVERY as i32 - 1, HEIGHT - 2)]
+    );
+
+    // The next row down puts it on the bottom edge, then it's gone
+    game.update();
+    assert_eq!(positions(&game)[0].1, HEIGHT - 1);
+    for _ in 0..SCROLL_EVERY {
+        game.update();
+    }
+    assert!(game.game_over);
+    assert_eq!(game.death_cause, Some(DeathCause::Scrolled));
+}
+
+#[test]
+fn hex_steering_turns_a_sixth_at_a_time() {
+    let mut game = Game::headless(SEED, (WIDTH, HEIGHT));
+    game.set_grid(GridKind::Hex);
+    game.tick_interval = 1;
+    game.steer(Direction::Right);
+    game.snake.body = vec![Segment {
+        position: (5, 4),
+        segment_type: SegmentType::Head,
+    }];
+    game.foods = vec![food((0, 0), FoodType::RustyScrap)];
+    game.update();
+    assert_eq!(positions(&game), [(6, 4)]);
+
+    // Up and down mean nothing on a hex board, and only one turn counts per move
+    assert!(!game.steer(Direction::Up));
+    assert!(game.steer(Direction::Right));
+    assert!(!game.steer(Direction::Right));
+    game.update();
+    assert_eq!(positions(&game), [(6, 5)], "south-east from an even row");
+
+    assert!(game.steer(Direction::Right));
+    game.update();
+    assert_eq!(positions(&game), [(6, 6)], "south-west from an odd row");
+}
+
+// A started game on the diagonal board, heading north-east
+fn diagonal_game(body: &[((i32, i32), SegmentType)]) -> Game {
+    let mut game = Game::headless(SEED, (WIDTH, HEIGHT));
+    game.set_grid(GridKind::Diagonal);
+    game.tick_interval = 1;
+    game.steer(Direction::Right);
+    game.snake.body = body
+        .iter()
+        .map(|(position, segment_type)| Segment {
+            position: *position,
+            segment_type: segment_type.clone(),
+        })
+        .collect();
+    game.foods = vec![food((0, 0), FoodType::RustyScrap)];
+    // Both halves of the chord before the next move
+    assert!(game.steer(Direction::Up));
+    assert!(game.steer(Direction::Right));
+    game
+}
+
+#[test]
+fn a_chord_steers_diagonally() {
+    let mut game = diagonal_game(&[((5, 5), SegmentType::Head)]);
+    game.update();
+    assert!(!game.game_over);
+    assert_eq!(positions(&game), [(6, 4)]);
+}
+
+#[test]
+fn slipping_diagonally_through_the_body_is_a_collision() {
+    let mut game = diagonal_game(&[
+        ((5, 5), SegmentType::Head),
+        ((6, 5), SegmentType::EmptyStomach),
+        ((5, 4), SegmentType::Tail),
+    ]);
+    game.update();
+    assert!(game.game_over);
+    assert_eq!(game.death_cause, Some(DeathCause::SelfCollision));
+}
+
+#[test]
+fn a_heavy_snake_slows_down_as_it_grows() {
+    let rules = Rules {
+        length_speed: LengthSpeed::Heavy,
+        ..Rules::default()
+    };
+    assert_eq!(rules.tick_interval(10, 1), 10);
+    assert_eq!(rules.tick_interval(10, 13), 12);
+    assert_eq!(rules.tick_interval(10, 500), 20, "never below half speed");
+
+    let hard = Rules {
+        length_speed: LengthSpeed::Hard,
+        ..Rules::default()
+    };
+    assert_eq!(hard.tick_interval(10, 13), 8);
+    assert_eq!(hard.tick_interval(10, 500), 5, "never above double speed");
+
+    let mut game = game(&[((5, 5), SegmentType::Head)], Direction::Right, Vec::new());
+    game.rules = rules;
+    game.base_interval = 10;
+    game.tick_interval = 10;
+    for _ in 0..12 {
+        game.grow();
+    }
+    for _ in 0..10 {
+        game.update();
+    }
+    assert_eq!(game.tick_interval, 12);
+}
+
+#[test]
+fn rules_read_back_the_words_they_are_logged_as() {
+    let rules = Rules {
+        length_speed: LengthSpeed::Hard,
+        stomach_capacity: 5,
+        goal: Goal::parse("score:200"),
+        input_timing: InputTiming::Strict,
+        slow_start: true,
+        topology: Topology::parse("left:twist top:bounce").unwrap(),
+    };
+    let words = rules.words();
+    assert_eq!(
+        words.join(" "),
+        "length_speed:hard stomach_capacity:5 goal:score:200 input_timing:strict \
+         slow_start:on left:twist top:bounce"
+    );
+    let parsed = Rules::parse(words.iter().map(String::as_str)).unwrap();
+    assert_eq!(parsed, rules);
+    assert_eq!(parsed.fingerprint(), rules.fingerprint());
+
+    let classic = Rules::default();
+    let words = classic.words();
+    assert_eq!(
+        Rules::parse(words.iter().map(String::as_str)),
+        Some(classic)
+    );
+    assert!(Rules::parse(["goal:forever"]).is_none());
+}
+
+#[test]
+fn a_near_miss_pays_once_for_every_pass_by_a_wall() {
+    let mut game = game(
+        &[((WIDTH - 3, 5), SegmentType::Head)],
+        Direction::Right,
+        Vec::new(),
+    );
+    game.wrap_around = false;
+    game.update();
+    assert_eq!(game.near_misses, 0);
+    game.update();
+    assert_eq!(game.near_misses, 1);
+    assert_eq!(game.score, 1);
+    assert!(game.events.contains(&GameEvent::NearMiss((WIDTH, 5))));
+
+    // Sliding along the wall is the same pass
+    game.steer(Direction::Down);
+    game.update();
+    assert_eq!(game.near_misses, 1);
+    // Coming back after leaving it is another
+    game.steer(Direction::Left);
+    game.update();
+    game.steer(Direction::Down);
+    game.update();
+    assert_eq!(game.near_misses, 1);
+    game.steer(Direction::Right);
+    game.update();
+    assert_eq!(game.near_misses, 2);
+}
+
+#[test]
+fn adaptive_difficulty_eases_off_after_deaths_and_close_calls() {
+    let mut game = game(
+        &[((WIDTH - 1, 5), SegmentType::Head)],
+        Direction::Right,
+        Vec::new(),
+    );
+    game.wrap_around = false;
+    game.adaptive = Some(Adaptive::default());
+    game.steer(Direction::Up);
+    game.update();
+    assert!(!game.game_over);
+    let adaptive = game.adaptive.as_mut().unwrap();
+    assert_eq!(
+        adaptive.words()[3],
+        "close_calls:1",
+        "turned away from the wall"
+    );
+    adaptive.died();
+    for _ in 1..1800 {
+        adaptive.tick();
+    }
+    assert_eq!(adaptive.level, -1);
+    assert_eq!(adaptive.interval(10), 12);
+    assert_eq!(adaptive.metal_rest(), 20);
+    let words = adaptive.words();
+    assert_eq!(
+        Adaptive::parse(words.iter().map(String::as_str)).as_ref(),
+        Some(&*adaptive)
+    );
+
+    // Once the death is out of the window, clean play picks the pace up again
+    for _ in 0..60 * 7200 {
+        adaptive.tick();
+    }
+    assert_eq!(adaptive.level, MAX_LEVEL);
+    assert_eq!(adaptive.metal_pieces(), 2);
+}
+
+#[test]
+fn the_slow_start_eases_up_to_full_speed_over_the_first_foods() {
+    let rules = Rules {
+        slow_start: true,
+        ..Rules::default()
+    };
+    assert_eq!(
+        rules.slow_start_interval(6, 0),
+        10,
+        "60% speed before any food"
+    );
+    assert_eq!(rules.slow_start_interval(6, 5), 8);
+    assert_eq!(rules.slow_start_interval(6, SLOW_START_FOODS), 6);
+    assert_eq!(Rules::default().slow_start_interval(6, 0), 6);
+
+    let mut game = game(
+        &[((5, 5), SegmentType::Head)],
+        Direction::Right,
+        vec![food((6, 5), FoodType::RustyScrap)],
+    );
+    game.rules = rules;
+    game.tick_interval = 10;
+    assert_eq!(game.move_interval(), 17);
+    while game.foods_eaten == 0 {
+        game.update();
+    }
+    assert_eq!(game.move_interval(), 16);
+    assert!(game.rules.assisted());
+}
+
+#[test]
+fn a_speed_up_is_announced_before_it_takes_over() {
+    let mut game = game(&[((5, 5), SegmentType::Head)], Direction::Right, Vec::new());
+    game.rules = Rules {
+        length_speed: LengthSpeed::Hard,
+        ..Rules::default()
+    };
+    game.base_interval = 10;
+    game.tick_interval = 10;
+    for _ in 0..12 {
+        game.grow();
+    }
+    for _ in 0..10 {
+        game.update();
+    }
+    assert_eq!(
+        game.tick_interval, 10,
+        "still the old pace during the warning"
+    );
+    let speed_up = game.speed_up.expect("a speed-up on its way");
+    assert_eq!(speed_up.interval, 8);
+    for _ in 0..speed_up.frames_left {
+        game.update();
+    }
+    assert_eq!(game.tick_interval, 8);
+    assert_eq!(game.speed_up, None);
+}
+
+#[test]
+fn quick_presses_between_moves_cannot_reverse_into_the_neck() {
+    let body = [
+        ((5, 5), SegmentType::Head),
+        ((4, 5), SegmentType::EmptyStomach),
+        ((3, 5), SegmentType::Tail),
+    ];
+    let mut game = game(&body, Direction::Right, Vec::new());
+
+    // Up then Left before the move: Left would go straight back the way it came
+    assert!(game.steer(Direction::Up));
+    assert!(!game.steer(Direction::Left));
+    game.update();
+    assert!(!game.game_over);
+    assert_eq!(positions(&game)[0], (5, 4));
+
+    // Only the last move counts, so Right then Down still turns from Up
+    assert!(game.steer(Direction::Right));
+    assert!(!game.steer(Direction::Down));
+    assert!(game.steer(Direction::Left));
+    game.update();
+    assert_eq!(positions(&game)[0], (4, 4));
+}
+
+#[test]
+fn strict_input_takes_the_latest_valid_turn_at_the_move() {
+    let body = [
+        ((5, 5), SegmentType::Head),
+        ((4, 5), SegmentType::EmptyStomach),
+        ((3, 5), SegmentType::Tail),
+    ];
+    let mut game = game(&body, Direction::Right, Vec::new());
+    game.rules.input_timing = InputTiming::Strict;
+
+    // Left would be straight back into the neck, so the Up before it wins
+    game.steer(Direction::Up);
+    game.steer(Direction::Left);
+    assert_eq!(game.queued, [Direction::Up, Direction::Left]);
+    assert_eq!(
+        game.snake.direction,
+        Direction::Right,
+        "nothing turns before the move"
+    );
+    game.update();
+
+    assert!(!game.game_over);
+    assert_eq!(positions(&game)[0], (5, 4));
+    assert!(game.queued.is_empty());
+}
+
+#[test]
+fn food_only_spawns_on_free_cells() {
+    let body = [
+        ((5, 5), SegmentType::Head),
+        ((4, 5), SegmentType::EmptyStomach),
+        ((3, 5), SegmentType::Tail),
+    ];
+    let mut game = game(&body, Direction::Right, vec![food((7, 7), FoodType::Water)]);
+    game.puddles.push(Puddle::new((8, 8)));
+    let free = game.free_cells();
+    assert_eq!(free.len(), (WIDTH * HEIGHT) as usize - 5);
+    for taken in [(5, 5), (4, 5), (3, 5), (7, 7), (8, 8)] {
+        assert!(!free.contains(&taken), "{:?}", taken);
+    }
+
+    // Everything but one cell taken: that one is all there is
+    game.foods.clear();
+    game.puddles.clear();
+    let last = (WIDTH - 1, HEIGHT - 1);
+    game.snake.body = (0..HEIGHT)
+        .flat_map(|y| (0..WIDTH).map(move |x| (x, y)))
+        .filter(|cell| *cell != last)
+        .map(|position| Segment {
+            position,
+            segment_type: SegmentType::EmptyStomach,
+        })
+        .collect();
+    assert_eq!(game.free_cells(), vec![last]);
+}
+
+#[test]
+fn harder_ai_tiers_score_more() {
+    // Over a short game Medium and Hard are close, and where the food happens to land
+    // decides a single seed, so the ordering is only asked of the mean over many
+    const SEEDS: u64 = 16;
+    let mean = |difficulty: Difficulty| {
+        let total: u32 = (0..SEEDS)
+            .map(|seed| {
+                let game = Game::headless(seed, (WIDTH, HEIGHT));
+                headless::play(game, &mut Ai::new(difficulty, seed), 200).score
+            })
+            .sum();
+        total / SEEDS as u32
+    };
+    let (easy, medium, hard) = (
+        mean(Difficulty::Easy),
+        mean(Difficulty::Medium),
+        mean(Difficulty::Hard),
+    );
+    assert!(easy < medium && medium < hard, "{easy} {medium} {hard}");
+}
+
+#[test]
+fn transitions_record_each_move_with_its_reward() {
+    let body = [
+        ((5, 5), SegmentType::Head),
+        ((4, 5), SegmentType::EmptyStomach),
+        ((3, 5), SegmentType::Tail),
+    ];
+    let game = game(
+        &body,
+        Direction::Right,
+        vec![food((6, 5), FoodType::RustyScrap)],
+    );
+    let tensor = transitions::state(&game);
+    let cell = |channel: usize, (x, y): (usize, usize)| {
+        tensor[(channel * HEIGHT as usize + y) * WIDTH as usize + x]
+    };
+    assert_eq!(
+        tensor.len(),
+        transitions::CHANNELS.len() * (WIDTH * HEIGHT) as usize
+    );
+    assert_eq!(
+        (cell(0, (5, 5)), cell(1, (4, 5)), cell(2, (6, 5))),
+        (1, 1, 1)
+    );
+    assert_eq!(tensor.iter().map(|v| *v as usize).sum::<usize>(), 4);
+
+    let mut out = Vec::new();
+    transitions::record(7, game, &mut Ai::new(Difficulty::Medium, SEED), 2, &mut out).unwrap();
+    let lines: Vec<&str> = std::str::from_utf8(&out).unwrap().lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("{\"game\":7,"));
+    assert!(lines[0].contains("\"action\":1,\"reward\":1,"));
+    assert!(lines[0].ends_with("\"done\":false}"));
+    assert!(lines[1].ends_with("\"done\":true}"));
+}
+
+#[test]
+fn env_steps_reward_points_and_end_on_death() {
+    let mut env = SnakeEnv::new(SEED, Encoding::Features);
+    let observation = env.reset();
+    assert_eq!(observation.len(), FEATURES.len());
+    assert_eq!(observation[4], 1.0, "moving right");
+
+    let head = env.game.snake.body[0].position;
+    env.game.foods = vec![food((head.0 + 1, head.1), FoodType::RustyScrap)];
+    let (_, reward, done) = env.step(Direction::Right);
+    assert_eq!((reward, done), (1.0, false));
+
+    env.game.wrap_around = false;
+    let y = env.game.snake.body[0].position.1;
+    env.game.snake.body[0].position = (WIDTH - 1, y);
+    let (_, reward, done) = env.step(Direction::Right);
+    assert_eq!((reward, done), (-1.0, true));
+
+    env.encoding = Encoding::Grid;
+    let observation = env.reset();
+    assert_eq!(
+        observation.len(),
+        transitions::CHANNELS.len() * (WIDTH * HEIGHT) as usize
+    );
+    assert_eq!(env.moves, 0);
+}
+
+#[test]
+fn state_hash_follows_the_game_and_nothing_else() {
+    let mut a = Game::headless(SEED, (WIDTH, HEIGHT));
+    let mut b = Game::headless(SEED, (WIDTH, HEIGHT));
+    assert_eq!(a.state_hash(), b.state_hash());
+    // Hashing doesn't use up any of the game's random numbers
+    assert_eq!(a.state_hash(), a.state_hash());
+
+    for game in [&mut a, &mut b] {
+        game.tick_interval = 1;
+        game.steer(Direction::Right);
+        game.update();
+    }
+    assert_eq!(a.state_hash(), b.state_hash());
+    b.steer(Direction::Up);
+    a.update();
+    b.update();
+    assert_ne!(a.state_hash(), b.state_hash());
+}
+
+fn entry(name: &str, score: u32, time: u64) -> HighScoreEntry {
+    HighScoreEntry {
+        name: name.to_string(),
+        score,
+        mutators: Mutators::default(),
+        perfect: false,
+        assisted: false,
+        time,
+    }
+}
+
+#[test]
+fn merging_high_scores_unions_instead_of_overwriting() {
+    let mut game = Game::headless(SEED, (WIDTH, HEIGHT));
+    game.high_scores = vec![entry("ann", 50, 1), entry("bob", 30, 2), entry("cy", 10, 3)];
+    let report = game.merge_high_scores(vec![
+        entry("bob", 30, 2), // The same entry, synced before
+        entry("dee", 40, 4),
+        entry("bob", 30, 5), // Same name and score, set another time
+        entry("eve", 20, 6),
+        entry("fay", 5, 7),
+    ]);
+
+    let table: Vec<(&str, u32)> = game
+        .high_scores
+        .iter()
+        .map(|e| (e.name.as_str(), e.score))
+        .collect();
+    assert_eq!(
+        table,
+        [
+            ("ann", 50),
+            ("dee", 40),
+            ("bob", 30),
+            ("bob", 30),
+            ("eve", 20)
+        ]
+    );
+    assert_eq!(report.duplicates, 1);
+    assert_eq!(
+        report.added,
+        [
+            ("dee".to_string(), 40),
+            ("bob".to_string(), 30),
+            ("eve".to_string(), 20)
+        ]
+    );
+    assert_eq!(report.crowded_out, 1);
+}
+
+#[test]
+fn score_writes_keep_three_earlier_generations() {
+    let dir = std::env::temp_dir().join(format!("rusty_snake_backups_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("high_scores.txt");
+    for contents in ["1", "2", "3", "4", "5"] {
+        game::write_with_backups(&path, contents).unwrap();
+    }
+
+    let read = |path: &std::path::Path| std::fs::read_to_string(path).ok();
+    assert_eq!(read(&path).as_deref(), Some("5"));
+    let backups: Vec<Option<String>> = (1..=SCORE_BACKUPS + 1)
+        .map(|generation| read(&game::backup_path(&path, generation)))
+        .collect();
+    assert_eq!(
+        backups,
+        [
+            Some("4".to_string()),
+            Some("3".to_string()),
+            Some("2".to_string()),
+            None
+        ]
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn names_are_measured_and_cut_in_graphemes() {
+    let mut name = String::new();
+    names::type_into(&mut name, "Åsa 🐍");
+    assert_eq!(names::length(&name), 5);
+    assert!(name.len() > 5, "more bytes than graphemes");
+
+    // A flag is two code points but one character, backspace takes it whole
+    names::type_into(&mut name, "🇸🇪");
+    assert_eq!(names::length(&name), 6);
+    names::backspace(&mut name);
+    assert_eq!(name, "Åsa 🐍");
+
+    // The file separators and control characters never make it in
+    names::type_into(&mut name, ",=\n");
+    assert_eq!(name, "Åsa 🐍");
+
+    names::type_into(&mut name, "ÖÖÖÖÖÖÖÖ");
+    assert_eq!(names::length(&name), MAX_NAME_LENGTH);
+
+    assert_eq!(names::shorten("Åsa 🐍", 10), "Åsa 🐍");
+    assert_eq!(names::shorten("Bartholomew the 🐍", 6), "Barth…");
+}